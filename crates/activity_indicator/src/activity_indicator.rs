@@ -2,15 +2,15 @@ use auto_update::{AutoUpdateStatus, AutoUpdater, DismissErrorMessage};
 use editor::Editor;
 use futures::StreamExt;
 use gpui::{
-    actions, svg, AppContext, CursorStyle, EventEmitter, InteractiveElement as _, Model,
-    ParentElement as _, Render, SharedString, StatefulInteractiveElement, Styled, View,
+    actions, svg, AnchorCorner, AppContext, CursorStyle, EventEmitter, InteractiveElement as _,
+    Model, ParentElement as _, Render, SharedString, StatefulInteractiveElement, Styled, View,
     ViewContext, VisualContext as _,
 };
-use language::{LanguageRegistry, LanguageServerBinaryStatus};
+use language::{InstallProgress, LanguageRegistry, LanguageServerBinaryStatus, LanguageServerId};
 use project::{LanguageServerProgress, Project};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc};
-use ui::prelude::*;
+use ui::{popover_menu, prelude::*, ButtonLike, ContextMenu};
 use util::ResultExt;
 use workspace::{item::ItemHandle, StatusItemView, Workspace};
 
@@ -35,6 +35,7 @@ struct LspStatus {
 }
 
 struct PendingWork<'a> {
+    language_server_id: LanguageServerId,
     language_server_name: &'a str,
     progress_token: &'a str,
     progress: &'a LanguageServerProgress,
@@ -126,6 +127,12 @@ impl ActivityIndicator {
         cx.notify();
     }
 
+    fn dismiss_cached_servers(&mut self, cx: &mut ViewContext<Self>) {
+        self.statuses
+            .retain(|status| !matches!(status.status, LanguageServerBinaryStatus::Cached));
+        cx.notify();
+    }
+
     fn dismiss_error_message(&mut self, _: &DismissErrorMessage, cx: &mut ViewContext<Self>) {
         if let Some(updater) = &self.auto_updater {
             updater.update(cx, |updater, cx| {
@@ -143,7 +150,7 @@ impl ActivityIndicator {
             .read(cx)
             .language_server_statuses()
             .rev()
-            .filter_map(|status| {
+            .filter_map(|(language_server_id, status)| {
                 if status.pending_work.is_empty() {
                     None
                 } else {
@@ -151,6 +158,7 @@ impl ActivityIndicator {
                         .pending_work
                         .iter()
                         .map(|(token, progress)| PendingWork {
+                            language_server_id,
                             language_server_name: status.name.as_str(),
                             progress_token: token.as_str(),
                             progress,
@@ -163,27 +171,63 @@ impl ActivityIndicator {
             .flatten()
     }
 
+    fn has_pending_language_server_work(&self, cx: &AppContext) -> bool {
+        self.pending_language_server_work(cx).next().is_some()
+    }
+
+    /// Lists each piece of pending language server work, cancelling the work that's clicked
+    /// when its server allows it.
+    fn build_pending_work_menu(&mut self, cx: &mut ViewContext<Self>) -> View<ContextMenu> {
+        let project = self.project.clone();
+        let pending_work = self
+            .pending_language_server_work(cx)
+            .map(|work| {
+                (
+                    work.language_server_id,
+                    work.progress_token.to_string(),
+                    SharedString::from(work.message()),
+                    work.progress.is_cancellable,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        ContextMenu::build(cx, move |mut menu, _| {
+            for (language_server_id, token, message, is_cancellable) in pending_work {
+                let project = project.clone();
+                menu = menu.custom_entry(
+                    move |_| {
+                        h_flex()
+                            .w_full()
+                            .gap_4()
+                            .justify_between()
+                            .child(Label::new(message.clone()))
+                            .when(is_cancellable, |this| {
+                                this.child(Label::new("Cancel").color(Color::Muted))
+                            })
+                            .into_any_element()
+                    },
+                    move |cx| {
+                        if is_cancellable {
+                            project.update(cx, |project, cx| {
+                                project.cancel_language_server_work(
+                                    language_server_id,
+                                    token.clone(),
+                                    cx,
+                                )
+                            });
+                        }
+                    },
+                );
+            }
+            menu
+        })
+    }
+
     fn content_to_render(&mut self, cx: &mut ViewContext<Self>) -> Content {
         // Show any language server has pending activity.
         let mut pending_work = self.pending_language_server_work(cx);
-        if let Some(PendingWork {
-            language_server_name,
-            progress_token,
-            progress,
-        }) = pending_work.next()
-        {
-            let mut message = language_server_name.to_string();
-
-            message.push_str(": ");
-            if let Some(progress_message) = progress.message.as_ref() {
-                message.push_str(progress_message);
-            } else {
-                message.push_str(progress_token);
-            }
-
-            if let Some(percentage) = progress.percentage {
-                write!(&mut message, " ({}%)", percentage).unwrap();
-            }
+        if let Some(work) = pending_work.next() {
+            let mut message = work.message();
 
             let additional_work_count = pending_work.count();
             if additional_work_count > 0 {
@@ -198,16 +242,34 @@ impl ActivityIndicator {
         }
 
         // Show any language server installation info.
-        let mut downloading = SmallVec::<[_; 3]>::new();
+        let mut downloading = SmallVec::<[String; 3]>::new();
+        let mut extracting = SmallVec::<[_; 3]>::new();
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
         let mut failed = SmallVec::<[_; 3]>::new();
+        let mut cached = SmallVec::<[_; 3]>::new();
         for status in &self.statuses {
             let name = status.name.clone();
             match status.status {
                 LanguageServerBinaryStatus::CheckingForUpdate => checking_for_update.push(name),
-                LanguageServerBinaryStatus::Downloading => downloading.push(name),
+                LanguageServerBinaryStatus::Downloading => downloading.push(name.to_string()),
+                LanguageServerBinaryStatus::Installing(InstallProgress::Downloading {
+                    downloaded_bytes,
+                    total_bytes,
+                }) => downloading.push(match total_bytes {
+                    Some(total_bytes) if total_bytes > 0 => {
+                        format!("{name} ({}%)", downloaded_bytes * 100 / total_bytes)
+                    }
+                    _ => format!(
+                        "{name} ({:.1} MB)",
+                        downloaded_bytes as f64 / (1024. * 1024.)
+                    ),
+                }),
+                LanguageServerBinaryStatus::Installing(InstallProgress::Extracting) => {
+                    extracting.push(name)
+                }
                 LanguageServerBinaryStatus::Failed { .. } => failed.push(name),
-                LanguageServerBinaryStatus::Downloaded | LanguageServerBinaryStatus::Cached => {}
+                LanguageServerBinaryStatus::Cached => cached.push(name),
+                LanguageServerBinaryStatus::Downloaded => {}
             }
         }
 
@@ -221,6 +283,16 @@ impl ActivityIndicator {
                 ),
                 on_click: None,
             };
+        } else if !extracting.is_empty() {
+            return Content {
+                icon: Some(DOWNLOAD_ICON),
+                message: format!(
+                    "Installing {} language server{}...",
+                    extracting.join(", "),
+                    if extracting.len() > 1 { "s" } else { "" }
+                ),
+                on_click: None,
+            };
         } else if !checking_for_update.is_empty() {
             return Content {
                 icon: Some(DOWNLOAD_ICON),
@@ -247,6 +319,16 @@ impl ActivityIndicator {
                     this.show_error_message(&Default::default(), cx)
                 })),
             };
+        } else if !cached.is_empty() {
+            return Content {
+                icon: Some(WARNING_ICON),
+                message: format!(
+                    "Couldn't check for updates to {} language server{}, which may be out of date. Click to dismiss.",
+                    cached.join(", "),
+                    if cached.len() > 1 { "s" } else { "" }
+                ),
+                on_click: Some(Arc::new(|this, cx| this.dismiss_cached_servers(cx))),
+            };
         }
 
         // Show any application auto-update info.
@@ -289,6 +371,24 @@ impl ActivityIndicator {
     }
 }
 
+impl PendingWork<'_> {
+    fn message(&self) -> String {
+        let mut message = self.language_server_name.to_string();
+
+        message.push_str(": ");
+        if let Some(progress_message) = self.progress.message.as_ref() {
+            message.push_str(progress_message);
+        } else {
+            message.push_str(self.progress_token);
+        }
+
+        if let Some(percentage) = self.progress.percentage {
+            write!(&mut message, " ({}%)", percentage).unwrap();
+        }
+        message
+    }
+}
+
 impl EventEmitter<Event> for ActivityIndicator {}
 
 impl Render for ActivityIndicator {
@@ -308,9 +408,20 @@ impl Render for ActivityIndicator {
                 }))
         }
 
-        result
+        let result = result
             .children(content.icon.map(|icon| svg().path(icon)))
-            .child(Label::new(SharedString::from(content.message)).size(LabelSize::Small))
+            .child(Label::new(SharedString::from(content.message)).size(LabelSize::Small));
+
+        if self.has_pending_language_server_work(cx) {
+            let this = cx.view().clone();
+            popover_menu("activity-indicator-pending-work")
+                .anchor(AnchorCorner::BottomLeft)
+                .trigger(ButtonLike::new("activity-indicator-trigger").child(result))
+                .menu(move |cx| Some(this.update(cx, |this, cx| this.build_pending_work_menu(cx))))
+                .into_any_element()
+        } else {
+            result.into_any_element()
+        }
     }
 }
 
@@ -10,6 +10,7 @@ use async_tungstenite::tungstenite::{
     error::Error as WebsocketError,
     http::{Request, StatusCode},
 };
+use feature_flags::FeatureFlagAppExt;
 use futures::{
     channel::oneshot, future::LocalBoxFuture, AsyncReadExt, FutureExt, SinkExt, StreamExt,
     TryFutureExt as _, TryStreamExt,
@@ -38,7 +39,7 @@ use std::{
     sync::{atomic::AtomicU64, Arc, Weak},
     time::{Duration, Instant},
 };
-use telemetry::Telemetry;
+use telemetry::{EventCategory, Telemetry};
 use thiserror::Error;
 use url::Url;
 use util::channel::ReleaseChannel;
@@ -346,10 +347,53 @@ impl<T: 'static> Drop for PendingEntitySubscription<T> {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone, Deserialize)]
 pub struct TelemetrySettings {
     pub diagnostics: bool,
     pub metrics: bool,
+    pub endpoint_url: Option<String>,
+    pub exporter: TelemetryExporter,
+    pub otlp_collector_url: Option<String>,
+    pub local_log: bool,
+    pub categories: TelemetryCategories,
+    pub sample_rates: TelemetrySampleRates,
+    pub system_metrics_window_in_minutes: u64,
+    pub memory_pressure_thresholds_in_megabytes: Vec<u64>,
+    pub installation_id_rotation_in_days: Option<u64>,
+    pub noise: TelemetryNoise,
+    pub deduplication_window_in_seconds: Option<u64>,
+    pub extensions: HashMap<String, bool>,
+}
+
+/// The format that usage data is sent in.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryExporter {
+    /// Zed's own event format, as expected by Zed's servers.
+    Zed,
+    /// OpenTelemetry logs over HTTP, for routing into an existing observability stack.
+    Otlp,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct TelemetryCategories {
+    pub editor: bool,
+    pub assistant: bool,
+    pub call: bool,
+    pub system: bool,
+    pub errors: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TelemetrySampleRates {
+    pub cpu: f32,
+    pub memory: f32,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TelemetryNoise {
+    pub enabled: bool,
+    pub epsilon: f64,
 }
 
 /// Control what info is collected by Zed.
@@ -363,6 +407,183 @@ pub struct TelemetrySettingsContent {
     ///
     /// Default: true
     pub metrics: Option<bool>,
+    /// The URL that usage data is sent to, for organizations running their own collector.
+    /// When unset, data is sent to Zed's servers.
+    ///
+    /// Default: null
+    pub endpoint_url: Option<String>,
+    /// The format that usage data is sent in. When set to `otlp` and no `endpoint_url`
+    /// is given, data is sent to a collector on `localhost:4318`.
+    ///
+    /// Default: zed
+    pub exporter: Option<TelemetryExporter>,
+    /// The URL of an OpenTelemetry collector to also send usage data to as OTLP/HTTP logs,
+    /// alongside the configured `exporter`.
+    ///
+    /// Default: null
+    pub otlp_collector_url: Option<String>,
+    /// Write usage data as NDJSON to `~/Library/Application Support/Zed/telemetry/events.ndjson`
+    /// instead of sending it anywhere.
+    ///
+    /// Default: false
+    pub local_log: Option<bool>,
+    /// Which kinds of usage data to send when `metrics` is enabled.
+    pub categories: Option<TelemetryCategoriesContent>,
+    /// The fraction of periodic system samples to send, between 0 and 1.
+    pub sample_rates: Option<TelemetrySampleRatesContent>,
+    /// How many minutes of CPU and memory samples to summarize into each event.
+    ///
+    /// Default: 15
+    pub system_metrics_window_in_minutes: Option<u64>,
+    /// Report when Zed's memory usage rises above each of these amounts, along with the
+    /// estimated size of its caches.
+    ///
+    /// Default: [2048, 4096, 8192]
+    pub memory_pressure_thresholds_in_megabytes: Option<Vec<u64>>,
+    /// Replace the anonymous installation id with a new random one after this many days,
+    /// so that usage data can't be linked across longer periods. When unset, the id is kept.
+    ///
+    /// Default: null
+    pub installation_id_rotation_in_days: Option<u64>,
+    /// Random noise to add to numeric metrics like memory, CPU usage and latency before
+    /// they are sent, so that only aggregates across many machines are meaningful.
+    pub noise: Option<TelemetryNoiseContent>,
+    /// Collapse identical events reported within this many seconds of each other into a
+    /// single event with a `count`. When unset, every event is sent.
+    ///
+    /// Default: null
+    pub deduplication_window_in_seconds: Option<u64>,
+    /// Whether each extension may send usage data, keyed by extension id.
+    /// Extensions that aren't listed may send usage data when `metrics` is enabled.
+    ///
+    /// Default: {}
+    pub extensions: Option<HashMap<String, bool>>,
+}
+
+/// Which kinds of usage data to send when `metrics` is enabled.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct TelemetryCategoriesContent {
+    /// Editing activity, like the file types opened and the actions used.
+    ///
+    /// Default: true
+    pub editor: Option<bool>,
+    /// Assistant and Copilot usage.
+    ///
+    /// Default: true
+    pub assistant: Option<bool>,
+    /// Calls and channels usage.
+    ///
+    /// Default: true
+    pub call: Option<bool>,
+    /// CPU and memory usage of Zed itself.
+    ///
+    /// Default: true
+    pub system: Option<bool>,
+    /// Errors encountered while running Zed.
+    ///
+    /// Default: true
+    pub errors: Option<bool>,
+}
+
+/// The fraction of periodic system samples to send, between 0 and 1.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TelemetrySampleRatesContent {
+    /// The fraction of CPU usage samples to send.
+    ///
+    /// Default: 1.0
+    pub cpu: Option<f32>,
+    /// The fraction of memory usage samples to send.
+    ///
+    /// Default: 1.0
+    pub memory: Option<f32>,
+}
+
+/// Random noise to add to numeric metrics before they are sent.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TelemetryNoiseContent {
+    /// Whether to add noise to numeric metrics.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// How much noise to add, where smaller values add more noise. Must be greater than 0.
+    ///
+    /// Default: 1.0
+    pub epsilon: Option<f64>,
+}
+
+impl TelemetrySettings {
+    pub fn is_category_enabled(&self, category: EventCategory) -> bool {
+        self.metrics
+            && match category {
+                EventCategory::App => true,
+                EventCategory::Editor => self.categories.editor,
+                EventCategory::Assistant => self.categories.assistant,
+                EventCategory::Call => self.categories.call,
+                EventCategory::System => self.categories.system,
+                EventCategory::Error => self.categories.errors,
+                EventCategory::Extension => true,
+            }
+    }
+
+    /// The fraction of events of this type that are sent,
+    /// or `None` if every event of this type is sent.
+    pub fn sample_rate(&self, event: &Event) -> Option<f32> {
+        let sample_rate = match event {
+            Event::Cpu { .. } => self.sample_rates.cpu,
+            Event::Memory { .. } => self.sample_rates.memory,
+            _ => return None,
+        };
+        if sample_rate.is_nan() {
+            Some(1.)
+        } else {
+            Some(sample_rate.clamp(0., 1.))
+        }
+    }
+
+    /// How long CPU and memory samples are collected for before a summary is sent.
+    pub fn system_metrics_window(&self) -> Duration {
+        Duration::from_secs(self.system_metrics_window_in_minutes.max(1) * 60)
+    }
+
+    /// The memory usage thresholds that `MemoryPressure` events are reported for, in bytes.
+    pub fn memory_pressure_thresholds(&self) -> Vec<u64> {
+        self.memory_pressure_thresholds_in_megabytes
+            .iter()
+            .map(|megabytes| megabytes.saturating_mul(1024 * 1024))
+            .collect()
+    }
+
+    /// How long identical events are collapsed into one for, or `None` if they aren't.
+    pub fn deduplication_window(&self) -> Option<Duration> {
+        self.deduplication_window_in_seconds
+            .filter(|&seconds| seconds > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// How often the installation id is replaced, or `None` if it is kept.
+    pub fn installation_id_rotation(&self) -> Option<Duration> {
+        self.installation_id_rotation_in_days
+            .filter(|&days| days > 0)
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+
+    /// The privacy parameter to add noise to numeric metrics with,
+    /// or `None` if no noise should be added.
+    pub fn noise_epsilon(&self) -> Option<f64> {
+        if !self.noise.enabled {
+            return None;
+        }
+        if self.noise.epsilon.is_finite() && self.noise.epsilon > 0. {
+            Some(self.noise.epsilon)
+        } else {
+            Some(1.)
+        }
+    }
+
+    pub fn is_extension_enabled(&self, extension_id: &str) -> bool {
+        self.is_category_enabled(EventCategory::Extension)
+            && self.extensions.get(extension_id).copied().unwrap_or(true)
+    }
 }
 
 impl settings::Settings for TelemetrySettings {
@@ -375,26 +596,22 @@ impl settings::Settings for TelemetrySettings {
         user_values: &[&Self::FileContent],
         _: &mut AppContext,
     ) -> Result<Self> {
-        Ok(Self {
-            diagnostics: user_values.first().and_then(|v| v.diagnostics).unwrap_or(
-                default_value
-                    .diagnostics
-                    .ok_or_else(Self::missing_default)?,
-            ),
-            metrics: user_values
-                .first()
-                .and_then(|v| v.metrics)
-                .unwrap_or(default_value.metrics.ok_or_else(Self::missing_default)?),
-        })
+        Self::load_via_json_merge(default_value, user_values)
     }
 }
 
 impl Client {
     pub fn new(http: Arc<dyn HttpClient>, cx: &mut AppContext) -> Arc<Self> {
+        let telemetry = Telemetry::new(http.clone(), cx);
+        cx.on_flag_exposure({
+            let telemetry = telemetry.clone();
+            move |flag, enabled| telemetry.report_flag_exposure(flag, enabled)
+        });
+
         Arc::new(Self {
             id: AtomicU64::new(0),
             peer: Peer::new(0),
-            telemetry: Telemetry::new(http.clone(), cx),
+            telemetry,
             http,
             state: Default::default(),
 
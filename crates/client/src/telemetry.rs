@@ -1,24 +1,76 @@
+mod aggregation;
+mod builder;
+mod consent;
+mod dedup;
 mod event_coalescer;
-
-use crate::{TelemetrySettings, ZED_SERVER_URL};
+mod file_extension;
+mod gpu;
+mod memory_pressure;
+mod network;
+mod noise;
+mod otlp;
+mod panic_hook;
+mod policy;
+mod power;
+mod rate_limiter;
+mod rotation;
+mod schema;
+mod sent_summary;
+mod sentinel;
+mod session_activity;
+mod sink;
+mod spool;
+mod system_specs;
+mod threads;
+
+use crate::{TelemetryExporter, TelemetrySettings, ZED_SERVER_URL};
+use anyhow::Result;
+use async_compression::futures::bufread::GzipEncoder;
 use chrono::{DateTime, Utc};
-use futures::Future;
-use gpui::{AppContext, AppMetadata, BackgroundExecutor, Task};
-use lazy_static::lazy_static;
+use futures::{AsyncReadExt, Future};
+use gpui::{
+    AppContext, AppMetadata, BackgroundExecutor, FrameTimingsSummary, InputLatencySummary, Task,
+};
 use parking_lot::Mutex;
+use postage::watch;
+use rand::Rng;
 use serde::Serialize;
+use serde_json::{Map, Value};
 use settings::{Settings, SettingsStore};
-use std::{env, io::Write, mem, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    io::Write,
+    mem, panic,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use sysinfo::{
     CpuRefreshKind, Pid, PidExt, ProcessExt, ProcessRefreshKind, RefreshKind, System, SystemExt,
 };
 use tempfile::NamedTempFile;
-use util::http::HttpClient;
-#[cfg(not(debug_assertions))]
-use util::ResultExt;
-use util::{channel::ReleaseChannel, TryFutureExt};
+use util::http::{
+    AsyncBody, HttpClient, HttpObserver, Method, Request, RequestOutcome, Response, RetryAttempt,
+    StatusCode,
+};
+use util::{channel::ReleaseChannel, retry_delay, ResultExt, TryFutureExt};
 
-use self::event_coalescer::EventCoalescer;
+use self::{
+    aggregation::MetricSamples, dedup::DuplicateRun, event_coalescer::EventCoalescer,
+    memory_pressure::MemoryPressureMonitor, network::NetworkRequests, rate_limiter::RateLimiter,
+    sentinel::Sentinel, session_activity::SessionActivity, threads::ThreadSampler,
+};
+pub use self::{
+    aggregation::MetricSummary,
+    builder::EditorEventBuilder,
+    consent::{load_consent, TelemetryConsent},
+    memory_pressure::{register_memory_accounting, MemoryAccounting, MemoryPressureTrigger},
+    noise::NoiseParameters,
+    sent_summary::SentSummary,
+    sink::{LocalLogSink, OtlpCollectorSink, TelemetrySink},
+    system_specs::SystemSpecs,
+    threads::ThreadGroupUsage,
+};
 
 pub struct Telemetry {
     http_client: Arc<dyn HttpClient>,
@@ -28,12 +80,15 @@ pub struct Telemetry {
 
 struct TelemetryState {
     settings: TelemetrySettings,
+    /// Why telemetry is disabled regardless of the settings, if it is.
+    disabled_by_policy: Option<Arc<str>>,
+    consent: TelemetryConsent,
     metrics_id: Option<Arc<str>>,      // Per logged-in user
     installation_id: Option<Arc<str>>, // Per app installation (different for dev, nightly, preview, and stable)
     session_id: Option<Arc<str>>,      // Per app launch
     release_channel: Option<&'static str>,
     app_metadata: AppMetadata,
-    architecture: &'static str,
+    system_specs: SystemSpecs,
     events_queue: Vec<EventWrapper>,
     flush_events_task: Option<Task<()>>,
     log_file: Option<NamedTempFile>,
@@ -41,30 +96,102 @@ struct TelemetryState {
     first_event_date_time: Option<DateTime<Utc>>,
     event_coalescer: EventCoalescer,
     max_queue_size: usize,
+    max_unsent_events: usize,
+    dropped_event_count: usize,
+    rate_limiter: RateLimiter,
+    duplicate_run: Option<DuplicateRun>,
+    language_server_processes: HashMap<u32, LanguageServerProcess>,
+    network_requests: NetworkRequests,
+    spool_path: Option<PathBuf>,
+    sentinel_path: Option<PathBuf>,
+    sentinel: Sentinel,
+    sent_summary: SentSummary,
+    sent_summary_path: Option<PathBuf>,
+    sinks: Vec<SinkQueue>,
+    gzip_enabled: bool,
+    flushed_batches: VecDeque<FlushedBatch>,
+    flush_errors: VecDeque<String>,
+    inspector_changes: (watch::Sender<()>, watch::Receiver<()>),
+    launched_at: Instant,
+    first_edit_reported: bool,
+    session_activity: SessionActivity,
+    exposed_flags: HashSet<&'static str>,
 }
 
-const EVENTS_URL_PATH: &'static str = "/api/events";
+/// The events waiting to be delivered to a [`TelemetrySink`].
+struct SinkQueue {
+    sink: Arc<dyn TelemetrySink>,
+    events: VecDeque<EventWrapper>,
+    flush_task: Option<Task<()>>,
+}
+
+struct LanguageServerProcess {
+    server_name: String,
+    server_version: Option<String>,
+}
+
+/// The outcome of sending a ping with [`Telemetry::test_connectivity`].
+#[derive(Clone, Debug)]
+pub struct ConnectivityReport {
+    pub url: String,
+    pub latency: Duration,
+    /// The response status, or a description of why no response was received.
+    pub result: Result<StatusCode, String>,
+}
+
+impl ConnectivityReport {
+    pub fn is_success(&self) -> bool {
+        self.result
+            .as_ref()
+            .map_or(false, |status| status.is_success())
+    }
+}
 
-lazy_static! {
-    static ref EVENTS_URL: String = format!("{}{}", *ZED_SERVER_URL, EVENTS_URL_PATH);
+/// A batch of events that was handed to the HTTP client.
+#[derive(Clone, Debug)]
+pub struct FlushedBatch {
+    pub flushed_at: DateTime<Utc>,
+    pub request_body: String,
 }
 
+/// Everything the telemetry inspector shows, serialized exactly as it is (or was) sent.
+#[derive(Clone, Debug)]
+pub struct InspectorSnapshot {
+    pub queued_events: Vec<String>,
+    pub flushed_batches: Vec<FlushedBatch>,
+    pub flush_errors: Vec<String>,
+}
+
+const EVENTS_URL_PATH: &'static str = "/api/events";
+
+/// Response header the server uses to advertise the request encodings it accepts.
+const ACCEPT_ENCODING_HEADER: &'static str = "x-zed-accept-encoding";
+
 #[derive(Serialize, Debug)]
 struct EventRequestBody {
+    schema_version: u32,
     installation_id: Option<Arc<str>>,
     session_id: Option<Arc<str>>,
     is_staff: Option<bool>,
     app_version: Option<String>,
-    os_name: &'static str,
-    os_version: Option<String>,
-    architecture: &'static str,
     release_channel: Option<&'static str>,
+    system_specs: SystemSpecs,
     events: Vec<EventWrapper>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Clone, Serialize, Debug)]
 struct EventWrapper {
     signed_in: bool,
+    /// The fraction of events of this type that were sent, for extrapolating server-side.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sample_rate: Option<f32>,
+    /// The noise added to the event's metrics, when `telemetry.noise` is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    noise: Option<NoiseParameters>,
+    /// How many identical events this stands for, when `telemetry.deduplication_window_in_seconds`
+    /// collapsed a run of them into one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<usize>,
     #[serde(flatten)]
     event: Event,
 }
@@ -76,6 +203,28 @@ pub enum AssistantKind {
     Inline,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSeverity {
+    Warning,
+    Error,
+    Panic,
+}
+
+/// What went wrong with a language server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerFailureKind {
+    /// The server exited or failed to start.
+    Crashed,
+    /// The server was restarted, either by the user or after failing.
+    Restarted,
+    /// No binary could be downloaded for the server.
+    DownloadFailed,
+    /// The server's process used more memory than [`LANGUAGE_SERVER_MEMORY_THRESHOLD`].
+    ExceededMemoryThreshold,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(tag = "type")]
 pub enum Event {
@@ -105,25 +254,109 @@ pub enum Event {
         model: &'static str,
         milliseconds_since_first_event: i64,
     },
+    /// Summarizes the CPU usage samples taken over `telemetry.system_metrics_window_in_minutes`.
     Cpu {
-        usage_as_percentage: f32,
-        core_count: u32,
+        sample_count: usize,
+        usage_as_percentage: MetricSummary,
+        /// The busiest groups of the process's threads, as of the last sample.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        threads: Vec<ThreadGroupUsage>,
         milliseconds_since_first_event: i64,
     },
+    /// Summarizes the memory usage samples taken over `telemetry.system_metrics_window_in_minutes`.
     Memory {
+        sample_count: usize,
+        memory_in_bytes: MetricSummary,
+        virtual_memory_in_bytes: MetricSummary,
+        milliseconds_since_first_event: i64,
+    },
+    /// Zed's memory usage crossed a `telemetry.memory_pressure_thresholds_in_megabytes`
+    /// threshold, or the OS reported that the system is running low on memory.
+    MemoryPressure {
+        trigger: MemoryPressureTrigger,
         memory_in_bytes: u64,
-        virtual_memory_in_bytes: u64,
+        /// The threshold that was crossed, for [`MemoryPressureTrigger::Threshold`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        threshold_in_bytes: Option<u64>,
+        /// The estimated size of Zed's caches, see [`MemoryAccounting`].
+        cache_sizes_in_bytes: BTreeMap<&'static str, u64>,
+        milliseconds_since_first_event: i64,
+    },
+    Gpu {
+        usage_as_percentage: f32,
+        milliseconds_since_first_event: i64,
+    },
+    Power {
+        on_battery: bool,
+        battery_percentage: f32,
+        cpu_speed_limit: Option<u32>,
+        milliseconds_since_first_event: i64,
+    },
+    FrameTime {
+        frame_count: usize,
+        mean_in_microseconds: u64,
+        p50_in_microseconds: u64,
+        p95_in_microseconds: u64,
+        max_in_microseconds: u64,
+        milliseconds_since_first_event: i64,
+    },
+    InputLatency {
+        sample_count: usize,
+        bucket_counts: Vec<usize>,
+        p50_in_milliseconds: u64,
+        p95_in_milliseconds: u64,
+        p99_in_milliseconds: u64,
+        max_in_milliseconds: u64,
+        milliseconds_since_first_event: i64,
+    },
+    /// Summarizes the requests made to one of Zed's APIs over
+    /// `telemetry.system_metrics_window_in_minutes`.
+    NetworkRequests {
+        host: String,
+        request_count: usize,
+        retry_count: usize,
+        /// Requests that received no response, e.g. because the connection failed.
+        error_count: usize,
+        status_counts: BTreeMap<u16, usize>,
+        latency_in_milliseconds: MetricSummary,
+        milliseconds_since_first_event: i64,
+    },
+    Ping {
+        milliseconds_since_first_event: i64,
+    },
+    Session {
+        active_duration_in_milliseconds: u64,
+        idle_duration_in_milliseconds: u64,
+        milliseconds_since_first_event: i64,
+    },
+    Timing {
+        name: &'static str,
+        duration_in_milliseconds: u64,
         milliseconds_since_first_event: i64,
     },
     App {
         operation: String,
         milliseconds_since_first_event: i64,
     },
+    DroppedEvents {
+        count: usize,
+        milliseconds_since_first_event: i64,
+    },
+    RateLimitedEvents {
+        event_type: &'static str,
+        count: usize,
+        milliseconds_since_first_event: i64,
+    },
     Setting {
         setting: &'static str,
         value: String,
         milliseconds_since_first_event: i64,
     },
+    FlagExposure {
+        flag: &'static str,
+        enabled: bool,
+        milliseconds_since_first_event: i64,
+    },
     Edit {
         duration: i64,
         environment: &'static str,
@@ -134,6 +367,109 @@ pub enum Event {
         action: String,
         milliseconds_since_first_event: i64,
     },
+    LanguageServer {
+        server_name: String,
+        server_version: Option<String>,
+        kind: LanguageServerFailureKind,
+        milliseconds_since_first_event: i64,
+    },
+    Extension {
+        extension_id: String,
+        name: String,
+        properties: Map<String, Value>,
+        milliseconds_since_first_event: i64,
+    },
+    Error {
+        severity: ErrorSeverity,
+        message: String,
+        context: &'static str,
+        backtrace: Vec<String>,
+        milliseconds_since_first_event: i64,
+    },
+    /// The previous session ended without shutting down cleanly or panicking, e.g. because
+    /// it was killed or crashed in native code. The readings are the last ones taken.
+    UnexpectedShutdown {
+        uptime_in_milliseconds: u64,
+        memory_in_bytes: Option<u64>,
+        cpu_usage_as_percentage: Option<f32>,
+        milliseconds_since_first_event: i64,
+    },
+}
+
+/// The kind of usage data an event carries, each of which can be disabled separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventCategory {
+    App,
+    Editor,
+    Assistant,
+    Call,
+    System,
+    Error,
+    Extension,
+}
+
+impl Event {
+    pub fn category(&self) -> EventCategory {
+        match self {
+            Event::App { .. }
+            | Event::Ping { .. }
+            | Event::Session { .. }
+            | Event::DroppedEvents { .. }
+            | Event::RateLimitedEvents { .. }
+            | Event::FlagExposure { .. } => EventCategory::App,
+            Event::Editor { .. }
+            | Event::Setting { .. }
+            | Event::Edit { .. }
+            | Event::Action { .. } => EventCategory::Editor,
+            Event::Copilot { .. } | Event::Assistant { .. } => EventCategory::Assistant,
+            Event::Call { .. } => EventCategory::Call,
+            Event::Error { .. }
+            | Event::LanguageServer { .. }
+            | Event::UnexpectedShutdown { .. } => EventCategory::Error,
+            Event::Extension { .. } => EventCategory::Extension,
+            Event::Cpu { .. }
+            | Event::Memory { .. }
+            | Event::MemoryPressure { .. }
+            | Event::Gpu { .. }
+            | Event::Power { .. }
+            | Event::FrameTime { .. }
+            | Event::InputLatency { .. }
+            | Event::NetworkRequests { .. }
+            | Event::Timing { .. } => EventCategory::System,
+        }
+    }
+
+    /// The name of this event's type, as serialized in its `type` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Editor { .. } => "Editor",
+            Event::Copilot { .. } => "Copilot",
+            Event::Call { .. } => "Call",
+            Event::Assistant { .. } => "Assistant",
+            Event::Cpu { .. } => "Cpu",
+            Event::Memory { .. } => "Memory",
+            Event::MemoryPressure { .. } => "MemoryPressure",
+            Event::Gpu { .. } => "Gpu",
+            Event::Power { .. } => "Power",
+            Event::FrameTime { .. } => "FrameTime",
+            Event::InputLatency { .. } => "InputLatency",
+            Event::NetworkRequests { .. } => "NetworkRequests",
+            Event::Timing { .. } => "Timing",
+            Event::App { .. } => "App",
+            Event::Ping { .. } => "Ping",
+            Event::Session { .. } => "Session",
+            Event::DroppedEvents { .. } => "DroppedEvents",
+            Event::RateLimitedEvents { .. } => "RateLimitedEvents",
+            Event::Setting { .. } => "Setting",
+            Event::FlagExposure { .. } => "FlagExposure",
+            Event::Edit { .. } => "Edit",
+            Event::Action { .. } => "Action",
+            Event::LanguageServer { .. } => "LanguageServer",
+            Event::Extension { .. } => "Extension",
+            Event::Error { .. } => "Error",
+            Event::UnexpectedShutdown { .. } => "UnexpectedShutdown",
+        }
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -142,12 +478,37 @@ const MAX_QUEUE_LEN: usize = 5;
 #[cfg(not(debug_assertions))]
 const MAX_QUEUE_LEN: usize = 50;
 
+/// Hard cap on queued events, in case they can't be flushed (e.g. no installation id yet).
+/// Once reached, the oldest events are dropped first.
+const MAX_UNSENT_EVENTS: usize = 1000;
+
 #[cfg(debug_assertions)]
 const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
 
 #[cfg(not(debug_assertions))]
 const FLUSH_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
+/// Language server processes using more memory than this are reported, once per process.
+pub const LANGUAGE_SERVER_MEMORY_THRESHOLD: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Properties beyond this many are dropped from extension events.
+const MAX_EXTENSION_EVENT_PROPERTIES: usize = 32;
+
+/// String properties of extension events are truncated to this many characters.
+const MAX_EXTENSION_EVENT_STRING_LEN: usize = 256;
+
+/// How many times a batch is posted before it is discarded.
+const MAX_FLUSH_ATTEMPTS: u32 = 5;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How long to wait for the final batch to be sent when quitting, within gpui's
+/// `SHUTDOWN_TIMEOUT`. The batch is sent on the next launch if this elapses.
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_millis(80);
+
+/// How many flushed batches and flush errors are kept for the telemetry inspector.
+const MAX_INSPECTOR_ENTRIES: usize = 20;
+
 impl Telemetry {
     pub fn new(client: Arc<dyn HttpClient>, cx: &mut AppContext) -> Arc<Self> {
         let release_channel = cx
@@ -158,8 +519,19 @@ impl Telemetry {
 
         let state = Arc::new(Mutex::new(TelemetryState {
             settings: TelemetrySettings::get_global(cx).clone(),
+            disabled_by_policy: if cfg!(any(test, feature = "test-support")) {
+                None
+            } else {
+                policy::disabled_reason().map(Into::into)
+            },
+            // Until `start` provides the stored consent, nothing may be sent
+            consent: if cfg!(any(test, feature = "test-support")) {
+                TelemetryConsent::Granted
+            } else {
+                TelemetryConsent::Unasked
+            },
             app_metadata: cx.app_metadata(),
-            architecture: env::consts::ARCH,
+            system_specs: SystemSpecs::new(cx),
             release_channel,
             installation_id: None,
             metrics_id: None,
@@ -171,6 +543,47 @@ impl Telemetry {
             first_event_date_time: None,
             event_coalescer: EventCoalescer::new(),
             max_queue_size: MAX_QUEUE_LEN,
+            max_unsent_events: MAX_UNSENT_EVENTS,
+            dropped_event_count: 0,
+            rate_limiter: RateLimiter::new(),
+            duplicate_run: None,
+            language_server_processes: HashMap::default(),
+            network_requests: NetworkRequests::new(&ZED_SERVER_URL),
+            spool_path: if cfg!(any(test, feature = "test-support")) {
+                None
+            } else {
+                Some(util::paths::TELEMETRY_SPOOL.clone())
+            },
+            sentinel_path: if cfg!(any(test, feature = "test-support")) {
+                None
+            } else {
+                Some(util::paths::TELEMETRY_SENTINEL.clone())
+            },
+            sentinel: Sentinel::new(Utc::now()),
+            sent_summary: SentSummary::default(),
+            sent_summary_path: if cfg!(any(test, feature = "test-support")) {
+                None
+            } else {
+                Some(util::paths::TELEMETRY_SENT_SUMMARY.clone())
+            },
+            sinks: vec![
+                SinkQueue::new(Arc::new(LocalLogSink::new(
+                    if cfg!(any(test, feature = "test-support")) {
+                        None
+                    } else {
+                        Some(util::paths::TELEMETRY_LOCAL_LOG.clone())
+                    },
+                ))),
+                SinkQueue::new(Arc::new(OtlpCollectorSink::new(client.clone()))),
+            ],
+            gzip_enabled: false,
+            flushed_batches: VecDeque::new(),
+            flush_errors: VecDeque::new(),
+            inspector_changes: watch::channel(),
+            launched_at: Instant::now(),
+            first_edit_reported: false,
+            session_activity: SessionActivity::new(Instant::now()),
+            exposed_flags: HashSet::default(),
         }));
 
         #[cfg(not(debug_assertions))]
@@ -187,6 +600,22 @@ impl Telemetry {
             })
             .detach();
 
+        if let Some(sent_summary_path) = state.lock().sent_summary_path.clone() {
+            cx.background_executor()
+                .spawn({
+                    let state = state.clone();
+                    async move {
+                        // Batches may have been sent before the summary finished loading
+                        if let Some(loaded) = sent_summary::load(&sent_summary_path).log_err() {
+                            let mut state = state.lock();
+                            let sent_this_session = mem::replace(&mut state.sent_summary, loaded);
+                            state.sent_summary.merge(sent_this_session);
+                        }
+                    }
+                })
+                .detach();
+        }
+
         cx.observe_global::<SettingsStore>({
             let state = state.clone();
 
@@ -197,7 +626,6 @@ impl Telemetry {
         })
         .detach();
 
-        // TODO: Replace all hardware stuff with nested SystemSpecs json
         let this = Arc::new(Self {
             http_client: client,
             executor: cx.background_executor().clone(),
@@ -216,35 +644,234 @@ impl Telemetry {
 
     #[cfg(any(test, feature = "test-support"))]
     fn shutdown_telemetry(self: &Arc<Self>) -> impl Future<Output = ()> {
-        Task::ready(())
+        self.flush_events_before_shutdown()
     }
 
     // Skip calling this function in tests.
     // TestAppContext ends up calling this function on shutdown and it panics when trying to find the TelemetrySettings
     #[cfg(not(any(test, feature = "test-support")))]
     fn shutdown_telemetry(self: &Arc<Self>) -> impl Future<Output = ()> {
+        self.report_session_event();
         self.report_app_event("close".to_string());
         // TODO: close final edit period and make sure it's sent
-        Task::ready(())
+        self.flush_events_before_shutdown()
+    }
+
+    /// Spools the queued events so they survive the app exiting, then tries to send
+    /// them before the app quits, only leaving them in the spool if that fails.
+    fn flush_events_before_shutdown(self: &Arc<Self>) -> Task<()> {
+        if let Some(sentinel_path) = &self.state.lock().sentinel_path {
+            sentinel::remove(sentinel_path).log_err();
+        }
+
+        let Some((spool_path, json_bytes)) = self.spool_events() else {
+            return Task::ready(());
+        };
+        if self.state.lock().consent != TelemetryConsent::Granted {
+            return Task::ready(());
+        }
+
+        let this = self.clone();
+        let executor = self.executor.clone();
+        self.executor.spawn(async move {
+            let gzip_enabled = this.state.lock().gzip_enabled;
+            let send = async {
+                match this.post_events(&json_bytes, gzip_enabled, 0).await {
+                    Ok(response) => response.status().is_success(),
+                    Err(_) => false,
+                }
+            };
+            let timeout = async {
+                executor.timer(SHUTDOWN_FLUSH_TIMEOUT).await;
+                false
+            };
+            if smol::future::or(send, timeout).await {
+                this.record_sent_batch(&json_bytes);
+                spool::remove(&spool_path, &json_bytes).log_err();
+            }
+        })
+    }
+
+    /// Writes any queued events to the on-disk spool, to be sent on the next launch.
+    /// Returns the spool path and the request body that was written to it.
+    ///
+    /// The sinks are flushed too, as they have no spool of their own.
+    fn spool_events(self: &Arc<Self>) -> Option<(PathBuf, Vec<u8>)> {
+        self.flush_sinks();
+
+        let mut state = self.state.lock();
+        let spool_path = state.spool_path.clone()?;
+        let events = mem::take(&mut state.events_queue);
+        state.flush_events_task.take();
+        if events.is_empty() {
+            return None;
+        }
+
+        let request_body = state.request_body(events);
+        drop(state);
+
+        let json_bytes = serde_json::to_vec(&request_body).log_err()?;
+        spool::append(&spool_path, &json_bytes).log_err()?;
+        Some((spool_path, json_bytes))
+    }
+
+    fn replay_spooled_events(self: &Arc<Self>) {
+        let state = self.state.lock();
+        let Some(spool_path) = state.spool_path.clone() else {
+            return;
+        };
+        let metrics_enabled = state.settings.metrics
+            && state.disabled_by_policy.is_none()
+            && state.consent == TelemetryConsent::Granted;
+        // Leave the spool alone until sending over the network is allowed again
+        if state.settings.local_log || state.consent == TelemetryConsent::Unasked {
+            return;
+        }
+        drop(state);
+
+        let this = self.clone();
+        self.executor
+            .spawn(
+                async move {
+                    let request_bodies = spool::take(&spool_path)?;
+                    if metrics_enabled {
+                        for request_body in request_bodies {
+                            // The spool may have been written by an older version of Zed
+                            let Some(request_body) = serde_json::from_str(&request_body)
+                                .map_err(anyhow::Error::from)
+                                .and_then(schema::migrate)
+                                .and_then(|request_body| Ok(serde_json::to_vec(&request_body)?))
+                                .log_err()
+                            else {
+                                continue;
+                            };
+                            this.post_events_with_retry(request_body).await;
+                        }
+                    }
+                    anyhow::Ok(())
+                }
+                .log_err(),
+            )
+            .detach();
+    }
+
+    /// Replaces the installation id if `installation_id_rotation_in_days` has elapsed since it
+    /// was last replaced. Events reported before then are sent with the new id, as the
+    /// id is only added to a batch when it is flushed.
+    fn rotate_installation_id(self: &Arc<Self>) {
+        let state = self.state.lock();
+        let Some(cadence) = state.settings.installation_id_rotation() else {
+            return;
+        };
+        if state.installation_id.is_none() {
+            return;
+        }
+        drop(state);
+
+        let state = self.state.clone();
+        self.executor
+            .spawn(
+                async move {
+                    if let Some(installation_id) =
+                        rotation::rotate_if_due(cadence, Utc::now()).await?
+                    {
+                        state.lock().installation_id = Some(installation_id.into());
+                    }
+                    anyhow::Ok(())
+                }
+                .log_err(),
+            )
+            .detach();
+    }
+
+    /// Reports the previous session if it left its sentinel behind, then writes this session's.
+    fn check_for_unexpected_shutdown(self: &Arc<Self>) {
+        let state = self.state.lock();
+        let Some(sentinel_path) = state.sentinel_path.clone() else {
+            return;
+        };
+        let sentinel = state.sentinel.clone();
+        drop(state);
+
+        let this = self.clone();
+        self.executor
+            .spawn(
+                async move {
+                    if let Some(previous_sentinel) = sentinel::take(&sentinel_path)? {
+                        this.report_unexpected_shutdown(previous_sentinel);
+                    }
+                    sentinel::write(&sentinel_path, &sentinel)
+                }
+                .log_err(),
+            )
+            .detach();
+    }
+
+    fn report_unexpected_shutdown(self: &Arc<Self>, previous_sentinel: Sentinel) {
+        let event = Event::UnexpectedShutdown {
+            uptime_in_milliseconds: previous_sentinel.uptime_in_milliseconds(),
+            memory_in_bytes: previous_sentinel.memory_in_bytes,
+            cpu_usage_as_percentage: previous_sentinel.cpu_usage_as_percentage,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    /// Records the latest readings in the sentinel, to be reported if this session ends unexpectedly.
+    fn update_sentinel(self: &Arc<Self>, memory_in_bytes: u64, cpu_usage_as_percentage: f32) {
+        let mut state = self.state.lock();
+        let Some(sentinel_path) = state.sentinel_path.clone() else {
+            return;
+        };
+        state.sentinel.updated_at = Utc::now();
+        state.sentinel.memory_in_bytes = Some(memory_in_bytes);
+        state.sentinel.cpu_usage_as_percentage = Some(cpu_usage_as_percentage);
+        let sentinel = state.sentinel.clone();
+        drop(state);
+
+        self.executor
+            .spawn(async move { sentinel::write(&sentinel_path, &sentinel).log_err() })
+            .detach();
     }
 
     pub fn log_file_path(&self) -> Option<PathBuf> {
         Some(self.state.lock().log_file.as_ref()?.path().to_path_buf())
     }
 
+    /// Starts reporting, with the consent that was stored alongside the installation id,
+    /// see [`load_consent`].
     pub fn start(
         self: &Arc<Self>,
         installation_id: Option<String>,
         session_id: String,
+        consent: TelemetryConsent,
         cx: &mut AppContext,
     ) {
         let mut state = self.state.lock();
         state.installation_id = installation_id.map(|id| id.into());
         state.session_id = Some(session_id.into());
+        state.consent = consent;
         drop(state);
 
+        self.rotate_installation_id();
+        self.replay_spooled_events();
+        self.check_for_unexpected_shutdown();
+
+        self.executor
+            .spawn({
+                let state = self.state.clone();
+                async move {
+                    let (os_build, gpu) = SystemSpecs::load_slow_specs().await;
+                    let mut state = state.lock();
+                    state.system_specs.os_build = os_build;
+                    state.system_specs.gpu = gpu;
+                }
+            })
+            .detach();
+
         let this = self.clone();
-        cx.spawn(|_| async move {
+        cx.spawn(|mut cx| async move {
             // Avoiding calling `System::new_all()`, as there have been crashes related to it
             let refresh_kind = RefreshKind::new()
                 .with_memory() // For memory usage
@@ -252,29 +879,106 @@ impl Telemetry {
                 .with_cpu(CpuRefreshKind::everything()); // For core count
 
             let mut system = System::new_with_specifics(refresh_kind);
+            let thread_sampler = ThreadSampler::new();
 
             // Avoiding calling `refresh_all()`, just update what we need
             system.refresh_specifics(refresh_kind);
 
             // Waiting some amount of time before the first query is important to get a reasonable value
             // https://docs.rs/sysinfo/0.29.10/sysinfo/trait.ProcessExt.html#tymethod.cpu_usage
-            const DURATION_BETWEEN_SYSTEM_EVENTS: Duration = Duration::from_secs(4 * 60);
+            const DURATION_BETWEEN_SYSTEM_SAMPLES: Duration = Duration::from_secs(60);
+
+            // Samples are summarized into a single event per window, rather than sent individually
+            let mut cpu_usage = MetricSamples::default();
+            let mut memory = MetricSamples::default();
+            let mut virtual_memory = MetricSamples::default();
+            let mut window_started_at = Instant::now();
+            let mut memory_pressure_monitor = MemoryPressureMonitor::default();
 
             loop {
-                smol::Timer::after(DURATION_BETWEEN_SYSTEM_EVENTS).await;
+                smol::Timer::after(DURATION_BETWEEN_SYSTEM_SAMPLES).await;
 
                 system.refresh_specifics(refresh_kind);
 
                 let current_process = Pid::from_u32(std::process::id());
                 let Some(process) = system.processes().get(&current_process) else {
                     let process = current_process;
-                    log::error!("Failed to find own process {process:?} in system process table");
-                    // TODO: Fire an error telemetry event
+                    let message =
+                        format!("Failed to find own process {process:?} in system process table");
+                    log::error!("{message}");
+                    this.report_error(ErrorSeverity::Error, message, "system metrics");
                     return;
                 };
 
-                this.report_memory_event(process.memory(), process.virtual_memory());
-                this.report_cpu_event(process.cpu_usage(), system.cpus().len() as u32);
+                cpu_usage.record(process.cpu_usage() as f64);
+                memory.record(process.memory() as f64);
+                virtual_memory.record(process.virtual_memory() as f64);
+                this.check_language_server_memory(&system);
+                this.update_sentinel(process.memory(), process.cpu_usage());
+
+                let thresholds = this.state.lock().settings.memory_pressure_thresholds();
+                let triggers = memory_pressure_monitor.check(
+                    process.memory(),
+                    thresholds,
+                    memory_pressure::os_memory_pressure(),
+                );
+                if !triggers.is_empty() {
+                    let cache_sizes = cx
+                        .update(|cx| memory_pressure::cache_sizes(cx))
+                        .unwrap_or_default();
+                    for (trigger, threshold_in_bytes) in triggers {
+                        this.report_memory_pressure_event(
+                            trigger,
+                            process.memory(),
+                            threshold_in_bytes,
+                            cache_sizes.clone(),
+                        );
+                    }
+                }
+
+                let window = this.state.lock().settings.system_metrics_window();
+                if window_started_at.elapsed() < window {
+                    continue;
+                }
+                window_started_at = Instant::now();
+
+                this.report_session_event();
+                let sample_count = cpu_usage.len();
+                if let Some(usage_as_percentage) = cpu_usage.take_summary() {
+                    this.report_cpu_event(
+                        sample_count,
+                        usage_as_percentage,
+                        thread_sampler.sample(process),
+                    );
+                }
+                let sample_count = memory.len();
+                if let Some((memory_in_bytes, virtual_memory_in_bytes)) =
+                    memory.take_summary().zip(virtual_memory.take_summary())
+                {
+                    this.report_memory_event(
+                        sample_count,
+                        memory_in_bytes,
+                        virtual_memory_in_bytes,
+                    );
+                }
+
+                if let Some(usage_as_percentage) = gpu::utilization().await {
+                    this.report_gpu_event(usage_as_percentage);
+                }
+                if let Some(power_state) = power::state().await {
+                    this.report_power_event(
+                        power_state.on_battery,
+                        power_state.battery_percentage,
+                        power_state.cpu_speed_limit,
+                    );
+                }
+                if let Ok(Some(frame_timings)) = cx.update(|cx| cx.take_frame_timings()) {
+                    this.report_frame_time_event(frame_timings);
+                }
+                if let Ok(Some(input_latency)) = cx.update(|cx| cx.take_input_latency()) {
+                    this.report_input_latency_event(input_latency);
+                }
+                this.report_network_request_events();
             }
         })
         .detach();
@@ -297,24 +1001,13 @@ impl Telemetry {
         drop(state);
     }
 
-    pub fn report_editor_event(
-        self: &Arc<Self>,
-        file_extension: Option<String>,
-        vim_mode: bool,
-        operation: &'static str,
-        copilot_enabled: bool,
-        copilot_enabled_for_language: bool,
-    ) {
-        let event = Event::Editor {
-            file_extension,
-            vim_mode,
-            operation,
-            copilot_enabled,
-            copilot_enabled_for_language,
-            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
-        };
-
-        self.report_event(event)
+    /// Starts building an editor event for `operation`, which is reported once sent:
+    ///
+    /// ```ignore
+    /// telemetry.editor_event("save").file_extension(extension).vim_mode(true).send();
+    /// ```
+    pub fn editor_event(self: &Arc<Self>, operation: &'static str) -> EditorEventBuilder {
+        EditorEventBuilder::new(self.clone(), operation)
     }
 
     pub fn report_copilot_event(
@@ -326,7 +1019,7 @@ impl Telemetry {
         let event = Event::Copilot {
             suggestion_id,
             suggestion_accepted,
-            file_extension,
+            file_extension: file_extension::redact(file_extension),
             milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
         };
 
@@ -365,10 +1058,16 @@ impl Telemetry {
         self.report_event(event)
     }
 
-    pub fn report_cpu_event(self: &Arc<Self>, usage_as_percentage: f32, core_count: u32) {
+    pub fn report_cpu_event(
+        self: &Arc<Self>,
+        sample_count: usize,
+        usage_as_percentage: MetricSummary,
+        threads: Vec<ThreadGroupUsage>,
+    ) {
         let event = Event::Cpu {
+            sample_count,
             usage_as_percentage,
-            core_count,
+            threads,
             milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
         };
 
@@ -377,10 +1076,12 @@ impl Telemetry {
 
     pub fn report_memory_event(
         self: &Arc<Self>,
-        memory_in_bytes: u64,
-        virtual_memory_in_bytes: u64,
+        sample_count: usize,
+        memory_in_bytes: MetricSummary,
+        virtual_memory_in_bytes: MetricSummary,
     ) {
         let event = Event::Memory {
+            sample_count,
             memory_in_bytes,
             virtual_memory_in_bytes,
             milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
@@ -389,64 +1090,404 @@ impl Telemetry {
         self.report_event(event)
     }
 
-    pub fn report_app_event(self: &Arc<Self>, operation: String) {
-        self.report_app_event_with_date_time(operation, Utc::now());
-    }
-
-    fn report_app_event_with_date_time(
+    fn report_memory_pressure_event(
         self: &Arc<Self>,
-        operation: String,
-        date_time: DateTime<Utc>,
-    ) -> Event {
-        let event = Event::App {
-            operation,
-            milliseconds_since_first_event: self.milliseconds_since_first_event(date_time),
+        trigger: MemoryPressureTrigger,
+        memory_in_bytes: u64,
+        threshold_in_bytes: Option<u64>,
+        cache_sizes_in_bytes: BTreeMap<&'static str, u64>,
+    ) {
+        let event = Event::MemoryPressure {
+            trigger,
+            memory_in_bytes,
+            threshold_in_bytes,
+            cache_sizes_in_bytes,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
         };
 
-        self.report_event(event.clone());
-
-        event
+        self.report_event(event)
     }
 
-    pub fn report_setting_event(self: &Arc<Self>, setting: &'static str, value: String) {
-        let event = Event::Setting {
-            setting,
-            value,
+    pub fn report_gpu_event(self: &Arc<Self>, usage_as_percentage: f32) {
+        let event = Event::Gpu {
+            usage_as_percentage,
             milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
         };
 
         self.report_event(event)
     }
 
-    pub fn log_edit_event(self: &Arc<Self>, environment: &'static str) {
-        let mut state = self.state.lock();
-        let period_data = state.event_coalescer.log_event(environment);
-        drop(state);
-
-        if let Some((start, end, environment)) = period_data {
-            let event = Event::Edit {
-                duration: end.timestamp_millis() - start.timestamp_millis(),
-                environment,
-                milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
-            };
+    pub fn report_power_event(
+        self: &Arc<Self>,
+        on_battery: bool,
+        battery_percentage: f32,
+        cpu_speed_limit: Option<u32>,
+    ) {
+        let event = Event::Power {
+            on_battery,
+            battery_percentage,
+            cpu_speed_limit,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
 
-            self.report_event(event);
-        }
+        self.report_event(event)
     }
 
-    pub fn report_action_event(self: &Arc<Self>, source: &'static str, action: String) {
-        let event = Event::Action {
-            source,
-            action,
+    pub fn report_frame_time_event(self: &Arc<Self>, frame_timings: FrameTimingsSummary) {
+        let event = Event::FrameTime {
+            frame_count: frame_timings.frame_count,
+            mean_in_microseconds: frame_timings.mean.as_micros() as u64,
+            p50_in_microseconds: frame_timings.p50.as_micros() as u64,
+            p95_in_microseconds: frame_timings.p95.as_micros() as u64,
+            max_in_microseconds: frame_timings.max.as_micros() as u64,
             milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
         };
 
         self.report_event(event)
     }
 
-    fn milliseconds_since_first_event(self: &Arc<Self>, date_time: DateTime<Utc>) -> i64 {
-        let mut state = self.state.lock();
-
+    /// Records how long a named operation took, e.g. loading settings or opening the first window.
+    pub fn report_timing(self: &Arc<Self>, name: &'static str, duration: Duration) {
+        let event = Event::Timing {
+            name,
+            duration_in_milliseconds: duration.as_millis() as u64,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    /// The time elapsed since telemetry was created, which happens early during app startup.
+    pub fn time_since_launch(&self) -> Duration {
+        self.state.lock().launched_at.elapsed()
+    }
+
+    pub fn report_input_latency_event(self: &Arc<Self>, input_latency: InputLatencySummary) {
+        let event = Event::InputLatency {
+            sample_count: input_latency.sample_count,
+            bucket_counts: input_latency.bucket_counts,
+            p50_in_milliseconds: input_latency.p50.as_millis() as u64,
+            p95_in_milliseconds: input_latency.p95.as_millis() as u64,
+            p99_in_milliseconds: input_latency.p99.as_millis() as u64,
+            max_in_milliseconds: input_latency.max.as_millis() as u64,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    fn report_network_request_events(self: &Arc<Self>) {
+        let hosts = self.state.lock().network_requests.take();
+        for (host, mut requests) in hosts {
+            let Some(latency_in_milliseconds) = requests.latency_in_milliseconds.take_summary()
+            else {
+                continue;
+            };
+            let event = Event::NetworkRequests {
+                host,
+                request_count: requests.request_count,
+                retry_count: requests.retry_count,
+                error_count: requests.error_count,
+                status_counts: requests.status_counts,
+                latency_in_milliseconds,
+                milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+            };
+
+            self.report_event(event)
+        }
+    }
+
+    /// Records a usage event on behalf of an extension, unless the user has opted out of
+    /// usage data for it. Events are namespaced under the extension's id and only keep
+    /// scalar properties, so extensions can't report core events or nest arbitrary data.
+    pub fn report_extension_event(
+        self: &Arc<Self>,
+        extension_id: &str,
+        name: &str,
+        properties: Map<String, Value>,
+    ) {
+        if !self
+            .state
+            .lock()
+            .settings
+            .is_extension_enabled(extension_id)
+        {
+            return;
+        }
+
+        let properties = properties
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let value = match value {
+                    Value::String(value) => {
+                        Value::String(value.chars().take(MAX_EXTENSION_EVENT_STRING_LEN).collect())
+                    }
+                    Value::Bool(_) | Value::Number(_) => value,
+                    Value::Null | Value::Array(_) | Value::Object(_) => return None,
+                };
+                Some((key, value))
+            })
+            .take(MAX_EXTENSION_EVENT_PROPERTIES)
+            .collect();
+
+        let event = Event::Extension {
+            extension_id: extension_id.to_string(),
+            name: name.to_string(),
+            properties,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    pub fn report_language_server_event(
+        self: &Arc<Self>,
+        server_name: &str,
+        server_version: Option<&str>,
+        kind: LanguageServerFailureKind,
+    ) {
+        let event = Event::LanguageServer {
+            server_name: server_name.to_string(),
+            server_version: server_version.map(str::to_string),
+            kind,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    /// Reports the language server running in the given process if it ever uses more than
+    /// [`LANGUAGE_SERVER_MEMORY_THRESHOLD`]. Processes are forgotten once they exit.
+    pub fn watch_language_server_memory(
+        &self,
+        process_id: u32,
+        server_name: &str,
+        server_version: Option<&str>,
+    ) {
+        self.state.lock().language_server_processes.insert(
+            process_id,
+            LanguageServerProcess {
+                server_name: server_name.to_string(),
+                server_version: server_version.map(str::to_string),
+            },
+        );
+    }
+
+    fn check_language_server_memory(self: &Arc<Self>, system: &System) {
+        let mut exceeded = Vec::new();
+        self.state
+            .lock()
+            .language_server_processes
+            .retain(
+                |process_id, server| match system.process(Pid::from_u32(*process_id)) {
+                    Some(process) if process.memory() > LANGUAGE_SERVER_MEMORY_THRESHOLD => {
+                        exceeded.push((server.server_name.clone(), server.server_version.clone()));
+                        false
+                    }
+                    Some(_) => true,
+                    None => false,
+                },
+            );
+
+        for (server_name, server_version) in exceeded {
+            self.report_language_server_event(
+                &server_name,
+                server_version.as_deref(),
+                LanguageServerFailureKind::ExceededMemoryThreshold,
+            );
+        }
+    }
+
+    /// Records that the user interacted with Zed, for telling active time apart from idle time.
+    pub fn report_input_activity(&self) {
+        self.state
+            .lock()
+            .session_activity
+            .record_input(Instant::now());
+    }
+
+    /// Records whether a Zed window is focused. Time spent in other apps always counts as idle.
+    pub fn set_window_active(&self, window_active: bool) {
+        self.state
+            .lock()
+            .session_activity
+            .set_window_active(window_active, Instant::now());
+    }
+
+    fn report_session_event(self: &Arc<Self>) {
+        let summary = self
+            .state
+            .lock()
+            .session_activity
+            .take_summary(Instant::now());
+        let event = Event::Session {
+            active_duration_in_milliseconds: summary.active.as_millis() as u64,
+            idle_duration_in_milliseconds: summary.idle.as_millis() as u64,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    pub fn report_app_event(self: &Arc<Self>, operation: String) {
+        self.report_app_event_with_date_time(operation, Utc::now());
+    }
+
+    fn report_app_event_with_date_time(
+        self: &Arc<Self>,
+        operation: String,
+        date_time: DateTime<Utc>,
+    ) -> Event {
+        let event = Event::App {
+            operation,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(date_time),
+        };
+
+        self.report_event(event.clone());
+
+        event
+    }
+
+    pub fn report_setting_event(self: &Arc<Self>, setting: &'static str, value: String) {
+        let event = Event::Setting {
+            setting,
+            value,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    /// Reports that the session checked a feature flag, at most once per flag per session, so
+    /// that staged rollouts can be correlated with crash and performance data.
+    pub fn report_flag_exposure(self: &Arc<Self>, flag: &'static str, enabled: bool) {
+        if !self.state.lock().exposed_flags.insert(flag) {
+            return;
+        }
+
+        let event = Event::FlagExposure {
+            flag,
+            enabled,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    pub fn report_error(
+        self: &Arc<Self>,
+        severity: ErrorSeverity,
+        message: String,
+        context: &'static str,
+    ) {
+        let event = Event::Error {
+            severity,
+            message,
+            context,
+            backtrace: Vec::new(),
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    /// Reports panics as error events ahead of any previously installed panic hook.
+    pub fn install_panic_hook(self: &Arc<Self>) {
+        let this = Arc::downgrade(self);
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Some(this) = this.upgrade() {
+                this.report_panic(info);
+            }
+            previous_hook(info);
+        }));
+    }
+
+    fn report_panic(self: &Arc<Self>, info: &panic::PanicInfo) {
+        // The panic may have happened while the state was locked on this thread
+        let Some(mut state) = self.state.try_lock() else {
+            return;
+        };
+        if state.disabled_by_policy.is_some()
+            || state.consent == TelemetryConsent::Denied
+            || !state.settings.is_category_enabled(EventCategory::Error)
+        {
+            return;
+        }
+
+        let milliseconds_since_first_event =
+            state
+                .first_event_date_time
+                .map_or(0, |first_event_date_time| {
+                    Utc::now().timestamp_millis() - first_event_date_time.timestamp_millis()
+                });
+        let signed_in = state.metrics_id.is_some();
+        let event = EventWrapper {
+            signed_in,
+            sample_rate: None,
+            noise: None,
+            count: None,
+            event: Event::Error {
+                severity: ErrorSeverity::Panic,
+                message: panic_hook::panic_message(info),
+                context: "panic",
+                backtrace: panic_hook::capture_backtrace(),
+                milliseconds_since_first_event,
+            },
+        };
+        self.queue_for_sinks(&mut state, &event);
+        if !state.settings.local_log {
+            state.events_queue.push(event);
+        }
+        // The panic is reported above, so it shouldn't also be reported as an unexpected shutdown
+        if let Some(sentinel_path) = &state.sentinel_path {
+            sentinel::remove(sentinel_path).log_err();
+        }
+        drop(state);
+
+        // The app is about to abort, so the events can only be sent on the next launch
+        self.spool_events();
+    }
+
+    pub fn log_edit_event(self: &Arc<Self>, environment: &'static str) {
+        let mut state = self.state.lock();
+        let period_data = state.event_coalescer.log_event(environment);
+        let time_to_first_edit = if state.first_edit_reported {
+            None
+        } else {
+            state.first_edit_reported = true;
+            Some(state.launched_at.elapsed())
+        };
+        drop(state);
+
+        if let Some(time_to_first_edit) = time_to_first_edit {
+            self.report_timing("first edit", time_to_first_edit);
+        }
+
+        if let Some((start, end, environment)) = period_data {
+            let event = Event::Edit {
+                duration: end.timestamp_millis() - start.timestamp_millis(),
+                environment,
+                milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+            };
+
+            self.report_event(event);
+        }
+    }
+
+    pub fn report_action_event(self: &Arc<Self>, source: &'static str, action: String) {
+        let event = Event::Action {
+            source,
+            action,
+            milliseconds_since_first_event: self.milliseconds_since_first_event(Utc::now()),
+        };
+
+        self.report_event(event)
+    }
+
+    fn milliseconds_since_first_event(self: &Arc<Self>, date_time: DateTime<Utc>) -> i64 {
+        let mut state = self.state.lock();
+
         match state.first_event_date_time {
             Some(first_event_date_time) => {
                 date_time.timestamp_millis() - first_event_date_time.timestamp_millis()
@@ -458,10 +1499,55 @@ impl Telemetry {
         }
     }
 
-    fn report_event(self: &Arc<Self>, event: Event) {
+    fn report_event(self: &Arc<Self>, mut event: Event) {
         let mut state = self.state.lock();
 
-        if !state.settings.metrics {
+        if state.disabled_by_policy.is_some()
+            || state.consent == TelemetryConsent::Denied
+            || !state.settings.is_category_enabled(event.category())
+        {
+            return;
+        }
+
+        let deduplication = state
+            .settings
+            .deduplication_window()
+            .map(|window| (dedup::key(&event), window));
+        if let Some((key, window)) = &deduplication {
+            if state.count_duplicate(key, *window, Instant::now()) {
+                return;
+            }
+        }
+
+        let sample_rate = state.settings.sample_rate(&event);
+        if let Some(sample_rate) = sample_rate {
+            if !rand::thread_rng().gen_bool(sample_rate as f64) {
+                return;
+            }
+        }
+
+        if !state.rate_limiter.allow(event.name(), Instant::now()) {
+            return;
+        }
+
+        let noise = state
+            .settings
+            .noise_epsilon()
+            .and_then(|epsilon| noise::apply(&mut event, epsilon, &mut rand::thread_rng()));
+
+        let signed_in = state.metrics_id.is_some();
+        let event = EventWrapper {
+            signed_in,
+            sample_rate,
+            noise,
+            count: None,
+            event,
+        };
+        state.duplicate_run = deduplication.map(|(key, _)| DuplicateRun::new(key, Instant::now()));
+        self.queue_for_sinks(&mut state, &event);
+
+        // Nothing is sent to the endpoint while the local log is enabled
+        if state.settings.local_log {
             return;
         }
 
@@ -474,8 +1560,13 @@ impl Telemetry {
             }));
         }
 
-        let signed_in = state.metrics_id.is_some();
-        state.events_queue.push(EventWrapper { signed_in, event });
+        state.events_queue.push(event);
+        if state.events_queue.len() > state.max_unsent_events {
+            let excess = state.events_queue.len() - state.max_unsent_events;
+            state.events_queue.drain(..excess);
+            state.dropped_event_count += excess;
+        }
+        state.notify_inspector();
 
         if state.installation_id.is_some() {
             if state.events_queue.len() >= state.max_queue_size {
@@ -485,6 +1576,124 @@ impl Telemetry {
         }
     }
 
+    /// Delivers events to `sink` as well, for as long as it is enabled.
+    pub fn add_sink(&self, sink: Arc<dyn TelemetrySink>) {
+        self.state.lock().sinks.push(SinkQueue::new(sink));
+    }
+
+    fn queue_for_sinks(self: &Arc<Self>, state: &mut TelemetryState, event: &EventWrapper) {
+        let TelemetryState {
+            sinks, settings, ..
+        } = state;
+        for (ix, queue) in sinks.iter_mut().enumerate() {
+            if !queue.sink.is_enabled(settings) {
+                continue;
+            }
+
+            queue.events.push_back(event.clone());
+            if queue.events.len() > MAX_UNSENT_EVENTS {
+                queue.events.pop_front();
+            }
+            if queue.flush_task.is_none() {
+                let this = self.clone();
+                let executor = self.executor.clone();
+                let flush_interval = queue.sink.flush_interval();
+                queue.flush_task = Some(self.executor.spawn(async move {
+                    executor.timer(flush_interval).await;
+                    this.flush_sink(ix);
+                }));
+            }
+        }
+    }
+
+    fn flush_sinks(self: &Arc<Self>) {
+        let sink_count = self.state.lock().sinks.len();
+        for ix in 0..sink_count {
+            self.flush_sink(ix);
+        }
+    }
+
+    fn flush_sink(self: &Arc<Self>, ix: usize) {
+        let mut state = self.state.lock();
+        let Some(queue) = state.sinks.get_mut(ix) else {
+            return;
+        };
+        queue.flush_task.take();
+        let events = Vec::from(mem::take(&mut queue.events));
+        let sink = queue.sink.clone();
+        if events.is_empty() {
+            return;
+        }
+
+        let Some(request_body) = serde_json::to_value(state.request_body(events)).log_err() else {
+            return;
+        };
+        let delivery = sink.deliver(request_body, &state.settings);
+        drop(state);
+
+        let this = self.clone();
+        self.executor
+            .spawn(async move {
+                if let Err(error) = delivery.await {
+                    let message = format!("telemetry {} failed: {error}", sink.name());
+                    log::warn!("{message}");
+                    this.state.lock().record_flush_error(message);
+                }
+            })
+            .detach();
+    }
+
+    /// Returns why telemetry has been disabled by the system or an administrator, overriding
+    /// the user's settings, if it has.
+    pub fn disabled_by_policy(&self) -> Option<Arc<str>> {
+        self.state.lock().disabled_by_policy.clone()
+    }
+
+    pub fn consent(&self) -> TelemetryConsent {
+        self.state.lock().consent
+    }
+
+    /// Records the user's answer to the consent prompt, e.g. from the welcome page.
+    ///
+    /// Granting consent sends the events spooled while unasked, whereas denying it
+    /// discards them along with any queued events.
+    pub fn set_consent(self: &Arc<Self>, consent: TelemetryConsent) {
+        let mut state = self.state.lock();
+        if state.consent == consent {
+            return;
+        }
+        state.consent = consent;
+        match consent {
+            TelemetryConsent::Unasked => {}
+            TelemetryConsent::Granted => {
+                drop(state);
+                self.replay_spooled_events();
+                self.flush_events();
+            }
+            TelemetryConsent::Denied => {
+                state.events_queue.clear();
+                state.flush_events_task.take();
+                state.dropped_event_count = 0;
+                state.rate_limiter.take_shed_counts();
+                state.notify_inspector();
+                let spool_path = state.spool_path.clone();
+                drop(state);
+
+                if let Some(spool_path) = spool_path {
+                    self.executor
+                        .spawn(async move { spool::take(&spool_path).log_err() })
+                        .detach();
+                }
+            }
+        }
+
+        if !cfg!(any(test, feature = "test-support")) {
+            self.executor
+                .spawn(async move { consent::save(consent).await.log_err() })
+                .detach();
+        }
+    }
+
     pub fn metrics_id(self: &Arc<Self>) -> Option<Arc<str>> {
         self.state.lock().metrics_id.clone()
     }
@@ -497,15 +1706,72 @@ impl Telemetry {
         self.state.lock().is_staff
     }
 
+    pub fn inspector_snapshot(&self) -> InspectorSnapshot {
+        let state = self.state.lock();
+        InspectorSnapshot {
+            queued_events: state
+                .events_queue
+                .iter()
+                .filter_map(|event| serde_json::to_string(event).log_err())
+                .collect(),
+            flushed_batches: state.flushed_batches.iter().cloned().collect(),
+            flush_errors: state.flush_errors.iter().cloned().collect(),
+        }
+    }
+
+    /// Yields whenever the contents of the [`InspectorSnapshot`] may have changed.
+    pub fn inspector_changes(&self) -> watch::Receiver<()> {
+        self.state.lock().inspector_changes.1.clone()
+    }
+
     pub fn flush_events(self: &Arc<Self>) {
         let mut state = self.state.lock();
-        state.first_event_date_time = None;
+        if state.consent == TelemetryConsent::Unasked {
+            // Keep the events until the user answers, without holding them all in memory
+            drop(state);
+            self.spool_events();
+            return;
+        }
         let mut events = mem::take(&mut state.events_queue);
+        let signed_in = state.metrics_id.is_some();
+        let milliseconds_since_first_event =
+            state
+                .first_event_date_time
+                .map_or(0, |first_event_date_time| {
+                    Utc::now().timestamp_millis() - first_event_date_time.timestamp_millis()
+                });
+        if state.dropped_event_count > 0 {
+            events.push(EventWrapper {
+                signed_in,
+                sample_rate: None,
+                noise: None,
+                count: None,
+                event: Event::DroppedEvents {
+                    count: mem::take(&mut state.dropped_event_count),
+                    milliseconds_since_first_event,
+                },
+            });
+        }
+        let shed_counts = state.rate_limiter.take_shed_counts();
+        for (&event_type, &count) in &shed_counts {
+            events.push(EventWrapper {
+                signed_in,
+                sample_rate: None,
+                noise: None,
+                count: None,
+                event: Event::RateLimitedEvents {
+                    event_type,
+                    count,
+                    milliseconds_since_first_event,
+                },
+            });
+        }
+        state.first_event_date_time = None;
         state.flush_events_task.take();
-        drop(state);
         if events.is_empty() {
             return;
         }
+        drop(state);
 
         let this = self.clone();
         self.executor
@@ -524,45 +1790,349 @@ impl Telemetry {
                     }
 
                     {
-                        let state = this.state.lock();
-                        let request_body = EventRequestBody {
-                            installation_id: state.installation_id.clone(),
-                            session_id: state.session_id.clone(),
-                            is_staff: state.is_staff.clone(),
-                            app_version: state
-                                .app_metadata
-                                .app_version
-                                .map(|version| version.to_string()),
-                            os_name: state.app_metadata.os_name,
-                            os_version: state
-                                .app_metadata
-                                .os_version
-                                .map(|version| version.to_string()),
-                            architecture: state.architecture,
-
-                            release_channel: state.release_channel,
-                            events,
-                        };
+                        let mut state = this.state.lock();
+                        let request_body = state.request_body(events);
                         json_bytes.clear();
                         serde_json::to_writer(&mut json_bytes, &request_body)?;
+                        state.record_flushed_batch(FlushedBatch {
+                            flushed_at: Utc::now(),
+                            request_body: String::from_utf8_lossy(&json_bytes).into_owned(),
+                        });
                     }
 
-                    this.http_client
-                        .post_json(EVENTS_URL.as_str(), json_bytes.into())
-                        .await?;
+                    if !this.post_events_with_retry(json_bytes).await {
+                        // Report the shed events with a later batch instead
+                        this.state
+                            .lock()
+                            .rate_limiter
+                            .restore_shed_counts(shed_counts);
+                    }
                     anyhow::Ok(())
                 }
                 .log_err(),
             )
             .detach();
     }
+
+    /// Returns whether the batch was accepted by the server.
+    async fn post_events_with_retry(self: &Arc<Self>, json_bytes: Vec<u8>) -> bool {
+        let mut attempt = 0;
+        loop {
+            let gzip_enabled = self.state.lock().gzip_enabled;
+            match self.post_events(&json_bytes, gzip_enabled, attempt).await {
+                Ok(response) if response.status().is_success() => {
+                    // Servers opt into compressed payloads by advertising it on any response.
+                    let server_accepts_gzip = response
+                        .headers()
+                        .get(ACCEPT_ENCODING_HEADER)
+                        .and_then(|value| value.to_str().ok())
+                        .map_or(false, |value| value.contains("gzip"));
+                    self.state.lock().gzip_enabled = server_accepts_gzip;
+                    self.record_sent_batch(&json_bytes);
+                    return true;
+                }
+                Ok(response)
+                    if gzip_enabled && response.status() == StatusCode::UNSUPPORTED_MEDIA_TYPE =>
+                {
+                    // The server no longer accepts compressed payloads, resend uncompressed.
+                    self.state.lock().gzip_enabled = false;
+                    continue;
+                }
+                Ok(response) => {
+                    let message =
+                        format!("telemetry request failed with status {}", response.status());
+                    log::warn!("{message}");
+                    self.state.lock().record_flush_error(message);
+                }
+                Err(error) => {
+                    let message = format!("telemetry request failed: {error}");
+                    log::warn!("{message}");
+                    self.state.lock().record_flush_error(message);
+                }
+            }
+
+            attempt += 1;
+            if attempt >= MAX_FLUSH_ATTEMPTS {
+                log::error!("discarding telemetry batch after {attempt} failed attempts");
+                return false;
+            }
+
+            self.executor
+                .timer(retry_delay(INITIAL_RETRY_DELAY, MAX_RETRY_DELAY, attempt))
+                .await;
+        }
+    }
+
+    /// Counts a batch that the server accepted in the [`SentSummary`], saving it to disk.
+    /// Only called from background tasks, so the summary is written synchronously.
+    fn record_sent_batch(&self, json_bytes: &[u8]) {
+        let mut state = self.state.lock();
+        state.sent_summary.record_batch(json_bytes, Utc::now());
+        state.notify_inspector();
+        let Some(sent_summary_path) = state.sent_summary_path.clone() else {
+            return;
+        };
+        let sent_summary = state.sent_summary.clone();
+        drop(state);
+
+        sent_summary::save(&sent_summary_path, &sent_summary).log_err();
+    }
+
+    /// Returns a tally of everything the server has accepted from this installation.
+    pub fn sent_summary(&self) -> SentSummary {
+        self.state.lock().sent_summary.clone()
+    }
+
+    /// Sends a single synthetic ping event to the configured endpoint, bypassing the queue,
+    /// and reports how the request went. Failures are also shown in the telemetry inspector.
+    pub fn test_connectivity(self: &Arc<Self>) -> Task<ConnectivityReport> {
+        let (url, json_bytes) = {
+            let mut state = self.state.lock();
+            let url = state.events_url();
+            if let Some(reason) = &state.disabled_by_policy {
+                return Task::ready(ConnectivityReport {
+                    url,
+                    latency: Duration::ZERO,
+                    result: Err(reason.to_string()),
+                });
+            }
+            if state.consent != TelemetryConsent::Granted {
+                return Task::ready(ConnectivityReport {
+                    url,
+                    latency: Duration::ZERO,
+                    result: Err("nothing is sent without consent to send telemetry".into()),
+                });
+            }
+            if state.settings.local_log {
+                return Task::ready(ConnectivityReport {
+                    url,
+                    latency: Duration::ZERO,
+                    result: Err(
+                        "nothing is sent over the network while `telemetry.local_log` is enabled"
+                            .into(),
+                    ),
+                });
+            }
+
+            let request_body = state.request_body(vec![EventWrapper {
+                signed_in: state.metrics_id.is_some(),
+                sample_rate: None,
+                noise: None,
+                count: None,
+                event: Event::Ping {
+                    milliseconds_since_first_event: 0,
+                },
+            }]);
+            let json_bytes = match serde_json::to_vec(&request_body) {
+                Ok(json_bytes) => json_bytes,
+                Err(error) => {
+                    return Task::ready(ConnectivityReport {
+                        url,
+                        latency: Duration::ZERO,
+                        result: Err(error.to_string()),
+                    })
+                }
+            };
+            state.record_flushed_batch(FlushedBatch {
+                flushed_at: Utc::now(),
+                request_body: String::from_utf8_lossy(&json_bytes).into_owned(),
+            });
+            (url, json_bytes)
+        };
+
+        let this = self.clone();
+        self.executor.spawn(async move {
+            let started_at = Instant::now();
+            let gzip_enabled = this.state.lock().gzip_enabled;
+            let result = match this.post_events(&json_bytes, gzip_enabled, 0).await {
+                Ok(response) => Ok(response.status()),
+                // Include the whole error chain, which is where TLS and proxy errors end up
+                Err(error) => Err(format!("{error:#}")),
+            };
+            let report = ConnectivityReport {
+                url,
+                latency: started_at.elapsed(),
+                result,
+            };
+
+            if !report.is_success() {
+                let message = match &report.result {
+                    Ok(status) => format!("telemetry test failed with status {status}"),
+                    Err(error) => format!("telemetry test failed: {error}"),
+                };
+                log::warn!("{message}");
+                this.state.lock().record_flush_error(message);
+            }
+            report
+        })
+    }
+
+    /// Posts a request body to the events endpoint. `retry_attempt` is how many times the
+    /// body failed to send before, for the network request telemetry.
+    async fn post_events(
+        &self,
+        json_bytes: &[u8],
+        gzip: bool,
+        retry_attempt: u32,
+    ) -> Result<Response<AsyncBody>> {
+        // Resolved on every request, so that changes to the endpoint setting apply immediately.
+        let (events_url, exporter) = {
+            let state = self.state.lock();
+            (state.events_url(), state.settings.exporter)
+        };
+        let otlp_json_bytes;
+        let json_bytes = match exporter {
+            TelemetryExporter::Zed => json_bytes,
+            TelemetryExporter::Otlp => {
+                let request_body = serde_json::from_slice(json_bytes)?;
+                otlp_json_bytes =
+                    serde_json::to_vec(&otlp::logs_request(request_body, Utc::now()))?;
+                otlp_json_bytes.as_slice()
+            }
+        };
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(events_url)
+            .header("Content-Type", "application/json")
+            .extension(RetryAttempt(retry_attempt));
+        let request = if gzip {
+            let mut compressed_bytes = Vec::new();
+            GzipEncoder::new(json_bytes)
+                .read_to_end(&mut compressed_bytes)
+                .await?;
+            request
+                .header("Content-Encoding", "gzip")
+                .body(compressed_bytes.into())?
+        } else {
+            request.body(json_bytes.to_vec().into())?
+        };
+        Ok(self.http_client.send(request).await?)
+    }
+}
+
+impl HttpObserver for Telemetry {
+    fn request_completed(&self, outcome: RequestOutcome) {
+        let mut state = self.state.lock();
+        if state.settings.is_category_enabled(EventCategory::System) {
+            state.network_requests.record(outcome);
+        }
+    }
+}
+
+impl SinkQueue {
+    fn new(sink: Arc<dyn TelemetrySink>) -> Self {
+        Self {
+            sink,
+            events: VecDeque::new(),
+            flush_task: None,
+        }
+    }
+}
+
+impl TelemetryState {
+    fn notify_inspector(&mut self) {
+        *self.inspector_changes.0.borrow_mut() = ();
+    }
+
+    fn record_flushed_batch(&mut self, batch: FlushedBatch) {
+        if self.flushed_batches.len() >= MAX_INSPECTOR_ENTRIES {
+            self.flushed_batches.pop_front();
+        }
+        self.flushed_batches.push_back(batch);
+        self.notify_inspector();
+    }
+
+    fn record_flush_error(&mut self, error: String) {
+        if self.flush_errors.len() >= MAX_INSPECTOR_ENTRIES {
+            self.flush_errors.pop_front();
+        }
+        self.flush_errors.push_back(error);
+        self.notify_inspector();
+    }
+
+    /// Counts an event against the last queued event instead of queueing it again, if the
+    /// two are identical and the run of duplicates started within `window`.
+    fn count_duplicate(&mut self, key: &Value, window: Duration, now: Instant) -> bool {
+        if !self
+            .duplicate_run
+            .as_ref()
+            .map_or(false, |run| run.is_continued_by(key, window, now))
+        {
+            return false;
+        }
+
+        let TelemetryState {
+            events_queue,
+            sinks,
+            settings,
+            ..
+        } = self;
+        let mut queued_last_events = sinks
+            .iter_mut()
+            .filter(|queue| queue.sink.is_enabled(settings))
+            .map(|queue| queue.events.back_mut())
+            .collect::<Vec<_>>();
+        if !settings.local_log {
+            queued_last_events.push(events_queue.last_mut());
+        }
+
+        // Once the first event of the run has been flushed from any queue, a new run starts
+        let mut last_events = Vec::new();
+        for last in queued_last_events {
+            match last {
+                Some(last) if dedup::key(&last.event) == *key => last_events.push(last),
+                _ => return false,
+            }
+        }
+        if last_events.is_empty() {
+            return false;
+        }
+
+        for last in last_events {
+            last.count = Some(last.count.unwrap_or(1) + 1);
+        }
+        self.notify_inspector();
+        true
+    }
+
+    fn events_url(&self) -> String {
+        if let Some(endpoint_url) = &self.settings.endpoint_url {
+            return endpoint_url.clone();
+        }
+
+        match self.settings.exporter {
+            TelemetryExporter::Zed => format!("{}{}", *ZED_SERVER_URL, EVENTS_URL_PATH),
+            TelemetryExporter::Otlp => otlp::DEFAULT_LOGS_URL.to_string(),
+        }
+    }
+
+    fn request_body(&self, events: Vec<EventWrapper>) -> EventRequestBody {
+        EventRequestBody {
+            schema_version: schema::CURRENT_VERSION,
+            installation_id: self.installation_id.clone(),
+            session_id: self.session_id.clone(),
+            is_staff: self.is_staff.clone(),
+            app_version: self
+                .app_metadata
+                .app_version
+                .map(|version| version.to_string()),
+            release_channel: self.release_channel,
+            system_specs: self.system_specs.clone(),
+            events,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{TelemetryCategoriesContent, TelemetrySampleRatesContent};
     use chrono::TimeZone;
     use gpui::TestAppContext;
+    use std::{
+        collections::BTreeMap,
+        sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    };
     use util::http::FakeHttpClient;
 
     #[gpui::test]
@@ -576,7 +2146,7 @@ mod tests {
             let telemetry = Telemetry::new(http, cx);
 
             telemetry.state.lock().max_queue_size = 4;
-            telemetry.start(installation_id, session_id, cx);
+            telemetry.start(installation_id, session_id, TelemetryConsent::Granted, cx);
 
             assert!(is_empty_state(&telemetry));
 
@@ -659,7 +2229,7 @@ mod tests {
         cx.update(|cx| {
             let telemetry = Telemetry::new(http, cx);
             telemetry.state.lock().max_queue_size = 4;
-            telemetry.start(installation_id, session_id, cx);
+            telemetry.start(installation_id, session_id, TelemetryConsent::Granted, cx);
 
             assert!(is_empty_state(&telemetry));
 
@@ -696,6 +2266,699 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_flush_retries_failed_requests(
+        executor: BackgroundExecutor,
+        cx: &mut TestAppContext,
+    ) {
+        init_test(cx);
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let http = FakeHttpClient::create({
+            let request_count = request_count.clone();
+            move |_| {
+                let attempt = request_count.fetch_add(1, SeqCst);
+                async move {
+                    Ok(Response::builder()
+                        .status(if attempt < 2 { 500 } else { 200 })
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+
+        telemetry.report_app_event("test".to_string());
+        telemetry.flush_events();
+        executor.run_until_parked();
+        assert_eq!(request_count.load(SeqCst), 1);
+
+        // Each retry waits at most 1.5x the maximum delay
+        for expected_request_count in 2..=3 {
+            executor.advance_clock(MAX_RETRY_DELAY * 2);
+            assert_eq!(request_count.load(SeqCst), expected_request_count);
+        }
+
+        // The third attempt succeeded, so no more requests are made
+        executor.advance_clock(MAX_RETRY_DELAY * 2);
+        assert_eq!(request_count.load(SeqCst), 3);
+    }
+
+    #[gpui::test]
+    fn test_spooled_events_are_replayed_on_start(cx: &mut TestAppContext) {
+        init_test(cx);
+        let spool_dir = tempfile::tempdir().unwrap();
+        let spool_path = spool_dir.path().join("spool.ndjson");
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let http = FakeHttpClient::create({
+            let request_count = request_count.clone();
+            move |_| {
+                request_count.fetch_add(1, SeqCst);
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+
+        cx.update(|cx| {
+            let telemetry = Telemetry::new(http.clone(), cx);
+            telemetry.state.lock().spool_path = Some(spool_path.clone());
+            telemetry.report_app_event("test".to_string());
+            telemetry.spool_events();
+            assert!(is_empty_state(&telemetry));
+        });
+        cx.run_until_parked();
+        assert_eq!(request_count.load(SeqCst), 0);
+        assert!(spool_path.exists());
+
+        cx.update(|cx| {
+            let telemetry = Telemetry::new(http, cx);
+            telemetry.state.lock().spool_path = Some(spool_path.clone());
+            telemetry.start(
+                Some("installation_id".to_string()),
+                "session_id".to_string(),
+                TelemetryConsent::Granted,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        assert_eq!(request_count.load(SeqCst), 1);
+        assert!(!spool_path.exists());
+    }
+
+    #[gpui::test]
+    fn test_unexpected_shutdown_is_reported_on_start(cx: &mut TestAppContext) {
+        init_test(cx);
+        let sentinel_dir = tempfile::tempdir().unwrap();
+        let sentinel_path = sentinel_dir.path().join("running.json");
+        let launched_at = Utc.with_ymd_and_hms(1990, 4, 12, 12, 0, 0).unwrap();
+        sentinel::write(
+            &sentinel_path,
+            &Sentinel {
+                launched_at,
+                updated_at: launched_at + chrono::Duration::minutes(5),
+                memory_in_bytes: Some(2048),
+                cpu_usage_as_percentage: Some(12.5),
+            },
+        )
+        .unwrap();
+
+        let telemetry = cx.update(|cx| {
+            let telemetry = Telemetry::new(FakeHttpClient::with_200_response(), cx);
+            telemetry.state.lock().sentinel_path = Some(sentinel_path.clone());
+            telemetry.start(
+                None,
+                "session_id".to_string(),
+                TelemetryConsent::Granted,
+                cx,
+            );
+            telemetry
+        });
+        cx.run_until_parked();
+
+        assert!(matches!(
+            telemetry.state.lock().events_queue[..],
+            [EventWrapper {
+                event: Event::UnexpectedShutdown {
+                    uptime_in_milliseconds: 300_000,
+                    memory_in_bytes: Some(2048),
+                    ..
+                },
+                ..
+            }]
+        ));
+        // This session's sentinel is removed when it shuts down
+        assert!(sentinel_path.exists());
+        telemetry.flush_events_before_shutdown().detach();
+        assert!(!sentinel_path.exists());
+    }
+
+    #[gpui::test]
+    fn test_gzip_is_negotiated_with_server(cx: &mut TestAppContext) {
+        init_test(cx);
+        let content_encodings = Arc::new(Mutex::new(Vec::new()));
+        let http = FakeHttpClient::create({
+            let content_encodings = content_encodings.clone();
+            move |request| {
+                content_encodings.lock().push(
+                    request
+                        .headers()
+                        .get("Content-Encoding")
+                        .map(|value| value.to_str().unwrap().to_string()),
+                );
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .header(ACCEPT_ENCODING_HEADER, "gzip")
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+        for _ in 0..2 {
+            telemetry.report_app_event("test".to_string());
+            telemetry.flush_events();
+            cx.run_until_parked();
+        }
+
+        assert_eq!(
+            *content_encodings.lock(),
+            vec![None, Some("gzip".to_string())]
+        );
+    }
+
+    #[gpui::test]
+    fn test_endpoint_url_setting(cx: &mut TestAppContext) {
+        init_test(cx);
+        let request_uris = Arc::new(Mutex::new(Vec::new()));
+        let http = FakeHttpClient::create({
+            let request_uris = request_uris.clone();
+            move |request| {
+                request_uris.lock().push(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+        telemetry.report_app_event("test".to_string());
+        telemetry.flush_events();
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.endpoint_url = Some("https://collector.example.com/events".into());
+                });
+            });
+        });
+        telemetry.report_app_event("test".to_string());
+        telemetry.flush_events();
+        cx.run_until_parked();
+
+        assert_eq!(
+            *request_uris.lock(),
+            vec![
+                format!("{}{}", *ZED_SERVER_URL, EVENTS_URL_PATH),
+                "https://collector.example.com/events".to_string()
+            ]
+        );
+    }
+
+    #[gpui::test]
+    fn test_disabled_categories_are_not_queued(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.categories = Some(TelemetryCategoriesContent {
+                        call: Some(false),
+                        ..Default::default()
+                    });
+                });
+            });
+
+            let telemetry = Telemetry::new(FakeHttpClient::with_200_response(), cx);
+            telemetry.report_call_event("join", Some(1), None);
+            assert!(telemetry.state.lock().events_queue.is_empty());
+
+            telemetry.report_app_event("test".to_string());
+            assert_eq!(telemetry.state.lock().events_queue.len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    fn test_periodic_events_are_sampled(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.sample_rates = Some(TelemetrySampleRatesContent {
+                        cpu: Some(0.),
+                        memory: Some(1.),
+                    });
+                });
+            });
+
+            let telemetry = Telemetry::new(FakeHttpClient::with_200_response(), cx);
+            let summary = |value| MetricSummary {
+                min: value,
+                avg: value,
+                max: value,
+                p95: value,
+            };
+            telemetry.report_cpu_event(1, summary(50.), Vec::new());
+            assert!(telemetry.state.lock().events_queue.is_empty());
+
+            telemetry.report_memory_event(1, summary(1024.), summary(2048.));
+            telemetry.report_app_event("test".to_string());
+            let state = telemetry.state.lock();
+            let sample_rates = state
+                .events_queue
+                .iter()
+                .map(|event| event.sample_rate)
+                .collect::<Vec<_>>();
+            assert_eq!(sample_rates, vec![Some(1.), None]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_inspector_snapshot(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_404_response(), cx));
+
+        telemetry.report_app_event("test".to_string());
+        let snapshot = telemetry.inspector_snapshot();
+        assert_eq!(snapshot.queued_events.len(), 1);
+        assert!(snapshot.flushed_batches.is_empty());
+
+        telemetry.flush_events();
+        cx.run_until_parked();
+        let snapshot = telemetry.inspector_snapshot();
+        assert!(snapshot.queued_events.is_empty());
+        assert_eq!(snapshot.flushed_batches.len(), 1);
+        assert!(snapshot.flushed_batches[0]
+            .request_body
+            .contains(r#""operation":"test""#));
+        assert_eq!(
+            snapshot.flush_errors,
+            vec!["telemetry request failed with status 404 Not Found".to_string()]
+        );
+    }
+
+    #[gpui::test]
+    fn test_flush_on_shutdown_clears_spool(cx: &mut TestAppContext) {
+        init_test(cx);
+        let spool_dir = tempfile::tempdir().unwrap();
+        let spool_path = spool_dir.path().join("spool.ndjson");
+
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_200_response(), cx));
+        telemetry.state.lock().spool_path = Some(spool_path.clone());
+        telemetry.report_app_event("test".to_string());
+
+        let flush = telemetry.flush_events_before_shutdown();
+        assert!(spool_path.exists());
+        cx.run_until_parked();
+        drop(flush);
+        assert!(!spool_path.exists());
+        assert!(is_empty_state(&telemetry));
+    }
+
+    #[gpui::test]
+    fn test_first_edit_reports_timing_once(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_404_response(), cx));
+
+        telemetry.log_edit_event("test");
+        telemetry.log_edit_event("test");
+
+        let state = telemetry.state.lock();
+        let timings = state
+            .events_queue
+            .iter()
+            .filter(|wrapper| {
+                matches!(
+                    wrapper.event,
+                    Event::Timing {
+                        name: "first edit",
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(timings, 1);
+    }
+
+    #[gpui::test]
+    fn test_unsent_events_are_capped(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_404_response(), cx));
+        telemetry.state.lock().max_unsent_events = 3;
+
+        for i in 0..5 {
+            telemetry.report_app_event(format!("test {i}"));
+        }
+
+        {
+            let state = telemetry.state.lock();
+            assert_eq!(state.dropped_event_count, 2);
+            let operations = state
+                .events_queue
+                .iter()
+                .map(|wrapper| match &wrapper.event {
+                    Event::App { operation, .. } => operation.as_str(),
+                    _ => panic!("unexpected event"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(operations, ["test 2", "test 3", "test 4"]);
+        }
+
+        telemetry.flush_events();
+        cx.run_until_parked();
+        let snapshot = telemetry.inspector_snapshot();
+        assert!(snapshot.flushed_batches[0]
+            .request_body
+            .contains(r#""type":"DroppedEvents","count":2"#));
+        assert_eq!(telemetry.state.lock().dropped_event_count, 0);
+    }
+
+    #[gpui::test]
+    fn test_local_log(cx: &mut TestAppContext) {
+        init_test(cx);
+        let request_count = Arc::new(Mutex::new(0));
+        let http = FakeHttpClient::create({
+            let request_count = request_count.clone();
+            move |_| {
+                *request_count.lock() += 1;
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.local_log = Some(true);
+                });
+            });
+        });
+
+        let log_dir = tempfile::tempdir().unwrap();
+        let local_log_path = log_dir.path().join("events.ndjson");
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+        telemetry.state.lock().sinks.clear();
+        telemetry.add_sink(Arc::new(LocalLogSink::new(Some(local_log_path.clone()))));
+
+        telemetry.report_app_event("first".to_string());
+        telemetry.report_app_event("second".to_string());
+        telemetry.flush_events();
+        telemetry.flush_sinks();
+        cx.run_until_parked();
+
+        let contents = std::fs::read_to_string(&local_log_path).unwrap();
+        let lines = contents.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""operation":"first""#));
+        assert!(lines[1].contains(r#""operation":"second""#));
+        assert_eq!(*request_count.lock(), 0);
+        assert!(is_empty_state(&telemetry));
+    }
+
+    #[gpui::test]
+    async fn test_sinks_are_flushed_independently(
+        executor: BackgroundExecutor,
+        cx: &mut TestAppContext,
+    ) {
+        init_test(cx);
+        let requested_urls = Arc::new(Mutex::new(Vec::new()));
+        let http = FakeHttpClient::create({
+            let requested_urls = requested_urls.clone();
+            move |request| {
+                requested_urls.lock().push(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.endpoint_url = Some("http://zed.test/api/events".into());
+                    settings.otlp_collector_url = Some("http://collector.test/v1/logs".into());
+                });
+            });
+        });
+
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+        telemetry.report_app_event("open".to_string());
+
+        executor.advance_clock(FLUSH_INTERVAL);
+        assert_eq!(*requested_urls.lock(), ["http://zed.test/api/events"]);
+
+        executor.advance_clock(sink::OTLP_COLLECTOR_FLUSH_INTERVAL - FLUSH_INTERVAL);
+        assert_eq!(
+            *requested_urls.lock(),
+            [
+                "http://zed.test/api/events",
+                "http://collector.test/v1/logs"
+            ]
+        );
+        assert!(telemetry
+            .state
+            .lock()
+            .sinks
+            .iter()
+            .all(|queue| queue.events.is_empty()));
+    }
+
+    #[gpui::test]
+    fn test_policy_disables_telemetry(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http = FakeHttpClient::with_200_response();
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+        assert_eq!(telemetry.disabled_by_policy(), None);
+
+        telemetry.state.lock().disabled_by_policy = Some("disabled by policy".into());
+        telemetry.report_app_event("open".to_string());
+        telemetry.report_error(ErrorSeverity::Error, "error".to_string(), "test");
+        assert!(telemetry.state.lock().events_queue.is_empty());
+        assert!(telemetry
+            .state
+            .lock()
+            .sinks
+            .iter()
+            .all(|queue| queue.events.is_empty()));
+    }
+
+    #[gpui::test]
+    fn test_flag_exposures_are_reported_once(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http = FakeHttpClient::with_200_response();
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+
+        telemetry.report_flag_exposure("channels", true);
+        telemetry.report_flag_exposure("channels", true);
+        telemetry.report_flag_exposure("copilot-chat", false);
+
+        let exposures = telemetry
+            .state
+            .lock()
+            .events_queue
+            .iter()
+            .filter_map(|event| match event.event {
+                Event::FlagExposure { flag, enabled, .. } => Some((flag, enabled)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(exposures, [("channels", true), ("copilot-chat", false)]);
+    }
+
+    #[gpui::test]
+    fn test_editor_event_builder(cx: &mut TestAppContext) {
+        init_test(cx);
+        let http = FakeHttpClient::with_200_response();
+        let telemetry = cx.update(|cx| Telemetry::new(http, cx));
+
+        telemetry
+            .editor_event("save")
+            .file_extension(Some("RS".into()))
+            .vim_mode(true)
+            .send();
+        telemetry.editor_event("open").send();
+
+        let state = telemetry.state.lock();
+        let events = state
+            .events_queue
+            .iter()
+            .map(|event| &event.event)
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            events[..],
+            [
+                Event::Editor {
+                    operation: "save",
+                    file_extension: Some(file_extension),
+                    vim_mode: true,
+                    copilot_enabled: false,
+                    copilot_enabled_for_language: false,
+                    ..
+                },
+                Event::Editor {
+                    operation: "open",
+                    file_extension: None,
+                    vim_mode: false,
+                    ..
+                },
+            ] if file_extension == "rs"
+        ));
+    }
+
+    #[gpui::test]
+    fn test_sent_summary(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_200_response(), cx));
+        let summary_dir = tempfile::tempdir().unwrap();
+        let summary_path = summary_dir.path().join("sent.json");
+        telemetry.state.lock().sent_summary_path = Some(summary_path.clone());
+
+        telemetry.report_app_event("open".to_string());
+        telemetry.report_setting_event("theme", "One Dark".to_string());
+        telemetry.report_app_event("close".to_string());
+        telemetry.flush_events();
+        cx.run_until_parked();
+
+        let summary = telemetry.sent_summary();
+        assert_eq!(summary.batch_count, 1);
+        assert!(summary.bytes_sent > 0);
+        assert!(summary.last_sent_at.is_some());
+        assert_eq!(
+            summary.event_counts,
+            BTreeMap::from_iter([("App".to_string(), 2), ("Setting".to_string(), 1)])
+        );
+        assert_eq!(sent_summary::load(&summary_path).unwrap(), summary);
+    }
+
+    #[gpui::test]
+    fn test_consent(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_200_response(), cx));
+        let spool_dir = tempfile::tempdir().unwrap();
+        let spool_path = spool_dir.path().join("telemetry.ndjson");
+        {
+            let mut state = telemetry.state.lock();
+            state.spool_path = Some(spool_path.clone());
+            state.consent = TelemetryConsent::Unasked;
+        }
+
+        // Events are spooled rather than sent until consent is granted
+        telemetry.report_app_event("open".to_string());
+        telemetry.flush_events();
+        cx.run_until_parked();
+        assert_eq!(telemetry.sent_summary().batch_count, 0);
+        assert!(telemetry.state.lock().events_queue.is_empty());
+        assert!(spool_path.exists());
+
+        telemetry.set_consent(TelemetryConsent::Granted);
+        cx.run_until_parked();
+        assert_eq!(telemetry.sent_summary().batch_count, 1);
+        assert!(!spool_path.exists());
+
+        telemetry.set_consent(TelemetryConsent::Denied);
+        telemetry.report_app_event("close".to_string());
+        assert!(telemetry.state.lock().events_queue.is_empty());
+        assert_eq!(telemetry.consent(), TelemetryConsent::Denied);
+    }
+
+    #[gpui::test]
+    fn test_consecutive_duplicates_are_collapsed(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.deduplication_window_in_seconds = Some(10);
+                });
+            });
+        });
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_200_response(), cx));
+
+        for operation in ["open", "open", "open", "close", "open"] {
+            telemetry.report_app_event(operation.to_string());
+        }
+
+        let state = telemetry.state.lock();
+        let events = state
+            .events_queue
+            .iter()
+            .map(|event| match &event.event {
+                Event::App { operation, .. } => (operation.as_str(), event.count),
+                event => panic!("unexpected event {event:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, [("open", Some(3)), ("close", None), ("open", None)]);
+    }
+
+    #[gpui::test]
+    fn test_extension_events(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<TelemetrySettings>(cx, |settings| {
+                    settings.extensions = Some(HashMap::from_iter([("opted-out".into(), false)]));
+                });
+            });
+        });
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_404_response(), cx));
+
+        let properties = serde_json::json!({
+            "count": 3,
+            "enabled": true,
+            "label": "x".repeat(MAX_EXTENSION_EVENT_STRING_LEN + 10),
+            "nested": { "type": "App" },
+        });
+        let Value::Object(properties) = properties else {
+            unreachable!()
+        };
+        telemetry.report_extension_event("opted-out", "used", properties.clone());
+        telemetry.report_extension_event("opted-in", "used", properties);
+
+        let state = telemetry.state.lock();
+        assert_eq!(state.events_queue.len(), 1);
+        let Event::Extension {
+            extension_id,
+            name,
+            properties,
+            ..
+        } = &state.events_queue[0].event
+        else {
+            panic!("expected an extension event");
+        };
+        assert_eq!(extension_id, "opted-in");
+        assert_eq!(name, "used");
+        assert_eq!(
+            properties.keys().collect::<Vec<_>>(),
+            ["count", "enabled", "label"]
+        );
+        assert_eq!(
+            properties["label"].as_str().unwrap().len(),
+            MAX_EXTENSION_EVENT_STRING_LEN
+        );
+    }
+
+    #[gpui::test]
+    async fn test_connectivity_report(cx: &mut TestAppContext) {
+        init_test(cx);
+        let telemetry = cx.update(|cx| Telemetry::new(FakeHttpClient::with_404_response(), cx));
+
+        let report = telemetry.test_connectivity().await;
+        assert_eq!(report.result, Ok(StatusCode::NOT_FOUND));
+        assert!(!report.is_success());
+
+        let snapshot = telemetry.inspector_snapshot();
+        assert!(snapshot.flushed_batches[0]
+            .request_body
+            .contains(r#""type":"Ping""#));
+        assert_eq!(
+            snapshot.flush_errors,
+            vec!["telemetry test failed with status 404 Not Found".to_string()]
+        );
+        assert!(is_empty_state(&telemetry));
+    }
+
     // TODO:
     // Test settings
     // Update FakeHTTPClient to keep track of the number of requests and assert on it
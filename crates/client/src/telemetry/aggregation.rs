@@ -0,0 +1,74 @@
+use serde::Serialize;
+
+/// The distribution of a metric's samples over an aggregation window.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct MetricSummary {
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub p95: f64,
+}
+
+/// Collects the samples of a metric taken over an aggregation window, so that a single
+/// summarized event can be sent for the whole window.
+#[derive(Default)]
+pub struct MetricSamples {
+    samples: Vec<f64>,
+}
+
+impl MetricSamples {
+    pub fn record(&mut self, sample: f64) {
+        if sample.is_finite() {
+            self.samples.push(sample);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Summarizes the samples recorded since the last summary, or returns `None` if there are none.
+    pub fn take_summary(&mut self) -> Option<MetricSummary> {
+        let mut samples = std::mem::take(&mut self.samples);
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_by(f64::total_cmp);
+        // Nearest-rank percentile
+        let p95_ix = (samples.len() as f64 * 0.95).ceil() as usize - 1;
+        Some(MetricSummary {
+            min: samples[0],
+            avg: samples.iter().sum::<f64>() / samples.len() as f64,
+            max: samples[samples.len() - 1],
+            p95: samples[p95_ix],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_summary() {
+        let mut samples = MetricSamples::default();
+        assert_eq!(samples.take_summary(), None);
+
+        for sample in (1..=20).rev() {
+            samples.record(sample as f64);
+        }
+        samples.record(f64::NAN);
+        assert_eq!(samples.len(), 20);
+        assert_eq!(
+            samples.take_summary(),
+            Some(MetricSummary {
+                min: 1.,
+                avg: 10.5,
+                max: 20.,
+                p95: 19.,
+            })
+        );
+        assert_eq!(samples.len(), 0);
+    }
+}
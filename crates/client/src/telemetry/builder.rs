@@ -0,0 +1,63 @@
+use super::{file_extension, Event, Telemetry};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Builds an [`Event::Editor`], see [`Telemetry::editor_event`]. Fields that aren't set
+/// default to `None` or `false`.
+#[must_use = "the event is only reported once `send` is called"]
+pub struct EditorEventBuilder {
+    telemetry: Arc<Telemetry>,
+    operation: &'static str,
+    file_extension: Option<String>,
+    vim_mode: bool,
+    copilot_enabled: bool,
+    copilot_enabled_for_language: bool,
+}
+
+impl EditorEventBuilder {
+    pub(super) fn new(telemetry: Arc<Telemetry>, operation: &'static str) -> Self {
+        Self {
+            telemetry,
+            operation,
+            file_extension: None,
+            vim_mode: false,
+            copilot_enabled: false,
+            copilot_enabled_for_language: false,
+        }
+    }
+
+    pub fn file_extension(mut self, file_extension: Option<String>) -> Self {
+        self.file_extension = file_extension;
+        self
+    }
+
+    pub fn vim_mode(mut self, vim_mode: bool) -> Self {
+        self.vim_mode = vim_mode;
+        self
+    }
+
+    pub fn copilot_enabled(mut self, copilot_enabled: bool) -> Self {
+        self.copilot_enabled = copilot_enabled;
+        self
+    }
+
+    pub fn copilot_enabled_for_language(mut self, copilot_enabled_for_language: bool) -> Self {
+        self.copilot_enabled_for_language = copilot_enabled_for_language;
+        self
+    }
+
+    /// Reports the event, subject to the current telemetry settings.
+    pub fn send(self) {
+        let event = Event::Editor {
+            operation: self.operation,
+            file_extension: file_extension::redact(self.file_extension),
+            vim_mode: self.vim_mode,
+            copilot_enabled: self.copilot_enabled,
+            copilot_enabled_for_language: self.copilot_enabled_for_language,
+            milliseconds_since_first_event: self
+                .telemetry
+                .milliseconds_since_first_event(Utc::now()),
+        };
+        self.telemetry.report_event(event)
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use serde::{Deserialize, Serialize};
+
+const CONSENT_KEY: &str = "telemetry_consent";
+
+/// Whether the user has agreed to telemetry being sent, as answered on first run.
+///
+/// Events are spooled but never sent while [`TelemetryConsent::Unasked`], and are not
+/// collected at all once [`TelemetryConsent::Denied`]. The telemetry settings still apply
+/// on top of a grant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryConsent {
+    Unasked,
+    Granted,
+    Denied,
+}
+
+/// Reads the consent stored alongside the installation id.
+///
+/// Installations that predate the consent prompt were never asked, and keep sending
+/// telemetry according to their settings, so they are treated as having granted it.
+/// `existing_installation` is `None` when the installation id couldn't be read.
+pub async fn load_consent(existing_installation: Option<bool>) -> Result<TelemetryConsent> {
+    if let Some(consent) = KEY_VALUE_STORE.read_kvp(CONSENT_KEY)? {
+        return Ok(serde_json::from_str(&consent)?);
+    }
+
+    match existing_installation {
+        Some(false) => Ok(TelemetryConsent::Unasked),
+        Some(true) => {
+            save(TelemetryConsent::Granted).await?;
+            Ok(TelemetryConsent::Granted)
+        }
+        // This may be an existing installation, which isn't prompted, but the grant isn't
+        // stored, so that it's decided again once the installation id can be read.
+        None => Ok(TelemetryConsent::Granted),
+    }
+}
+
+pub(super) async fn save(consent: TelemetryConsent) -> Result<()> {
+    KEY_VALUE_STORE
+        .write_kvp(CONSENT_KEY.to_string(), serde_json::to_string(&consent)?)
+        .await
+}
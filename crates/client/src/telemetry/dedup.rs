@@ -0,0 +1,34 @@
+use super::Event;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// The last event that was queued, which identical events reported soon after it are
+/// counted against instead of being queued themselves.
+pub struct DuplicateRun {
+    key: Value,
+    started_at: Instant,
+}
+
+impl DuplicateRun {
+    pub fn new(key: Value, now: Instant) -> Self {
+        Self {
+            key,
+            started_at: now,
+        }
+    }
+
+    /// Whether an event with the given key continues this run, which only lasts for `window`
+    /// after its first event, so that long runs are still reported periodically.
+    pub fn is_continued_by(&self, key: &Value, window: Duration, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) < window && self.key == *key
+    }
+}
+
+/// Identifies an event by its type and fields, ignoring when it was reported.
+pub fn key(event: &Event) -> Value {
+    let mut key = serde_json::to_value(event).unwrap_or_default();
+    if let Value::Object(fields) = &mut key {
+        fields.remove("milliseconds_since_first_event");
+    }
+    key
+}
@@ -0,0 +1,133 @@
+/// File extensions that are reported as-is. Anything else is reported as [`OTHER`], so that
+/// unusual extensions, which may identify proprietary formats, never leave the machine.
+const KNOWN_FILE_EXTENSIONS: &[&str] = &[
+    "astro",
+    "bash",
+    "bzl",
+    "c",
+    "cc",
+    "cjs",
+    "clj",
+    "cljs",
+    "cmake",
+    "conf",
+    "cpp",
+    "cr",
+    "cs",
+    "css",
+    "csv",
+    "cts",
+    "cxx",
+    "d",
+    "dart",
+    "diff",
+    "dockerfile",
+    "el",
+    "elm",
+    "erb",
+    "erl",
+    "ex",
+    "exs",
+    "f90",
+    "fish",
+    "fs",
+    "gleam",
+    "go",
+    "gql",
+    "gradle",
+    "graphql",
+    "groovy",
+    "h",
+    "haml",
+    "hbs",
+    "hcl",
+    "heex",
+    "hpp",
+    "hrl",
+    "hs",
+    "htm",
+    "html",
+    "ini",
+    "java",
+    "jl",
+    "js",
+    "json",
+    "jsonc",
+    "jsx",
+    "kt",
+    "kts",
+    "less",
+    "lock",
+    "lua",
+    "m",
+    "md",
+    "mdx",
+    "mjs",
+    "ml",
+    "mli",
+    "mm",
+    "mts",
+    "nix",
+    "nu",
+    "php",
+    "pl",
+    "pm",
+    "proto",
+    "ps1",
+    "py",
+    "r",
+    "rb",
+    "rkt",
+    "rs",
+    "sass",
+    "scala",
+    "scm",
+    "scss",
+    "sh",
+    "sql",
+    "sv",
+    "svelte",
+    "svg",
+    "swift",
+    "tex",
+    "tf",
+    "toml",
+    "ts",
+    "tsx",
+    "txt",
+    "ua",
+    "v",
+    "vue",
+    "wgsl",
+    "xml",
+    "yaml",
+    "yml",
+    "zig",
+    "zsh",
+];
+
+/// What unknown file extensions are reported as.
+const OTHER: &str = "other";
+
+/// Maps extensions outside of [`KNOWN_FILE_EXTENSIONS`] to [`OTHER`].
+pub fn redact(file_extension: Option<String>) -> Option<String> {
+    let file_extension = file_extension?.to_lowercase();
+    if KNOWN_FILE_EXTENSIONS.contains(&file_extension.as_str()) {
+        Some(file_extension)
+    } else {
+        Some(OTHER.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact(Some("rs".into())), Some("rs".into()));
+        assert_eq!(redact(Some("TSX".into())), Some("tsx".into()));
+        assert_eq!(redact(Some("acmeconfig".into())), Some("other".into()));
+        assert_eq!(redact(None), None);
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(target_os = "macos")]
+use smol::process::Command;
+
+/// Samples how busy the GPU is, as reported by the graphics driver through IOKit.
+pub async fn utilization() -> Option<f32> {
+    parse_utilization(&accelerator_info().await?)
+}
+
+/// The name of the GPU, as reported by the graphics driver through IOKit.
+pub async fn model() -> Option<String> {
+    parse_model(&accelerator_info().await?)
+}
+
+#[cfg(target_os = "macos")]
+async fn accelerator_info() -> Option<String> {
+    let output = Command::new("/usr/sbin/ioreg")
+        .args(["-r", "-d", "1", "-c", "IOAccelerator"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// IOKit is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+async fn accelerator_info() -> Option<String> {
+    None
+}
+
+fn parse_utilization(ioreg_output: &str) -> Option<f32> {
+    const KEY: &str = "\"Device Utilization %\"=";
+
+    let start = ioreg_output.find(KEY)? + KEY.len();
+    let digits = ioreg_output[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?;
+    digits.parse().ok()
+}
+
+fn parse_model(ioreg_output: &str) -> Option<String> {
+    const KEY: &str = "\"model\" = \"";
+
+    let start = ioreg_output.find(KEY)? + KEY.len();
+    let model = ioreg_output[start..].split('"').next()?;
+    Some(model.to_string()).filter(|model| !model.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = r#"+-o AGXAcceleratorG13X  <class AGXAcceleratorG13X, id 0x1000000, registered>
+    {
+      "model" = "Apple M1 Pro"
+      "PerformanceStatistics" = {"In use system memory"=1405370368,"Device Utilization %"=17,"Renderer Utilization %"=16}
+    }"#;
+
+    #[test]
+    fn test_parse_utilization() {
+        assert_eq!(parse_utilization(OUTPUT), Some(17.));
+        assert_eq!(parse_utilization("no accelerator"), None);
+    }
+
+    #[test]
+    fn test_parse_model() {
+        assert_eq!(parse_model(OUTPUT), Some("Apple M1 Pro".to_string()));
+        assert_eq!(parse_model("no accelerator"), None);
+    }
+}
@@ -0,0 +1,268 @@
+use gpui::AppContext;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Estimates how much memory one of Zed's caches is holding, for the breakdown included
+/// in `MemoryPressure` events. Register implementations with [`register_memory_accounting`].
+pub trait MemoryAccounting: 'static {
+    /// The name the cache is reported under. The sizes of caches sharing a name are added
+    /// together, e.g. the buffers of each open project.
+    fn name(&self) -> &'static str;
+
+    /// Returns `None` once the cache no longer exists, after which it is unregistered.
+    fn estimated_size_in_bytes(&self, cx: &AppContext) -> Option<u64>;
+}
+
+#[derive(Default)]
+struct MemoryAccountants(Vec<Box<dyn MemoryAccounting>>);
+
+pub fn register_memory_accounting(accounting: impl MemoryAccounting, cx: &mut AppContext) {
+    cx.default_global::<MemoryAccountants>()
+        .0
+        .push(Box::new(accounting));
+}
+
+/// The estimated size of each registered cache, keyed by name.
+pub fn cache_sizes(cx: &mut AppContext) -> BTreeMap<&'static str, u64> {
+    let mut sizes = BTreeMap::new();
+    if cx.try_global::<MemoryAccountants>().is_none() {
+        return sizes;
+    }
+
+    cx.update_global::<MemoryAccountants, _>(|accountants, cx| {
+        accountants.0.retain(|accounting| {
+            let Some(size) = accounting.estimated_size_in_bytes(cx) else {
+                return false;
+            };
+            *sizes.entry(accounting.name()).or_default() += size;
+            true
+        })
+    });
+    sizes
+}
+
+/// What caused a `MemoryPressure` event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPressureTrigger {
+    /// Zed's memory usage crossed a `telemetry.memory_pressure_thresholds_in_megabytes` threshold.
+    Threshold,
+    /// The OS reported that the system is running low on memory.
+    OsWarning,
+    /// The OS reported that the system is about to run out of memory.
+    OsCritical,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OsMemoryPressure {
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// Watches memory usage for the conditions that `MemoryPressure` events are reported for,
+/// each of which is only reported when it starts.
+#[derive(Default)]
+pub struct MemoryPressureMonitor {
+    exceeded_thresholds: BTreeSet<u64>,
+    os_pressure: OsMemoryPressure,
+}
+
+impl MemoryPressureMonitor {
+    /// Returns the conditions that started since the last check, along with the threshold
+    /// that was crossed for [`MemoryPressureTrigger::Threshold`]. When several thresholds
+    /// are crossed at once, only the highest is returned.
+    pub fn check(
+        &mut self,
+        memory_in_bytes: u64,
+        thresholds_in_bytes: impl IntoIterator<Item = u64>,
+        os_pressure: Option<OsMemoryPressure>,
+    ) -> Vec<(MemoryPressureTrigger, Option<u64>)> {
+        let mut triggers = Vec::new();
+
+        let mut crossed_threshold = None;
+        for threshold in thresholds_in_bytes {
+            if memory_in_bytes < threshold {
+                self.exceeded_thresholds.remove(&threshold);
+            } else if self.exceeded_thresholds.insert(threshold) {
+                crossed_threshold = crossed_threshold.max(Some(threshold));
+            }
+        }
+        if crossed_threshold.is_some() {
+            triggers.push((MemoryPressureTrigger::Threshold, crossed_threshold));
+        }
+
+        if let Some(os_pressure) = os_pressure {
+            if os_pressure > self.os_pressure {
+                match os_pressure {
+                    OsMemoryPressure::Normal => {}
+                    OsMemoryPressure::Warning => {
+                        triggers.push((MemoryPressureTrigger::OsWarning, None))
+                    }
+                    OsMemoryPressure::Critical => {
+                        triggers.push((MemoryPressureTrigger::OsCritical, None))
+                    }
+                }
+            }
+            self.os_pressure = os_pressure;
+        }
+
+        triggers
+    }
+}
+
+/// Reads the memory pressure level the kernel maintains for the whole system.
+#[cfg(target_os = "macos")]
+pub fn os_memory_pressure() -> Option<OsMemoryPressure> {
+    use std::mem;
+
+    let mut level: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>();
+    let result = unsafe {
+        libc::sysctlbyname(
+            b"kern.memorystatus_vm_pressure_level\0".as_ptr().cast(),
+            (&mut level as *mut libc::c_int).cast(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+
+    // The values of `DISPATCH_MEMORYPRESSURE_*`
+    match level {
+        2 => Some(OsMemoryPressure::Warning),
+        4 => Some(OsMemoryPressure::Critical),
+        _ => Some(OsMemoryPressure::Normal),
+    }
+}
+
+/// Reads the share of time that tasks were stalled waiting on memory, from the kernel's
+/// pressure stall information.
+#[cfg(target_os = "linux")]
+pub fn os_memory_pressure() -> Option<OsMemoryPressure> {
+    let contents = std::fs::read_to_string("/proc/pressure/memory").ok()?;
+    Some(parse_pressure_stall_information(&contents))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn os_memory_pressure() -> Option<OsMemoryPressure> {
+    None
+}
+
+#[cfg(any(test, target_os = "linux"))]
+fn parse_pressure_stall_information(contents: &str) -> OsMemoryPressure {
+    /// The percentage of the last ten seconds stalled on memory that counts as pressure.
+    const STALL_PERCENTAGE: f64 = 10.;
+
+    let avg10 = |kind: &str| {
+        contents
+            .lines()
+            .find(|line| line.starts_with(kind))?
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("avg10="))?
+            .parse::<f64>()
+            .ok()
+    };
+    if avg10("full").map_or(false, |avg10| avg10 >= STALL_PERCENTAGE) {
+        OsMemoryPressure::Critical
+    } else if avg10("some").map_or(false, |avg10| avg10 >= STALL_PERCENTAGE) {
+        OsMemoryPressure::Warning
+    } else {
+        OsMemoryPressure::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    struct FixedSize(&'static str, Option<u64>);
+
+    impl MemoryAccounting for FixedSize {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn estimated_size_in_bytes(&self, _: &AppContext) -> Option<u64> {
+            self.1
+        }
+    }
+
+    #[gpui::test]
+    fn test_cache_sizes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            assert!(cache_sizes(cx).is_empty());
+
+            register_memory_accounting(FixedSize("buffers", Some(10)), cx);
+            register_memory_accounting(FixedSize("buffers", Some(5)), cx);
+            register_memory_accounting(FixedSize("logs", Some(1)), cx);
+            register_memory_accounting(FixedSize("released", None), cx);
+            assert_eq!(
+                cache_sizes(cx),
+                BTreeMap::from_iter([("buffers", 15), ("logs", 1)])
+            );
+            assert_eq!(cx.global::<MemoryAccountants>().0.len(), 3);
+        });
+    }
+
+    #[test]
+    fn test_memory_pressure_monitor() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        let thresholds = [2 * GB, 4 * GB, 8 * GB];
+        let mut monitor = MemoryPressureMonitor::default();
+
+        assert!(monitor.check(GB, thresholds, None).is_empty());
+        assert_eq!(
+            monitor.check(5 * GB, thresholds, None),
+            [(MemoryPressureTrigger::Threshold, Some(4 * GB))]
+        );
+        assert!(monitor.check(5 * GB, thresholds, None).is_empty());
+        assert!(monitor.check(3 * GB, thresholds, None).is_empty());
+        assert_eq!(
+            monitor.check(5 * GB, thresholds, Some(OsMemoryPressure::Warning)),
+            [
+                (MemoryPressureTrigger::Threshold, Some(4 * GB)),
+                (MemoryPressureTrigger::OsWarning, None)
+            ]
+        );
+        assert!(monitor
+            .check(5 * GB, thresholds, Some(OsMemoryPressure::Warning))
+            .is_empty());
+        assert!(monitor
+            .check(5 * GB, thresholds, Some(OsMemoryPressure::Normal))
+            .is_empty());
+        assert_eq!(
+            monitor.check(5 * GB, thresholds, Some(OsMemoryPressure::Critical)),
+            [(MemoryPressureTrigger::OsCritical, None)]
+        );
+    }
+
+    #[test]
+    fn test_parse_pressure_stall_information() {
+        let contents = "some avg10=0.00 avg60=0.12 avg300=0.05 total=1234\n\
+                        full avg10=0.00 avg60=0.00 avg300=0.00 total=12\n";
+        assert_eq!(
+            parse_pressure_stall_information(contents),
+            OsMemoryPressure::Normal
+        );
+
+        let contents = "some avg10=25.50 avg60=10.00 avg300=2.00 total=1234\n\
+                        full avg10=3.00 avg60=1.00 avg300=0.50 total=12\n";
+        assert_eq!(
+            parse_pressure_stall_information(contents),
+            OsMemoryPressure::Warning
+        );
+
+        let contents = "some avg10=60.00 avg60=10.00 avg300=2.00 total=1234\n\
+                        full avg10=40.00 avg60=1.00 avg300=0.50 total=12\n";
+        assert_eq!(
+            parse_pressure_stall_information(contents),
+            OsMemoryPressure::Critical
+        );
+    }
+}
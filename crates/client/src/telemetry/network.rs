@@ -0,0 +1,116 @@
+use super::aggregation::MetricSamples;
+use std::collections::BTreeMap;
+use util::http::{RequestOutcome, Url};
+
+/// Third-party APIs that Zed calls directly, whose requests are reported alongside the
+/// requests to Zed's own servers.
+const THIRD_PARTY_API_HOSTS: &[&str] = &["api.openai.com"];
+
+/// The requests sent to a single host over an aggregation window.
+#[derive(Default)]
+pub struct HostRequests {
+    pub request_count: usize,
+    /// How many of the requests were retries of an earlier request.
+    pub retry_count: usize,
+    /// How many of the requests received no response at all.
+    pub error_count: usize,
+    pub status_counts: BTreeMap<u16, usize>,
+    pub latency_in_milliseconds: MetricSamples,
+}
+
+/// Collects the outcomes of the requests made to Zed's APIs, keyed by host. Requests to
+/// any other host are ignored.
+pub struct NetworkRequests {
+    server_host: Option<String>,
+    hosts: BTreeMap<String, HostRequests>,
+}
+
+impl NetworkRequests {
+    pub fn new(server_url: &str) -> Self {
+        Self {
+            server_host: Url::parse(server_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string)),
+            hosts: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, outcome: RequestOutcome) {
+        let Some(host) = outcome.host.filter(|host| self.is_reported_host(host)) else {
+            return;
+        };
+
+        let requests = self.hosts.entry(host).or_default();
+        requests.request_count += 1;
+        if outcome.retry_attempt > 0 {
+            requests.retry_count += 1;
+        }
+        match outcome.status {
+            Some(status) => *requests.status_counts.entry(status.as_u16()).or_default() += 1,
+            None => requests.error_count += 1,
+        }
+        requests
+            .latency_in_milliseconds
+            .record(outcome.latency.as_secs_f64() * 1000.);
+    }
+
+    /// Returns the requests recorded since the last call.
+    pub fn take(&mut self) -> BTreeMap<String, HostRequests> {
+        std::mem::take(&mut self.hosts)
+    }
+
+    fn is_reported_host(&self, host: &str) -> bool {
+        let is_server_host = self.server_host.as_ref().map_or(false, |server_host| {
+            host == server_host
+                || host
+                    .strip_suffix(server_host.as_str())
+                    .map_or(false, |subdomain| subdomain.ends_with('.'))
+        });
+        is_server_host || THIRD_PARTY_API_HOSTS.contains(&host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use util::http::StatusCode;
+
+    fn outcome(host: &str, retry_attempt: u32, status: Option<StatusCode>) -> RequestOutcome {
+        RequestOutcome {
+            host: Some(host.to_string()),
+            retry_attempt,
+            latency: Duration::from_millis(100),
+            status,
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut requests = NetworkRequests::new("https://zed.dev");
+        requests.record(outcome("zed.dev", 0, Some(StatusCode::OK)));
+        requests.record(outcome("zed.dev", 1, Some(StatusCode::BAD_GATEWAY)));
+        requests.record(outcome("collab.zed.dev", 0, None));
+        requests.record(outcome("api.openai.com", 0, Some(StatusCode::OK)));
+        requests.record(outcome("notzed.dev", 0, Some(StatusCode::OK)));
+        requests.record(outcome("github.com", 0, Some(StatusCode::OK)));
+
+        let mut hosts = requests.take();
+        assert_eq!(
+            hosts.keys().collect::<Vec<_>>(),
+            ["api.openai.com", "collab.zed.dev", "zed.dev"]
+        );
+
+        let zed = hosts.get_mut("zed.dev").unwrap();
+        assert_eq!(zed.request_count, 2);
+        assert_eq!(zed.retry_count, 1);
+        assert_eq!(zed.error_count, 0);
+        assert_eq!(zed.status_counts, BTreeMap::from_iter([(200, 1), (502, 1)]));
+        assert_eq!(
+            zed.latency_in_milliseconds.take_summary().unwrap().max,
+            100.
+        );
+        assert_eq!(hosts["collab.zed.dev"].error_count, 1);
+        assert!(requests.take().is_empty());
+    }
+}
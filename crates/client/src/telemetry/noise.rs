@@ -0,0 +1,235 @@
+use super::{Event, MetricSummary};
+use rand::Rng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Noise is clamped to this many scales either side of the true value, so that no single
+/// report is wildly off.
+const BOUND_IN_SCALES: f64 = 3.;
+
+/// How much each kind of metric is expected to vary between machines, which the noise is
+/// proportional to.
+const PERCENTAGE_SENSITIVITY: f64 = 5.;
+const MEMORY_SENSITIVITY_IN_BYTES: f64 = 64. * 1024. * 1024.;
+const FRAME_TIME_SENSITIVITY_IN_MICROSECONDS: f64 = 2000.;
+const INPUT_LATENCY_SENSITIVITY_IN_MILLISECONDS: f64 = 5.;
+const NETWORK_LATENCY_SENSITIVITY_IN_MILLISECONDS: f64 = 20.;
+
+/// Describes the noise that was added to an event's numeric fields, so that aggregates can
+/// account for it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NoiseParameters {
+    pub mechanism: &'static str,
+    pub epsilon: f64,
+    pub bound_in_scales: f64,
+    /// The scale of the Laplace distribution the noise for each field was drawn from.
+    pub scales: BTreeMap<&'static str, f64>,
+}
+
+/// Adds bounded Laplace noise to the event's numeric metrics, returning the parameters used,
+/// or `None` if the event has no metrics that noise is applied to.
+pub fn apply(event: &mut Event, epsilon: f64, rng: &mut impl Rng) -> Option<NoiseParameters> {
+    let mut noise = Noise {
+        epsilon,
+        rng,
+        scales: BTreeMap::new(),
+    };
+
+    match event {
+        Event::Cpu {
+            usage_as_percentage,
+            threads,
+            ..
+        } => {
+            noise.add_summary(
+                "usage_as_percentage",
+                usage_as_percentage,
+                PERCENTAGE_SENSITIVITY,
+            );
+            for thread in threads {
+                noise.add_f32(
+                    "threads.usage_as_percentage",
+                    &mut thread.usage_as_percentage,
+                    PERCENTAGE_SENSITIVITY,
+                );
+            }
+        }
+        Event::Gpu {
+            usage_as_percentage,
+            ..
+        } => {
+            noise.add_f32(
+                "usage_as_percentage",
+                usage_as_percentage,
+                PERCENTAGE_SENSITIVITY,
+            );
+        }
+        Event::Memory {
+            memory_in_bytes,
+            virtual_memory_in_bytes,
+            ..
+        } => {
+            noise.add_summary(
+                "memory_in_bytes",
+                memory_in_bytes,
+                MEMORY_SENSITIVITY_IN_BYTES,
+            );
+            noise.add_summary(
+                "virtual_memory_in_bytes",
+                virtual_memory_in_bytes,
+                MEMORY_SENSITIVITY_IN_BYTES,
+            );
+        }
+        Event::MemoryPressure {
+            memory_in_bytes,
+            cache_sizes_in_bytes,
+            ..
+        } => {
+            noise.add_u64(
+                "memory_in_bytes",
+                memory_in_bytes,
+                MEMORY_SENSITIVITY_IN_BYTES,
+            );
+            for size in cache_sizes_in_bytes.values_mut() {
+                noise.add_u64("cache_sizes_in_bytes", size, MEMORY_SENSITIVITY_IN_BYTES);
+            }
+        }
+        Event::FrameTime {
+            mean_in_microseconds,
+            p50_in_microseconds,
+            p95_in_microseconds,
+            max_in_microseconds,
+            ..
+        } => {
+            for (field, value) in [
+                ("mean_in_microseconds", mean_in_microseconds),
+                ("p50_in_microseconds", p50_in_microseconds),
+                ("p95_in_microseconds", p95_in_microseconds),
+                ("max_in_microseconds", max_in_microseconds),
+            ] {
+                noise.add_u64(field, value, FRAME_TIME_SENSITIVITY_IN_MICROSECONDS);
+            }
+        }
+        Event::InputLatency {
+            p50_in_milliseconds,
+            p95_in_milliseconds,
+            p99_in_milliseconds,
+            max_in_milliseconds,
+            ..
+        } => {
+            for (field, value) in [
+                ("p50_in_milliseconds", p50_in_milliseconds),
+                ("p95_in_milliseconds", p95_in_milliseconds),
+                ("p99_in_milliseconds", p99_in_milliseconds),
+                ("max_in_milliseconds", max_in_milliseconds),
+            ] {
+                noise.add_u64(field, value, INPUT_LATENCY_SENSITIVITY_IN_MILLISECONDS);
+            }
+        }
+        Event::NetworkRequests {
+            latency_in_milliseconds,
+            ..
+        } => {
+            noise.add_summary(
+                "latency_in_milliseconds",
+                latency_in_milliseconds,
+                NETWORK_LATENCY_SENSITIVITY_IN_MILLISECONDS,
+            );
+        }
+        _ => return None,
+    }
+
+    Some(NoiseParameters {
+        mechanism: "bounded_laplace",
+        epsilon,
+        bound_in_scales: BOUND_IN_SCALES,
+        scales: noise.scales,
+    })
+}
+
+struct Noise<'a, R> {
+    epsilon: f64,
+    rng: &'a mut R,
+    scales: BTreeMap<&'static str, f64>,
+}
+
+impl<R: Rng> Noise<'_, R> {
+    fn add_f32(&mut self, field: &'static str, value: &mut f32, sensitivity: f64) {
+        *value = self.add(field, *value as f64, sensitivity) as f32;
+    }
+
+    fn add_u64(&mut self, field: &'static str, value: &mut u64, sensitivity: f64) {
+        *value = self.add(field, *value as f64, sensitivity).round() as u64;
+    }
+
+    /// Adds noise to each statistic of the summary, all of which are drawn with the same scale.
+    fn add_summary(&mut self, field: &'static str, summary: &mut MetricSummary, sensitivity: f64) {
+        for value in [
+            &mut summary.min,
+            &mut summary.avg,
+            &mut summary.max,
+            &mut summary.p95,
+        ] {
+            *value = self.add(field, *value, sensitivity);
+        }
+    }
+
+    fn add(&mut self, field: &'static str, value: f64, sensitivity: f64) -> f64 {
+        let scale = sensitivity / self.epsilon;
+        self.scales.insert(field, scale);
+
+        // Inverse transform sampling of the Laplace distribution
+        let u = self.rng.gen_range(-0.5..0.5_f64);
+        let noise = -scale * u.signum() * (1. - 2. * u.abs()).ln();
+        let bound = BOUND_IN_SCALES * scale;
+        (value + noise.clamp(-bound, bound)).max(0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_noise_is_bounded() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let summary = |value| MetricSummary {
+            min: value,
+            avg: value,
+            max: value,
+            p95: value,
+        };
+        let memory_in_bytes = 1024. * 1024. * 1024.;
+        for _ in 0..1000 {
+            let mut event = Event::Memory {
+                sample_count: 1,
+                memory_in_bytes: summary(memory_in_bytes),
+                virtual_memory_in_bytes: summary(0.),
+                milliseconds_since_first_event: 0,
+            };
+            let parameters = apply(&mut event, 2., &mut rng).unwrap();
+            let scale = MEMORY_SENSITIVITY_IN_BYTES / 2.;
+            assert_eq!(parameters.scales["memory_in_bytes"], scale);
+
+            let Event::Memory {
+                memory_in_bytes: noisy_memory_in_bytes,
+                virtual_memory_in_bytes: noisy_virtual_memory_in_bytes,
+                ..
+            } = event
+            else {
+                unreachable!()
+            };
+            let difference = noisy_memory_in_bytes.p95 - memory_in_bytes;
+            assert!(difference.abs() <= BOUND_IN_SCALES * scale);
+            assert!(noisy_virtual_memory_in_bytes.max >= 0.);
+            assert!(noisy_virtual_memory_in_bytes.max <= BOUND_IN_SCALES * scale);
+        }
+
+        let mut event = Event::App {
+            operation: "open".into(),
+            milliseconds_since_first_event: 0,
+        };
+        assert_eq!(apply(&mut event, 2., &mut rng), None);
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+/// Where an OpenTelemetry collector accepts OTLP/HTTP logs by default.
+pub const DEFAULT_LOGS_URL: &'static str = "http://localhost:4318/v1/logs";
+
+/// Converts a request body in Zed's own format into an OTLP/HTTP JSON logs request.
+///
+/// The session-level fields become resource attributes, with nested objects flattened into
+/// dotted keys, and each event becomes a log record whose body is the event type and whose
+/// attributes are the event fields.
+pub fn logs_request(request_body: Value, observed_at: DateTime<Utc>) -> Value {
+    let mut request_body = match request_body {
+        Value::Object(request_body) => request_body,
+        _ => Map::new(),
+    };
+    let events = match request_body.remove("events") {
+        Some(Value::Array(events)) => events,
+        _ => Vec::new(),
+    };
+
+    let observed_time_unix_nano = observed_at
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .to_string();
+    let mut resource_attributes = vec![attribute("service.name", &json!("zed")).unwrap()];
+    resource_attributes.extend(flattened_attributes("", &request_body));
+
+    let log_records = events
+        .into_iter()
+        .filter_map(|event| {
+            let Value::Object(mut event) = event else {
+                return None;
+            };
+            let event_type = event.remove("type").unwrap_or(Value::Null);
+            Some(json!({
+                "observedTimeUnixNano": observed_time_unix_nano,
+                "body": { "stringValue": event_type.as_str().unwrap_or("unknown") },
+                "attributes": attributes(&event),
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "resourceLogs": [{
+            "resource": { "attributes": resource_attributes },
+            "scopeLogs": [{
+                "scope": { "name": "zed.telemetry" },
+                "logRecords": log_records,
+            }],
+        }],
+    })
+}
+
+fn attributes(object: &Map<String, Value>) -> Vec<Value> {
+    object
+        .iter()
+        .filter_map(|(key, value)| attribute(key, value))
+        .collect()
+}
+
+fn flattened_attributes(prefix: &str, object: &Map<String, Value>) -> Vec<Value> {
+    object
+        .iter()
+        .flat_map(|(key, value)| {
+            let key = format!("{prefix}{key}");
+            match value {
+                Value::Object(object) => flattened_attributes(&format!("{key}."), object),
+                value => attribute(&key, value).into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+fn attribute(key: &str, value: &Value) -> Option<Value> {
+    let value = match value {
+        Value::Null => return None,
+        Value::Bool(value) => json!({ "boolValue": value }),
+        // OTLP's JSON encoding represents 64-bit integers as strings
+        Value::Number(value) => match value.as_i64() {
+            Some(value) => json!({ "intValue": value.to_string() }),
+            None => json!({ "doubleValue": value.as_f64() }),
+        },
+        Value::String(value) => json!({ "stringValue": value }),
+        Value::Array(_) | Value::Object(_) => json!({ "stringValue": value.to_string() }),
+    };
+    Some(json!({ "key": key, "value": value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_logs_request() {
+        let observed_at = Utc.with_ymd_and_hms(1990, 4, 12, 12, 0, 0).unwrap();
+        let request_body = json!({
+            "session_id": "session",
+            "is_staff": null,
+            "system_specs": {
+                "core_count": 8,
+            },
+            "events": [{
+                "type": "Cpu",
+                "signed_in": false,
+                "usage_as_percentage": 12.5,
+                "milliseconds_since_first_event": 0,
+            }],
+        });
+
+        assert_eq!(
+            logs_request(request_body, observed_at),
+            json!({
+                "resourceLogs": [{
+                    "resource": {
+                        "attributes": [
+                            { "key": "service.name", "value": { "stringValue": "zed" } },
+                            { "key": "session_id", "value": { "stringValue": "session" } },
+                            { "key": "system_specs.core_count", "value": { "intValue": "8" } },
+                        ]
+                    },
+                    "scopeLogs": [{
+                        "scope": { "name": "zed.telemetry" },
+                        "logRecords": [{
+                            "observedTimeUnixNano": "639921600000000000",
+                            "body": { "stringValue": "Cpu" },
+                            "attributes": [
+                                { "key": "signed_in", "value": { "boolValue": false } },
+                                { "key": "usage_as_percentage", "value": { "doubleValue": 12.5 } },
+                                { "key": "milliseconds_since_first_event", "value": { "intValue": "0" } },
+                            ],
+                        }],
+                    }],
+                }],
+            })
+        );
+    }
+}
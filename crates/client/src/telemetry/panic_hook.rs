@@ -0,0 +1,78 @@
+use std::{backtrace::Backtrace, panic::PanicInfo};
+
+const MAX_BACKTRACE_FRAMES: usize = 64;
+
+pub fn panic_message(info: &PanicInfo) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<Any>".to_string());
+    match info.location() {
+        Some(location) => format!("{payload} at {}:{}", location.file(), location.line()),
+        None => payload,
+    }
+}
+
+pub fn capture_backtrace() -> Vec<String> {
+    sanitize_backtrace(&Backtrace::force_capture().to_string())
+}
+
+/// Keeps only the symbol name of each frame, dropping the file paths and addresses
+/// that can identify the machine, along with the frames of the panic machinery itself.
+fn sanitize_backtrace(backtrace: &str) -> Vec<String> {
+    let mut frames = backtrace
+        .lines()
+        .filter_map(|line| {
+            let (index, symbol) = line.trim().split_once(": ")?;
+            index.parse::<usize>().ok()?;
+            Some(strip_symbol_hash(symbol).to_string())
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(ix) = frames.iter().rposition(|frame| {
+        frame == "rust_begin_unwind"
+            || frame.starts_with("std::panicking::")
+            || frame.starts_with("core::panicking::")
+    }) {
+        frames.drain(0..=ix);
+    }
+    frames.truncate(MAX_BACKTRACE_FRAMES);
+    frames
+}
+
+fn strip_symbol_hash(symbol: &str) -> &str {
+    match symbol.rsplit_once("::h") {
+        Some((name, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            name
+        }
+        _ => symbol,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_backtrace() {
+        let backtrace = r#"   0: std::backtrace::Backtrace::force_capture
+             at /rustc/82e1608df/library/std/src/backtrace.rs:310:9
+   1: rust_begin_unwind
+             at /rustc/82e1608df/library/std/src/panicking.rs:645:5
+   2: core::panicking::panic_fmt
+             at /rustc/82e1608df/library/core/src/panicking.rs:72:14
+   3: editor::Editor::handle_input::h0123456789abcdef
+             at /Users/someone/zed/crates/editor/src/editor.rs:2231:13
+   4: gpui::app::AppContext::update"#;
+
+        assert_eq!(
+            sanitize_backtrace(backtrace),
+            vec![
+                "editor::Editor::handle_input".to_string(),
+                "gpui::app::AppContext::update".to_string()
+            ]
+        );
+    }
+}
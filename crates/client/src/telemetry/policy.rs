@@ -0,0 +1,114 @@
+use std::env;
+
+/// Disables telemetry when set to anything other than an empty string, `0` or `false`.
+const DISABLE_TELEMETRY_ENV_VAR: &str = "ZED_DISABLE_TELEMETRY";
+
+/// The name of the managed preference (macOS) or policy value (Windows) that disables telemetry.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const DISABLE_TELEMETRY_POLICY: &str = "DisableTelemetry";
+
+#[cfg(target_os = "macos")]
+const MANAGED_PREFERENCES_DOMAIN: &str = "dev.zed.Zed";
+
+#[cfg(target_os = "windows")]
+const POLICY_REGISTRY_KEY: &str = r"SOFTWARE\Policies\Zed";
+
+/// Returns why telemetry has been disabled by the environment Zed is running in, if it has.
+/// This takes precedence over the user's settings.
+pub fn disabled_reason() -> Option<String> {
+    if env::var(DISABLE_TELEMETRY_ENV_VAR).map_or(false, |value| env_var_disables_telemetry(&value))
+    {
+        return Some(format!(
+            "Telemetry is disabled by the {DISABLE_TELEMETRY_ENV_VAR} environment variable."
+        ));
+    }
+
+    if platform_policy_disables_telemetry() {
+        return Some("Telemetry is disabled by a policy set by your organization.".to_string());
+    }
+
+    None
+}
+
+fn env_var_disables_telemetry(value: &str) -> bool {
+    !matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "" | "0" | "false"
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn platform_policy_disables_telemetry() -> bool {
+    use core_foundation::{
+        base::{CFType, TCFType},
+        boolean::CFBoolean,
+        number::CFNumber,
+        string::CFString,
+    };
+    use core_foundation_sys::preferences::{
+        CFPreferencesAppValueIsForced, CFPreferencesCopyAppValue,
+    };
+
+    let key = CFString::from_static_string(DISABLE_TELEMETRY_POLICY);
+    let domain = CFString::from_static_string(MANAGED_PREFERENCES_DOMAIN);
+    unsafe {
+        // Only values installed through a configuration profile are forced, so users
+        // can't trip this by writing the preference with `defaults`.
+        if CFPreferencesAppValueIsForced(key.as_concrete_TypeRef(), domain.as_concrete_TypeRef())
+            == 0
+        {
+            return false;
+        }
+
+        let value =
+            CFPreferencesCopyAppValue(key.as_concrete_TypeRef(), domain.as_concrete_TypeRef());
+        if value.is_null() {
+            return false;
+        }
+        let value = CFType::wrap_under_create_rule(value);
+        if let Some(value) = value.downcast::<CFBoolean>() {
+            return value.into();
+        }
+        value
+            .downcast::<CFNumber>()
+            .and_then(|value| value.to_i64())
+            .map_or(false, |value| value != 0)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_policy_disables_telemetry() -> bool {
+    use winreg::{
+        enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+        RegKey,
+    };
+
+    [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER]
+        .into_iter()
+        .any(|hive| {
+            RegKey::predef(hive)
+                .open_subkey(POLICY_REGISTRY_KEY)
+                .and_then(|key| key.get_value::<u32, _>(DISABLE_TELEMETRY_POLICY))
+                .map_or(false, |value| value != 0)
+        })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_policy_disables_telemetry() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var_disables_telemetry() {
+        assert!(env_var_disables_telemetry("1"));
+        assert!(env_var_disables_telemetry("true"));
+        assert!(env_var_disables_telemetry("yes"));
+        assert!(!env_var_disables_telemetry(""));
+        assert!(!env_var_disables_telemetry("0"));
+        assert!(!env_var_disables_telemetry(" FALSE "));
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(target_os = "macos")]
+use smol::process::Command;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_percentage: f32,
+    /// The percentage of its maximum speed that the CPU is allowed to run at,
+    /// lowered by the OS when the machine is too hot.
+    pub cpu_speed_limit: Option<u32>,
+}
+
+/// Samples the battery and thermal state through `pmset`, returning `None`
+/// on machines without a battery.
+pub async fn state() -> Option<PowerState> {
+    let battery_output = pmset(&["-g", "batt"]).await?;
+    let thermal_output = pmset(&["-g", "therm"]).await.unwrap_or_default();
+    parse_state(&battery_output, &thermal_output)
+}
+
+#[cfg(target_os = "macos")]
+async fn pmset(args: &[&str]) -> Option<String> {
+    let output = Command::new("/usr/bin/pmset")
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `pmset` is only available on macOS.
+#[cfg(not(target_os = "macos"))]
+async fn pmset(_args: &[&str]) -> Option<String> {
+    None
+}
+
+fn parse_state(battery_output: &str, thermal_output: &str) -> Option<PowerState> {
+    let battery_percentage = battery_output
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|word| word.strip_suffix('%')?.parse().ok())?;
+    let on_battery = battery_output.contains("'Battery Power'");
+    let cpu_speed_limit = thermal_output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() == "CPU_Speed_Limit" {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    });
+
+    Some(PowerState {
+        on_battery,
+        battery_percentage,
+        cpu_speed_limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        let battery_output = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t87%; discharging; 6:12 remaining present: true\n";
+        let thermal_output = "Note: No thermal warning level has been recorded\n\tCPU_Scheduler_Limit \t= 100\n\tCPU_Available_CPUs \t= 10\n\tCPU_Speed_Limit \t= 80\n";
+
+        assert_eq!(
+            parse_state(battery_output, thermal_output),
+            Some(PowerState {
+                on_battery: true,
+                battery_percentage: 87.,
+                cpu_speed_limit: Some(80),
+            })
+        );
+        assert_eq!(parse_state("Now drawing from 'AC Power'\n", ""), None);
+    }
+}
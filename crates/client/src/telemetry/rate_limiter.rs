@@ -0,0 +1,101 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// How many events of a single type can be reported in a burst.
+const BURST_SIZE: f64 = 60.;
+/// How quickly the allowance for each event type recovers after a burst.
+const REFILL_INTERVAL: Duration = Duration::from_secs(1);
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Limits how often each type of event can be reported, counting the events it sheds.
+pub struct RateLimiter {
+    buckets: HashMap<&'static str, TokenBucket>,
+    shed_counts: BTreeMap<&'static str, usize>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            buckets: HashMap::default(),
+            shed_counts: BTreeMap::default(),
+        }
+    }
+
+    /// Takes a token from the bucket for `event_type`, returning whether the event may be reported.
+    pub fn allow(&mut self, event_type: &'static str, now: Instant) -> bool {
+        let bucket = self.buckets.entry(event_type).or_insert(TokenBucket {
+            tokens: BURST_SIZE,
+            last_refill: now,
+        });
+
+        let refilled = now
+            .saturating_duration_since(bucket.last_refill)
+            .as_secs_f64()
+            / REFILL_INTERVAL.as_secs_f64();
+        bucket.tokens = (bucket.tokens + refilled).min(BURST_SIZE);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1. {
+            bucket.tokens -= 1.;
+            true
+        } else {
+            *self.shed_counts.entry(event_type).or_default() += 1;
+            false
+        }
+    }
+
+    /// Returns how many events of each type were shed since the last call.
+    pub fn take_shed_counts(&mut self) -> BTreeMap<&'static str, usize> {
+        std::mem::take(&mut self.shed_counts)
+    }
+
+    /// Adds back shed counts that couldn't be reported, so they are included in a later batch.
+    pub fn restore_shed_counts(&mut self, shed_counts: BTreeMap<&'static str, usize>) {
+        for (event_type, count) in shed_counts {
+            *self.shed_counts.entry(event_type).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut rate_limiter = RateLimiter::new();
+        let now = Instant::now();
+
+        for _ in 0..BURST_SIZE as usize {
+            assert!(rate_limiter.allow("Editor", now));
+        }
+        assert!(!rate_limiter.allow("Editor", now));
+        assert!(!rate_limiter.allow("Editor", now));
+
+        // Each event type has its own allowance
+        assert!(rate_limiter.allow("Action", now));
+
+        // Tokens are refilled over time
+        let later = now + REFILL_INTERVAL * 2;
+        assert!(rate_limiter.allow("Editor", later));
+        assert!(rate_limiter.allow("Editor", later));
+        assert!(!rate_limiter.allow("Editor", later));
+
+        let shed_counts = rate_limiter.take_shed_counts();
+        assert_eq!(shed_counts, BTreeMap::from_iter([("Editor", 3)]));
+        assert!(rate_limiter.take_shed_counts().is_empty());
+
+        rate_limiter.restore_shed_counts(shed_counts);
+        assert!(!rate_limiter.allow("Editor", later));
+        assert_eq!(
+            rate_limiter.take_shed_counts(),
+            BTreeMap::from_iter([("Editor", 4)])
+        );
+    }
+}
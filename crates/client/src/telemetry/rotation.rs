@@ -0,0 +1,76 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use db::kvp::KEY_VALUE_STORE;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The key the installation id is stored under when Zed launches.
+const INSTALLATION_ID_KEY: &str = "installation_id";
+const ROTATED_AT_KEY: &str = "installation_id_rotated_at";
+
+/// Replaces the stored installation id with a new random one once `cadence` has elapsed
+/// since it was last replaced, returning the new id.
+///
+/// The first call only starts the clock, so turning rotation on doesn't immediately
+/// change the id.
+pub(super) async fn rotate_if_due(cadence: Duration, now: DateTime<Utc>) -> Result<Option<String>> {
+    let rotated_at = KEY_VALUE_STORE
+        .read_kvp(ROTATED_AT_KEY)?
+        .and_then(|rotated_at| DateTime::parse_from_rfc3339(&rotated_at).ok())
+        .map(|rotated_at| rotated_at.with_timezone(&Utc));
+    if let Some(rotated_at) = rotated_at {
+        if !is_due(rotated_at, cadence, now) {
+            return Ok(None);
+        }
+    } else {
+        KEY_VALUE_STORE
+            .write_kvp(ROTATED_AT_KEY.to_string(), now.to_rfc3339())
+            .await?;
+        return Ok(None);
+    }
+
+    let installation_id = Uuid::new_v4().to_string();
+    KEY_VALUE_STORE
+        .write_kvp(INSTALLATION_ID_KEY.to_string(), installation_id.clone())
+        .await?;
+    KEY_VALUE_STORE
+        .write_kvp(ROTATED_AT_KEY.to_string(), now.to_rfc3339())
+        .await?;
+    Ok(Some(installation_id))
+}
+
+fn is_due(rotated_at: DateTime<Utc>, cadence: Duration, now: DateTime<Utc>) -> bool {
+    chrono::Duration::from_std(cadence).map_or(false, |cadence| now - rotated_at >= cadence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_rotate_if_due() {
+        let cadence = Duration::from_secs(30 * 24 * 60 * 60);
+        let now = Utc::now();
+
+        assert_eq!(rotate_if_due(cadence, now).await.unwrap(), None);
+        assert_eq!(
+            rotate_if_due(cadence, now + chrono::Duration::days(29))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let rotated_at = now + chrono::Duration::days(30);
+        let installation_id = rotate_if_due(cadence, rotated_at).await.unwrap().unwrap();
+        assert_eq!(
+            KEY_VALUE_STORE.read_kvp(INSTALLATION_ID_KEY).unwrap(),
+            Some(installation_id)
+        );
+        assert_eq!(
+            rotate_if_due(cadence, rotated_at + chrono::Duration::days(1))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
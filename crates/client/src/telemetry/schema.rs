@@ -0,0 +1,160 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Map, Value};
+
+/// The version of the request body and event shapes that this client sends.
+///
+/// Bump this whenever the shape of `EventRequestBody` or `Event` changes in a way
+/// the server needs to know about, and add a migration for bodies spooled by
+/// earlier versions of Zed.
+pub const CURRENT_VERSION: u32 = 4;
+
+/// Migrations between consecutive schema versions, starting from version 1.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] =
+    &[migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4];
+
+/// Upgrades a request body written by any earlier version of Zed to the current schema.
+/// Bodies from newer versions of Zed are left untouched.
+pub fn migrate(request_body: Value) -> Result<Value> {
+    let Value::Object(mut request_body) = request_body else {
+        return Err(anyhow!("telemetry request body is not an object"));
+    };
+
+    // Request bodies only started carrying a version in version 2.
+    let mut version = request_body
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(1) as u32;
+    while version < CURRENT_VERSION {
+        let migration = (version as usize)
+            .checked_sub(1)
+            .and_then(|index| MIGRATIONS.get(index))
+            .ok_or_else(|| anyhow!("no telemetry schema migration from version {version}"))?;
+        migration(&mut request_body);
+        version += 1;
+        request_body.insert("schema_version".into(), version.into());
+    }
+
+    Ok(Value::Object(request_body))
+}
+
+/// Version 1 bodies have the same shape as version 2, which only adds the version itself.
+fn migrate_v1_to_v2(_: &mut Map<String, Value>) {}
+
+/// Version 3 moves the hardware fields into a nested `system_specs` object, including the
+/// core count that version 2 sent with every CPU event.
+fn migrate_v2_to_v3(request_body: &mut Map<String, Value>) {
+    let mut system_specs = Map::new();
+    for key in ["os_name", "os_version", "architecture"] {
+        if let Some(value) = request_body.remove(key) {
+            system_specs.insert(key.into(), value);
+        }
+    }
+
+    if let Some(Value::Array(events)) = request_body.get_mut("events") {
+        for event in events {
+            if let Some(core_count) = event
+                .as_object_mut()
+                .and_then(|event| event.remove("core_count"))
+            {
+                system_specs.insert("core_count".into(), core_count);
+            }
+        }
+    }
+
+    request_body.insert("system_specs".into(), Value::Object(system_specs));
+}
+
+/// Version 4 summarizes CPU and memory samples over a window, where version 3 sent each
+/// sample as its own event. Each of those becomes a summary of a single sample.
+fn migrate_v3_to_v4(request_body: &mut Map<String, Value>) {
+    let Some(Value::Array(events)) = request_body.get_mut("events") else {
+        return;
+    };
+
+    for event in events {
+        let Some(event) = event.as_object_mut() else {
+            continue;
+        };
+        let fields: &[&str] = match event.get("type").and_then(Value::as_str) {
+            Some("Cpu") => &["usage_as_percentage"],
+            Some("Memory") => &["memory_in_bytes", "virtual_memory_in_bytes"],
+            _ => continue,
+        };
+
+        for field in fields {
+            if let Some(value) = event.get(*field).cloned() {
+                let summary = json!({ "min": value, "avg": value, "max": value, "p95": value });
+                event.insert(field.to_string(), summary);
+            }
+        }
+        event.insert("sample_count".into(), 1.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        assert_eq!(
+            migrate(json!({ "session_id": "a", "events": [] })).unwrap(),
+            json!({ "session_id": "a", "events": [], "schema_version": 4, "system_specs": {} })
+        );
+        assert_eq!(
+            migrate(json!({
+                "schema_version": 2,
+                "os_name": "macOS",
+                "architecture": "aarch64",
+                "events": [{ "type": "Cpu", "usage_as_percentage": 10.0, "core_count": 8 }],
+            }))
+            .unwrap(),
+            json!({
+                "schema_version": 4,
+                "events": [{
+                    "type": "Cpu",
+                    "sample_count": 1,
+                    "usage_as_percentage": { "min": 10.0, "avg": 10.0, "max": 10.0, "p95": 10.0 },
+                }],
+                "system_specs": { "os_name": "macOS", "architecture": "aarch64", "core_count": 8 },
+            })
+        );
+        assert_eq!(
+            migrate(json!({
+                "schema_version": 3,
+                "events": [
+                    { "type": "Memory", "memory_in_bytes": 1024, "virtual_memory_in_bytes": 2048 },
+                    { "type": "App", "operation": "open" },
+                ],
+            }))
+            .unwrap(),
+            json!({
+                "schema_version": 4,
+                "events": [
+                    {
+                        "type": "Memory",
+                        "sample_count": 1,
+                        "memory_in_bytes": { "min": 1024, "avg": 1024, "max": 1024, "p95": 1024 },
+                        "virtual_memory_in_bytes": {
+                            "min": 2048,
+                            "avg": 2048,
+                            "max": 2048,
+                            "p95": 2048,
+                        },
+                    },
+                    { "type": "App", "operation": "open" },
+                ],
+            })
+        );
+        assert_eq!(
+            migrate(json!({ "schema_version": 4, "events": [] })).unwrap(),
+            json!({ "schema_version": 4, "events": [] })
+        );
+        assert_eq!(
+            migrate(json!({ "schema_version": 99, "events": [] })).unwrap(),
+            json!({ "schema_version": 99, "events": [] })
+        );
+        assert!(migrate(json!([])).is_err());
+        assert!(migrate(json!({ "schema_version": 0, "events": [] })).is_err());
+    }
+}
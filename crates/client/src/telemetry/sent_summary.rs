@@ -0,0 +1,73 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path};
+
+/// A running tally of the telemetry that was accepted by the server, kept on disk so that
+/// users can check what has been sent across sessions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SentSummary {
+    /// When the first batch counted here was sent.
+    pub since: Option<DateTime<Utc>>,
+    pub last_sent_at: Option<DateTime<Utc>>,
+    pub batch_count: usize,
+    pub bytes_sent: u64,
+    /// How many events of each type were sent.
+    pub event_counts: BTreeMap<String, usize>,
+}
+
+impl SentSummary {
+    /// Counts a request body that was sent, in Zed's own format.
+    pub fn record_batch(&mut self, request_body_json: &[u8], sent_at: DateTime<Utc>) {
+        self.since.get_or_insert(sent_at);
+        self.last_sent_at = Some(sent_at);
+        self.batch_count += 1;
+        self.bytes_sent += request_body_json.len() as u64;
+
+        let request_body = serde_json::from_slice::<Value>(request_body_json).unwrap_or_default();
+        if let Some(Value::Array(events)) = request_body.get("events") {
+            for event in events {
+                let event_type = event
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown");
+                *self.event_counts.entry(event_type.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Adds the counts of `other`, e.g. for batches sent before the summary was loaded from disk.
+    pub fn merge(&mut self, other: SentSummary) {
+        self.since = self.since.into_iter().chain(other.since).min();
+        self.last_sent_at = self
+            .last_sent_at
+            .into_iter()
+            .chain(other.last_sent_at)
+            .max();
+        self.batch_count += other.batch_count;
+        self.bytes_sent += other.bytes_sent;
+        for (event_type, count) in other.event_counts {
+            *self.event_counts.entry(event_type).or_default() += count;
+        }
+    }
+}
+
+pub fn load(path: &Path) -> Result<SentSummary> {
+    match fs::read(path) {
+        Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(SentSummary::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn save(path: &Path, summary: &SentSummary) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_vec(summary)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
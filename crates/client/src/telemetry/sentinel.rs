@@ -0,0 +1,93 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind, path::Path};
+
+/// Written while Zed is running and removed when it exits cleanly, so that finding it on
+/// launch means the previous session ended without reaching a shutdown or panic hook.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sentinel {
+    pub launched_at: DateTime<Utc>,
+    /// When the readings below were last taken.
+    pub updated_at: DateTime<Utc>,
+    pub memory_in_bytes: Option<u64>,
+    pub cpu_usage_as_percentage: Option<f32>,
+}
+
+impl Sentinel {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            launched_at: now,
+            updated_at: now,
+            memory_in_bytes: None,
+            cpu_usage_as_percentage: None,
+        }
+    }
+
+    /// How long the session had been running when the sentinel was last updated.
+    pub fn uptime_in_milliseconds(&self) -> u64 {
+        (self.updated_at - self.launched_at)
+            .num_milliseconds()
+            .max(0) as u64
+    }
+}
+
+/// Replaces the sentinel, writing it to a temporary file first so it is never left half-written.
+pub fn write(path: &Path, sentinel: &Sentinel) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_vec(sentinel)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Reads and removes the sentinel left by the previous session, if there is one.
+pub fn take(path: &Path) -> Result<Option<Sentinel>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    fs::remove_file(path)?;
+    Ok(Some(serde_json::from_slice(&contents)?))
+}
+
+pub fn remove(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_write_and_take() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry").join("sentinel.json");
+        assert_eq!(take(&path).unwrap(), None);
+
+        let launched_at = Utc::now();
+        let mut sentinel = Sentinel::new(launched_at);
+        write(&path, &sentinel).unwrap();
+        sentinel.updated_at = launched_at + Duration::seconds(90);
+        sentinel.memory_in_bytes = Some(1024);
+        write(&path, &sentinel).unwrap();
+
+        let taken = take(&path).unwrap().unwrap();
+        assert_eq!(taken.uptime_in_milliseconds(), 90_000);
+        assert_eq!(taken.memory_in_bytes, Some(1024));
+        assert_eq!(take(&path).unwrap(), None);
+
+        write(&path, &sentinel).unwrap();
+        remove(&path).unwrap();
+        remove(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
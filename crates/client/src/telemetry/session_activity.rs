@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+/// Input events further apart than this are treated as the user having been idle in between.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+/// Splits the time Zed is open into time spent actively using it and time it was left idle,
+/// based on when input events arrive and whether any window is focused.
+pub struct SessionActivity {
+    period_started_at: Instant,
+    last_input_at: Option<Instant>,
+    active: Duration,
+    window_active: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ActivitySummary {
+    pub active: Duration,
+    pub idle: Duration,
+}
+
+impl SessionActivity {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            period_started_at: now,
+            last_input_at: None,
+            active: Duration::ZERO,
+            window_active: true,
+        }
+    }
+
+    pub fn record_input(&mut self, now: Instant) {
+        if !self.window_active {
+            return;
+        }
+
+        self.close_active_span(now);
+        self.last_input_at = Some(now);
+    }
+
+    pub fn set_window_active(&mut self, window_active: bool, now: Instant) {
+        if !window_active {
+            self.close_active_span(now);
+            self.last_input_at = None;
+        }
+        self.window_active = window_active;
+    }
+
+    /// Returns how much of the time since the summary was last taken was active and idle.
+    pub fn take_summary(&mut self, now: Instant) -> ActivitySummary {
+        self.close_active_span(now);
+        let total = now.saturating_duration_since(self.period_started_at);
+        let active = std::mem::take(&mut self.active).min(total);
+        self.period_started_at = now;
+        ActivitySummary {
+            active,
+            idle: total - active,
+        }
+    }
+
+    /// Counts the time since the last input as active, up to the idle timeout.
+    fn close_active_span(&mut self, now: Instant) {
+        if let Some(last_input_at) = self.last_input_at {
+            let start = last_input_at.max(self.period_started_at);
+            self.active += now.saturating_duration_since(start).min(IDLE_TIMEOUT);
+            self.last_input_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_activity() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut activity = SessionActivity::new(start);
+
+        // A minute of typing, followed by ten minutes without input
+        activity.record_input(at(0));
+        activity.record_input(at(30));
+        activity.record_input(at(60));
+        activity.record_input(at(660));
+        assert_eq!(
+            activity.take_summary(at(660)),
+            ActivitySummary {
+                active: Duration::from_secs(60) + IDLE_TIMEOUT,
+                idle: Duration::from_secs(600) - IDLE_TIMEOUT,
+            }
+        );
+
+        // Time spent in other apps is idle, even shortly after typing
+        activity.record_input(at(670));
+        activity.set_window_active(false, at(680));
+        activity.record_input(at(690));
+        activity.set_window_active(true, at(700));
+        activity.record_input(at(710));
+        assert_eq!(
+            activity.take_summary(at(720)),
+            ActivitySummary {
+                active: Duration::from_secs(30),
+                idle: Duration::from_secs(30),
+            }
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use super::otlp;
+use crate::TelemetrySettings;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use futures::{future::BoxFuture, FutureExt};
+use serde_json::Value;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use util::http::HttpClient;
+
+/// How long events are batched for before being sent to an OpenTelemetry collector.
+pub(super) const OTLP_COLLECTOR_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A destination that events are delivered to in addition to the configured endpoint. Each sink
+/// has its own queue, is enabled independently, and is flushed on its own schedule.
+pub trait TelemetrySink: Send + Sync + 'static {
+    /// A short name for the sink, used in logs and the telemetry inspector.
+    fn name(&self) -> &'static str;
+
+    /// Whether events should currently be queued for this sink.
+    fn is_enabled(&self, settings: &TelemetrySettings) -> bool;
+
+    /// How long events are batched for before they are delivered.
+    fn flush_interval(&self) -> Duration;
+
+    /// Delivers a request body in Zed's own format, containing one or more events.
+    fn deliver(
+        &self,
+        request_body: Value,
+        settings: &TelemetrySettings,
+    ) -> BoxFuture<'static, Result<()>>;
+}
+
+/// Writes each event as a single NDJSON line to a local file, see `telemetry.local_log`.
+pub struct LocalLogSink {
+    path: Option<PathBuf>,
+}
+
+impl LocalLogSink {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+}
+
+impl TelemetrySink for LocalLogSink {
+    fn name(&self) -> &'static str {
+        "local log"
+    }
+
+    fn is_enabled(&self, settings: &TelemetrySettings) -> bool {
+        settings.local_log
+    }
+
+    fn flush_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn deliver(
+        &self,
+        request_body: Value,
+        _: &TelemetrySettings,
+    ) -> BoxFuture<'static, Result<()>> {
+        // Appends are small, so they're written right away rather than on the executor,
+        // which lets events that are flushed while panicking reach the file.
+        let result = match &self.path {
+            Some(path) => append_events(path, request_body),
+            None => Ok(()),
+        };
+        futures::future::ready(result).boxed()
+    }
+}
+
+fn append_events(path: &Path, request_body: Value) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut json_bytes = Vec::new();
+    if let Some(Value::Array(events)) = request_body.get("events") {
+        for event in events {
+            serde_json::to_writer(&mut json_bytes, event)?;
+            json_bytes.push(b'\n');
+        }
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&json_bytes)?;
+    Ok(())
+}
+
+/// Sends events as OTLP/HTTP logs to the collector in `telemetry.otlp_collector_url`.
+pub struct OtlpCollectorSink {
+    http_client: Arc<dyn HttpClient>,
+}
+
+impl OtlpCollectorSink {
+    pub fn new(http_client: Arc<dyn HttpClient>) -> Self {
+        Self { http_client }
+    }
+}
+
+impl TelemetrySink for OtlpCollectorSink {
+    fn name(&self) -> &'static str {
+        "otlp collector"
+    }
+
+    fn is_enabled(&self, settings: &TelemetrySettings) -> bool {
+        settings.otlp_collector_url.is_some() && !settings.local_log
+    }
+
+    fn flush_interval(&self) -> Duration {
+        OTLP_COLLECTOR_FLUSH_INTERVAL
+    }
+
+    fn deliver(
+        &self,
+        request_body: Value,
+        settings: &TelemetrySettings,
+    ) -> BoxFuture<'static, Result<()>> {
+        let http_client = self.http_client.clone();
+        let url = settings
+            .otlp_collector_url
+            .clone()
+            .unwrap_or_else(|| otlp::DEFAULT_LOGS_URL.to_string());
+        async move {
+            let json_bytes = serde_json::to_vec(&otlp::logs_request(request_body, Utc::now()))?;
+            let response = http_client.post_json(&url, json_bytes.into()).await?;
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(anyhow!("request failed with status {}", response.status()))
+            }
+        }
+        .boxed()
+    }
+}
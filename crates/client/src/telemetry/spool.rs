@@ -0,0 +1,93 @@
+use anyhow::Result;
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+/// Appends a serialized request body to the spool as a single NDJSON line.
+pub fn append(path: &Path, request_body_json: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(request_body_json)?;
+    file.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads every spooled request body and removes the spool, so that each body
+/// is only ever replayed once.
+pub fn take(path: &Path) -> Result<Vec<String>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    fs::remove_file(path)?;
+
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Removes a single spooled request body, deleting the spool once it is empty.
+pub fn remove(path: &Path, request_body_json: &[u8]) -> Result<()> {
+    let request_body_json = String::from_utf8_lossy(request_body_json);
+    let mut request_bodies = take(path)?;
+    if let Some(ix) = request_bodies
+        .iter()
+        .position(|request_body| *request_body == request_body_json)
+    {
+        request_bodies.remove(ix);
+    }
+
+    for request_body in request_bodies {
+        append(path, request_body.as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_take() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spool").join("telemetry.ndjson");
+
+        assert!(take(&path).unwrap().is_empty());
+
+        append(&path, br#"{"events":[1]}"#).unwrap();
+        append(&path, br#"{"events":[2]}"#).unwrap();
+
+        assert_eq!(
+            take(&path).unwrap(),
+            vec![
+                r#"{"events":[1]}"#.to_string(),
+                r#"{"events":[2]}"#.to_string()
+            ]
+        );
+        assert!(!path.exists());
+        assert!(take(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry.ndjson");
+
+        append(&path, br#"{"events":[1]}"#).unwrap();
+        append(&path, br#"{"events":[2]}"#).unwrap();
+
+        remove(&path, br#"{"events":[1]}"#).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"events\":[2]}\n");
+
+        remove(&path, br#"{"events":[2]}"#).unwrap();
+        assert!(!path.exists());
+    }
+}
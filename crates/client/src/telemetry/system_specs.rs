@@ -0,0 +1,67 @@
+use gpui::AppContext;
+use serde::Serialize;
+use smol::process::Command;
+use std::env;
+use sysinfo::{CpuExt, CpuRefreshKind, RefreshKind, System, SystemExt};
+
+/// The hardware and operating system Zed is running on, sent once per request body.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SystemSpecs {
+    pub os_name: &'static str,
+    pub os_version: Option<String>,
+    pub os_build: Option<String>,
+    pub architecture: &'static str,
+    pub cpu_model: Option<String>,
+    pub core_count: u32,
+    pub total_memory_in_bytes: u64,
+    pub gpu: Option<String>,
+    pub display_scale_factors: Vec<f32>,
+}
+
+impl SystemSpecs {
+    /// Collects the specs that are cheap to query. The OS build and GPU need to run other
+    /// processes, and are filled in later by [`SystemSpecs::load_slow_specs`].
+    pub fn new(cx: &AppContext) -> Self {
+        let system = System::new_with_specifics(
+            RefreshKind::new()
+                .with_cpu(CpuRefreshKind::new())
+                .with_memory(),
+        );
+        let app_metadata = cx.app_metadata();
+
+        Self {
+            os_name: app_metadata.os_name,
+            os_version: app_metadata
+                .os_version
+                .map(|os_version| os_version.to_string()),
+            os_build: None,
+            architecture: env::consts::ARCH,
+            cpu_model: system
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string())
+                .filter(|brand| !brand.is_empty()),
+            core_count: system.cpus().len() as u32,
+            total_memory_in_bytes: system.total_memory(),
+            gpu: None,
+            display_scale_factors: cx
+                .displays()
+                .iter()
+                .map(|display| display.scale_factor())
+                .collect(),
+        }
+    }
+
+    /// Returns the OS build and GPU model, if they can be determined.
+    pub async fn load_slow_specs() -> (Option<String>, Option<String>) {
+        let os_build = Command::new("/usr/bin/sw_vers")
+            .arg("-buildVersion")
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|os_build| !os_build.is_empty());
+        (os_build, super::gpu::model().await)
+    }
+}
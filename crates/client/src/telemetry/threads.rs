@@ -0,0 +1,205 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use sysinfo::Process;
+
+/// How many groups of threads are included in each Cpu event.
+const MAX_THREAD_GROUPS: usize = 5;
+
+/// The CPU usage of the threads sharing a name, see [`ThreadSampler::sample`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ThreadGroupUsage {
+    pub name: String,
+    pub thread_count: usize,
+    pub usage_as_percentage: f32,
+}
+
+struct ThreadUsage {
+    name: String,
+    is_main: bool,
+    usage_as_percentage: f32,
+}
+
+/// Samples the CPU usage of Zed's own threads, so that usage can be attributed to the
+/// main thread, the background executor's pool, or the threads of other subsystems.
+pub struct ThreadSampler {
+    #[cfg(target_os = "macos")]
+    main_thread_handle: u64,
+}
+
+impl ThreadSampler {
+    /// Must be called on the main thread, which is how it is told apart from the others.
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "macos")]
+            main_thread_handle: unsafe { libc::pthread_self() as usize as u64 },
+        }
+    }
+
+    /// Returns the busiest groups of threads, where threads are grouped by their name with
+    /// any trailing index removed, e.g. the `sqlez worker 0` and `sqlez worker 1` threads.
+    pub fn sample(&self, process: &Process) -> Vec<ThreadGroupUsage> {
+        group_threads(self.threads(process))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn threads(&self, _: &Process) -> Vec<ThreadUsage> {
+        use std::{ffi::CStr, mem};
+
+        /// `pth_cpu_usage` is scaled so that this means a whole core.
+        const TH_USAGE_SCALE: f32 = 1000.;
+
+        let pid = std::process::id() as libc::c_int;
+        let mut handles = vec![0u64; 1024];
+        let len = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDLISTTHREADS,
+                0,
+                handles.as_mut_ptr().cast(),
+                (handles.len() * mem::size_of::<u64>()) as libc::c_int,
+            )
+        };
+        if len <= 0 {
+            return Vec::new();
+        }
+        handles.truncate(len as usize / mem::size_of::<u64>());
+
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                let mut info = unsafe { mem::zeroed::<libc::proc_threadinfo>() };
+                let size = mem::size_of::<libc::proc_threadinfo>() as libc::c_int;
+                let len = unsafe {
+                    libc::proc_pidinfo(
+                        pid,
+                        libc::PROC_PIDTHREADINFO,
+                        handle,
+                        (&mut info as *mut libc::proc_threadinfo).cast(),
+                        size,
+                    )
+                };
+                if len != size {
+                    return None;
+                }
+
+                let name = unsafe { CStr::from_ptr(info.pth_name.as_ptr()) };
+                Some(ThreadUsage {
+                    name: name.to_string_lossy().into_owned(),
+                    is_main: handle == self.main_thread_handle,
+                    usage_as_percentage: info.pth_cpu_usage as f32 / TH_USAGE_SCALE * 100.,
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn threads(&self, process: &Process) -> Vec<ThreadUsage> {
+        use sysinfo::{PidExt, ProcessExt};
+
+        process
+            .tasks
+            .iter()
+            .map(|(tid, task)| ThreadUsage {
+                name: task.name().to_string(),
+                is_main: tid.as_u32() == std::process::id(),
+                usage_as_percentage: task.cpu_usage(),
+            })
+            .collect()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    fn threads(&self, _: &Process) -> Vec<ThreadUsage> {
+        Vec::new()
+    }
+}
+
+fn group_threads(threads: Vec<ThreadUsage>) -> Vec<ThreadGroupUsage> {
+    let mut groups = HashMap::<String, ThreadGroupUsage>::default();
+    for thread in threads {
+        let name = if thread.is_main {
+            "main".to_string()
+        } else {
+            let name = thread
+                .name
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .trim_end_matches(|c: char| c == ' ' || c == '-' || c == '_' || c == '#');
+            if name.is_empty() {
+                "unnamed".to_string()
+            } else {
+                name.to_string()
+            }
+        };
+
+        let group = groups.entry(name.clone()).or_insert(ThreadGroupUsage {
+            name,
+            thread_count: 0,
+            usage_as_percentage: 0.,
+        });
+        group.thread_count += 1;
+        group.usage_as_percentage += thread.usage_as_percentage;
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by(|a, b| {
+        b.usage_as_percentage
+            .total_cmp(&a.usage_as_percentage)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups.truncate(MAX_THREAD_GROUPS);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_threads() {
+        let thread = |name: &str, is_main, usage_as_percentage| ThreadUsage {
+            name: name.to_string(),
+            is_main,
+            usage_as_percentage,
+        };
+        let groups = group_threads(vec![
+            thread("", true, 30.),
+            thread("", false, 5.),
+            thread("", false, 10.),
+            thread("sqlez worker 0", false, 1.),
+            thread("sqlez worker 1", false, 2.),
+            thread("fs-watcher", false, 0.5),
+            thread("a", false, 0.),
+            thread("b", false, 0.),
+        ]);
+
+        assert_eq!(
+            groups,
+            [
+                ThreadGroupUsage {
+                    name: "main".into(),
+                    thread_count: 1,
+                    usage_as_percentage: 30.,
+                },
+                ThreadGroupUsage {
+                    name: "unnamed".into(),
+                    thread_count: 2,
+                    usage_as_percentage: 15.,
+                },
+                ThreadGroupUsage {
+                    name: "sqlez worker".into(),
+                    thread_count: 2,
+                    usage_as_percentage: 3.,
+                },
+                ThreadGroupUsage {
+                    name: "fs-watcher".into(),
+                    thread_count: 1,
+                    usage_as_percentage: 0.5,
+                },
+                ThreadGroupUsage {
+                    name: "a".into(),
+                    thread_count: 1,
+                    usage_as_percentage: 0.,
+                },
+            ]
+        );
+    }
+}
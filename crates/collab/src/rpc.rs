@@ -228,6 +228,20 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(
+                forward_read_only_project_request::<proto::CallHierarchyIncomingCalls>,
+            )
+            .add_request_handler(
+                forward_read_only_project_request::<proto::CallHierarchyOutgoingCalls>,
+            )
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(
+                forward_read_only_project_request::<proto::TypeHierarchySupertypes>,
+            )
+            .add_request_handler(forward_read_only_project_request::<proto::TypeHierarchySubtypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetCodeLenses>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetLinkedEditingRanges>)
             .add_request_handler(forward_mutating_project_request::<proto::GetCompletions>)
             .add_request_handler(
                 forward_mutating_project_request::<proto::ApplyCompletionAdditionalEdits>,
@@ -237,6 +251,7 @@ impl Server {
             )
             .add_request_handler(forward_mutating_project_request::<proto::GetCodeActions>)
             .add_request_handler(forward_mutating_project_request::<proto::ApplyCodeAction>)
+            .add_request_handler(forward_mutating_project_request::<proto::ExecuteCodeLens>)
             .add_request_handler(forward_mutating_project_request::<proto::PrepareRename>)
             .add_request_handler(forward_mutating_project_request::<proto::PerformRename>)
             .add_request_handler(forward_mutating_project_request::<proto::ReloadBuffers>)
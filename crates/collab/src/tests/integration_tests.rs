@@ -19,7 +19,8 @@ use language::{
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
 use project::{
-    search::SearchQuery, DiagnosticSummary, FormatTrigger, HoverBlockKind, Project, ProjectPath,
+    search::SearchQuery, CallHierarchyDirection, DiagnosticSummary, FormatTrigger, HoverBlockKind,
+    Project, ProjectPath, TypeHierarchyDirection,
 };
 use rand::prelude::*;
 use rpc::proto::ChannelRole;
@@ -4737,6 +4738,485 @@ async fn test_references(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_call_hierarchy(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Set up a fake language server.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+    client_a.language_registry().add(Arc::new(language));
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root",
+            json!({
+                "one.rs": "fn one() {}",
+                "two.rs": "fn two() { one::one() }",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/root", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Open the file on client B.
+    let open_buffer = project_b.update(cx_b, |p, cx| p.open_buffer((worktree_id, "one.rs"), cx));
+    let buffer_b = cx_b.executor().spawn(open_buffer).await.unwrap();
+
+    let item = |name: &str, path: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let one = item(
+        "one",
+        "/root/one.rs",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+    );
+    let two = item(
+        "two",
+        "/root/two.rs",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+    );
+
+    // Request the call hierarchy of a symbol as the guest.
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    fake_language_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let one = one.clone();
+        move |params, _| {
+            assert_eq!(
+                params
+                    .text_document_position_params
+                    .text_document
+                    .uri
+                    .as_str(),
+                "file:///root/one.rs"
+            );
+            let one = one.clone();
+            async move { Ok(Some(vec![one])) }
+        }
+    });
+    fake_language_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| {
+            assert_eq!(params.item.name, "one");
+            let two = two.clone();
+            async move {
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: two,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 16),
+                        lsp::Position::new(0, 19),
+                    )],
+                }]))
+            }
+        },
+    );
+
+    let items = project_b
+        .update(cx_b, |p, cx| p.prepare_call_hierarchy(&buffer_b, 3, cx))
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "one");
+    assert_eq!(items[0].location.buffer, buffer_b);
+
+    let calls = project_b
+        .update(cx_b, |p, cx| {
+            p.call_hierarchy_calls(&items[0], CallHierarchyDirection::Incoming, cx)
+        })
+        .await
+        .unwrap();
+    cx_b.read(|cx| {
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].item.name, "two");
+
+        let two_buffer = calls[0].item.location.buffer.read(cx);
+        assert_eq!(
+            two_buffer.file().unwrap().path().as_ref(),
+            Path::new("two.rs")
+        );
+        assert_eq!(calls[0].item.location.range.to_offset(two_buffer), 3..6);
+        assert_eq!(calls[0].call_locations.len(), 1);
+        assert_eq!(
+            calls[0].call_locations[0].buffer,
+            calls[0].item.location.buffer
+        );
+        assert_eq!(
+            calls[0].call_locations[0].range.to_offset(two_buffer),
+            16..19
+        );
+    });
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_type_hierarchy(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    // The requests aren't exposed by the project, so they're declared here, with untyped
+    // parameters and results.
+    enum PrepareTypeHierarchy {}
+
+    impl lsp::request::Request for PrepareTypeHierarchy {
+        type Params = serde_json::Value;
+        type Result = serde_json::Value;
+        const METHOD: &'static str = "textDocument/prepareTypeHierarchy";
+    }
+
+    enum TypeHierarchySupertypes {}
+
+    impl lsp::request::Request for TypeHierarchySupertypes {
+        type Params = serde_json::Value;
+        type Result = serde_json::Value;
+        const METHOD: &'static str = "typeHierarchy/supertypes";
+    }
+
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Set up a fake language server.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language.set_fake_lsp_adapter(Default::default()).await;
+    client_a.language_registry().add(Arc::new(language));
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root",
+            json!({
+                "dog.rs": "struct Dog; impl Animal for Dog {}",
+                "animal.rs": "trait Animal {}",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/root", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Open the file on client B.
+    let open_buffer = project_b.update(cx_b, |p, cx| p.open_buffer((worktree_id, "dog.rs"), cx));
+    let buffer_b = cx_b.executor().spawn(open_buffer).await.unwrap();
+
+    let item = |name: &str, kind: lsp::SymbolKind, path: &str, start: u32, end: u32| {
+        let range = lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end));
+        json!({
+            "name": name,
+            "kind": kind,
+            "uri": lsp::Url::from_file_path(path).unwrap(),
+            "range": range,
+            "selectionRange": range,
+            "data": { "id": name },
+        })
+    };
+    let dog = item("Dog", lsp::SymbolKind::STRUCT, "/root/dog.rs", 7, 10);
+    let animal = item(
+        "Animal",
+        lsp::SymbolKind::INTERFACE,
+        "/root/animal.rs",
+        6,
+        12,
+    );
+
+    // Request the supertypes of a type as the guest.
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    fake_language_server.handle_request::<PrepareTypeHierarchy, _, _>(move |params, _| {
+        assert_eq!(params["textDocument"]["uri"], "file:///root/dog.rs");
+        let dog = dog.clone();
+        async move { Ok(json!([dog])) }
+    });
+    fake_language_server.handle_request::<TypeHierarchySupertypes, _, _>(move |params, _| {
+        // The item is sent back to the server as it reported it.
+        assert_eq!(params["item"]["data"], json!({ "id": "Dog" }));
+        let animal = animal.clone();
+        async move { Ok(json!([animal])) }
+    });
+
+    let items = project_b
+        .update(cx_b, |p, cx| p.prepare_type_hierarchy(&buffer_b, 8, cx))
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "Dog");
+    assert_eq!(items[0].location.buffer, buffer_b);
+
+    let supertypes = project_b
+        .update(cx_b, |p, cx| {
+            p.type_hierarchy_related_types(&items[0], TypeHierarchyDirection::Supertypes, cx)
+        })
+        .await
+        .unwrap();
+    cx_b.read(|cx| {
+        assert_eq!(supertypes.len(), 1);
+        assert_eq!(supertypes[0].name, "Animal");
+        assert_eq!(supertypes[0].kind, lsp::SymbolKind::INTERFACE);
+
+        let animal_buffer = supertypes[0].location.buffer.read(cx);
+        assert_eq!(
+            animal_buffer.file().unwrap().path().as_ref(),
+            Path::new("animal.rs")
+        );
+        assert_eq!(supertypes[0].location.range.to_offset(animal_buffer), 6..12);
+    });
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_code_lenses(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Set up a fake language server.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+    client_a.language_registry().add(Arc::new(language));
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root",
+            json!({
+                "main.rs": "fn main() {}\n\n#[test]\nfn test() {}",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/root", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Open the file on client B.
+    let open_buffer = project_b.update(cx_b, |p, cx| p.open_buffer((worktree_id, "main.rs"), cx));
+    let buffer_b = cx_b.executor().spawn(open_buffer).await.unwrap();
+
+    // Request the code lenses as the guest. The lens without a command is resolved by the
+    // host.
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    let command = |title: &str| lsp::Command {
+        title: title.to_string(),
+        command: title.to_lowercase(),
+        arguments: None,
+    };
+    fake_language_server.handle_request::<lsp::request::CodeLensRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.text_document.uri.as_str(), "file:///root/main.rs");
+            Ok(Some(vec![
+                lsp::CodeLens {
+                    range: lsp::Range::new(lsp::Position::new(3, 3), lsp::Position::new(3, 7)),
+                    command: None,
+                    data: None,
+                },
+                lsp::CodeLens {
+                    range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                    command: Some(command("Run")),
+                    data: None,
+                },
+            ]))
+        },
+    );
+    fake_language_server.handle_request::<lsp::request::CodeLensResolve, _, _>(
+        move |lens, _| async move {
+            Ok(lsp::CodeLens {
+                command: Some(command("Test")),
+                ..lens
+            })
+        },
+    );
+
+    let lenses = project_b
+        .update(cx_b, |p, cx| p.code_lenses(&buffer_b, cx))
+        .await
+        .unwrap();
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(
+            lenses
+                .iter()
+                .map(|lens| (lens.command.title.as_str(), lens.range.to_offset(buffer)))
+                .collect::<Vec<_>>(),
+            [("Run", 3..7), ("Test", 25..29)]
+        );
+    });
+
+    // Execute one of the lenses as the guest.
+    let mut execute_requests = fake_language_server
+        .handle_request::<lsp::request::ExecuteCommand, _, _>(|params, _| async move {
+            assert_eq!(params.command, "test");
+            Ok(None)
+        });
+    let project_transaction = project_b
+        .update(cx_b, |p, cx| p.execute_code_lens(&lenses[1], cx))
+        .await
+        .unwrap();
+    execute_requests.next().await.unwrap();
+    assert!(project_transaction.0.is_empty());
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_linked_editing_ranges(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Set up a fake language server.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                linked_editing_range_provider: Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+    client_a.language_registry().add(Arc::new(language));
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/root",
+            json!({
+                "main.rs": "fn main() { let tag = tag; }",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/root", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Open the file on client B.
+    let open_buffer = project_b.update(cx_b, |p, cx| p.open_buffer((worktree_id, "main.rs"), cx));
+    let buffer_b = cx_b.executor().spawn(open_buffer).await.unwrap();
+
+    // Request the linked editing ranges as the guest.
+    let fake_language_server = fake_language_servers.next().await.unwrap();
+    fake_language_server.handle_request::<lsp::request::LinkedEditingRange, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 17)
+            );
+            Ok(Some(lsp::LinkedEditingRanges {
+                ranges: vec![
+                    lsp::Range::new(lsp::Position::new(0, 16), lsp::Position::new(0, 19)),
+                    lsp::Range::new(lsp::Position::new(0, 22), lsp::Position::new(0, 25)),
+                ],
+                word_pattern: None,
+            }))
+        },
+    );
+
+    let ranges = project_b
+        .update(cx_b, |p, cx| p.linked_editing_ranges(&buffer_b, 17, cx))
+        .await
+        .unwrap();
+    buffer_b.read_with(cx_b, |buffer, _| {
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|range| range.to_offset(buffer))
+                .collect::<Vec<_>>(),
+            [16..19, 22..25]
+        );
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_project_search(
     executor: BackgroundExecutor,
@@ -5055,7 +5535,12 @@ async fn test_project_symbols(
         },
         Some(tree_sitter_rust::language()),
     );
-    let mut fake_language_servers = language.set_fake_lsp_adapter(Default::default()).await;
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::LanguageServer::workspace_symbol_capabilities(),
+            ..Default::default()
+        }))
+        .await;
     client_a.language_registry().add(Arc::new(language));
 
     client_a
@@ -407,6 +407,8 @@ impl Copilot {
                 let binary = LanguageServerBinary {
                     path: node_path,
                     arguments,
+                    socket: None,
+                    env: None,
                 };
 
                 let server = LanguageServer::new(
@@ -203,6 +203,8 @@ gpui::actions!(
         SplitSelectionIntoLines,
         Tab,
         TabPrev,
+        TexlabBuild,
+        TexlabForwardSearch,
         ToggleInlayHints,
         ToggleSoftWrap,
         Transpose,
@@ -0,0 +1,232 @@
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockProperties, BlockStyle, RenderBlock},
+    Editor, EditorMode,
+};
+use collections::HashSet;
+use gpui::ViewContext;
+use language::language_settings::language_settings;
+use multi_buffer::{Anchor, ToPoint};
+use project::CodeLens;
+use std::{sync::Arc, time::Duration};
+use ui::prelude::*;
+use util::ResultExt;
+
+pub(crate) const CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Requests the code lenses of the editor's buffers whose languages enable them, and shows
+/// each line's lenses in a block above it.
+pub(crate) fn refresh_code_lenses(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .filter(|buffer| {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).code_lens
+        })
+        .collect::<Vec<_>>();
+    if buffers.is_empty() {
+        editor.code_lens_task = None;
+        remove_code_lens_blocks(editor, cx);
+        return;
+    }
+
+    editor.code_lens_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(CODE_LENS_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let Some(requests) = project
+            .update(&mut cx, |project, cx| {
+                buffers
+                    .into_iter()
+                    .map(|buffer| {
+                        let request = project.code_lenses(&buffer, cx);
+                        (buffer, request)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let mut lenses_by_buffer = Vec::new();
+        for (buffer, request) in requests {
+            if let Some(lenses) = request.await.log_err() {
+                lenses_by_buffer.push((buffer, lenses));
+            }
+        }
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let multibuffer = editor.buffer.read(cx);
+                let snapshot = multibuffer.snapshot(cx);
+                let mut lenses = Vec::<(Anchor, CodeLens)>::new();
+                for (buffer, buffer_lenses) in lenses_by_buffer {
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let context = excerpt_range.context;
+                        for lens in &buffer_lenses {
+                            if lens
+                                .range
+                                .start
+                                .cmp(&context.start, &buffer_snapshot)
+                                .is_lt()
+                                || lens.range.start.cmp(&context.end, &buffer_snapshot).is_gt()
+                            {
+                                continue;
+                            }
+                            let position = snapshot.anchor_in_excerpt(excerpt_id, lens.range.start);
+                            lenses.push((position, lens.clone()));
+                        }
+                    }
+                }
+                lenses.sort_by(|(a, _), (b, _)| a.cmp(b, &snapshot));
+
+                // Lenses on the same line share a block, indented to the first of them.
+                let mut blocks = Vec::new();
+                let mut lenses = lenses.into_iter().peekable();
+                while let Some((position, lens)) = lenses.next() {
+                    let row = position.to_point(&snapshot).row;
+                    let mut line_lenses = vec![lens];
+                    while let Some((_, lens)) =
+                        lenses.next_if(|(next, _)| next.to_point(&snapshot).row == row)
+                    {
+                        line_lenses.push(lens);
+                    }
+                    blocks.push(BlockProperties {
+                        position,
+                        height: 1,
+                        style: BlockStyle::Sticky,
+                        render: render_code_lenses(line_lenses),
+                        disposition: BlockDisposition::Above,
+                    });
+                }
+
+                remove_code_lens_blocks(editor, cx);
+                editor.code_lens_blocks = editor.insert_blocks(blocks, None, cx);
+            })
+            .log_err();
+    }));
+}
+
+fn remove_code_lens_blocks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if !editor.code_lens_blocks.is_empty() {
+        let blocks = editor.code_lens_blocks.drain(..).collect::<HashSet<_>>();
+        editor.remove_blocks(blocks, None, cx);
+    }
+}
+
+fn render_code_lenses(lenses: Vec<CodeLens>) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let editor = cx.view.clone();
+        h_flex()
+            .id(cx.block_id)
+            .pl(cx.gutter_width)
+            .w(cx.max_width + cx.gutter_width)
+            .child(div().flex_shrink_0().w(cx.anchor_x - cx.gutter_width))
+            .children(lenses.iter().enumerate().map(|(ix, lens)| {
+                let lens = lens.clone();
+                let editor = editor.clone();
+                h_flex()
+                    .when(ix > 0, |this| {
+                        this.child(Label::new("|").size(LabelSize::XSmall).color(Color::Muted))
+                    })
+                    .child(
+                        Button::new(ix, lens.command.title.clone())
+                            .label_size(LabelSize::XSmall)
+                            .color(Color::Muted)
+                            .style(ButtonStyle::Transparent)
+                            .on_click(move |_, cx| {
+                                editor.update(cx, |editor, cx| {
+                                    editor.execute_code_lens(&lens, cx).detach_and_log_err(cx)
+                                })
+                            }),
+                    )
+            }))
+            .into_any_element()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use futures::StreamExt;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_code_lenses(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| settings.defaults.code_lens = Some(true));
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let lens = |line, command: Option<&str>| lsp::CodeLens {
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 2)),
+            command: command.map(|title| lsp::Command {
+                title: title.to_string(),
+                command: "run".to_string(),
+                arguments: None,
+            }),
+            data: None,
+        };
+        let mut requests =
+            cx.handle_request::<lsp::request::CodeLensRequest, _, _>(move |_, _, _| async move {
+                Ok(Some(vec![
+                    lens(0, Some("Run")),
+                    lens(0, None),
+                    lens(2, Some("Run")),
+                ]))
+            });
+        let mut resolve_requests =
+            cx.handle_request::<lsp::request::CodeLensResolve, _, _>(|_, lens, _| async move {
+                Ok(lsp::CodeLens {
+                    command: Some(lsp::Command {
+                        title: "Debug".to_string(),
+                        command: "debug".to_string(),
+                        arguments: None,
+                    }),
+                    ..lens
+                })
+            });
+
+        cx.set_state(indoc! {"
+            fn a() {}
+
+            fn b() {}ˇ
+        "});
+        cx.executor().advance_clock(CODE_LENS_DEBOUNCE_TIMEOUT * 2);
+        requests.next().await;
+        resolve_requests.next().await;
+        cx.executor().run_until_parked();
+
+        // The two lenses of the first line share a block.
+        cx.update_editor(|editor, _| assert_eq!(editor.code_lens_blocks.len(), 2));
+
+        update_test_language_settings(&mut cx, |settings| {
+            settings.defaults.code_lens = Some(false)
+        });
+        cx.update_editor(|editor, _| assert!(editor.code_lens_blocks.is_empty()));
+    }
+}
@@ -66,6 +66,8 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = BTreeMap<TypeId, HashMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+/// The styles of the semantic tokens that language servers reported, sorted by position.
+type SemanticHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
 
 pub struct DisplayMap {
     buffer: Model<MultiBuffer>,
@@ -77,6 +79,7 @@ pub struct DisplayMap {
     block_map: BlockMap,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
     pub clip_at_line_ends: bool,
 }
 
@@ -109,6 +112,7 @@ impl DisplayMap {
             block_map,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Default::default(),
             clip_at_line_ends: false,
         }
     }
@@ -134,6 +138,7 @@ impl DisplayMap {
             block_snapshot,
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
         }
     }
@@ -254,6 +259,12 @@ impl DisplayMap {
         }
     }
 
+    /// Replaces the highlights of the semantic tokens, which must be sorted by position,
+    /// and which are layered between the syntax highlighting and the text highlights.
+    pub fn set_semantic_highlights(&mut self, highlights: Vec<(Range<Anchor>, HighlightStyle)>) {
+        self.semantic_highlights = highlights.into();
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
         let highlights = self.text_highlights.get(&Some(type_id))?;
         Some((highlights.0, &highlights.1))
@@ -329,6 +340,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a SemanticHighlights>,
     pub inlay_highlight_style: Option<HighlightStyle>,
     pub suggestion_highlight_style: Option<HighlightStyle>,
 }
@@ -348,6 +360,7 @@ pub struct DisplaySnapshot {
     block_snapshot: block_map::BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
     clip_at_line_ends: bool,
 }
 
@@ -515,6 +528,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
                 inlay_highlight_style,
                 suggestion_highlight_style,
             },
@@ -900,6 +914,11 @@ impl DisplaySnapshot {
         self.text_highlights.get(&Some(type_id)).cloned()
     }
 
+    #[cfg(test)]
+    pub(crate) fn semantic_highlights(&self) -> &[(Range<Anchor>, HighlightStyle)] {
+        &self.semantic_highlights
+    }
+
     #[allow(unused)]
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) fn inlay_highlights<Tag: ?Sized + 'static>(
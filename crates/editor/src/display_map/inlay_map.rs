@@ -14,7 +14,7 @@ use std::{
 use sum_tree::{Bias, Cursor, SumTree, TreeMap};
 use text::{Patch, Rope};
 
-use super::Highlights;
+use super::{Highlights, SemanticHighlights};
 
 pub struct InlayMap {
     snapshot: InlaySnapshot,
@@ -186,10 +186,19 @@ pub struct InlayBufferRows<'a> {
 struct HighlightEndpoint {
     offset: InlayOffset,
     is_start: bool,
-    tag: Option<TypeId>,
+    tag: HighlightTag,
     style: HighlightStyle,
 }
 
+/// Identifies the highlight that an endpoint starts or ends. Semantic tokens are ordered
+/// first, so that the other highlights are layered on top of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightTag {
+    /// The index of the semantic token, as adjacent tokens can share a style.
+    SemanticToken(usize),
+    Text(Option<TypeId>),
+}
+
 impl PartialOrd for HighlightEndpoint {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
@@ -215,7 +224,7 @@ pub struct InlayChunks<'a> {
     inlay_highlight_style: Option<HighlightStyle>,
     suggestion_highlight_style: Option<HighlightStyle>,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightTag, HighlightStyle>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -1026,6 +1035,17 @@ impl InlaySnapshot {
         cursor.seek(&range.start, Bias::Right, &());
 
         let mut highlight_endpoints = Vec::new();
+        if let Some(semantic_highlights) = highlights.semantic_highlights {
+            if !semantic_highlights.is_empty() {
+                self.apply_semantic_highlights(
+                    &mut cursor,
+                    &range,
+                    semantic_highlights,
+                    &mut highlight_endpoints,
+                );
+                cursor.seek(&range.start, Bias::Right, &());
+            }
+        }
         if let Some(text_highlights) = highlights.text_highlights {
             if !text_highlights.is_empty() {
                 self.apply_text_highlights(
@@ -1100,13 +1120,13 @@ impl InlaySnapshot {
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
                         is_start: true,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.end.to_offset(&self.buffer)),
                         is_start: false,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                 }
@@ -1116,6 +1136,52 @@ impl InlaySnapshot {
         }
     }
 
+    fn apply_semantic_highlights(
+        &self,
+        cursor: &mut Cursor<'_, Transform, (InlayOffset, usize)>,
+        range: &Range<InlayOffset>,
+        semantic_highlights: &SemanticHighlights,
+        highlight_endpoints: &mut Vec<HighlightEndpoint>,
+    ) {
+        while cursor.start().0 < range.end {
+            let transform_start = self
+                .buffer
+                .anchor_after(self.to_buffer_offset(cmp::max(range.start, cursor.start().0)));
+            let transform_end =
+                {
+                    let overshoot = InlayOffset(range.end.0 - cursor.start().0 .0);
+                    self.buffer.anchor_before(self.to_buffer_offset(cmp::min(
+                        cursor.end(&()).0,
+                        cursor.start().0 + overshoot,
+                    )))
+                };
+
+            let start_ix = semantic_highlights.partition_point(|(probe, _)| {
+                probe.end.cmp(&transform_start, &self.buffer).is_le()
+            });
+            for (ix, (range, style)) in semantic_highlights.iter().enumerate().skip(start_ix) {
+                if range.start.cmp(&transform_end, &self.buffer).is_ge() {
+                    break;
+                }
+
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
+                    is_start: true,
+                    tag: HighlightTag::SemanticToken(ix),
+                    style: *style,
+                });
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: self.to_inlay_offset(range.end.to_offset(&self.buffer)),
+                    is_start: false,
+                    tag: HighlightTag::SemanticToken(ix),
+                    style: *style,
+                });
+            }
+
+            cursor.next(&());
+        }
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
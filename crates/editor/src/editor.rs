@@ -14,6 +14,7 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides it's behaviour.
 pub mod actions;
 mod blink_manager;
+mod code_lens;
 pub mod display_map;
 mod editor_settings;
 mod element;
@@ -24,12 +25,15 @@ mod highlight_matching_bracket;
 mod hover_popover;
 pub mod items;
 mod link_go_to_definition;
+mod linked_editing_ranges;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
+mod texlab_ext;
 
 #[cfg(test)]
 mod editor_tests;
@@ -42,6 +46,7 @@ use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
+use code_lens::refresh_code_lenses;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use copilot::Copilot;
@@ -76,6 +81,7 @@ use language::{
 };
 
 use link_go_to_definition::{GoToDefinitionLink, InlayHighlight, LinkGoToDefinitionState};
+use linked_editing_ranges::{linked_edits, refresh_linked_editing_ranges, LinkedEditingRanges};
 use lsp::{DiagnosticSeverity, LanguageServerId};
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
@@ -91,6 +97,7 @@ use rand::prelude::*;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
+use semantic_tokens::refresh_semantic_tokens;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use smallvec::SmallVec;
@@ -403,6 +410,11 @@ pub struct Editor {
     link_go_to_definition_state: LinkGoToDefinitionState,
     copilot_state: CopilotState,
     inlay_hint_cache: InlayHintCache,
+    semantic_tokens_task: Option<Task<()>>,
+    code_lens_task: Option<Task<()>>,
+    code_lens_blocks: Vec<BlockId>,
+    linked_editing_ranges: Option<LinkedEditingRanges>,
+    linked_editing_ranges_task: Option<Task<()>>,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -1364,11 +1376,26 @@ impl Editor {
                         cx.emit(EditorEvent::TitleChanged);
                     }));
                 }
-                project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
-                    };
-                }));
+                project_subscriptions.push(cx.subscribe(
+                    project,
+                    |editor, _, event, cx| match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::RefreshSemanticTokens => {
+                            refresh_semantic_tokens(editor, cx);
+                        }
+                        project::Event::RefreshCodeLenses => {
+                            refresh_code_lenses(editor, cx);
+                        }
+                        project::Event::LanguageServerAdded(_) => {
+                            refresh_semantic_tokens(editor, cx);
+                            refresh_code_lenses(editor, cx);
+                        }
+                        _ => {}
+                    },
+                ));
             }
         }
 
@@ -1437,6 +1464,11 @@ impl Editor {
             link_go_to_definition_state: Default::default(),
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
+            semantic_tokens_task: None,
+            code_lens_task: None,
+            code_lens_blocks: Vec::new(),
+            linked_editing_ranges: None,
+            linked_editing_ranges_task: None,
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             gutter_width: Default::default(),
@@ -1470,6 +1502,8 @@ impl Editor {
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
+        refresh_semantic_tokens(&mut this, cx);
+        refresh_code_lenses(&mut this, cx);
 
         if mode == EditorMode::Full {
             let should_auto_hide_scrollbars = cx.should_auto_hide_scrollbars();
@@ -1821,6 +1855,7 @@ impl Editor {
             }
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
+            refresh_linked_editing_ranges(self, cx);
             refresh_matching_bracket_highlights(self, cx);
             self.discard_copilot_suggestion(cx);
         }
@@ -2385,9 +2420,13 @@ impl Editor {
 
         drop(snapshot);
         self.transact(cx, |this, cx| {
+            let linked_edits = linked_edits(this, &edits, cx);
             this.buffer.update(cx, |buffer, cx| {
                 buffer.edit(edits, this.autoindent_mode.clone(), cx);
             });
+            if let Some((buffer, linked_edits)) = linked_edits {
+                buffer.update(cx, |buffer, cx| buffer.edit(linked_edits, None, cx));
+            }
 
             let new_anchor_selections = new_selections.iter().map(|e| &e.0);
             let new_selection_deltas = new_selections.iter().map(|e| e.1);
@@ -2433,7 +2472,7 @@ impl Editor {
             let had_active_copilot_suggestion = this.has_active_copilot_suggestion(cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
 
-            if !brace_inserted && EditorSettings::get_global(cx).use_on_type_format {
+            if !brace_inserted {
                 if let Some(on_type_format_task) =
                     this.trigger_on_type_formatting(text.to_string(), cx)
                 {
@@ -2733,6 +2772,11 @@ impl Editor {
         let text: Arc<str> = text.into();
         self.transact(cx, |this, cx| {
             let old_selections = this.selections.all_adjusted(cx);
+            let edits = old_selections
+                .iter()
+                .map(|s| (s.start..s.end, text.clone()))
+                .collect::<Vec<_>>();
+            let linked_edits = linked_edits(this, &edits, cx);
             let selection_anchors = this.buffer.update(cx, |buffer, cx| {
                 let anchors = {
                     let snapshot = buffer.read(cx);
@@ -2744,15 +2788,12 @@ impl Editor {
                         })
                         .collect::<Vec<_>>()
                 };
-                buffer.edit(
-                    old_selections
-                        .iter()
-                        .map(|s| (s.start..s.end, text.clone())),
-                    autoindent_mode,
-                    cx,
-                );
+                buffer.edit(edits, autoindent_mode, cx);
                 anchors
             });
+            if let Some((buffer, linked_edits)) = linked_edits {
+                buffer.update(cx, |buffer, cx| buffer.edit(linked_edits, None, cx));
+            }
 
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_anchors(selection_anchors);
@@ -3064,6 +3105,18 @@ impl Editor {
             .buffer
             .read(cx)
             .text_anchor_for_position(position.clone(), cx)?;
+        let use_on_type_format = {
+            let buffer = buffer.read(cx);
+            language_settings::language_settings(
+                buffer.language_at(buffer_position).as_ref(),
+                buffer.file(),
+                cx,
+            )
+            .use_on_type_format
+        };
+        if !use_on_type_format {
+            return None;
+        }
 
         // OnTypeFormatting returns a list of edits, no need to pass them between Zed instances,
         // hence we do LSP request & edit on host side only — add formats to host's history.
@@ -3407,6 +3460,29 @@ impl Editor {
         }))
     }
 
+    /// Runs the command of a code lens, opening the edits that its language server makes
+    /// while doing so.
+    pub fn execute_code_lens(
+        &mut self,
+        lens: &project::CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(workspace) = self.workspace() else {
+            return Task::ready(Ok(()));
+        };
+        let title = lens.command.title.clone();
+        let execute_code_lens = workspace
+            .read(cx)
+            .project()
+            .clone()
+            .update(cx, |project, cx| project.execute_code_lens(lens, cx));
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = execute_code_lens.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+    }
+
     async fn open_project_transaction(
         this: &WeakView<Editor>,
         workspace: WeakView<Workspace>,
@@ -8471,6 +8547,8 @@ impl Editor {
             } => {
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                refresh_semantic_tokens(self, cx);
+                refresh_code_lenses(self, cx);
                 if self.has_active_copilot_suggestion(cx) {
                     self.update_visible_copilot_suggestion(cx);
                 }
@@ -8521,9 +8599,13 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_semantic_tokens(self, cx);
+                refresh_code_lenses(self, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                refresh_semantic_tokens(self, cx);
+                refresh_code_lenses(self, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
@@ -8555,6 +8637,8 @@ impl Editor {
             )),
             cx,
         );
+        refresh_semantic_tokens(self, cx);
+        refresh_code_lenses(self, cx);
         cx.notify();
     }
 
@@ -8765,14 +8849,16 @@ impl Editor {
             .settings_at(0, cx)
             .show_copilot_suggestions;
 
-        let telemetry = project.read(cx).client().telemetry().clone();
-        telemetry.report_editor_event(
-            file_extension,
-            vim_mode,
-            operation,
-            copilot_enabled,
-            copilot_enabled_for_language,
-        )
+        project
+            .read(cx)
+            .client()
+            .telemetry()
+            .editor_event(operation)
+            .file_extension(file_extension)
+            .vim_mode(vim_mode)
+            .copilot_enabled(copilot_enabled)
+            .copilot_enabled_for_language(copilot_enabled_for_language)
+            .send()
     }
 
     /// Copy the highlighted chunks to the clipboard as JSON. The format is an array of lines,
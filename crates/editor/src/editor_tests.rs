@@ -7792,6 +7792,58 @@ async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_on_type_formatting_language_setting(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+                first_trigger_character: ";".to_string(),
+                more_trigger_character: None,
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    cx.handle_request::<lsp::request::OnTypeFormatting, _, _>({
+        let requests = requests.clone();
+        move |_, _, _| {
+            requests.fetch_add(1, atomic::Ordering::SeqCst);
+            async move {
+                Ok(Some(vec![lsp::TextEdit {
+                    new_text: " ".to_string(),
+                    range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 4)),
+                }]))
+            }
+        }
+    });
+
+    cx.set_state("let aˇ");
+    cx.update_editor(|editor, cx| editor.handle_input(";", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("let  a;ˇ");
+    assert_eq!(requests.load(atomic::Ordering::SeqCst), 1);
+
+    // Disabling on-type formatting for the language stops the requests.
+    update_test_language_settings(&mut cx, |settings| {
+        settings.languages.insert(
+            "Rust".into(),
+            LanguageSettingsContent {
+                use_on_type_format: Some(false),
+                ..Default::default()
+            },
+        );
+    });
+    cx.update_editor(|editor, cx| editor.handle_input(";", cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("let  a;;ˇ");
+    assert_eq!(requests.load(atomic::Ordering::SeqCst), 1);
+}
+
 #[gpui::test]
 async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7865,6 +7917,12 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             "Some other server name".into(),
             LspSettings {
+                enable: None,
+                binary: None,
+                version: None,
+                prefer_system_binary: None,
+                env: None,
+                settings: None,
                 initialization_options: Some(json!({
                     "some other init value": false
                 })),
@@ -7882,6 +7940,12 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enable: None,
+                binary: None,
+                version: None,
+                prefer_system_binary: None,
+                env: None,
+                settings: None,
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
@@ -7899,6 +7963,12 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enable: None,
+                binary: None,
+                version: None,
+                prefer_system_binary: None,
+                env: None,
+                settings: None,
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
@@ -7916,6 +7986,12 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
         project_settings.lsp.insert(
             language_server_name.into(),
             LspSettings {
+                enable: None,
+                binary: None,
+                version: None,
+                prefer_system_binary: None,
+                env: None,
+                settings: None,
                 initialization_options: None,
             },
         );
@@ -0,0 +1,204 @@
+use crate::{Editor, EditorMode};
+use gpui::{Model, ViewContext};
+use language::{Buffer, Point};
+use std::{ops::Range, sync::Arc, time::Duration};
+use text::{BufferSnapshot, ToOffset};
+use util::ResultExt;
+
+pub(crate) const LINKED_EDITING_RANGES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+
+/// The ranges of a buffer that the language server linked to the one at the newest cursor,
+/// whose edits are mirrored to each other.
+pub(crate) struct LinkedEditingRanges {
+    buffer: Model<Buffer>,
+    ranges: Vec<Range<text::Anchor>>,
+}
+
+impl LinkedEditingRanges {
+    fn range_containing(
+        &self,
+        range: Range<usize>,
+        snapshot: &BufferSnapshot,
+    ) -> Option<Range<usize>> {
+        self.ranges
+            .iter()
+            .map(|linked_range| {
+                linked_range.start.to_offset(snapshot)..linked_range.end.to_offset(snapshot)
+            })
+            .find(|linked_range| linked_range.start <= range.start && range.end <= linked_range.end)
+    }
+}
+
+/// Requests the ranges linked to the newest cursor, unless the cursor is still within the
+/// ones that are already known.
+pub(crate) fn refresh_linked_editing_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let head = editor.selections.newest_anchor().head();
+    let Some((buffer, position)) = editor.buffer.read(cx).text_anchor_for_position(head, cx) else {
+        editor.linked_editing_ranges = None;
+        return;
+    };
+
+    if let Some(linked_editing_ranges) = &editor.linked_editing_ranges {
+        let snapshot = buffer.read(cx).snapshot();
+        let offset = position.to_offset(&snapshot);
+        if linked_editing_ranges.buffer == buffer
+            && linked_editing_ranges
+                .range_containing(offset..offset, &snapshot)
+                .is_some()
+        {
+            return;
+        }
+    }
+    editor.linked_editing_ranges = None;
+
+    editor.linked_editing_ranges_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(LINKED_EDITING_RANGES_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let Some(request) = project
+            .update(&mut cx, |project, cx| {
+                project.linked_editing_ranges(&buffer, position, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let Some(ranges) = request.await.log_err() else {
+            return;
+        };
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let linked_editing_ranges = LinkedEditingRanges { buffer, ranges };
+                // The cursor may have left the ranges while they were being requested.
+                let snapshot = linked_editing_ranges.buffer.read(cx).snapshot();
+                let offset = position.to_offset(&snapshot);
+                if linked_editing_ranges.ranges.len() > 1
+                    && linked_editing_ranges
+                        .range_containing(offset..offset, &snapshot)
+                        .is_some()
+                {
+                    editor.linked_editing_ranges = Some(linked_editing_ranges);
+                }
+            })
+            .log_err();
+    }));
+}
+
+/// Returns the edits that mirror the given ones in the ranges linked to the ones they're
+/// made in, so that they can be applied along with them.
+pub(crate) fn linked_edits(
+    editor: &mut Editor,
+    edits: &[(Range<Point>, Arc<str>)],
+    cx: &mut ViewContext<Editor>,
+) -> Option<(Model<Buffer>, Vec<(Range<text::Anchor>, Arc<str>)>)> {
+    // Linked ranges hold a single word, such as the name of a tag, so typing a space ends it.
+    if edits
+        .iter()
+        .any(|(_, text)| text.chars().any(char::is_whitespace))
+    {
+        editor.linked_editing_ranges = None;
+        return None;
+    }
+    let linked_editing_ranges = editor.linked_editing_ranges.as_ref()?;
+
+    let multibuffer = editor.buffer.read(cx);
+    let snapshot = linked_editing_ranges.buffer.read(cx).snapshot();
+    let mut edited_ranges = Vec::new();
+    let mut mirrored_edits = Vec::new();
+    for (range, text) in edits {
+        let (start_buffer, start) = multibuffer.text_anchor_for_position(range.start, cx)?;
+        let (end_buffer, end) = multibuffer.text_anchor_for_position(range.end, cx)?;
+        if start_buffer != linked_editing_ranges.buffer || end_buffer != start_buffer {
+            continue;
+        }
+        let range = start.to_offset(&snapshot)..end.to_offset(&snapshot);
+        if let Some(linked_range) = linked_editing_ranges.range_containing(range.clone(), &snapshot)
+        {
+            let relative_range = range.start - linked_range.start..range.end - linked_range.start;
+            edited_ranges.push(linked_range);
+            mirrored_edits.push((relative_range, text.clone()));
+        }
+    }
+
+    let mut edits = Vec::new();
+    for linked_range in &linked_editing_ranges.ranges {
+        let linked_range =
+            linked_range.start.to_offset(&snapshot)..linked_range.end.to_offset(&snapshot);
+        // Ranges that are edited directly, e.g. with a cursor in each tag, are left alone.
+        if edited_ranges.contains(&linked_range) {
+            continue;
+        }
+        for (relative_range, text) in &mirrored_edits {
+            if linked_range.start + relative_range.end > linked_range.end {
+                continue;
+            }
+            let start = snapshot.anchor_before(linked_range.start + relative_range.start);
+            let end = snapshot.anchor_after(linked_range.start + relative_range.end);
+            edits.push((start..end, text.clone()));
+        }
+    }
+    if edits.is_empty() {
+        None
+    } else {
+        Some((linked_editing_ranges.buffer.clone(), edits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt;
+
+    #[gpui::test]
+    async fn test_linked_editing_ranges(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                linked_editing_range_provider: Some(
+                    lsp::LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let mut requests =
+            cx.handle_request::<lsp::request::LinkedEditingRange, _, _>(|_, _, _| async move {
+                Ok(Some(lsp::LinkedEditingRanges {
+                    ranges: vec![
+                        lsp::Range::new(lsp::Position::new(0, 1), lsp::Position::new(0, 4)),
+                        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+                    ],
+                    word_pattern: None,
+                }))
+            });
+
+        cx.set_state("<divˇ></div>");
+        cx.executor()
+            .advance_clock(LINKED_EDITING_RANGES_DEBOUNCE_TIMEOUT * 2);
+        requests.next().await;
+        cx.executor().run_until_parked();
+
+        cx.update_editor(|editor, cx| editor.handle_input("s", cx));
+        cx.assert_editor_state("<divsˇ></divs>");
+
+        cx.update_editor(|editor, cx| editor.backspace(&Default::default(), cx));
+        cx.update_editor(|editor, cx| editor.backspace(&Default::default(), cx));
+        cx.assert_editor_state("<diˇ></di>");
+
+        // Typing a space ends the tag's name.
+        cx.update_editor(|editor, cx| editor.handle_input(" ", cx));
+        cx.assert_editor_state("<di ˇ></di>");
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{Editor, EditorMode};
+use gpui::{px, FontStyle, HighlightStyle, UnderlineStyle, ViewContext};
+use language::language_settings::language_settings;
+use project::SemanticToken;
+use std::time::Duration;
+use theme::{ActiveTheme, SyntaxTheme};
+use util::ResultExt;
+
+pub(crate) const SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Requests the semantic tokens of the editor's buffers whose languages enable them, and
+/// highlights the editor with them. Buffers whose language servers don't provide semantic
+/// tokens keep only their syntax highlighting.
+pub(crate) fn refresh_semantic_tokens(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .filter(|buffer| {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx).semantic_tokens
+        })
+        .collect::<Vec<_>>();
+    if buffers.is_empty() {
+        editor.semantic_tokens_task = None;
+        editor
+            .display_map
+            .update(cx, |map, _| map.set_semantic_highlights(Vec::new()));
+        return;
+    }
+
+    editor.semantic_tokens_task = Some(cx.spawn(|editor, mut cx| async move {
+        cx.background_executor()
+            .timer(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT)
+            .await;
+
+        let Some(requests) = project
+            .update(&mut cx, |project, cx| {
+                buffers
+                    .into_iter()
+                    .map(|buffer| {
+                        let request = project.semantic_tokens(&buffer, cx);
+                        (buffer, request)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let mut tokens_by_buffer = Vec::new();
+        for (buffer, request) in requests {
+            if let Some(tokens) = request.await.log_err().flatten() {
+                tokens_by_buffer.push((buffer, tokens));
+            }
+        }
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                let syntax = cx.theme().syntax().clone();
+                let multibuffer = editor.buffer.read(cx);
+                let snapshot = multibuffer.snapshot(cx);
+                let mut highlights = Vec::new();
+                for (buffer, tokens) in tokens_by_buffer {
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let context = excerpt_range.context;
+                        for token in &tokens {
+                            if token
+                                .range
+                                .end
+                                .cmp(&context.start, &buffer_snapshot)
+                                .is_le()
+                                || token
+                                    .range
+                                    .start
+                                    .cmp(&context.end, &buffer_snapshot)
+                                    .is_ge()
+                            {
+                                continue;
+                            }
+                            if let Some(style) = semantic_token_style(token, &syntax) {
+                                let start =
+                                    snapshot.anchor_in_excerpt(excerpt_id, token.range.start);
+                                let end = snapshot.anchor_in_excerpt(excerpt_id, token.range.end);
+                                highlights.push((start..end, style));
+                            }
+                        }
+                    }
+                }
+                highlights.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start, &snapshot));
+
+                editor
+                    .display_map
+                    .update(cx, |map, _| map.set_semantic_highlights(highlights));
+                cx.notify();
+            })
+            .log_err();
+    }));
+}
+
+/// The style that a semantic token is highlighted with, on top of the syntax highlighting.
+/// Its type picks the syntax theme's style, e.g. `parameter` tokens are styled as variables,
+/// and some of its modifiers adjust that style.
+fn semantic_token_style(token: &SemanticToken, syntax: &SyntaxTheme) -> Option<HighlightStyle> {
+    let has_modifier = |name: &str| {
+        token
+            .modifiers
+            .iter()
+            .any(|modifier| modifier.as_ref() == name)
+    };
+
+    let highlight_name = match token.token_type.as_ref() {
+        "type" | "class" | "interface" | "struct" | "typeParameter" => Some("type"),
+        "enum" => Some("enum"),
+        "enumMember" => Some("variant"),
+        "function" | "method" => Some("function"),
+        "macro" => Some("preproc"),
+        "variable" | "parameter" if has_modifier("readonly") => Some("constant"),
+        "variable" | "parameter" => Some("variable"),
+        "property" => Some("property"),
+        "keyword" | "modifier" => Some("keyword"),
+        "comment" => Some("comment"),
+        "string" => Some("string"),
+        "number" => Some("number"),
+        "regexp" => Some("string.regex"),
+        "operator" => Some("operator"),
+        "decorator" => Some("attribute"),
+        _ => None,
+    };
+    let mut style = highlight_name
+        .map(|name| syntax.get(name))
+        .unwrap_or_default();
+
+    if has_modifier("deprecated") {
+        style.fade_out = Some(0.3);
+    }
+    if has_modifier("static") {
+        style.font_style = Some(FontStyle::Italic);
+    }
+    // rust-analyzer marks mutable variables and references with a `mutable` modifier.
+    if has_modifier("mutable") {
+        style.underline = Some(UnderlineStyle {
+            thickness: px(1.),
+            color: style.color,
+            wavy: false,
+        });
+    }
+
+    (style != HighlightStyle::default()).then_some(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use futures::StreamExt;
+    use gpui::Hsla;
+    use indoc::indoc;
+    use multi_buffer::ToOffset;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_semantic_token_style() {
+        let syntax = SyntaxTheme::new_test([("variable", Hsla::red()), ("constant", Hsla::blue())]);
+        let token = |token_type: &str, modifiers: &[&str]| SemanticToken {
+            range: text::Anchor::MIN..text::Anchor::MAX,
+            token_type: token_type.into(),
+            modifiers: modifiers
+                .iter()
+                .map(|modifier| Arc::from(*modifier))
+                .collect(),
+        };
+
+        let style = semantic_token_style(&token("parameter", &[]), &syntax).unwrap();
+        assert_eq!(style.color, Some(Hsla::red()));
+        assert_eq!(style.underline, None);
+
+        let style = semantic_token_style(&token("variable", &["readonly"]), &syntax).unwrap();
+        assert_eq!(style.color, Some(Hsla::blue()));
+
+        let style = semantic_token_style(&token("variable", &["mutable"]), &syntax).unwrap();
+        assert_eq!(style.underline.unwrap().color, Some(Hsla::red()));
+
+        // Without a style of its own, the token leaves the syntax highlighting as it is.
+        assert_eq!(semantic_token_style(&token("function", &[]), &syntax), None);
+        let style = semantic_token_style(&token("function", &["deprecated"]), &syntax).unwrap();
+        assert_eq!(style.fade_out, Some(0.3));
+    }
+
+    #[gpui::test]
+    async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.semantic_tokens = Some(true)
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::VARIABLE],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::new("mutable")],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        let mut requests = cx.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+            |_, _, _| async move {
+                Ok(Some(lsp::SemanticTokensResult::Tokens(
+                    lsp::SemanticTokens {
+                        result_id: None,
+                        data: vec![
+                            lsp::SemanticToken {
+                                delta_line: 0,
+                                delta_start: 8,
+                                length: 1,
+                                token_type: 0,
+                                token_modifiers_bitset: 1,
+                            },
+                            lsp::SemanticToken {
+                                delta_line: 1,
+                                delta_start: 0,
+                                length: 1,
+                                token_type: 0,
+                                token_modifiers_bitset: 1,
+                            },
+                        ],
+                    },
+                )))
+            },
+        );
+
+        cx.set_state(indoc! {"
+            let mut x = 1;
+            x += 1;ˇ
+        "});
+        cx.executor()
+            .advance_clock(SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT * 2);
+        requests.next().await;
+        cx.executor().run_until_parked();
+
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            let ranges = snapshot
+                .display_snapshot
+                .semantic_highlights()
+                .iter()
+                .map(|(range, style)| {
+                    assert!(style.underline.is_some());
+                    range.start.to_offset(&snapshot.buffer_snapshot)
+                        ..range.end.to_offset(&snapshot.buffer_snapshot)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(ranges, [8..9, 15..16]);
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use gpui::{Model, View, ViewContext, WindowContext};
+use language::{point_to_lsp, Buffer, Language};
+use lsp::LanguageServer;
+use project::lsp_ext_command::{
+    self, TexlabBuildParams, TexlabBuildStatus, TexlabForwardSearchStatus,
+};
+use text::ToPointUtf16;
+
+use crate::{element::register_action, Editor, TexlabBuild, TexlabForwardSearch};
+
+pub fn apply_related_actions(editor: &View<Editor>, cx: &mut WindowContext) {
+    let is_latex_related = editor.update(cx, |editor, cx| {
+        editor
+            .buffer()
+            .read(cx)
+            .all_buffers()
+            .iter()
+            .any(|b| match b.read(cx).language() {
+                Some(l) => is_latex_language(l),
+                None => false,
+            })
+    });
+
+    if is_latex_related {
+        register_action(editor, cx, build);
+        register_action(editor, cx, forward_search);
+    }
+}
+
+pub fn build(editor: &mut Editor, _: &TexlabBuild, cx: &mut ViewContext<'_, Editor>) {
+    let Some((buffer, server, position)) = texlab_at_newest_cursor(editor, cx) else {
+        return;
+    };
+    let Some(uri) = buffer_uri(&buffer, cx) else {
+        return;
+    };
+    let position = position.to_point_utf16(&buffer.read(cx).snapshot());
+    cx.background_executor()
+        .spawn(async move {
+            let result = server
+                .request::<lsp_ext_command::TexlabBuild>(TexlabBuildParams {
+                    text_document: lsp::TextDocumentIdentifier::new(uri),
+                    position: Some(point_to_lsp(position)),
+                })
+                .await?;
+            match result.status {
+                TexlabBuildStatus::Success | TexlabBuildStatus::Cancelled => Ok(()),
+                status => Err(anyhow!("texlab build finished with status {status:?}")),
+            }
+        })
+        .detach_and_log_err(cx);
+}
+
+pub fn forward_search(
+    editor: &mut Editor,
+    _: &TexlabForwardSearch,
+    cx: &mut ViewContext<'_, Editor>,
+) {
+    let Some((buffer, server, position)) = texlab_at_newest_cursor(editor, cx) else {
+        return;
+    };
+    let Some(uri) = buffer_uri(&buffer, cx) else {
+        return;
+    };
+    let position = position.to_point_utf16(&buffer.read(cx).snapshot());
+    cx.background_executor()
+        .spawn(async move {
+            let result = server
+                .request::<lsp_ext_command::TexlabForwardSearch>(lsp::TextDocumentPositionParams {
+                    text_document: lsp::TextDocumentIdentifier::new(uri),
+                    position: point_to_lsp(position),
+                })
+                .await?;
+            match result.status {
+                TexlabForwardSearchStatus::Success => Ok(()),
+                TexlabForwardSearchStatus::Unconfigured => Err(anyhow!(
+                    "no PDF viewer is configured for texlab's forward search. Set \
+                     `texlab.forwardSearch` in the `settings` of `texlab` in the `lsp` settings"
+                )),
+                status => Err(anyhow!(
+                    "texlab forward search failed with status {status:?}"
+                )),
+            }
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Finds the LaTeX buffer under the newest cursor, along with the texlab server that it's
+/// open in and the cursor's position in it.
+fn texlab_at_newest_cursor(
+    editor: &mut Editor,
+    cx: &mut ViewContext<'_, Editor>,
+) -> Option<(Model<Buffer>, Arc<LanguageServer>, text::Anchor)> {
+    let project = editor.project.as_ref()?;
+    let anchor = editor.selections.newest_anchor().head();
+    let buffer = editor.buffer().read(cx).buffer(anchor.buffer_id?)?;
+    let language = buffer.read(cx).language_at(anchor.text_anchor)?;
+    if !is_latex_language(&language) {
+        return None;
+    }
+    let server = project
+        .read(cx)
+        .language_servers_for_buffer(buffer.read(cx), cx)
+        .find_map(|(adapter, server)| {
+            (adapter.name.0.as_ref() == "texlab").then(|| server.clone())
+        })?;
+    Some((buffer, server, anchor.text_anchor))
+}
+
+fn buffer_uri(buffer: &Model<Buffer>, cx: &WindowContext) -> Option<lsp::Url> {
+    let file = project::File::from_dyn(buffer.read(cx).file())?;
+    lsp::Url::from_file_path(file.abs_path(cx)).ok()
+}
+
+fn is_latex_language(language: &Language) -> bool {
+    language.name().as_ref() == "LaTeX"
+}
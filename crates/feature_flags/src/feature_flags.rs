@@ -1,14 +1,20 @@
 use gpui::{AppContext, Subscription, ViewContext};
+use std::rc::Rc;
 
 #[derive(Default)]
 struct FeatureFlags {
     flags: Vec<String>,
     staff: bool,
+    on_exposure: Option<Rc<dyn Fn(&'static str, bool)>>,
 }
 
 impl FeatureFlags {
-    fn has_flag(&self, flag: &str) -> bool {
-        self.staff || self.flags.iter().find(|f| f.as_str() == flag).is_some()
+    fn has_flag(&self, flag: &'static str) -> bool {
+        let enabled = self.staff || self.flags.iter().find(|f| f.as_str() == flag).is_some();
+        if let Some(on_exposure) = &self.on_exposure {
+            on_exposure(flag, enabled);
+        }
+        enabled
     }
 }
 
@@ -42,6 +48,8 @@ pub trait FeatureFlagAppExt {
     fn set_staff(&mut self, staff: bool);
     fn has_flag<T: FeatureFlag>(&self) -> bool;
     fn is_staff(&self) -> bool;
+    /// Calls `callback` with the flag's name and whether it is enabled every time a flag is checked.
+    fn on_flag_exposure(&mut self, callback: impl Fn(&'static str, bool) + 'static);
 }
 
 impl FeatureFlagAppExt for AppContext {
@@ -67,4 +75,9 @@ impl FeatureFlagAppExt for AppContext {
             .map(|flags| flags.staff)
             .unwrap_or(false)
     }
+
+    fn on_flag_exposure(&mut self, callback: impl Fn(&'static str, bool) + 'static) {
+        let feature_flags = self.default_global::<FeatureFlags>();
+        feature_flags.on_exposure = Some(Rc::new(callback));
+    }
 }
@@ -1,5 +1,7 @@
 mod async_context;
 mod entity_map;
+mod frame_timings;
+mod input_latency;
 mod model_context;
 #[cfg(any(test, feature = "test-support"))]
 mod test_context;
@@ -7,6 +9,10 @@ mod test_context;
 pub use async_context::*;
 use derive_more::{Deref, DerefMut};
 pub use entity_map::*;
+use frame_timings::FrameTimings;
+pub use frame_timings::FrameTimingsSummary;
+use input_latency::InputLatencyHistogram;
+pub use input_latency::{InputLatencySummary, INPUT_LATENCY_BUCKETS};
 pub use model_context::*;
 use refineable::Refineable;
 use smol::future::FutureExt;
@@ -239,6 +245,8 @@ pub struct AppContext {
     pub(crate) quit_observers: SubscriberSet<(), QuitHandler>,
     pub(crate) layout_id_buffer: Vec<LayoutId>, // We recycle this memory across layout requests.
     pub(crate) propagate_event: bool,
+    pub(crate) frame_timings: FrameTimings,
+    pub(crate) input_latency: InputLatencyHistogram,
 }
 
 impl AppContext {
@@ -299,6 +307,8 @@ impl AppContext {
                 quit_observers: SubscriberSet::new(),
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
+                frame_timings: FrameTimings::default(),
+                input_latency: InputLatencyHistogram::default(),
             }),
         });
 
@@ -346,6 +356,18 @@ impl AppContext {
         self.app_metadata.clone()
     }
 
+    /// Get a summary of how long frames took to draw since this was last called,
+    /// or `None` if no frames were drawn in the meantime.
+    pub fn take_frame_timings(&mut self) -> Option<FrameTimingsSummary> {
+        self.frame_timings.take_summary()
+    }
+
+    /// Returns a histogram of the latency between keystrokes and the frames reflecting them
+    /// being drawn since this was last called, or `None` if no such keystrokes were measured.
+    pub fn take_input_latency(&mut self) -> Option<InputLatencySummary> {
+        self.input_latency.take_summary()
+    }
+
     /// Schedules all windows in the application to be redrawn. This can be called
     /// multiple times in an update cycle and still result in a single redraw.
     pub fn refresh(&mut self) {
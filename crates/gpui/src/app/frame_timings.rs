@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+/// Individual durations beyond this many frames are dropped until the timings are
+/// next taken, but the frames are still counted.
+const MAX_RECORDED_FRAMES: usize = 16 * 1024;
+
+/// Durations of the frames drawn across all windows since the timings were last taken.
+#[derive(Default)]
+pub(crate) struct FrameTimings {
+    frame_count: usize,
+    durations: Vec<Duration>,
+}
+
+/// A summary of how long frames took to draw, see [`crate::AppContext::take_frame_timings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameTimingsSummary {
+    /// The number of frames drawn.
+    pub frame_count: usize,
+    /// The mean duration of a frame.
+    pub mean: Duration,
+    /// The median duration of a frame.
+    pub p50: Duration,
+    /// The 95th percentile duration of a frame.
+    pub p95: Duration,
+    /// The longest duration of a frame.
+    pub max: Duration,
+}
+
+impl FrameTimings {
+    pub(crate) fn record(&mut self, duration: Duration) {
+        self.frame_count += 1;
+        if self.durations.len() < MAX_RECORDED_FRAMES {
+            self.durations.push(duration);
+        }
+    }
+
+    pub(crate) fn take_summary(&mut self) -> Option<FrameTimingsSummary> {
+        let frame_count = std::mem::take(&mut self.frame_count);
+        let mut durations = std::mem::take(&mut self.durations);
+        if durations.is_empty() {
+            return None;
+        }
+
+        durations.sort_unstable();
+        let percentile = |percentile: f64| {
+            let ix = ((durations.len() - 1) as f64 * percentile).round() as usize;
+            durations[ix]
+        };
+        Some(FrameTimingsSummary {
+            frame_count,
+            mean: durations.iter().sum::<Duration>() / durations.len() as u32,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            max: *durations.last().unwrap(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_timings_summary() {
+        let mut timings = FrameTimings::default();
+        assert_eq!(timings.take_summary(), None);
+
+        for millis in (1..=20).rev() {
+            timings.record(Duration::from_millis(millis));
+        }
+        assert_eq!(
+            timings.take_summary(),
+            Some(FrameTimingsSummary {
+                frame_count: 20,
+                mean: Duration::from_micros(10_500),
+                p50: Duration::from_millis(11),
+                p95: Duration::from_millis(19),
+                max: Duration::from_millis(20),
+            })
+        );
+        assert_eq!(timings.take_summary(), None);
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+/// The upper bounds of the buckets that keystroke latencies are counted in. Latencies
+/// beyond the last bound are counted in an extra overflow bucket.
+pub const INPUT_LATENCY_BUCKETS: [Duration; 8] = [
+    Duration::from_millis(4),
+    Duration::from_millis(8),
+    Duration::from_millis(16),
+    Duration::from_millis(33),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(500),
+];
+
+/// A histogram of the time between a keystroke being received and the frame
+/// reflecting it being drawn, across all windows since it was last taken.
+#[derive(Default)]
+pub(crate) struct InputLatencyHistogram {
+    bucket_counts: [usize; INPUT_LATENCY_BUCKETS.len() + 1],
+    max: Duration,
+}
+
+/// A summary of keystroke latencies, see [`crate::AppContext::take_input_latency`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputLatencySummary {
+    /// The number of keystrokes measured.
+    pub sample_count: usize,
+    /// How many keystrokes fell into each of [`INPUT_LATENCY_BUCKETS`], followed by
+    /// how many exceeded the last bucket.
+    pub bucket_counts: Vec<usize>,
+    /// The upper bound of the bucket containing the median latency.
+    pub p50: Duration,
+    /// The upper bound of the bucket containing the 95th percentile latency.
+    pub p95: Duration,
+    /// The upper bound of the bucket containing the 99th percentile latency.
+    pub p99: Duration,
+    /// The longest latency.
+    pub max: Duration,
+}
+
+impl InputLatencyHistogram {
+    pub(crate) fn record(&mut self, latency: Duration) {
+        let ix = INPUT_LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(INPUT_LATENCY_BUCKETS.len());
+        self.bucket_counts[ix] += 1;
+        self.max = self.max.max(latency);
+    }
+
+    pub(crate) fn take_summary(&mut self) -> Option<InputLatencySummary> {
+        let histogram = std::mem::take(self);
+        let sample_count = histogram.bucket_counts.iter().sum::<usize>();
+        if sample_count == 0 {
+            return None;
+        }
+
+        let percentile = |percentile: f64| {
+            let rank = (sample_count as f64 * percentile).ceil() as usize;
+            let mut seen = 0;
+            for (ix, count) in histogram.bucket_counts.iter().enumerate() {
+                seen += count;
+                if seen >= rank {
+                    return INPUT_LATENCY_BUCKETS
+                        .get(ix)
+                        .map_or(histogram.max, |bound| (*bound).min(histogram.max));
+                }
+            }
+            histogram.max
+        };
+        Some(InputLatencySummary {
+            sample_count,
+            bucket_counts: histogram.bucket_counts.to_vec(),
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: histogram.max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_latency_summary() {
+        let mut histogram = InputLatencyHistogram::default();
+        assert_eq!(histogram.take_summary(), None);
+
+        for _ in 0..90 {
+            histogram.record(Duration::from_millis(3));
+        }
+        for _ in 0..9 {
+            histogram.record(Duration::from_millis(40));
+        }
+        histogram.record(Duration::from_millis(750));
+
+        assert_eq!(
+            histogram.take_summary(),
+            Some(InputLatencySummary {
+                sample_count: 100,
+                bucket_counts: vec![90, 0, 0, 0, 9, 0, 0, 0, 1],
+                p50: Duration::from_millis(4),
+                p95: Duration::from_millis(50),
+                p99: Duration::from_millis(50),
+                max: Duration::from_millis(750),
+            })
+        );
+        assert_eq!(histogram.take_summary(), None);
+    }
+}
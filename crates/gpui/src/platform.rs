@@ -124,6 +124,9 @@ pub trait PlatformDisplay: Send + Sync + Debug {
 
     /// Get the bounds for this display
     fn bounds(&self) -> Bounds<GlobalPixels>;
+
+    /// Get the ratio of physical pixels to logical pixels on this display
+    fn scale_factor(&self) -> f32;
 }
 
 /// An opaque identifier for a hardware display
@@ -6,7 +6,9 @@ use cocoa::{
     foundation::{NSDictionary, NSPoint, NSRect, NSSize, NSString},
 };
 use core_foundation::uuid::{CFUUIDGetUUIDBytes, CFUUIDRef};
-use core_graphics::display::{CGDirectDisplayID, CGDisplayBounds, CGGetActiveDisplayList};
+use core_graphics::display::{
+    CGDirectDisplayID, CGDisplay, CGDisplayBounds, CGGetActiveDisplayList,
+};
 use objc::{msg_send, sel, sel_impl};
 use uuid::Uuid;
 
@@ -163,4 +165,11 @@ impl PlatformDisplay for MacDisplay {
             }
         }
     }
+
+    fn scale_factor(&self) -> f32 {
+        match CGDisplay::new(self.0).display_mode() {
+            Some(mode) if mode.width() > 0 => mode.pixel_width() as f32 / mode.width() as f32,
+            _ => 1.,
+        }
+    }
 }
@@ -34,4 +34,8 @@ impl PlatformDisplay for TestDisplay {
     fn bounds(&self) -> crate::Bounds<crate::GlobalPixels> {
         self.bounds
     }
+
+    fn scale_factor(&self) -> f32 {
+        2.
+    }
 }
@@ -34,7 +34,7 @@ use std::{
         atomic::{AtomicUsize, Ordering::SeqCst},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 use util::{measure, ResultExt};
 
@@ -276,6 +276,7 @@ pub struct Window {
     pub(crate) focus: Option<FocusId>,
     focus_enabled: bool,
     pending_input: Option<PendingInput>,
+    pub(crate) pending_keystroke_at: Option<Instant>,
 
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) focus_invalidated: bool,
@@ -342,7 +343,16 @@ impl Window {
             let mut cx = cx.to_async();
             move || {
                 measure("frame duration", || {
-                    handle.update(&mut cx, |_, cx| cx.draw()).log_err();
+                    handle
+                        .update(&mut cx, |_, cx| {
+                            let frame_start = Instant::now();
+                            cx.draw();
+                            cx.app.frame_timings.record(frame_start.elapsed());
+                            if let Some(keystroke_at) = cx.window.pending_keystroke_at.take() {
+                                cx.app.input_latency.record(keystroke_at.elapsed());
+                            }
+                        })
+                        .log_err();
                 })
             }
         }));
@@ -380,10 +390,23 @@ impl Window {
         platform_window.on_input({
             let mut cx = cx.to_async();
             Box::new(move |event| {
-                handle
+                let keystroke_at = matches!(event, PlatformInput::KeyDown(_)).then(Instant::now);
+                let handled = handle
                     .update(&mut cx, |_, cx| cx.dispatch_event(event))
                     .log_err()
-                    .unwrap_or(false)
+                    .unwrap_or(false);
+                // Only keystrokes that lead to a redraw are measured. Effects have been
+                // flushed by now, so the window is dirty if the keystroke changed anything.
+                if let Some(keystroke_at) = keystroke_at {
+                    handle
+                        .update(&mut cx, |_, cx| {
+                            if cx.window.dirty {
+                                cx.window.pending_keystroke_at.get_or_insert(keystroke_at);
+                            }
+                        })
+                        .log_err();
+                }
+                handled
             })
         });
 
@@ -418,6 +441,7 @@ impl Window {
             focus: None,
             focus_enabled: true,
             pending_input: None,
+            pending_keystroke_at: None,
 
             #[cfg(any(test, feature = "test-support"))]
             focus_invalidated: false,
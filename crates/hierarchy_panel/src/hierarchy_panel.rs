@@ -0,0 +1,636 @@
+mod hierarchy_panel_settings;
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use futures::{future::LocalBoxFuture, FutureExt};
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, KeyContext,
+    Model, Render, Task, View, VisualContext, WeakView,
+};
+use hierarchy_panel_settings::HierarchyPanelSettings;
+use language::ToOffset;
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{
+    CallHierarchyDirection, CallHierarchyItem, Fs, Location, Project, TypeHierarchyDirection,
+    TypeHierarchyItem,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{prelude::*, ListItem, Tooltip};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const HIERARCHY_PANEL_KEY: &'static str = "HierarchyPanel";
+
+actions!(
+    hierarchy_panel,
+    [
+        ToggleFocus,
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSupertypes,
+        ShowSubtypes,
+        ExpandSelectedEntry,
+        CollapseSelectedEntry,
+    ]
+);
+
+pub fn init(cx: &mut AppContext) {
+    HierarchyPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(cx);
+            })
+            .register_action(|workspace, _: &ShowIncomingCalls, cx| {
+                let hierarchy = Hierarchy::Calls(CallHierarchyDirection::Incoming);
+                HierarchyPanel::show_hierarchy(workspace, hierarchy, cx);
+            })
+            .register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+                let hierarchy = Hierarchy::Calls(CallHierarchyDirection::Outgoing);
+                HierarchyPanel::show_hierarchy(workspace, hierarchy, cx);
+            })
+            .register_action(|workspace, _: &ShowSupertypes, cx| {
+                let hierarchy = Hierarchy::Types(TypeHierarchyDirection::Supertypes);
+                HierarchyPanel::show_hierarchy(workspace, hierarchy, cx);
+            })
+            .register_action(|workspace, _: &ShowSubtypes, cx| {
+                let hierarchy = Hierarchy::Types(TypeHierarchyDirection::Subtypes);
+                HierarchyPanel::show_hierarchy(workspace, hierarchy, cx);
+            });
+    })
+    .detach();
+}
+
+/// Shows the callers or the callees of a symbol, or the supertypes or the subtypes of a type,
+/// as a tree whose entries are loaded from the language server as they're expanded.
+pub struct HierarchyPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    hierarchy: Hierarchy,
+    roots: Vec<HierarchyEntry>,
+    /// The path of indices from the roots to the selected entry.
+    selection: Option<Vec<usize>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Hierarchy {
+    Calls(CallHierarchyDirection),
+    Types(TypeHierarchyDirection),
+}
+
+impl Hierarchy {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Calls(CallHierarchyDirection::Incoming) => "Incoming Calls",
+            Self::Calls(CallHierarchyDirection::Outgoing) => "Outgoing Calls",
+            Self::Types(TypeHierarchyDirection::Supertypes) => "Supertypes",
+            Self::Types(TypeHierarchyDirection::Subtypes) => "Subtypes",
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        match self {
+            Self::Calls(CallHierarchyDirection::Incoming) => {
+                Self::Calls(CallHierarchyDirection::Outgoing)
+            }
+            Self::Calls(CallHierarchyDirection::Outgoing) => {
+                Self::Calls(CallHierarchyDirection::Incoming)
+            }
+            Self::Types(TypeHierarchyDirection::Supertypes) => {
+                Self::Types(TypeHierarchyDirection::Subtypes)
+            }
+            Self::Types(TypeHierarchyDirection::Subtypes) => {
+                Self::Types(TypeHierarchyDirection::Supertypes)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn file_name(&self) -> Option<String> {
+        let uri = match self {
+            Self::Call(item) => &item.uri,
+            Self::Type(item) => &item.uri,
+        };
+        let path = uri.to_file_path().ok()?;
+        Some(path.file_name()?.to_string_lossy().to_string())
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            Self::Call(item) => &item.location,
+            Self::Type(item) => &item.location,
+        }
+    }
+}
+
+fn hierarchy_items<T: 'static>(
+    request: Task<Result<Vec<T>>>,
+    to_item: fn(T) -> HierarchyItem,
+) -> LocalBoxFuture<'static, Result<Vec<HierarchyItem>>> {
+    request
+        .map(move |items| anyhow::Ok(items?.into_iter().map(to_item).collect()))
+        .boxed_local()
+}
+
+struct HierarchyEntry {
+    item: HierarchyItem,
+    /// The entry's children in the hierarchy, once they're loaded.
+    children: Option<Vec<HierarchyEntry>>,
+    expanded: bool,
+    loading: Option<Task<()>>,
+}
+
+impl HierarchyEntry {
+    fn new(item: HierarchyItem) -> Self {
+        Self {
+            item,
+            children: None,
+            expanded: false,
+            loading: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+}
+
+impl HierarchyPanel {
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| Self {
+            workspace,
+            project,
+            fs,
+            focus_handle: cx.focus_handle(),
+            width: None,
+            pending_serialization: Task::ready(None),
+            hierarchy: Hierarchy::Calls(CallHierarchyDirection::Incoming),
+            roots: Vec::new(),
+            selection: None,
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(HIERARCHY_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedHierarchyPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        HIERARCHY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedHierarchyPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Shows the hierarchy of the symbol under the newest cursor of the active editor.
+    fn show_hierarchy(
+        workspace: &mut Workspace,
+        hierarchy: Hierarchy,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        }) else {
+            return;
+        };
+        let items = workspace
+            .project()
+            .update(cx, |project, cx| match hierarchy {
+                Hierarchy::Calls(_) => hierarchy_items(
+                    project.prepare_call_hierarchy(&buffer, position, cx),
+                    HierarchyItem::Call,
+                ),
+                Hierarchy::Types(_) => hierarchy_items(
+                    project.prepare_type_hierarchy(&buffer, position, cx),
+                    HierarchyItem::Type,
+                ),
+            });
+
+        cx.spawn(|workspace, mut cx| async move {
+            let items = items.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.set_roots(items, hierarchy, cx));
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn set_roots(
+        &mut self,
+        items: Vec<HierarchyItem>,
+        hierarchy: Hierarchy,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.hierarchy = hierarchy;
+        self.roots = items.into_iter().map(HierarchyEntry::new).collect();
+        self.selection = None;
+        // A single symbol's children are what was asked for, so they're shown right away.
+        if self.roots.len() == 1 {
+            self.selection = Some(vec![0]);
+            self.expand(&[0], cx);
+        }
+        cx.notify();
+    }
+
+    fn reverse_hierarchy(&mut self, cx: &mut ViewContext<Self>) {
+        let items = self.roots.drain(..).map(|root| root.item).collect();
+        self.set_roots(items, self.hierarchy.reversed(), cx);
+    }
+
+    fn entry(&self, path: &[usize]) -> Option<&HierarchyEntry> {
+        let (first, rest) = path.split_first()?;
+        let mut entry = self.roots.get(*first)?;
+        for ix in rest {
+            entry = entry.children.as_ref()?.get(*ix)?;
+        }
+        Some(entry)
+    }
+
+    fn entry_mut(&mut self, path: &[usize]) -> Option<&mut HierarchyEntry> {
+        let (first, rest) = path.split_first()?;
+        let mut entry = self.roots.get_mut(*first)?;
+        for ix in rest {
+            entry = entry.children.as_mut()?.get_mut(*ix)?;
+        }
+        Some(entry)
+    }
+
+    /// The paths of the entries that aren't inside collapsed entries, in the order in which
+    /// they're displayed.
+    fn visible_entries(&self) -> Vec<Vec<usize>> {
+        fn push_visible(
+            entries: &[HierarchyEntry],
+            path: &mut Vec<usize>,
+            out: &mut Vec<Vec<usize>>,
+        ) {
+            for (ix, entry) in entries.iter().enumerate() {
+                path.push(ix);
+                out.push(path.clone());
+                if entry.expanded {
+                    if let Some(children) = &entry.children {
+                        push_visible(children, path, out);
+                    }
+                }
+                path.pop();
+            }
+        }
+
+        let mut visible_entries = Vec::new();
+        push_visible(&self.roots, &mut Vec::new(), &mut visible_entries);
+        visible_entries
+    }
+
+    fn expand(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entry(path) else {
+            return;
+        };
+        let request = (entry.children.is_none() && entry.loading.is_none()).then(|| {
+            let item = entry.item.clone();
+            let hierarchy = self.hierarchy;
+            self.project
+                .update(cx, |project, cx| match (&item, hierarchy) {
+                    (HierarchyItem::Call(item), Hierarchy::Calls(direction)) => {
+                        hierarchy_items(project.call_hierarchy_calls(item, direction, cx), |call| {
+                            HierarchyItem::Call(call.item)
+                        })
+                    }
+                    (HierarchyItem::Type(item), Hierarchy::Types(direction)) => hierarchy_items(
+                        project.type_hierarchy_related_types(item, direction, cx),
+                        HierarchyItem::Type,
+                    ),
+                    // The roots are replaced along with the hierarchy, so their kinds match.
+                    _ => futures::future::ready(Ok(Vec::new())).boxed_local(),
+                })
+        });
+
+        let Some(entry) = self.entry_mut(path) else {
+            return;
+        };
+        entry.expanded = true;
+        if let Some(request) = request {
+            let path = path.to_vec();
+            entry.loading = Some(cx.spawn(|this, mut cx| async move {
+                let items = request.await.log_err().unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    if let Some(entry) = this.entry_mut(&path) {
+                        entry.children = Some(items.into_iter().map(HierarchyEntry::new).collect());
+                        entry.loading = None;
+                    }
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        cx.notify();
+    }
+
+    fn collapse(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.entry_mut(path) {
+            entry.expanded = false;
+            cx.notify();
+        }
+    }
+
+    fn toggle_expanded(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        if self.entry(path).map_or(false, |entry| entry.expanded) {
+            self.collapse(path, cx);
+        } else {
+            self.expand(path, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let visible_entries = self.visible_entries();
+        let ix = self
+            .selection
+            .as_ref()
+            .and_then(|selection| visible_entries.iter().position(|path| path == selection))
+            .map_or(0, |ix| ix + 1);
+        if let Some(path) = visible_entries.into_iter().nth(ix) {
+            self.selection = Some(path);
+            cx.notify();
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let visible_entries = self.visible_entries();
+        let ix = self
+            .selection
+            .as_ref()
+            .and_then(|selection| visible_entries.iter().position(|path| path == selection))
+            .map_or(visible_entries.len(), |ix| ix)
+            .checked_sub(1);
+        if let Some(path) = ix.and_then(|ix| visible_entries.into_iter().nth(ix)) {
+            self.selection = Some(path);
+            cx.notify();
+        }
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selection.clone() {
+            self.expand(&path, cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        let Some(mut path) = self.selection.clone() else {
+            return;
+        };
+        if self.entry(&path).map_or(false, |entry| entry.expanded) {
+            self.collapse(&path, cx);
+        } else if path.len() > 1 {
+            path.pop();
+            self.selection = Some(path);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selection.clone() {
+            self.open_entry(&path, cx);
+        }
+    }
+
+    fn open_entry(&mut self, path: &[usize], cx: &mut ViewContext<Self>) {
+        let Some(location) = self.entry(path).map(|entry| entry.item.location().clone()) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let position = location.range.start.to_offset(location.buffer.read(cx));
+                let editor = workspace.open_project_item::<Editor>(location.buffer.clone(), cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([position..position])
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    fn render_entry(&self, ix: usize, path: Vec<usize>, cx: &mut ViewContext<Self>) -> ListItem {
+        let entry = self.entry(&path).expect("visible entries exist");
+        let item = &entry.item;
+        let file_name = item.file_name();
+        let toggle = match &entry.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(entry.expanded),
+        };
+        let selected = self.selection.as_ref() == Some(&path);
+
+        ListItem::new(ix)
+            .indent_level(path.len() - 1)
+            .selected(selected)
+            .toggle(toggle)
+            .on_toggle(cx.listener({
+                let path = path.clone();
+                move |this, _, cx| this.toggle_expanded(&path, cx)
+            }))
+            .on_click(cx.listener(move |this, _, cx| {
+                this.selection = Some(path.clone());
+                this.open_entry(&path, cx);
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(item.name().to_string()))
+                    .children(file_name.map(|file_name| {
+                        Label::new(file_name)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                    })),
+            )
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::default();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = self.hierarchy.title();
+        let reverse_tooltip = format!("Show {}", self.hierarchy.reversed().title());
+        let entries = self
+            .visible_entries()
+            .into_iter()
+            .enumerate()
+            .map(|(ix, path)| self.render_entry(ix, path, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .key_context(self.dispatch_context())
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::confirm))
+            .track_focus(&self.focus_handle)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(title))
+                    .when(!self.roots.is_empty(), |this| {
+                        this.child(
+                            IconButton::new("reverse-hierarchy", IconName::ArrowCircle)
+                                .tooltip(move |cx| Tooltip::text(reverse_tooltip.clone(), cx))
+                                .on_click(cx.listener(|this, _, cx| this.reverse_hierarchy(cx))),
+                        )
+                    }),
+            )
+            .child(if entries.is_empty() {
+                v_flex()
+                    .p_4()
+                    .child(
+                        Label::new(
+                            "Show the calls of a symbol or the hierarchy of a type from its editor.",
+                        )
+                        .color(Color::Muted),
+                    )
+                    .into_any_element()
+            } else {
+                v_flex()
+                    .id("hierarchy-entries")
+                    .size_full()
+                    .overflow_y_scroll()
+                    .children(entries)
+                    .into_any_element()
+            })
+    }
+}
+
+impl FocusableView for HierarchyPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "HierarchyPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        HierarchyPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<HierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Link)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -21,6 +21,7 @@ pub mod markdown;
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::{HashMap, HashSet};
+use fs::Fs;
 use futures::{
     channel::{mpsc, oneshot},
     future::Shared,
@@ -39,6 +40,7 @@ use std::{
     any::Any,
     borrow::Cow,
     cell::RefCell,
+    ffi::OsString,
     fmt::Debug,
     hash::Hash,
     mem,
@@ -54,7 +56,12 @@ use syntax_map::SyntaxSnapshot;
 use theme::{SyntaxTheme, Theme};
 use tree_sitter::{self, Query};
 use unicase::UniCase;
-use util::{http::HttpClient, paths::PathExt};
+use util::{
+    fs::{find_executable, remove_stale_versions},
+    github::{download_verified, github_release_for_version, latest_github_release, GithubRelease},
+    http::HttpClient,
+    paths::PathExt,
+};
 use util::{post_inc, ResultExt, TryFutureExt as _, UnwrapFuture};
 
 pub use buffer::Operation;
@@ -65,6 +72,9 @@ pub use outline::{Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
 pub use text::LineEnding;
 pub use tree_sitter::{Parser, Tree};
+/// How many versions of each language server are kept installed, so that switching back to
+/// a recent version doesn't need another download.
+const INSTALLED_VERSIONS_TO_KEEP: usize = 2;
 
 /// Initializes the `language` crate.
 ///
@@ -163,6 +173,28 @@ impl CachedLspAdapter {
         self.adapter.fetch_latest_server_version(delegate).await
     }
 
+    pub async fn check_prerequisites(&self, delegate: &dyn LspAdapterDelegate) -> Result<()> {
+        self.adapter.check_prerequisites(delegate).await
+    }
+
+    /// Looks for the language server on the `PATH`, and in the other places it's commonly
+    /// installed in.
+    pub async fn find_system_binary(&self) -> Option<LanguageServerBinary> {
+        let system_binary = self.adapter.system_binary()?;
+        let path = find_executable(
+            system_binary.name,
+            std::env::var_os("PATH").as_deref(),
+            &system_binary.search_paths,
+        )
+        .await?;
+        Some(LanguageServerBinary {
+            path,
+            arguments: system_binary.arguments,
+            socket: None,
+            env: None,
+        })
+    }
+
     pub fn will_fetch_server(
         &self,
         delegate: &Arc<dyn LspAdapterDelegate>,
@@ -200,6 +232,16 @@ impl CachedLspAdapter {
             .await
     }
 
+    pub async fn remove_stale_versions(
+        &self,
+        container_dir: PathBuf,
+        binary: &LanguageServerBinary,
+    ) {
+        self.adapter
+            .remove_stale_versions(container_dir, binary)
+            .await
+    }
+
     pub fn can_be_reinstalled(&self) -> bool {
         self.adapter.can_be_reinstalled()
     }
@@ -219,6 +261,10 @@ impl CachedLspAdapter {
         self.adapter.workspace_configuration(workspace_root, cx)
     }
 
+    pub async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        self.adapter.project_root(worktree_path, fs).await
+    }
+
     pub fn process_diagnostics(&self, params: &mut lsp::PublishDiagnosticsParams) {
         self.adapter.process_diagnostics(params)
     }
@@ -253,9 +299,93 @@ impl CachedLspAdapter {
 
 /// [`LspAdapterDelegate`] allows [`LspAdapter]` implementations to interface with the application
 // e.g. to display a notification or fetch data from the web.
+#[async_trait]
 pub trait LspAdapterDelegate: Send + Sync {
-    fn show_notification(&self, message: &str, cx: &mut AppContext);
+    fn show_notification(&self, message: &str);
     fn http_client(&self) -> Arc<dyn HttpClient>;
+    /// The version of the language server pinned in the settings, which adapters install
+    /// instead of the latest release.
+    fn pinned_server_version(&self) -> Option<String>;
+    /// Whether a copy of the language server that's already installed on the system should
+    /// be run instead of the one Zed installs.
+    fn prefers_system_binary(&self) -> bool;
+    /// Shows how far along installing the language server is.
+    fn report_install_progress(&self, progress: InstallProgress);
+    /// Finds the executable named `name` on the `PATH` of the user's login shell.
+    async fn which(&self, name: &str) -> Option<PathBuf>;
+}
+
+/// Downloads a language server's binary or archive to `destination`, reporting the progress
+/// to the delegate, and verifies it against `sha256` when the checksum is known.
+///
+/// Interrupted downloads are resumed and retried a few times before this fails, and a
+/// partial download is never left at `destination`.
+pub async fn download_server_binary(
+    delegate: &dyn LspAdapterDelegate,
+    url: &str,
+    sha256: Option<&str>,
+    destination: &Path,
+) -> Result<()> {
+    download_verified(
+        delegate.http_client(),
+        url,
+        sha256,
+        destination,
+        &|downloaded_bytes, total_bytes| {
+            delegate.report_install_progress(InstallProgress::Downloading {
+                downloaded_bytes,
+                total_bytes,
+            })
+        },
+    )
+    .await
+}
+
+/// Fetches the GitHub release of a language server to install, which is the version pinned
+/// in the settings if there is one, or else the latest release.
+///
+/// The user is told when the pinned version isn't the latest.
+pub async fn github_release_to_install(
+    repo_name_with_owner: &str,
+    pre_release: bool,
+    delegate: &dyn LspAdapterDelegate,
+) -> Result<GithubRelease> {
+    let latest_release =
+        latest_github_release(repo_name_with_owner, pre_release, delegate.http_client()).await;
+    let Some(pinned_version) = delegate.pinned_server_version() else {
+        return latest_release;
+    };
+
+    match latest_release {
+        Ok(latest_release) if latest_release.is_version(&pinned_version) => {
+            return Ok(latest_release)
+        }
+        Ok(latest_release) => delegate.show_notification(&format!(
+            "{repo_name_with_owner} is pinned to version {pinned_version} in your settings, but {} is the latest release.",
+            latest_release.name
+        )),
+        Err(error) => log::warn!(
+            "failed to check whether {pinned_version} is the latest release of {repo_name_with_owner}: {error:#}"
+        ),
+    }
+
+    github_release_for_version(
+        repo_name_with_owner,
+        &pinned_version,
+        delegate.http_client(),
+    )
+    .await
+}
+
+/// A language server executable that may already be installed on the system.
+pub struct SystemBinary {
+    /// The name of the executable.
+    pub name: &'static str,
+    /// Directories the executable is commonly installed in that aren't always on the
+    /// `PATH`, which are searched after it.
+    pub search_paths: Vec<PathBuf>,
+    /// The arguments to launch the executable with.
+    pub arguments: Vec<OsString>,
 }
 
 #[async_trait]
@@ -269,6 +399,18 @@ pub trait LspAdapter: 'static + Send + Sync {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>>;
 
+    /// Checks that the tools the language server needs are installed, before it's downloaded
+    /// or started. The error is shown to the user, so it should say how to install them.
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        Ok(())
+    }
+
+    /// How to find and run a copy of the language server installed by other tools, e.g. a
+    /// package manager, which is used when the `prefer_system_binary` setting is enabled.
+    fn system_binary(&self) -> Option<SystemBinary> {
+        None
+    }
+
     fn will_fetch_server(
         &self,
         _: &Arc<dyn LspAdapterDelegate>,
@@ -298,6 +440,34 @@ pub trait LspAdapter: 'static + Send + Sync {
         delegate: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary>;
 
+    /// The prefix of the names of the entries in the container directory that each hold
+    /// one installed version of the language server, like `rust-analyzer-` for
+    /// `rust-analyzer-2023-12-18`.
+    ///
+    /// Adapters that install the language server in place, or share one installation
+    /// between versions, return `None`.
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Removes old versions of the language server from `container_dir`, once `binary` has
+    /// been installed there.
+    ///
+    /// By default, all but the most recent of the entries named with
+    /// [`installed_version_prefix`](Self::installed_version_prefix) are removed. Adapters
+    /// that lay out their installations differently can clean them up here instead.
+    async fn remove_stale_versions(&self, container_dir: PathBuf, binary: &LanguageServerBinary) {
+        if let Some(prefix) = self.installed_version_prefix() {
+            remove_stale_versions(
+                &container_dir,
+                prefix,
+                &binary.path,
+                INSTALLED_VERSIONS_TO_KEEP,
+            )
+            .await;
+        }
+    }
+
     /// Returns `true` if a language server can be reinstalled.
     ///
     /// If language server initialization fails, a reinstallation will be attempted unless the value returned from this method is `false`.
@@ -346,6 +516,15 @@ pub trait LspAdapter: 'static + Send + Sync {
         serde_json::json!({})
     }
 
+    /// The directory to start the language server in for a worktree, when it isn't the
+    /// worktree's root, e.g. the root of a build system's workspace that contains it.
+    ///
+    /// It's looked for once per worktree, before the server is first started for it, and
+    /// worktrees whose project roots are the same share one instance of the server.
+    async fn project_root(&self, _worktree_path: &Path, _fs: &dyn Fs) -> Option<PathBuf> {
+        None
+    }
+
     /// Returns a list of code actions supported by a given LspAdapter
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         Some(vec![
@@ -608,7 +787,7 @@ pub struct Language {
     pub(crate) adapters: Vec<Arc<CachedLspAdapter>>,
 
     #[cfg(any(test, feature = "test-support"))]
-    fake_adapter: Option<(
+    fake_adapters: Vec<(
         mpsc::UnboundedSender<lsp::FakeLanguageServer>,
         Arc<FakeLspAdapter>,
     )>,
@@ -682,11 +861,23 @@ struct BracketConfig {
 pub enum LanguageServerBinaryStatus {
     CheckingForUpdate,
     Downloading,
+    Installing(InstallProgress),
     Downloaded,
     Cached,
     Failed { error: String },
 }
 
+/// A step of installing a language server, reported by its adapter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstallProgress {
+    Downloading {
+        downloaded_bytes: u64,
+        /// The size of the download, when the server reports it.
+        total_bytes: Option<u64>,
+    },
+    Extracting,
+}
+
 type AvailableLanguageId = usize;
 
 #[derive(Clone)]
@@ -1009,6 +1200,8 @@ impl LanguageRegistry {
         language: Arc<Language>,
         adapter: Arc<CachedLspAdapter>,
         root_path: Arc<Path>,
+        override_binary: Option<LanguageServerBinary>,
+        override_env: Option<HashMap<String, String>>,
         delegate: Arc<dyn LspAdapterDelegate>,
         cx: &mut AppContext,
     ) -> Option<PendingLanguageServer> {
@@ -1019,9 +1212,14 @@ impl LanguageRegistry {
         );
 
         #[cfg(any(test, feature = "test-support"))]
-        if language.fake_adapter.is_some() {
+        if let Some((servers_tx, fake_adapter)) = language
+            .fake_adapters
+            .iter()
+            .find(|(_, fake_adapter)| fake_adapter.name == adapter.name.0.as_ref())
+            .or_else(|| language.fake_adapters.first())
+            .cloned()
+        {
             let task = cx.spawn(|cx| async move {
-                let (servers_tx, fake_adapter) = language.fake_adapter.as_ref().unwrap();
                 let (server, mut fake_server) = lsp::FakeLanguageServer::new(
                     fake_adapter.name.to_string(),
                     fake_adapter.capabilities.clone(),
@@ -1032,7 +1230,6 @@ impl LanguageRegistry {
                     initializer(&mut fake_server);
                 }
 
-                let servers_tx = servers_tx.clone();
                 cx.background_executor()
                     .spawn(async move {
                         if fake_server
@@ -1073,46 +1270,71 @@ impl LanguageRegistry {
             cx.spawn(move |mut cx| async move {
                 login_shell_env_loaded.await;
 
-                let entry = this
-                    .lsp_binary_paths
-                    .lock()
-                    .entry(adapter.name.clone())
-                    .or_insert_with(|| {
-                        let adapter = adapter.clone();
-                        let language = language.clone();
-                        let delegate = delegate.clone();
-                        cx.spawn(|cx| {
-                            get_binary(
-                                adapter,
-                                language,
-                                delegate,
-                                container_dir,
-                                lsp_binary_statuses,
-                                cx,
-                            )
-                            .map_err(Arc::new)
+                // A binary configured in the settings is launched as is, without the adapter
+                // fetching or updating its own.
+                let mut binary = if let Some(binary) = override_binary {
+                    binary
+                } else {
+                    let entry = this
+                        .lsp_binary_paths
+                        .lock()
+                        .entry(adapter.name.clone())
+                        .or_insert_with(|| {
+                            let adapter = adapter.clone();
+                            let language = language.clone();
+                            let delegate = delegate.clone();
+                            cx.spawn(|cx| {
+                                get_binary(
+                                    adapter,
+                                    language,
+                                    delegate,
+                                    container_dir,
+                                    lsp_binary_statuses,
+                                    cx,
+                                )
+                                .map_err(Arc::new)
+                            })
+                            .shared()
                         })
-                        .shared()
-                    })
-                    .clone();
+                        .clone();
 
-                let binary = match entry.await {
-                    Ok(binary) => binary,
-                    Err(err) => anyhow::bail!("{err}"),
+                    match entry.await {
+                        Ok(binary) => binary,
+                        Err(err) => return Err(LanguageServerBinaryError(err.to_string()).into()),
+                    }
                 };
+                // The variables in the settings take precedence over the adapter's.
+                if let Some(override_env) = override_env {
+                    binary
+                        .env
+                        .get_or_insert_with(Default::default)
+                        .extend(override_env);
+                }
 
                 if let Some(task) = adapter.will_start_server(&delegate, &mut cx) {
                     task.await?;
                 }
 
-                lsp::LanguageServer::new(
-                    stderr_capture,
-                    server_id,
-                    binary,
-                    &root_path,
-                    adapter.code_action_kinds(),
-                    cx,
-                )
+                if binary.socket.is_some() {
+                    lsp::LanguageServer::connect(
+                        stderr_capture,
+                        server_id,
+                        binary,
+                        &root_path,
+                        adapter.code_action_kinds(),
+                        cx,
+                    )
+                    .await
+                } else {
+                    lsp::LanguageServer::new(
+                        stderr_capture,
+                        server_id,
+                        binary,
+                        &root_path,
+                        adapter.code_action_kinds(),
+                        cx,
+                    )
+                }
             })
         };
 
@@ -1129,6 +1351,11 @@ impl LanguageRegistry {
         self.lsp_binary_status_tx.subscribe()
     }
 
+    pub fn report_install_progress(&self, language: Arc<Language>, progress: InstallProgress) {
+        self.lsp_binary_status_tx
+            .send(language, LanguageServerBinaryStatus::Installing(progress));
+    }
+
     pub fn delete_server_container(
         &self,
         adapter: Arc<CachedLspAdapter>,
@@ -1156,6 +1383,12 @@ impl LanguageRegistry {
     pub fn next_language_server_id(&self) -> LanguageServerId {
         self.state.write().next_language_server_id()
     }
+
+    /// Makes the next server started with this adapter fetch its binary again, e.g. after
+    /// the version pinned in the settings changed.
+    pub fn forget_server_binary(&self, name: &LanguageServerName) {
+        self.lsp_binary_paths.lock().remove(name);
+    }
 }
 
 impl LanguageRegistryState {
@@ -1202,6 +1435,18 @@ impl Default for LanguageRegistry {
     }
 }
 
+/// The error a pending language server fails with when no binary could be fetched for it.
+#[derive(Debug)]
+pub struct LanguageServerBinaryError(pub String);
+
+impl std::fmt::Display for LanguageServerBinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for LanguageServerBinaryError {}
+
 async fn get_binary(
     adapter: Arc<CachedLspAdapter>,
     language: Arc<Language>,
@@ -1216,6 +1461,29 @@ async fn get_binary(
             .context("failed to create container directory")?;
     }
 
+    // A server whose prerequisites are missing couldn't run even if it was already installed.
+    if let Err(error) = adapter.check_prerequisites(delegate.as_ref()).await {
+        delegate.show_notification(&format!("{error:#}"));
+        statuses.send(
+            language.clone(),
+            LanguageServerBinaryStatus::Failed {
+                error: format!("{error:#}"),
+            },
+        );
+        return Err(error);
+    }
+
+    if delegate.prefers_system_binary() {
+        if let Some(binary) = adapter.find_system_binary().await {
+            log::info!(
+                "using the {} language server installed at {:?}",
+                adapter.name.0,
+                binary.path
+            );
+            return Ok(binary);
+        }
+    }
+
     if let Some(task) = adapter.will_fetch_server(&delegate, &mut cx) {
         task.await?;
     }
@@ -1229,11 +1497,17 @@ async fn get_binary(
     )
     .await;
 
+    // When the latest server can't be fetched, e.g. while offline, fall back to the last one
+    // that was installed and tell the user that it may be out of date.
     if let Err(error) = binary.as_ref() {
         if let Some(binary) = adapter
             .cached_server_binary(container_dir.to_path_buf(), delegate.as_ref())
             .await
         {
+            log::warn!(
+                "failed to fetch the latest {} language server, using a cached one: {error:#}",
+                adapter.name.0
+            );
             statuses.send(language.clone(), LanguageServerBinaryStatus::Cached);
             return Ok(binary);
         } else {
@@ -1269,6 +1543,9 @@ async fn fetch_latest_binary(
         .fetch_server_binary(version_info, container_dir.to_path_buf(), delegate)
         .await?;
     lsp_binary_statuses_tx.send(language.clone(), LanguageServerBinaryStatus::Downloaded);
+    adapter
+        .remove_stale_versions(container_dir.to_path_buf(), &binary)
+        .await;
 
     Ok(binary)
 }
@@ -1295,7 +1572,7 @@ impl Language {
             adapters: Vec::new(),
 
             #[cfg(any(test, feature = "test-support"))]
-            fake_adapter: None,
+            fake_adapters: Vec::new(),
         }
     }
 
@@ -1588,11 +1865,24 @@ impl Language {
     pub async fn set_fake_lsp_adapter(
         &mut self,
         fake_lsp_adapter: Arc<FakeLspAdapter>,
+    ) -> mpsc::UnboundedReceiver<lsp::FakeLanguageServer> {
+        self.fake_adapters.clear();
+        self.adapters.clear();
+        self.add_fake_lsp_adapter(fake_lsp_adapter).await
+    }
+
+    /// Adds another fake language server alongside the ones already set, in the
+    /// order that the language's servers are queried in.
+    #[cfg(any(test, feature = "test-support"))]
+    pub async fn add_fake_lsp_adapter(
+        &mut self,
+        fake_lsp_adapter: Arc<FakeLspAdapter>,
     ) -> mpsc::UnboundedReceiver<lsp::FakeLanguageServer> {
         let (servers_tx, servers_rx) = mpsc::unbounded();
-        self.fake_adapter = Some((servers_tx, fake_lsp_adapter.clone()));
+        self.fake_adapters
+            .push((servers_tx, fake_lsp_adapter.clone()));
         let adapter = CachedLspAdapter::new(Arc::new(fake_lsp_adapter)).await;
-        self.adapters = vec![adapter];
+        self.adapters.push(adapter);
         servers_rx
     }
 
@@ -76,12 +76,24 @@ pub struct LanguageSettings {
     pub ensure_final_newline_on_save: bool,
     /// How to perform a buffer format.
     pub formatter: Formatter,
+    /// Whether to ask the language server to format the code after typing one of the
+    /// characters that the server triggers formatting on.
+    pub use_on_type_format: bool,
     /// Zed's Prettier integration settings.
     /// If Prettier is enabled, Zed will use this its Prettier instance for any applicable file, if
     /// the project has no other Prettier installed.
     pub prettier: HashMap<String, serde_json::Value>,
     /// Whether to use language servers to provide code intelligence.
     pub enable_language_server: bool,
+    /// How the results of the language's language servers are combined, when it has more
+    /// than one.
+    pub language_server_merging: LanguageServerMergingSettings,
+    /// Whether to highlight the language's code with the semantic tokens its language
+    /// server provides, on top of its syntax highlighting.
+    pub semantic_tokens: bool,
+    /// Whether to show the code lenses that the language's language servers provide, such
+    /// as commands to run tests, above the code they refer to.
+    pub code_lens: bool,
     /// Controls whether Copilot provides suggestion immediately (true)
     /// or waits for a `copilot::Toggle` (false).
     pub show_copilot_suggestions: bool,
@@ -181,6 +193,12 @@ pub struct LanguageSettingsContent {
     /// Default: auto
     #[serde(default)]
     pub formatter: Option<Formatter>,
+    /// Whether to ask the language server to format the code after typing one of the
+    /// characters that the server triggers formatting on.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub use_on_type_format: Option<bool>,
     /// Zed's Prettier integration settings.
     /// If Prettier is enabled, Zed will use this its Prettier instance for any applicable file, if
     /// the project has no other Prettier installed.
@@ -193,6 +211,22 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub enable_language_server: Option<bool>,
+    /// How the results of the language's language servers are combined, when it has more
+    /// than one.
+    #[serde(default)]
+    pub language_server_merging: Option<LanguageServerMergingSettings>,
+    /// Whether to highlight the language's code with the semantic tokens its language
+    /// server provides, on top of its syntax highlighting.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub semantic_tokens: Option<bool>,
+    /// Whether to show the code lenses that the language's language servers provide, such
+    /// as commands to run tests, above the code they refer to.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub code_lens: Option<bool>,
     /// Controls whether Copilot provides suggestion immediately (true)
     /// or waits for a `copilot::Toggle` (false).
     ///
@@ -297,6 +331,42 @@ pub enum Formatter {
     },
 }
 
+/// How the results of each kind of request are combined when a language has several
+/// language servers.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct LanguageServerMergingSettings {
+    /// Default: all
+    #[serde(default = "LanguageServerMergeStrategy::all")]
+    pub completions: LanguageServerMergeStrategy,
+    /// Default: all
+    #[serde(default = "LanguageServerMergeStrategy::all")]
+    pub diagnostics: LanguageServerMergeStrategy,
+    /// Default: primary
+    #[serde(default = "LanguageServerMergeStrategy::primary")]
+    pub hovers: LanguageServerMergeStrategy,
+}
+
+/// Which of a language's language servers to use the results of.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerMergeStrategy {
+    /// Only use the first of the language's running language servers that supports
+    /// the request.
+    Primary,
+    /// Combine the results of all of the language servers.
+    All,
+}
+
+impl LanguageServerMergeStrategy {
+    fn all() -> Self {
+        Self::All
+    }
+
+    fn primary() -> Self {
+        Self::Primary
+    }
+}
+
 /// The settings for inlay hints.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct InlayHintSettings {
@@ -556,6 +626,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.preferred_line_length,
     );
     merge(&mut settings.formatter, src.formatter.clone());
+    merge(&mut settings.use_on_type_format, src.use_on_type_format);
     merge(&mut settings.prettier, src.prettier.clone());
     merge(&mut settings.format_on_save, src.format_on_save.clone());
     merge(
@@ -570,6 +641,12 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.enable_language_server,
         src.enable_language_server,
     );
+    merge(
+        &mut settings.language_server_merging,
+        src.language_server_merging,
+    );
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.code_lens, src.code_lens);
     merge(
         &mut settings.show_copilot_suggestions,
         src.show_copilot_suggestions,
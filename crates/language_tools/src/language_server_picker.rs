@@ -0,0 +1,272 @@
+use collections::HashMap;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use lsp::LanguageServerId;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    language_server_picker,
+    [RestartLanguageServer, StopLanguageServer]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LanguageServerPicker::register)
+        .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Restart,
+    Stop,
+}
+
+/// Lists the running language servers, to restart or stop one of them.
+pub struct LanguageServerPicker {
+    picker: View<Picker<LanguageServerPickerDelegate>>,
+}
+
+impl LanguageServerPicker {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &RestartLanguageServer, cx| {
+            Self::toggle(workspace, Operation::Restart, cx);
+        });
+        workspace.register_action(|workspace, _: &StopLanguageServer, cx| {
+            Self::toggle(workspace, Operation::Stop, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, operation: Operation, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            return;
+        }
+        workspace.toggle_modal(cx, move |cx| {
+            let delegate =
+                LanguageServerPickerDelegate::new(cx.view().downgrade(), project, operation, cx);
+            Self {
+                picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+            }
+        });
+    }
+}
+
+impl Render for LanguageServerPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for LanguageServerPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for LanguageServerPicker {}
+impl ModalView for LanguageServerPicker {}
+
+struct LanguageServerEntry {
+    server_id: LanguageServerId,
+    name: String,
+    details: String,
+}
+
+pub struct LanguageServerPickerDelegate {
+    language_server_picker: WeakView<LanguageServerPicker>,
+    project: Model<Project>,
+    operation: Operation,
+    entries: Vec<LanguageServerEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl LanguageServerPickerDelegate {
+    fn new(
+        language_server_picker: WeakView<LanguageServerPicker>,
+        project: Model<Project>,
+        operation: Operation,
+        cx: &mut ViewContext<LanguageServerPicker>,
+    ) -> Self {
+        let project_ref = project.read(cx);
+        let mut servers = project_ref
+            .running_language_servers()
+            .map(|(worktree_id, language, server)| {
+                let root_name = project_ref
+                    .worktree_for_id(worktree_id, cx)
+                    .map(|worktree| worktree.read(cx).root_name().to_string());
+                (language, server, root_name)
+            })
+            .collect::<Vec<_>>();
+        // A server that's shared by several worktrees is listed once.
+        servers.sort_by_key(|(_, server, _)| server.server_id());
+        servers.dedup_by_key(|(_, server, _)| server.server_id());
+
+        let memory_by_process = process_memory(
+            servers
+                .iter()
+                .filter_map(|(_, server, _)| server.process_id()),
+        );
+        let entries = servers
+            .into_iter()
+            .map(|(language, server, root_name)| {
+                let mut details = vec![language.name().to_string()];
+                details.extend(root_name);
+                details.push(server.root_path().display().to_string());
+                if let Some(process_id) = server.process_id() {
+                    details.push(format!("PID {process_id}"));
+                    if let Some(memory) = memory_by_process.get(&process_id) {
+                        details.push(format!("{:.1} MB", *memory as f64 / (1024. * 1024.)));
+                    }
+                }
+                LanguageServerEntry {
+                    server_id: server.server_id(),
+                    name: server.name().to_string(),
+                    details: details.join(" · "),
+                }
+            })
+            .collect();
+
+        Self {
+            language_server_picker,
+            project,
+            operation,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+/// The memory used by each of the given processes, in bytes.
+fn process_memory(process_ids: impl IntoIterator<Item = u32>) -> HashMap<u32, u64> {
+    let mut system = System::new();
+    process_ids
+        .into_iter()
+        .filter_map(|process_id| {
+            let pid = Pid::from_u32(process_id);
+            system.refresh_process(pid);
+            Some((process_id, system.process(pid)?.memory()))
+        })
+        .collect()
+}
+
+impl PickerDelegate for LanguageServerPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        match self.operation {
+            Operation::Restart => "Select a language server to restart...".into(),
+            Operation::Stop => "Select a language server to stop...".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let server_id = self.entries[mat.candidate_id].server_id;
+            self.project.update(cx, |project, cx| match self.operation {
+                Operation::Restart => project.restart_language_server(server_id, cx),
+                Operation::Stop => project.stop_language_server_with_id(server_id, cx).detach(),
+            });
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.language_server_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    v_flex()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(Label::new(entry.details.clone()).color(Color::Muted)),
+                ),
+        )
+    }
+}
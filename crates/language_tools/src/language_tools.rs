@@ -1,15 +1,25 @@
+mod language_server_picker;
 mod lsp_log;
+mod rpc_trace;
+mod sent_telemetry_view;
 mod syntax_tree_view;
+mod telemetry_log;
 
 #[cfg(test)]
 mod lsp_log_tests;
 
 use gpui::AppContext;
 
+pub use language_server_picker::{LanguageServerPicker, RestartLanguageServer, StopLanguageServer};
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
+pub use sent_telemetry_view::{SentTelemetryView, ShowSentTelemetry};
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
+pub use telemetry_log::{OpenTelemetryInspector, TelemetryInspector};
 
 pub fn init(cx: &mut AppContext) {
+    language_server_picker::init(cx);
     lsp_log::init(cx);
+    sent_telemetry_view::init(cx);
     syntax_tree_view::init(cx);
+    telemetry_log::init(cx);
 }
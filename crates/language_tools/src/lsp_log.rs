@@ -1,3 +1,5 @@
+use crate::rpc_trace::{Direction, RpcTraceEntry, RpcTracer};
+use client::telemetry::{register_memory_accounting, MemoryAccounting};
 use collections::{HashMap, VecDeque};
 use editor::{actions::MoveToEnd, Editor, EditorEvent};
 use futures::{channel::mpsc, StreamExt};
@@ -8,22 +10,28 @@ use gpui::{
 };
 use language::{LanguageServerId, LanguageServerName};
 use lsp::IoKind;
-use project::{search::SearchQuery, Project};
-use std::{borrow::Cow, sync::Arc};
+use project::{search::SearchQuery, Project, WorktreeId};
+use std::{borrow::Cow, sync::Arc, time::Instant};
 use ui::{popover_menu, prelude::*, Button, Checkbox, ContextMenu, Label, Selection};
+use util::paths::HOME;
 use workspace::{
     item::{Item, ItemHandle},
+    notifications::simple_message_notification::MessageNotification,
     searchable::{SearchEvent, SearchableItem, SearchableItemHandle},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
 };
 
-const SEND_LINE: &str = "// Send:";
-const RECEIVE_LINE: &str = "// Receive:";
 const MAX_STORED_LOG_ENTRIES: usize = 2000;
 
 pub struct LogStore {
     projects: HashMap<WeakModel<Project>, ProjectState>,
-    io_tx: mpsc::UnboundedSender<(WeakModel<Project>, LanguageServerId, IoKind, String)>,
+    io_tx: mpsc::UnboundedSender<(
+        WeakModel<Project>,
+        LanguageServerId,
+        IoKind,
+        String,
+        Instant,
+    )>,
 }
 
 struct ProjectState {
@@ -34,13 +42,16 @@ struct ProjectState {
 struct LanguageServerState {
     log_messages: VecDeque<String>,
     rpc_state: Option<LanguageServerRpcState>,
+    /// The name and worktree of a server that kept crashing, whose logs are kept after the
+    /// server is removed so they can still be viewed.
+    crashed: Option<(LanguageServerName, WorktreeId)>,
     _io_logs_subscription: Option<lsp::Subscription>,
     _lsp_logs_subscription: Option<lsp::Subscription>,
 }
 
 struct LanguageServerRpcState {
-    rpc_messages: VecDeque<String>,
-    last_message_kind: Option<MessageKind>,
+    rpc_messages: VecDeque<RpcTraceEntry>,
+    tracer: RpcTracer,
 }
 
 pub struct LspLogView {
@@ -59,10 +70,33 @@ pub struct LspLogToolbarItemView {
     _log_view_subscription: Option<Subscription>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum MessageKind {
-    Send,
-    Receive,
+/// Estimates the memory held by the stored language server logs and RPC traces.
+struct LogStoreAccounting(WeakModel<LogStore>);
+
+impl MemoryAccounting for LogStoreAccounting {
+    fn name(&self) -> &'static str {
+        "language_server_logs"
+    }
+
+    fn estimated_size_in_bytes(&self, cx: &AppContext) -> Option<u64> {
+        let log_store = self.0.upgrade()?;
+        let size = log_store
+            .read(cx)
+            .projects
+            .values()
+            .flat_map(|project| project.servers.values())
+            .flat_map(|server| {
+                let rpc_messages = server
+                    .rpc_state
+                    .iter()
+                    .flat_map(|rpc| &rpc.rpc_messages)
+                    .map(|entry| &entry.body);
+                server.log_messages.iter().chain(rpc_messages)
+            })
+            .map(|message| message.len() as u64)
+            .sum();
+        Some(size)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -79,6 +113,7 @@ actions!(debug, [OpenLanguageServerLogs]);
 
 pub fn init(cx: &mut AppContext) {
     let log_store = cx.new_model(|cx| LogStore::new(cx));
+    register_memory_accounting(LogStoreAccounting(log_store.downgrade()), cx);
 
     cx.observe_new_views(move |workspace: &mut Workspace, cx| {
         let project = workspace.project();
@@ -86,6 +121,26 @@ pub fn init(cx: &mut AppContext) {
             log_store.update(cx, |store, cx| {
                 store.add_project(&project, cx);
             });
+
+            let log_store = log_store.clone();
+            cx.subscribe(project, move |workspace, project, event, cx| {
+                if let project::Event::LanguageServerCrashed {
+                    language_server_id,
+                    name,
+                    ..
+                } = event
+                {
+                    show_crash_notification(
+                        workspace,
+                        project,
+                        *language_server_id,
+                        name,
+                        log_store.clone(),
+                        cx,
+                    );
+                }
+            })
+            .detach();
         }
 
         let log_store = log_store.clone();
@@ -104,6 +159,40 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 }
 
+fn show_crash_notification(
+    workspace: &mut Workspace,
+    project: Model<Project>,
+    server_id: LanguageServerId,
+    server_name: &LanguageServerName,
+    log_store: Model<LogStore>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let message = format!(
+        "The {} language server keeps crashing and was stopped.",
+        server_name.0
+    );
+    let workspace_handle = cx.view().downgrade();
+    workspace.show_notification(server_id.0, cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(message)
+                .with_click_message("View Log")
+                .on_click(move |cx| {
+                    workspace_handle
+                        .update(cx, |workspace, cx| {
+                            let log_view = cx.new_view(|cx| {
+                                LspLogView::new(project.clone(), log_store.clone(), cx)
+                            });
+                            log_view.update(cx, |log_view, cx| {
+                                log_view.show_logs_for_server(server_id, cx)
+                            });
+                            workspace.add_item(Box::new(log_view), cx);
+                        })
+                        .ok();
+                })
+        })
+    });
+}
+
 impl LogStore {
     pub fn new(cx: &mut ModelContext<Self>) -> Self {
         let (io_tx, mut io_rx) = mpsc::unbounded();
@@ -112,10 +201,10 @@ impl LogStore {
             io_tx,
         };
         cx.spawn(|this, mut cx| async move {
-            while let Some((project, server_id, io_kind, message)) = io_rx.next().await {
+            while let Some((project, server_id, io_kind, message, at)) = io_rx.next().await {
                 if let Some(this) = this.upgrade() {
                     this.update(&mut cx, |this, cx| {
-                        this.on_io(project, server_id, io_kind, &message, cx);
+                        this.on_io(project, server_id, io_kind, &message, at, cx);
                     })?;
                 }
             }
@@ -145,6 +234,21 @@ impl LogStore {
                         project::Event::LanguageServerLog(id, message) => {
                             this.add_language_server_log(&project, *id, message, cx);
                         }
+                        project::Event::LanguageServerCrashed {
+                            language_server_id,
+                            name,
+                            worktree_id,
+                        } => {
+                            if let Some(server_state) = this
+                                .projects
+                                .get_mut(&project.downgrade())
+                                .and_then(|project_state| {
+                                    project_state.servers.get_mut(language_server_id)
+                                })
+                            {
+                                server_state.crashed = Some((name.clone(), *worktree_id));
+                            }
+                        }
                         _ => {}
                     }),
                 ],
@@ -164,6 +268,7 @@ impl LogStore {
             LanguageServerState {
                 rpc_state: None,
                 log_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
+                crashed: None,
                 _io_logs_subscription: None,
                 _lsp_logs_subscription: None,
             }
@@ -182,7 +287,13 @@ impl LogStore {
         server_state._io_logs_subscription = server.as_ref().map(|server| {
             server.on_io(move |io_kind, message| {
                 io_tx
-                    .unbounded_send((weak_project.clone(), id, io_kind, message.to_string()))
+                    .unbounded_send((
+                        weak_project.clone(),
+                        id,
+                        io_kind,
+                        message.to_string(),
+                        Instant::now(),
+                    ))
                     .ok();
             })
         });
@@ -243,8 +354,10 @@ impl LogStore {
         cx: &mut ModelContext<Self>,
     ) -> Option<()> {
         let project_state = self.projects.get_mut(&project.downgrade())?;
-        project_state.servers.remove(&id);
-        cx.notify();
+        if project_state.servers.get(&id)?.crashed.is_none() {
+            project_state.servers.remove(&id);
+            cx.notify();
+        }
         Some(())
     }
 
@@ -271,7 +384,7 @@ impl LogStore {
             .rpc_state
             .get_or_insert_with(|| LanguageServerRpcState {
                 rpc_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
-                last_message_kind: None,
+                tracer: RpcTracer::default(),
             });
         Some(rpc_state)
     }
@@ -295,11 +408,12 @@ impl LogStore {
         language_server_id: LanguageServerId,
         io_kind: IoKind,
         message: &str,
+        at: Instant,
         cx: &mut ModelContext<Self>,
     ) -> Option<()> {
-        let is_received = match io_kind {
-            IoKind::StdOut => true,
-            IoKind::StdIn => false,
+        let direction = match io_kind {
+            IoKind::StdOut => Direction::Receive,
+            IoKind::StdIn => Direction::Send,
             IoKind::StdErr => {
                 let project = project.upgrade()?;
                 let message = format!("stderr: {}", message.trim());
@@ -315,39 +429,32 @@ impl LogStore {
             .get_mut(&language_server_id)?
             .rpc_state
             .as_mut()?;
-        let kind = if is_received {
-            MessageKind::Receive
-        } else {
-            MessageKind::Send
-        };
+        let entry = state.tracer.trace(direction, message, at);
+        let line = entry.to_line();
 
-        let rpc_log_lines = &mut state.rpc_messages;
-        if state.last_message_kind != Some(kind) {
-            let line_before_message = match kind {
-                MessageKind::Send => SEND_LINE,
-                MessageKind::Receive => RECEIVE_LINE,
-            };
-            rpc_log_lines.push_back(line_before_message.to_string());
-            cx.emit(Event::NewServerLogEntry {
-                id: language_server_id,
-                entry: line_before_message.to_string(),
-                is_rpc: true,
-            });
-        }
-
-        while rpc_log_lines.len() >= MAX_STORED_LOG_ENTRIES {
-            rpc_log_lines.pop_front();
+        let rpc_log_entries = &mut state.rpc_messages;
+        while rpc_log_entries.len() >= MAX_STORED_LOG_ENTRIES {
+            rpc_log_entries.pop_front();
         }
-        let message = message.trim();
-        rpc_log_lines.push_back(message.to_string());
+        rpc_log_entries.push_back(entry);
         cx.emit(Event::NewServerLogEntry {
             id: language_server_id,
-            entry: message.to_string(),
+            entry: line,
             is_rpc: true,
         });
         cx.notify();
         Some(())
     }
+
+    fn rpc_trace(
+        &self,
+        project: &Model<Project>,
+        server_id: LanguageServerId,
+    ) -> Option<&VecDeque<RpcTraceEntry>> {
+        let project_state = self.projects.get(&project.downgrade())?;
+        let server_state = project_state.servers.get(&server_id)?;
+        Some(&server_state.rpc_state.as_ref()?.rpc_messages)
+    }
 }
 
 impl LspLogView {
@@ -494,6 +601,20 @@ impl LspLogView {
                         && self.current_server_id == Some(server_id),
                 })
             })
+            .chain(state.servers.iter().filter_map(|(&server_id, state)| {
+                let (server_name, worktree_id) = state.crashed.clone()?;
+                let worktree = self.project.read(cx).worktree_for_id(worktree_id, cx)?;
+                Some(LogMenuItem {
+                    server_id,
+                    server_name,
+                    worktree_root_name: format!("{} (crashed)", worktree.read(cx).root_name()),
+                    rpc_trace_enabled: state.rpc_state.is_some(),
+                    rpc_trace_selected: self.is_showing_rpc_trace
+                        && self.current_server_id == Some(server_id),
+                    logs_selected: !self.is_showing_rpc_trace
+                        && self.current_server_id == Some(server_id),
+                })
+            }))
             .chain(
                 self.project
                     .read(cx)
@@ -543,32 +664,15 @@ impl LspLogView {
         let rpc_log = self.log_store.update(cx, |log_store, _| {
             log_store
                 .enable_rpc_trace_for_language_server(&self.project, server_id)
-                .map(|state| log_contents(&state.rpc_messages))
+                .map(|state| {
+                    let lines = state.rpc_messages.iter().map(RpcTraceEntry::to_line);
+                    lines.collect::<Vec<_>>().join("\n")
+                })
         });
         if let Some(rpc_log) = rpc_log {
             self.current_server_id = Some(server_id);
             self.is_showing_rpc_trace = true;
             let (editor, editor_subscriptions) = Self::editor_for_logs(rpc_log, cx);
-            let language = self.project.read(cx).languages().language_for_name("JSON");
-            editor
-                .read(cx)
-                .buffer()
-                .read(cx)
-                .as_singleton()
-                .expect("log buffer should be a singleton")
-                .update(cx, |_, cx| {
-                    cx.spawn({
-                        let buffer = cx.handle();
-                        |_, mut cx| async move {
-                            let language = language.await.ok();
-                            buffer.update(&mut cx, |buffer, cx| {
-                                buffer.set_language(language, cx);
-                            })
-                        }
-                    })
-                    .detach_and_log_err(cx);
-                });
-
             self.editor = editor;
             self.editor_subscriptions = editor_subscriptions;
             cx.notify();
@@ -577,6 +681,35 @@ impl LspLogView {
         cx.focus(&self.focus_handle);
     }
 
+    /// Saves the RPC trace of the current language server to a file, as one JSON object
+    /// per message.
+    fn export_rpc_trace(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(server_id) = self.current_server_id else {
+            return;
+        };
+        let Some(entries) = self.log_store.read(cx).rpc_trace(&self.project, server_id) else {
+            return;
+        };
+        let mut contents = String::new();
+        for entry in entries {
+            if let Ok(line) = serde_json::to_string(entry) {
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+        }
+
+        let fs = self.project.read(cx).fs().clone();
+        let path = cx.prompt_for_new_path(&HOME);
+        cx.background_executor()
+            .spawn(async move {
+                if let Some(path) = path.await.ok().flatten() {
+                    fs.atomic_write(path, contents).await?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
     fn toggle_rpc_trace_for_server(
         &mut self,
         server_id: LanguageServerId,
@@ -735,11 +868,12 @@ impl Render for LspLogToolbarItemView {
         let Some(log_view) = self.log_view.clone() else {
             return div();
         };
-        let (menu_rows, current_server_id) = log_view.update(cx, |log_view, cx| {
-            let menu_rows = log_view.menu_items(cx).unwrap_or_default();
-            let current_server_id = log_view.current_server_id;
-            (menu_rows, current_server_id)
-        });
+        let (menu_rows, current_server_id, is_showing_rpc_trace) =
+            log_view.update(cx, |log_view, cx| {
+                let menu_rows = log_view.menu_items(cx).unwrap_or_default();
+                let current_server_id = log_view.current_server_id;
+                (menu_rows, current_server_id, log_view.is_showing_rpc_trace)
+            });
 
         let current_server = current_server_id.and_then(|current_server_id| {
             if let Ok(ix) = menu_rows.binary_search_by_key(&current_server_id, |e| e.server_id) {
@@ -853,25 +987,42 @@ impl Render for LspLogToolbarItemView {
                 .into()
             });
 
-        h_flex().size_full().child(lsp_menu).child(
-            div()
-                .child(
-                    Button::new("clear_log_button", "Clear").on_click(cx.listener(
-                        |this, _, cx| {
-                            if let Some(log_view) = this.log_view.as_ref() {
-                                log_view.update(cx, |log_view, cx| {
-                                    log_view.editor.update(cx, |editor, cx| {
-                                        editor.set_read_only(false);
-                                        editor.clear(cx);
-                                        editor.set_read_only(true);
-                                    });
-                                })
-                            }
-                        },
-                    )),
+        h_flex()
+            .size_full()
+            .child(lsp_menu)
+            .child(
+                div()
+                    .child(
+                        Button::new("clear_log_button", "Clear").on_click(cx.listener(
+                            |this, _, cx| {
+                                if let Some(log_view) = this.log_view.as_ref() {
+                                    log_view.update(cx, |log_view, cx| {
+                                        log_view.editor.update(cx, |editor, cx| {
+                                            editor.set_read_only(false);
+                                            editor.clear(cx);
+                                            editor.set_read_only(true);
+                                        });
+                                    })
+                                }
+                            },
+                        )),
+                    )
+                    .ml_2(),
+            )
+            .when(is_showing_rpc_trace, |this| {
+                this.child(
+                    div()
+                        .child(Button::new("export_rpc_trace_button", "Export").on_click(
+                            cx.listener(|this, _, cx| {
+                                if let Some(log_view) = this.log_view.as_ref() {
+                                    log_view
+                                        .update(cx, |log_view, cx| log_view.export_rpc_trace(cx));
+                                }
+                            }),
+                        ))
+                        .ml_2(),
                 )
-                .ml_2(),
-        )
+            })
     }
 }
 
@@ -95,6 +95,70 @@ async fn test_lsp_logs(cx: &mut TestAppContext) {
     });
 }
 
+#[test]
+fn test_rpc_tracer() {
+    use crate::rpc_trace::{Direction, MessageType, RpcTracer};
+    use std::time::{Duration, Instant};
+
+    let mut tracer = RpcTracer::default();
+    let start = Instant::now();
+
+    let request = tracer.trace(
+        Direction::Send,
+        r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/hover","params":{}}"#,
+        start,
+    );
+    assert_eq!(request.message_type, MessageType::Request);
+    assert_eq!(request.method.as_deref(), Some("textDocument/hover"));
+    assert_eq!(
+        request.to_line(),
+        r#"--> request textDocument/hover (id 1, 66 B) {"jsonrpc":"2.0","id":1,"method":"textDocument/hover","params":{}}"#
+    );
+
+    let notification = tracer.trace(
+        Direction::Receive,
+        r#"{"jsonrpc":"2.0","method":"window/logMessage","params":{}}"#,
+        start + Duration::from_millis(5),
+    );
+    assert_eq!(notification.message_type, MessageType::Notification);
+    assert_eq!(notification.id, None);
+
+    // Responses are matched with the requests the other side sent.
+    let response = tracer.trace(
+        Direction::Receive,
+        r#"{"jsonrpc":"2.0","id":1,"result":null}"#,
+        start + Duration::from_millis(12),
+    );
+    assert_eq!(response.message_type, MessageType::Response);
+    assert_eq!(response.method.as_deref(), Some("textDocument/hover"));
+    assert_eq!(response.duration, Some(Duration::from_millis(12)));
+    assert!(response
+        .to_line()
+        .starts_with("<-- response textDocument/hover (id 1, 12 ms, 38 B)"));
+
+    let error = tracer.trace(
+        Direction::Receive,
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"unknown"}}"#,
+        start,
+    );
+    assert!(error.is_error);
+    assert_eq!(error.method, None);
+    assert_eq!(error.duration, None);
+
+    let invalid = tracer.trace(Direction::Receive, "Content-Length: 12", start);
+    assert_eq!(invalid.message_type, MessageType::Invalid);
+
+    let long_message = format!(
+        r#"{{"jsonrpc":"2.0","method":"x","params":"{}"}}"#,
+        "a".repeat(2000)
+    );
+    let long_entry = tracer.trace(Direction::Send, &long_message, start);
+    assert_eq!(long_entry.size_in_bytes, long_message.len());
+    assert!(long_entry.body.ends_with('…'));
+    assert_eq!(long_entry.body.chars().count(), 1001);
+    assert!(long_entry.to_line().contains("(2.0 KB)"));
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     cx.update(|cx| {
         let settings_store = SettingsStore::test(cx);
@@ -0,0 +1,168 @@
+use collections::HashMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use util::truncate_and_trailoff;
+
+/// How many characters of each message are kept in the trace.
+const MAX_BODY_LEN: usize = 1000;
+/// Requests that are never responded to are forgotten once there are this many.
+const MAX_PENDING_REQUESTS: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Direction {
+    Send,
+    Receive,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MessageType {
+    Request,
+    Response,
+    Notification,
+    /// A message that isn't valid JSON-RPC.
+    Invalid,
+}
+
+/// A JSON-RPC message exchanged with a language server.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct RpcTraceEntry {
+    pub direction: Direction,
+    pub message_type: MessageType,
+    /// The method of the request or notification, or of the request that was responded to.
+    pub method: Option<String>,
+    pub id: Option<String>,
+    /// How long it took to respond to the request, for responses.
+    #[serde(
+        rename = "duration_in_milliseconds",
+        serialize_with = "serialize_milliseconds"
+    )]
+    pub duration: Option<Duration>,
+    pub is_error: bool,
+    pub size_in_bytes: usize,
+    /// The message, truncated to `MAX_BODY_LEN` characters.
+    pub body: String,
+}
+
+/// Turns the messages exchanged with a language server into trace entries, matching
+/// responses with the requests they respond to.
+#[derive(Default)]
+pub(crate) struct RpcTracer {
+    pending_requests: HashMap<(Direction, String), (String, Instant)>,
+}
+
+impl RpcTracer {
+    pub fn trace(&mut self, direction: Direction, message: &str, at: Instant) -> RpcTraceEntry {
+        let message = message.trim();
+        let parsed = serde_json::from_str::<Value>(message)
+            .ok()
+            .filter(Value::is_object);
+        let field = |name: &str| parsed.as_ref().and_then(|parsed| parsed.get(name));
+        let id = field("id").and_then(|id| match id {
+            Value::String(id) => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        });
+        let mut method = field("method").and_then(Value::as_str).map(str::to_string);
+
+        let mut duration = None;
+        let message_type = if parsed.is_none() {
+            MessageType::Invalid
+        } else if let Some(request_method) = &method {
+            match &id {
+                Some(id) => {
+                    if self.pending_requests.len() >= MAX_PENDING_REQUESTS {
+                        self.pending_requests.clear();
+                    }
+                    self.pending_requests
+                        .insert((direction, id.clone()), (request_method.clone(), at));
+                    MessageType::Request
+                }
+                None => MessageType::Notification,
+            }
+        } else {
+            // Each side responds to the requests that the other side sent.
+            let request_direction = match direction {
+                Direction::Send => Direction::Receive,
+                Direction::Receive => Direction::Send,
+            };
+            let request = id.as_ref().and_then(|id| {
+                self.pending_requests
+                    .remove(&(request_direction, id.clone()))
+            });
+            if let Some((request_method, sent_at)) = request {
+                method = Some(request_method);
+                duration = Some(at.saturating_duration_since(sent_at));
+            }
+            MessageType::Response
+        };
+
+        RpcTraceEntry {
+            direction,
+            message_type,
+            method,
+            id,
+            duration,
+            is_error: field("error").is_some(),
+            size_in_bytes: message.len(),
+            body: truncate_and_trailoff(message, MAX_BODY_LEN),
+        }
+    }
+}
+
+impl RpcTraceEntry {
+    /// Describes the message on a single line, e.g.
+    /// `<-- response textDocument/hover (id 3, 12 ms, 1.2 KB) {"jsonrpc":"2.0",...}`.
+    pub fn to_line(&self) -> String {
+        let arrow = match self.direction {
+            Direction::Send => "-->",
+            Direction::Receive => "<--",
+        };
+        let message_type = match (self.message_type, self.is_error) {
+            (MessageType::Response, true) => "error response",
+            (MessageType::Response, false) => "response",
+            (MessageType::Request, _) => "request",
+            (MessageType::Notification, _) => "notification",
+            (MessageType::Invalid, _) => "invalid message",
+        };
+
+        let mut details = Vec::new();
+        if let Some(id) = &self.id {
+            details.push(format!("id {id}"));
+        }
+        if let Some(duration) = self.duration {
+            details.push(format!("{} ms", duration.as_millis()));
+        }
+        details.push(format_size(self.size_in_bytes));
+
+        let mut line = format!("{arrow} {message_type}");
+        if let Some(method) = &self.method {
+            line.push(' ');
+            line.push_str(method);
+        }
+        format!("{line} ({}) {}", details.join(", "), self.body)
+    }
+}
+
+fn format_size(size_in_bytes: usize) -> String {
+    const KB: f64 = 1024.;
+    let size = size_in_bytes as f64;
+    if size < KB {
+        format!("{size_in_bytes} B")
+    } else if size < KB * KB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{:.1} MB", size / (KB * KB))
+    }
+}
+
+fn serialize_milliseconds<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    duration
+        .map(|duration| duration.as_secs_f64() * 1000.)
+        .serialize(serializer)
+}
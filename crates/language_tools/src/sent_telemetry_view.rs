@@ -0,0 +1,175 @@
+use client::{
+    telemetry::{SentSummary, Telemetry},
+    TelemetrySettings,
+};
+use futures::StreamExt;
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement, Render,
+    Subscription, Task, ViewContext, VisualContext, WindowContext,
+};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+actions!(telemetry, [ShowSentTelemetry]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ShowSentTelemetry, cx| {
+            let telemetry = workspace.client().telemetry().clone();
+            workspace.add_item(
+                Box::new(cx.new_view(|cx| SentTelemetryView::new(telemetry, cx))),
+                cx,
+            );
+        });
+    })
+    .detach();
+}
+
+/// Answers "What has Zed sent?" with a tally of the telemetry the server has accepted, so that
+/// users can check that turning telemetry off actually stops it from being sent.
+pub struct SentTelemetryView {
+    telemetry: Arc<Telemetry>,
+    summary: SentSummary,
+    focus_handle: FocusHandle,
+    _refresh_task: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+impl SentTelemetryView {
+    pub fn new(telemetry: Arc<Telemetry>, cx: &mut ViewContext<Self>) -> Self {
+        let mut changes = telemetry.inspector_changes();
+        let refresh_task = cx.spawn(|this, mut cx| async move {
+            while let Some(()) = changes.next().await {
+                let updated = this.update(&mut cx, |this, cx| {
+                    this.summary = this.telemetry.sent_summary();
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            summary: telemetry.sent_summary(),
+            telemetry,
+            focus_handle: cx.focus_handle(),
+            _refresh_task: refresh_task,
+            _settings_subscription: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        }
+    }
+
+    fn render_settings(&self, cx: &AppContext) -> impl IntoElement {
+        let settings = TelemetrySettings::get_global(cx);
+        let status = |enabled: bool| if enabled { "on" } else { "off" };
+
+        v_flex()
+            .gap_1()
+            .child(Label::new(format!(
+                "Usage data: {}",
+                status(settings.metrics)
+            )))
+            .child(Label::new(format!(
+                "Crash reports: {}",
+                status(settings.diagnostics)
+            )))
+            .children(
+                self.telemetry
+                    .disabled_by_policy()
+                    .map(|reason| Label::new(reason.to_string()).color(Color::Warning)),
+            )
+    }
+
+    fn render_summary(&self) -> impl IntoElement {
+        let summary = &self.summary;
+        let (Some(since), Some(last_sent_at)) = (summary.since, summary.last_sent_at) else {
+            return v_flex().child(Label::new("Nothing has been sent yet.").color(Color::Muted));
+        };
+
+        v_flex()
+            .gap_1()
+            .child(Label::new(format!(
+                "{} batches, {} in total, since {}",
+                summary.batch_count,
+                format_bytes(summary.bytes_sent),
+                since.format("%Y-%m-%d")
+            )))
+            .child(Label::new(format!(
+                "Last sent at {}",
+                last_sent_at.format("%Y-%m-%d %H:%M UTC")
+            )))
+            .child(v_flex().pt_2().children(summary.event_counts.iter().map(
+                |(event_type, count)| {
+                    h_flex()
+                        .justify_between()
+                        .child(Label::new(event_type.clone()))
+                        .child(Label::new(count.to_string()).color(Color::Muted))
+                },
+            )))
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.;
+    let bytes = bytes as f64;
+    if bytes < KIB {
+        format!("{bytes} B")
+    } else if bytes < KIB * KIB {
+        format!("{:.1} KB", bytes / KIB)
+    } else {
+        format!("{:.1} MB", bytes / (KIB * KIB))
+    }
+}
+
+impl Render for SentTelemetryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .track_focus(&self.focus_handle)
+            .child(
+                v_flex()
+                    .w_96()
+                    .gap_4()
+                    .mx_auto()
+                    .child(Headline::new("What has Zed sent?").size(HeadlineSize::Large))
+                    .child(self.render_settings(cx))
+                    .child(self.render_summary()),
+            )
+    }
+}
+
+impl FocusableView for SentTelemetryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for SentTelemetryView {}
+
+impl Item for SentTelemetryView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("Sent Telemetry")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
@@ -0,0 +1,160 @@
+use client::telemetry::{InspectorSnapshot, Telemetry};
+use editor::{Editor, EditorEvent};
+use futures::StreamExt;
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, IntoElement, Render,
+    Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use std::{fmt::Write as _, sync::Arc};
+use ui::{prelude::*, Label};
+use workspace::{item::Item, Workspace};
+
+actions!(debug, [OpenTelemetryInspector]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenTelemetryInspector, cx| {
+            let telemetry = workspace.client().telemetry().clone();
+            workspace.add_item(
+                Box::new(cx.new_view(|cx| TelemetryInspector::new(telemetry, cx))),
+                cx,
+            );
+        });
+    })
+    .detach();
+}
+
+/// Shows what Zed is about to send, what it has recently sent, and why sending failed.
+pub struct TelemetryInspector {
+    editor: View<Editor>,
+    focus_handle: FocusHandle,
+    _refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TelemetryInspector {
+    pub fn new(telemetry: Arc<Telemetry>, cx: &mut ViewContext<Self>) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_text(inspector_contents(&telemetry.inspector_snapshot()), cx);
+            editor.set_read_only(true);
+            editor.set_show_copilot_suggestions(false);
+            editor
+        });
+        let editor_subscription = cx.subscribe(
+            &editor,
+            |_, _, event: &EditorEvent, cx: &mut ViewContext<'_, TelemetryInspector>| {
+                cx.emit(event.clone())
+            },
+        );
+
+        let focus_handle = cx.focus_handle();
+        let focus_subscription = cx.on_focus(&focus_handle, |inspector, cx| {
+            cx.focus_view(&inspector.editor);
+        });
+
+        let mut changes = telemetry.inspector_changes();
+        let refresh_task = cx.spawn(|this, mut cx| async move {
+            while let Some(()) = changes.next().await {
+                let snapshot = telemetry.inspector_snapshot();
+                if this
+                    .update(&mut cx, |this, cx| this.show_snapshot(&snapshot, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            editor,
+            focus_handle,
+            _refresh_task: refresh_task,
+            _subscriptions: vec![editor_subscription, focus_subscription],
+        }
+    }
+
+    fn show_snapshot(&mut self, snapshot: &InspectorSnapshot, cx: &mut ViewContext<Self>) {
+        let contents = inspector_contents(snapshot);
+        self.editor.update(cx, |editor, cx| {
+            editor.set_read_only(false);
+            editor.set_text(contents, cx);
+            editor.set_read_only(true);
+        });
+        cx.notify();
+    }
+}
+
+fn inspector_contents(snapshot: &InspectorSnapshot) -> String {
+    let mut contents = String::new();
+
+    writeln!(
+        contents,
+        "// Queued events ({}):",
+        snapshot.queued_events.len()
+    )
+    .unwrap();
+    for event in &snapshot.queued_events {
+        writeln!(contents, "{event}").unwrap();
+    }
+
+    writeln!(
+        contents,
+        "\n// Recently flushed batches ({}):",
+        snapshot.flushed_batches.len()
+    )
+    .unwrap();
+    for batch in snapshot.flushed_batches.iter().rev() {
+        writeln!(contents, "// {}", batch.flushed_at.to_rfc3339()).unwrap();
+        writeln!(contents, "{}", batch.request_body).unwrap();
+    }
+
+    writeln!(
+        contents,
+        "\n// Recent flush errors ({}):",
+        snapshot.flush_errors.len()
+    )
+    .unwrap();
+    for error in snapshot.flush_errors.iter().rev() {
+        writeln!(contents, "// {error}").unwrap();
+    }
+
+    contents
+}
+
+impl Render for TelemetryInspector {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        self.editor
+            .update(cx, |editor, cx| editor.render(cx).into_any_element())
+    }
+}
+
+impl FocusableView for TelemetryInspector {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<EditorEvent> for TelemetryInspector {}
+
+impl Item for TelemetryInspector {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(workspace::item::ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("Telemetry Inspector")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
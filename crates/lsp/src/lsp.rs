@@ -6,7 +6,7 @@ use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use futures::{channel::oneshot, io::BufWriter, AsyncRead, AsyncWrite, FutureExt};
 use gpui::{AsyncAppContext, BackgroundExecutor, Task};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use postage::{barrier, prelude::Stream};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, value::RawValue, Value};
@@ -21,7 +21,7 @@ use std::{
     future::Future,
     io::Write,
     path::PathBuf,
-    str::{self, FromStr as _},
+    str::{self, FromStr},
     sync::{
         atomic::{AtomicUsize, Ordering::SeqCst},
         Arc, Weak,
@@ -34,6 +34,10 @@ use util::{ResultExt, TryFutureExt};
 const JSON_RPC_VERSION: &str = "2.0";
 const CONTENT_LEN_HEADER: &str = "Content-Length: ";
 const LSP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+/// How long to keep trying to connect to the socket of a language server that was just
+/// started, while it begins listening.
+const SOCKET_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const SOCKET_CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
 
 type NotificationHandler = Box<dyn Send + FnMut(Option<usize>, &str, AsyncAppContext)>;
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
@@ -53,6 +57,92 @@ pub enum IoKind {
 pub struct LanguageServerBinary {
     pub path: PathBuf,
     pub arguments: Vec<OsString>,
+    /// The socket to talk to the language server over, instead of its stdio. When `path`
+    /// is empty, the language server is expected to be running already.
+    #[serde(default)]
+    pub socket: Option<LanguageServerSocket>,
+    /// Environment variables to set for the language server, in addition to the ones Zed
+    /// was started with.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+}
+
+impl LanguageServerBinary {
+    /// A binary that's run without arguments and talks to Zed over its stdio.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            arguments: Vec::new(),
+            socket: None,
+            env: None,
+        }
+    }
+}
+
+/// Where a language server that communicates over a socket is listening.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerSocket {
+    /// A TCP address, like `127.0.0.1:9257`.
+    Tcp(String),
+    /// The path of a Unix domain socket.
+    Unix(PathBuf),
+}
+
+type SocketHalves = (
+    Box<dyn AsyncRead + Unpin + Send>,
+    Box<dyn AsyncWrite + Unpin + Send>,
+);
+
+impl LanguageServerSocket {
+    async fn connect(&self) -> Result<SocketHalves> {
+        match self {
+            Self::Tcp(address) => {
+                let stream = smol::net::TcpStream::connect(address.as_str()).await?;
+                Ok((Box::new(stream.clone()), Box::new(stream)))
+            }
+            #[cfg(unix)]
+            Self::Unix(path) => {
+                let stream = smol::net::unix::UnixStream::connect(path).await?;
+                Ok((Box::new(stream.clone()), Box::new(stream)))
+            }
+            #[cfg(not(unix))]
+            Self::Unix(_) => Err(anyhow!("Unix sockets aren't supported on this platform")),
+        }
+    }
+}
+
+impl FromStr for LanguageServerSocket {
+    type Err = anyhow::Error;
+
+    /// Parses a TCP address, or the path of a Unix socket prefixed with `unix:`.
+    fn from_str(socket: &str) -> Result<Self> {
+        if let Some(path) = socket.strip_prefix("unix:") {
+            Ok(Self::Unix(PathBuf::from(path)))
+        } else if socket.contains(':') {
+            Ok(Self::Tcp(socket.to_string()))
+        } else {
+            Err(anyhow!(
+                "invalid language server socket {socket:?}, expected `host:port` or `unix:<path>`"
+            ))
+        }
+    }
+}
+
+impl fmt::Display for LanguageServerSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{address}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A capability that a language server registered after it was initialized.
+struct CapabilityRegistration {
+    id: String,
+    method: String,
+    options: Value,
 }
 
 /// A running language server process.
@@ -61,7 +151,10 @@ pub struct LanguageServer {
     next_id: AtomicUsize,
     outbound_tx: channel::Sender<String>,
     name: String,
-    capabilities: ServerCapabilities,
+    version: Option<String>,
+    capabilities: RwLock<Arc<ServerCapabilities>>,
+    initial_capabilities: ServerCapabilities,
+    capability_registrations: Mutex<Vec<CapabilityRegistration>>,
     code_action_kinds: Option<Vec<CodeActionKind>>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<usize, ResponseHandler>>>>,
@@ -70,7 +163,9 @@ pub struct LanguageServer {
     #[allow(clippy::type_complexity)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     output_done_rx: Mutex<Option<barrier::Receiver>>,
+    exited_rx: barrier::Receiver,
     root_path: PathBuf,
+    process_id: Option<u32>,
     _server: Option<Mutex<Child>>,
 }
 
@@ -161,15 +256,10 @@ impl LanguageServer {
         code_action_kinds: Option<Vec<CodeActionKind>>,
         cx: AsyncAppContext,
     ) -> Result<Self> {
-        let working_dir = if root_path.is_dir() {
-            root_path
-        } else {
-            root_path.parent().unwrap_or_else(|| Path::new("/"))
-        };
-
         let mut server = process::Command::new(&binary.path)
-            .current_dir(working_dir)
+            .current_dir(working_dir(root_path))
             .args(binary.arguments)
+            .envs(binary.env.iter().flatten())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -212,6 +302,88 @@ impl LanguageServer {
         Ok(server)
     }
 
+    /// Connects to a language server over `binary.socket`, first starting `binary.path`
+    /// unless it's empty, for language servers that are told where to listen in their
+    /// arguments.
+    pub async fn connect(
+        stderr_capture: Arc<Mutex<Option<String>>>,
+        server_id: LanguageServerId,
+        binary: LanguageServerBinary,
+        root_path: &Path,
+        code_action_kinds: Option<Vec<CodeActionKind>>,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let socket = binary
+            .socket
+            .clone()
+            .ok_or_else(|| anyhow!("language server has no socket to connect to"))?;
+
+        let mut process = None;
+        let mut stderr = None;
+        if !binary.path.as_os_str().is_empty() {
+            let mut child = process::Command::new(&binary.path)
+                .current_dir(working_dir(root_path))
+                .args(&binary.arguments)
+                .envs(binary.env.iter().flatten())
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()?;
+            stderr = child.stderr.take();
+            process = Some(child);
+        }
+
+        let started_at = Instant::now();
+        let (reader, writer) = loop {
+            match socket.connect().await {
+                Ok(halves) => break halves,
+                Err(_) if process.is_some() && started_at.elapsed() < SOCKET_CONNECT_TIMEOUT => {
+                    cx.background_executor()
+                        .timer(SOCKET_CONNECT_RETRY_INTERVAL)
+                        .await;
+                }
+                Err(error) => {
+                    return Err(error)
+                        .with_context(|| format!("connecting to language server at {socket}"))
+                }
+            }
+        };
+
+        let mut server = Self::new_internal(
+            server_id.clone(),
+            writer,
+            reader,
+            stderr,
+            stderr_capture,
+            process,
+            root_path,
+            code_action_kinds,
+            cx,
+            move |notification| {
+                log::info!(
+                    "{} unhandled notification {}:\n{}",
+                    server_id,
+                    notification.method,
+                    serde_json::to_string_pretty(
+                        &notification
+                            .params
+                            .and_then(|params| Value::from_str(params.get()).ok())
+                            .unwrap_or(Value::Null)
+                    )
+                    .unwrap(),
+                );
+            },
+        );
+
+        server.name = match binary.path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => socket.to_string(),
+        };
+
+        Ok(server)
+    }
+
     fn new_internal<Stdin, Stdout, Stderr, F>(
         server_id: LanguageServerId,
         stdin: Stdin,
@@ -262,8 +434,10 @@ impl LanguageServer {
                 cx.spawn(|_| Self::handle_stderr(stderr, io_handlers, stderr_captures).log_err())
             })
             .unwrap_or_else(|| Task::Ready(Some(None)));
+        let (exited_tx, exited_rx) = barrier::channel();
         let input_task = cx.spawn(|_| async move {
             let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            drop(exited_tx);
             stdout.or(stderr)
         });
         let output_task = cx.background_executor().spawn({
@@ -283,14 +457,19 @@ impl LanguageServer {
             response_handlers,
             io_handlers,
             name: Default::default(),
+            version: None,
             capabilities: Default::default(),
+            initial_capabilities: Default::default(),
+            capability_registrations: Default::default(),
             code_action_kinds,
             next_id: Default::default(),
             outbound_tx,
             executor: cx.background_executor().clone(),
             io_tasks: Mutex::new(Some((input_task, output_task))),
             output_done_rx: Mutex::new(Some(output_done_rx)),
+            exited_rx,
             root_path: root_path.to_path_buf(),
+            process_id: server.as_ref().map(|server| server.id()),
             _server: server.map(|server| Mutex::new(server)),
         }
     }
@@ -300,6 +479,15 @@ impl LanguageServer {
         self.code_action_kinds.clone()
     }
 
+    /// Resolves once the language server's output has closed, which happens when its
+    /// process exits, whether it crashed or was shut down.
+    pub fn exited(&self) -> impl 'static + Send + Future<Output = ()> {
+        let mut exited_rx = self.exited_rx.clone();
+        async move {
+            exited_rx.recv().await;
+        }
+    }
+
     async fn handle_input<Stdout, F>(
         stdout: Stdout,
         mut on_unhandled_notification: F,
@@ -477,7 +665,13 @@ impl LanguageServer {
                         refresh_support: Some(true),
                     }),
                     diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
-                        refresh_support: None,
+                        refresh_support: Some(true),
+                    }),
+                    semantic_tokens: Some(SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
+                    code_lens: Some(CodeLensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
                     }),
                     ..Default::default()
                 }),
@@ -503,6 +697,7 @@ impl LanguageServer {
                         ..Default::default()
                     }),
                     completion: Some(CompletionClientCapabilities {
+                        dynamic_registration: Some(true),
                         completion_item: Some(CompletionItemCapability {
                             snippet_support: Some(true),
                             resolve_support: Some(CompletionItemCapabilityResolveSupport {
@@ -526,7 +721,7 @@ impl LanguageServer {
                     }),
                     hover: Some(HoverClientCapabilities {
                         content_format: Some(vec![MarkupKind::Markdown]),
-                        dynamic_registration: None,
+                        dynamic_registration: Some(true),
                     }),
                     inlay_hint: Some(InlayHintClientCapabilities {
                         resolve_support: Some(InlayHintResolveClientCapabilities {
@@ -545,15 +740,77 @@ impl LanguageServer {
                         ..Default::default()
                     }),
                     formatting: Some(DynamicRegistrationClientCapabilities {
-                        dynamic_registration: None,
+                        dynamic_registration: Some(true),
+                    }),
+                    range_formatting: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
                     }),
                     on_type_formatting: Some(DynamicRegistrationClientCapabilities {
-                        dynamic_registration: None,
+                        dynamic_registration: Some(true),
                     }),
                     diagnostic: Some(DiagnosticClientCapabilities {
                         related_document_support: Some(true),
                         dynamic_registration: None,
                     }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    type_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    code_lens: Some(CodeLensClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    linked_editing_range: Some(LinkedEditingRangeClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: None,
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            SemanticTokenType::NAMESPACE,
+                            SemanticTokenType::TYPE,
+                            SemanticTokenType::CLASS,
+                            SemanticTokenType::ENUM,
+                            SemanticTokenType::INTERFACE,
+                            SemanticTokenType::STRUCT,
+                            SemanticTokenType::TYPE_PARAMETER,
+                            SemanticTokenType::PARAMETER,
+                            SemanticTokenType::VARIABLE,
+                            SemanticTokenType::PROPERTY,
+                            SemanticTokenType::ENUM_MEMBER,
+                            SemanticTokenType::EVENT,
+                            SemanticTokenType::FUNCTION,
+                            SemanticTokenType::METHOD,
+                            SemanticTokenType::MACRO,
+                            SemanticTokenType::KEYWORD,
+                            SemanticTokenType::MODIFIER,
+                            SemanticTokenType::COMMENT,
+                            SemanticTokenType::STRING,
+                            SemanticTokenType::NUMBER,
+                            SemanticTokenType::REGEXP,
+                            SemanticTokenType::OPERATOR,
+                            SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            SemanticTokenModifier::DECLARATION,
+                            SemanticTokenModifier::DEFINITION,
+                            SemanticTokenModifier::READONLY,
+                            SemanticTokenModifier::STATIC,
+                            SemanticTokenModifier::DEPRECATED,
+                            SemanticTokenModifier::ABSTRACT,
+                            SemanticTokenModifier::ASYNC,
+                            SemanticTokenModifier::MODIFICATION,
+                            SemanticTokenModifier::DOCUMENTATION,
+                            SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        ..Default::default()
+                    }),
                     ..Default::default()
                 }),
                 experimental: Some(json!({
@@ -577,8 +834,10 @@ impl LanguageServer {
         let response = self.request::<request::Initialize>(params).await?;
         if let Some(info) = response.server_info {
             self.name = info.name;
+            self.version = info.version;
         }
-        self.capabilities = response.capabilities;
+        self.initial_capabilities = response.capabilities.clone();
+        *self.capabilities.get_mut() = Arc::new(response.capabilities);
 
         self.notify::<notification::Initialized>(InitializedParams {})?;
         Ok(Arc::new(self))
@@ -782,9 +1041,85 @@ impl LanguageServer {
         &self.name
     }
 
-    /// Get the reported capabilities of the running language server.
-    pub fn capabilities(&self) -> &ServerCapabilities {
-        &self.capabilities
+    /// Get the version of the running language server, if it reported one.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get the operating system process id of the running language server, if it runs as a
+    /// separate process.
+    pub fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
+    /// Get the reported capabilities of the running language server, including the ones that
+    /// it registered dynamically since it was initialized.
+    pub fn capabilities(&self) -> Arc<ServerCapabilities> {
+        self.capabilities.read().clone()
+    }
+
+    /// Adds a capability that the server registered dynamically, given the id of the
+    /// registration, the method of the requests it provides and its registration options.
+    /// Returns whether the method is one whose capability is known.
+    ///
+    /// The latest registration of a method takes precedence over the earlier ones, and over
+    /// the capability that the server advertised when it was initialized.
+    pub fn register_capability(
+        &self,
+        id: &str,
+        method: &str,
+        options: Option<Value>,
+    ) -> Result<bool> {
+        let registration = CapabilityRegistration {
+            id: id.to_string(),
+            method: method.to_string(),
+            options: options.unwrap_or_else(|| json!({})),
+        };
+        let mut capabilities = self.capabilities.write();
+        if !update_registered_capability(
+            Arc::make_mut(&mut capabilities),
+            method,
+            registration.options.clone(),
+        )? {
+            return Ok(false);
+        }
+        let mut registrations = self.capability_registrations.lock();
+        registrations.retain(|registration| registration.id != id);
+        registrations.push(registration);
+        *capabilities = Arc::new(self.registered_capabilities(&registrations));
+        Ok(true)
+    }
+
+    /// Removes a capability that the server registered dynamically, given the id of the
+    /// registration. The method's capability falls back to its earlier registrations, or
+    /// else to the one that the server advertised when it was initialized. Returns whether
+    /// the registration was known.
+    pub fn unregister_capability(&self, id: &str) -> bool {
+        let mut capabilities = self.capabilities.write();
+        let mut registrations = self.capability_registrations.lock();
+        let registration_count = registrations.len();
+        registrations.retain(|registration| registration.id != id);
+        if registrations.len() == registration_count {
+            return false;
+        }
+        *capabilities = Arc::new(self.registered_capabilities(&registrations));
+        true
+    }
+
+    fn registered_capabilities(
+        &self,
+        registrations: &[CapabilityRegistration],
+    ) -> ServerCapabilities {
+        let mut capabilities = self.initial_capabilities.clone();
+        for registration in registrations {
+            update_registered_capability(
+                &mut capabilities,
+                &registration.method,
+                registration.options.clone(),
+            )
+            .log_err();
+        }
+        capabilities
     }
 
     /// Get the id of the running language server.
@@ -921,6 +1256,82 @@ impl LanguageServer {
     }
 }
 
+/// Sets the capability that provides the requests of the given method from its registration
+/// options. Returns whether the method is one whose capability is known.
+fn update_registered_capability(
+    capabilities: &mut ServerCapabilities,
+    method: &str,
+    options: Value,
+) -> Result<bool> {
+    fn parse<T: DeserializeOwned>(options: Value) -> Result<T> {
+        Ok(serde_json::from_value(options)?)
+    }
+
+    match method {
+        "textDocument/completion" => capabilities.completion_provider = Some(parse(options)?),
+        "textDocument/hover" => {
+            capabilities.hover_provider = Some(HoverProviderCapability::Simple(true))
+        }
+        "textDocument/signatureHelp" => {
+            capabilities.signature_help_provider = Some(parse(options)?)
+        }
+        "textDocument/definition" => capabilities.definition_provider = Some(OneOf::Left(true)),
+        "textDocument/typeDefinition" => {
+            capabilities.type_definition_provider =
+                Some(TypeDefinitionProviderCapability::Simple(true))
+        }
+        "textDocument/implementation" => {
+            capabilities.implementation_provider =
+                Some(ImplementationProviderCapability::Simple(true))
+        }
+        "textDocument/references" => capabilities.references_provider = Some(OneOf::Left(true)),
+        "textDocument/documentHighlight" => {
+            capabilities.document_highlight_provider = Some(OneOf::Left(true))
+        }
+        "textDocument/documentSymbol" => {
+            capabilities.document_symbol_provider = Some(OneOf::Left(true))
+        }
+        "workspace/symbol" => capabilities.workspace_symbol_provider = Some(OneOf::Left(true)),
+        "textDocument/codeAction" => {
+            capabilities.code_action_provider =
+                Some(CodeActionProviderCapability::Options(parse(options)?))
+        }
+        "textDocument/codeLens" => capabilities.code_lens_provider = Some(parse(options)?),
+        "textDocument/formatting" => {
+            capabilities.document_formatting_provider = Some(OneOf::Left(true))
+        }
+        "textDocument/rangeFormatting" => {
+            capabilities.document_range_formatting_provider = Some(OneOf::Left(true))
+        }
+        "textDocument/onTypeFormatting" => {
+            capabilities.document_on_type_formatting_provider = Some(parse(options)?)
+        }
+        "textDocument/rename" => capabilities.rename_provider = Some(OneOf::Right(parse(options)?)),
+        "textDocument/semanticTokens" => {
+            capabilities.semantic_tokens_provider = Some(
+                SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(parse(
+                    options,
+                )?),
+            )
+        }
+        "textDocument/diagnostic" => {
+            capabilities.diagnostic_provider = Some(
+                DiagnosticServerCapabilities::RegistrationOptions(parse(options)?),
+            )
+        }
+        "textDocument/prepareCallHierarchy" => {
+            capabilities.call_hierarchy_provider = Some(CallHierarchyServerCapability::Simple(true))
+        }
+        "textDocument/linkedEditingRange" => {
+            capabilities.linked_editing_range_provider =
+                Some(LinkedEditingRangeServerCapabilities::Simple(true))
+        }
+        "workspace/executeCommand" => capabilities.execute_command_provider = Some(parse(options)?),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
 impl Drop for LanguageServer {
     fn drop(&mut self) {
         if let Some(shutdown) = self.shutdown() {
@@ -942,6 +1353,15 @@ impl Subscription {
     }
 }
 
+/// The directory to start a language server for `root_path` in.
+fn working_dir(root_path: &Path) -> &Path {
+    if root_path.is_dir() {
+        root_path
+    } else {
+        root_path.parent().unwrap_or_else(|| Path::new("/"))
+    }
+}
+
 impl fmt::Display for LanguageServerId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -1068,6 +1488,14 @@ impl LanguageServer {
             ..Default::default()
         }
     }
+
+    /// The [`Self::full_capabilities`] of a server that also provides workspace symbols.
+    pub fn workspace_symbol_capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            workspace_symbol_provider: Some(OneOf::Left(true)),
+            ..Self::full_capabilities()
+        }
+    }
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -1260,4 +1688,74 @@ mod tests {
         drop(server);
         fake.receive_notification::<notification::Exit>().await;
     }
+
+    #[gpui::test]
+    async fn test_capability_registrations(cx: &mut TestAppContext) {
+        let (server, mut fake) = FakeLanguageServer::new(
+            "the-lsp".to_string(),
+            ServerCapabilities {
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+        let server = server.initialize(None).await.unwrap();
+        let trigger_characters = |server: &LanguageServer| {
+            server
+                .capabilities()
+                .completion_provider
+                .as_ref()
+                .and_then(|provider| provider.trigger_characters.clone())
+        };
+
+        assert!(server
+            .register_capability(
+                "1",
+                "textDocument/completion",
+                Some(json!({ "triggerCharacters": [":"] })),
+            )
+            .unwrap());
+        assert!(server
+            .register_capability(
+                "2",
+                "textDocument/completion",
+                Some(json!({ "triggerCharacters": ["<"] })),
+            )
+            .unwrap());
+        assert!(!server
+            .register_capability("3", "textDocument/unknown", None)
+            .unwrap());
+        assert_eq!(trigger_characters(&server), Some(vec!["<".to_string()]));
+
+        // Unregistering the latest registration of a method falls back to the earlier one.
+        assert!(server.unregister_capability("2"));
+        assert_eq!(trigger_characters(&server), Some(vec![":".to_string()]));
+
+        // Once none are left, the method's capability is the one from initialization.
+        assert!(server.unregister_capability("1"));
+        assert_eq!(trigger_characters(&server), Some(vec![".".to_string()]));
+        assert!(!server.unregister_capability("1"));
+
+        fake.handle_request::<request::Shutdown, _, _>(|_, _| async move { Ok(()) });
+        drop(server);
+        fake.receive_notification::<notification::Exit>().await;
+    }
+
+    #[test]
+    fn test_parse_language_server_socket() {
+        assert_eq!(
+            "127.0.0.1:9257".parse::<LanguageServerSocket>().unwrap(),
+            LanguageServerSocket::Tcp("127.0.0.1:9257".into())
+        );
+        assert_eq!(
+            "unix:/tmp/server.sock"
+                .parse::<LanguageServerSocket>()
+                .unwrap(),
+            LanguageServerSocket::Unix("/tmp/server.sock".into())
+        );
+        assert!("server.sock".parse::<LanguageServerSocket>().is_err());
+    }
 }
@@ -192,6 +192,8 @@ impl Prettier {
             LanguageServerBinary {
                 path: node_path,
                 arguments: vec![prettier_server.into(), prettier_dir.as_path().into()],
+                socket: None,
+                env: None,
             },
             Path::new("/"),
             None,
@@ -0,0 +1,553 @@
+use crate::{
+    lsp_command::{
+        language_server_for_buffer, location_from_lsp, location_from_proto, location_in_buffer,
+        location_to_proto, LspCommand,
+    },
+    LanguageServerToQuery, Location, Project,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    point_to_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Buffer, CachedLspAdapter, PointUtf16, ToPointUtf16,
+};
+use lsp::{LanguageServer, LanguageServerId, ServerCapabilities};
+use std::{path::Path, sync::Arc};
+
+/// A symbol in a call hierarchy, such as a function or a method.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub language_server_id: LanguageServerId,
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    /// More details about the symbol, such as its signature.
+    pub detail: Option<String>,
+    pub uri: lsp::Url,
+    /// The location of the symbol's name, e.g. of a function's identifier.
+    pub location: Location,
+    /// The item as the language server reported it, which it expects to be sent back when
+    /// asked for the item's calls.
+    lsp_item: lsp::CallHierarchyItem,
+}
+
+/// A symbol that calls, or is called by, another one.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    pub item: CallHierarchyItem,
+    /// Where the calls are made. For incoming calls, these are in the buffer of the calling
+    /// item, and for outgoing calls, in the buffer of the item that makes them.
+    pub call_locations: Vec<Location>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    /// The calls made to an item.
+    Incoming,
+    /// The calls an item makes.
+    Outgoing,
+}
+
+impl CallHierarchyItem {
+    fn new(
+        lsp_item: lsp::CallHierarchyItem,
+        language_server_id: LanguageServerId,
+        location: Location,
+    ) -> Self {
+        Self {
+            language_server_id,
+            name: lsp_item.name.clone(),
+            kind: lsp_item.kind,
+            detail: lsp_item.detail.clone(),
+            uri: lsp_item.uri.clone(),
+            location,
+            lsp_item,
+        }
+    }
+
+    async fn from_lsp(
+        lsp_item: lsp::CallHierarchyItem,
+        project: &Model<Project>,
+        lsp_adapter: &CachedLspAdapter,
+        language_server: &LanguageServer,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let location = location_from_lsp(
+            lsp_item.uri.clone(),
+            lsp_item.selection_range,
+            project,
+            lsp_adapter,
+            language_server,
+            cx,
+        )
+        .await?;
+        Ok(Self::new(lsp_item, language_server.server_id(), location))
+    }
+
+    fn to_proto(
+        &self,
+        project: &mut Project,
+        peer_id: PeerId,
+        cx: &mut AppContext,
+    ) -> proto::CallHierarchyItem {
+        proto::CallHierarchyItem {
+            server_id: self.language_server_id.0 as u64,
+            location: Some(location_to_proto(
+                self.location.clone(),
+                project,
+                peer_id,
+                cx,
+            )),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        item: proto::CallHierarchyItem,
+        project: &Model<Project>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let location = item
+            .location
+            .ok_or_else(|| anyhow!("missing item location"))?;
+        let location = location_from_proto(location, project, cx).await?;
+        let lsp_item = serde_json::from_slice(&item.lsp_item)?;
+        Ok(Self::new(
+            lsp_item,
+            LanguageServerId(item.server_id as usize),
+            location,
+        ))
+    }
+}
+
+fn supports_call_hierarchy(capabilities: &ServerCapabilities) -> bool {
+    !matches!(
+        capabilities.call_hierarchy_provider,
+        None | Some(lsp::CallHierarchyServerCapability::Simple(false))
+    )
+}
+
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyPrepareParams {
+        lsp::CallHierarchyPrepareParams {
+            text_document_position_params: lsp::TextDocumentPositionParams::new(
+                lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(path).unwrap()),
+                point_to_lsp(self.position),
+            ),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::CallHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let mut items = Vec::new();
+        for lsp_item in lsp_items.unwrap_or_default() {
+            items.push(
+                CallHierarchyItem::from_lsp(
+                    lsp_item,
+                    &project,
+                    &lsp_adapter,
+                    &language_server,
+                    &mut cx,
+                )
+                .await?,
+            );
+        }
+        Ok(items)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<CallHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: items
+                .iter()
+                .map(|item| item.to_proto(project, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::new();
+        for item in message.items {
+            items.push(CallHierarchyItem::from_proto(item, &project, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> u64 {
+        message.buffer_id
+    }
+}
+
+/// Requests the calls made to an item. It's sent along with the buffer that the item is
+/// declared in.
+pub(crate) struct GetIncomingCalls {
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+/// Requests the calls that an item makes. It's sent along with the buffer that the item is
+/// declared in.
+pub(crate) struct GetOutgoingCalls {
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::CallHierarchyIncomingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyIncomingCallsParams {
+        lsp::CallHierarchyIncomingCallsParams {
+            item: self.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item = CallHierarchyItem::from_lsp(
+                lsp_call.from,
+                &project,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?;
+            let call_locations = lsp_call
+                .from_ranges
+                .into_iter()
+                .map(|range| location_in_buffer(item.location.buffer.clone(), range, &mut cx))
+                .collect::<Result<_>>()?;
+            calls.push(CallHierarchyCall {
+                item,
+                call_locations,
+            });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::CallHierarchyIncomingCalls {
+        proto::CallHierarchyIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::CallHierarchyIncomingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        calls: Vec<CallHierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::CallHierarchyCallsResponse {
+        calls_to_proto(calls, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::CallHierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::CallHierarchyIncomingCalls) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::CallHierarchyOutgoingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyOutgoingCallsParams {
+        lsp::CallHierarchyOutgoingCallsParams {
+            item: self.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item = CallHierarchyItem::from_lsp(
+                lsp_call.to,
+                &project,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?;
+            // The calls are made by the item that was asked about, which is in the buffer.
+            let call_locations = lsp_call
+                .from_ranges
+                .into_iter()
+                .map(|range| location_in_buffer(buffer.clone(), range, &mut cx))
+                .collect::<Result<_>>()?;
+            calls.push(CallHierarchyCall {
+                item,
+                call_locations,
+            });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::CallHierarchyOutgoingCalls {
+        proto::CallHierarchyOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::CallHierarchyOutgoingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        calls: Vec<CallHierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::CallHierarchyCallsResponse {
+        calls_to_proto(calls, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::CallHierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::CallHierarchyOutgoingCalls) -> u64 {
+        message.buffer_id
+    }
+}
+
+fn calls_to_proto(
+    calls: Vec<CallHierarchyCall>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::CallHierarchyCallsResponse {
+    proto::CallHierarchyCallsResponse {
+        calls: calls
+            .into_iter()
+            .map(|call| proto::CallHierarchyCall {
+                item: Some(call.item.to_proto(project, peer_id, cx)),
+                call_locations: call
+                    .call_locations
+                    .into_iter()
+                    .map(|location| location_to_proto(location, project, peer_id, cx))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+async fn calls_from_proto(
+    message: proto::CallHierarchyCallsResponse,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut calls = Vec::new();
+    for call in message.calls {
+        let item = call.item.ok_or_else(|| anyhow!("missing call item"))?;
+        let item = CallHierarchyItem::from_proto(item, &project, &mut cx).await?;
+        let mut call_locations = Vec::new();
+        for location in call.call_locations {
+            call_locations.push(location_from_proto(location, &project, &mut cx).await?);
+        }
+        calls.push(CallHierarchyCall {
+            item,
+            call_locations,
+        });
+    }
+    Ok(calls)
+}
+
+impl Project {
+    /// Resolves the symbol at the given position into the items of a call hierarchy, using
+    /// the first of the buffer's language servers that supports call hierarchies.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let buffer = buffer_handle.read(cx);
+        let position = position.to_point_utf16(buffer);
+        // The servers of remote projects are picked by the host.
+        let server = self
+            .language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| supports_call_hierarchy(&server.capabilities()))
+            .map_or(LanguageServerToQuery::Primary, |(_, server)| {
+                LanguageServerToQuery::Other(server.server_id())
+            });
+        self.request_lsp(
+            buffer_handle.clone(),
+            server,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Requests the calls made to or from the item, from the language server that reported it.
+    pub fn call_hierarchy_calls(
+        &self,
+        item: &CallHierarchyItem,
+        direction: CallHierarchyDirection,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        let lsp_item = item.lsp_item.clone();
+        match direction {
+            CallHierarchyDirection::Incoming => {
+                self.request_lsp(buffer, server, GetIncomingCalls { lsp_item }, cx)
+            }
+            CallHierarchyDirection::Outgoing => {
+                self.request_lsp(buffer, server, GetOutgoingCalls { lsp_item }, cx)
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Event, InlayHint, InlayHintLabel, Project, ProjectPath, ResolveState};
+use anyhow::{anyhow, Result};
+use gpui::{Model, ModelContext};
+use language::{point_from_lsp, Bias, Buffer};
+use lsp::LanguageServerId;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Sent by the Dart analysis server when the `closingLabels` initialization option is
+/// enabled, with labels naming what the closing brackets of long constructor calls and
+/// collection literals close, like `// Column`.
+pub(crate) enum PublishClosingLabels {}
+
+impl lsp::notification::Notification for PublishClosingLabels {
+    type Params = PublishClosingLabelsParams;
+    const METHOD: &'static str = "dart/textDocument/publishClosingLabels";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct PublishClosingLabelsParams {
+    pub uri: lsp::Url,
+    pub labels: Vec<LspClosingLabel>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct LspClosingLabel {
+    pub range: lsp::Range,
+    pub label: String,
+}
+
+/// A label shown after the end of a range of a buffer, naming what the range closes.
+#[derive(Clone, Debug)]
+pub(crate) struct ClosingLabel {
+    position: text::Anchor,
+    label: String,
+}
+
+impl Project {
+    /// Replaces the closing labels that the language server published for a buffer, and has
+    /// the inlay hints refreshed to show them.
+    pub(crate) fn update_closing_labels(
+        &mut self,
+        language_server_id: LanguageServerId,
+        params: PublishClosingLabelsParams,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let abs_path = params
+            .uri
+            .to_file_path()
+            .map_err(|_| anyhow!("URI is not a file"))?;
+        let Some((worktree, relative_path)) = self.find_local_worktree(&abs_path, cx) else {
+            return Ok(());
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        };
+        let Some(buffer) = self.get_open_buffer(&project_path, cx) else {
+            return Ok(());
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let labels = params
+            .labels
+            .into_iter()
+            .map(|label| {
+                let end = snapshot.clip_point_utf16(point_from_lsp(label.range.end), Bias::Left);
+                ClosingLabel {
+                    position: snapshot.anchor_after(end),
+                    label: label.label,
+                }
+            })
+            .collect();
+        self.closing_labels
+            .entry(snapshot.remote_id())
+            .or_default()
+            .insert(language_server_id, labels);
+        cx.emit(Event::RefreshInlayHints);
+        Ok(())
+    }
+
+    /// The closing labels of the buffer in the given range, as inlay hints. They're pushed
+    /// by the server rather than requested, so they're added to the hints it's asked for.
+    pub(crate) fn closing_label_hints(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<InlayHint> {
+        let buffer = buffer.read(cx);
+        let Some(labels_by_server) = self.closing_labels.get(&buffer.remote_id()) else {
+            return Vec::new();
+        };
+        labels_by_server
+            .values()
+            .flatten()
+            .filter(|label| {
+                label.position.cmp(&range.start, buffer).is_ge()
+                    && label.position.cmp(&range.end, buffer).is_le()
+            })
+            .map(|label| InlayHint {
+                position: label.position,
+                label: InlayHintLabel::String(format!("// {}", label.label)),
+                kind: None,
+                padding_left: true,
+                padding_right: false,
+                tooltip: None,
+                resolve_state: ResolveState::Resolved,
+            })
+            .collect()
+    }
+}
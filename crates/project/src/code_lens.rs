@@ -0,0 +1,333 @@
+use crate::{
+    lsp_command::{language_server_for_buffer, LspCommand},
+    LanguageServerToQuery, Project, ProjectTransaction,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use client::{
+    proto::{self, PeerId},
+    Client, TypedEnvelope,
+};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    point_from_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Bias, Buffer,
+};
+use lsp::{LanguageServer, LanguageServerId, ServerCapabilities};
+use std::{ops::Range, path::Path, sync::Arc};
+use util::ResultExt;
+
+/// A command that a language server shows above a range of a buffer, such as running the
+/// test declared there.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    pub language_server_id: LanguageServerId,
+    pub range: Range<text::Anchor>,
+    /// The command to run when the lens is clicked, whose title is shown as the lens.
+    pub command: lsp::Command,
+}
+
+impl CodeLens {
+    fn to_proto(&self) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: self.language_server_id.0 as u64,
+            start: Some(serialize_anchor(&self.range.start)),
+            end: Some(serialize_anchor(&self.range.end)),
+            command: serde_json::to_vec(&self.command).unwrap(),
+        }
+    }
+
+    fn from_proto(lens: proto::CodeLens) -> Result<Self> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        Ok(Self {
+            language_server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            command: serde_json::from_slice(&lens.command)?,
+        })
+    }
+}
+
+/// Requests the code lenses of a buffer. Lenses that come without a command are resolved
+/// when the server supports it, and dropped otherwise.
+pub(crate) struct GetCodeLenses;
+
+#[async_trait(?Send)]
+impl LspCommand for GetCodeLenses {
+    type Response = Vec<CodeLens>;
+    type LspRequest = lsp::request::CodeLensRequest;
+    type ProtoRequest = proto::GetCodeLenses;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.code_lens_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CodeLensParams {
+        lsp::CodeLensParams {
+            text_document: lsp::TextDocumentIdentifier::new(
+                lsp::Url::from_file_path(path).unwrap(),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_lenses: Option<Vec<lsp::CodeLens>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        let (_, language_server) =
+            language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+        let resolve_provider = language_server
+            .capabilities()
+            .code_lens_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+
+        let mut resolved_lenses = Vec::new();
+        for lsp_lens in lsp_lenses.unwrap_or_default() {
+            let lsp_lens = if lsp_lens.command.is_none() && resolve_provider {
+                match language_server
+                    .request::<lsp::request::CodeLensResolve>(lsp_lens)
+                    .await
+                    .log_err()
+                {
+                    Some(lsp_lens) => lsp_lens,
+                    None => continue,
+                }
+            } else {
+                lsp_lens
+            };
+            if let Some(command) = lsp_lens.command {
+                resolved_lenses.push((lsp_lens.range, command));
+            }
+        }
+
+        resolved_lenses.sort_by_key(|(range, _)| range.start);
+        buffer.update(&mut cx, |buffer, _| {
+            resolved_lenses
+                .into_iter()
+                .map(|(range, command)| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    CodeLens {
+                        language_server_id: server_id,
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        command,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLenses {
+        proto::GetCodeLenses {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeLenses,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        lenses: Vec<CodeLens>,
+        _: &mut Project,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetCodeLensesResponse {
+        proto::GetCodeLensesResponse {
+            lenses: lenses.iter().map(CodeLens::to_proto).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeLensesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<CodeLens>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .lenses
+            .into_iter()
+            .map(CodeLens::from_proto)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeLenses) -> u64 {
+        message.buffer_id
+    }
+}
+
+impl Project {
+    /// Requests the code lenses of the buffer from each of its language servers that provide
+    /// them, ordered by their position in the buffer. The servers of remote projects are
+    /// picked by the host.
+    pub fn code_lenses(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        if !self.is_local() {
+            return self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Primary,
+                GetCodeLenses,
+                cx,
+            );
+        }
+        let server_ids = self
+            .language_servers_for_buffer(buffer_handle.read(cx), cx)
+            .map(|(_, server)| server.server_id())
+            .collect::<Vec<_>>();
+        let requests = server_ids
+            .into_iter()
+            .map(|server_id| {
+                self.request_lsp(
+                    buffer_handle.clone(),
+                    LanguageServerToQuery::Other(server_id),
+                    GetCodeLenses,
+                    cx,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let buffer = buffer_handle.clone();
+        cx.spawn(move |_, mut cx| async move {
+            let mut lenses = Vec::new();
+            for request in requests {
+                if let Some(server_lenses) = request.await.log_err() {
+                    lenses.extend(server_lenses);
+                }
+            }
+            buffer.update(&mut cx, |buffer, _| {
+                lenses.sort_by(|a, b| a.range.start.cmp(&b.range.start, buffer))
+            })?;
+            Ok(lenses)
+        })
+    }
+
+    /// Asks the language server that reported the lens to execute its command, and returns
+    /// the edits that the server applied while doing so.
+    pub fn execute_code_lens(
+        &self,
+        lens: &CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        if self.is_local() {
+            self.execute_lsp_command(lens.language_server_id, lens.command.clone(), cx)
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::ExecuteCodeLens {
+                project_id,
+                server_id: lens.language_server_id.0 as u64,
+                command: serde_json::to_vec(&lens.command).unwrap(),
+            };
+            cx.spawn(move |this, mut cx| async move {
+                let response = client
+                    .request(request)
+                    .await?
+                    .transaction
+                    .ok_or_else(|| anyhow!("missing transaction"))?;
+                this.update(&mut cx, |this, cx| {
+                    this.deserialize_project_transaction(response, true, cx)
+                })?
+                .await
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
+    fn execute_lsp_command(
+        &self,
+        server_id: LanguageServerId,
+        command: lsp::Command,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(server) = self.language_server_for_id(server_id) else {
+            return Task::ready(Err(anyhow!("language server is no longer running")));
+        };
+
+        cx.spawn(move |this, mut cx| async move {
+            this.update(&mut cx, |this, _| {
+                this.last_workspace_edits_by_language_server
+                    .remove(&server.server_id());
+            })?;
+
+            server
+                .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                    command: command.command,
+                    arguments: command.arguments.unwrap_or_default(),
+                    ..Default::default()
+                })
+                .await?;
+
+            Ok(this.update(&mut cx, |this, _| {
+                this.last_workspace_edits_by_language_server
+                    .remove(&server.server_id())
+                    .unwrap_or_default()
+            })?)
+        })
+    }
+
+    pub(crate) async fn handle_execute_code_lens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ExecuteCodeLens>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ExecuteCodeLensResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let server_id = LanguageServerId(envelope.payload.server_id as usize);
+        let command = serde_json::from_slice(&envelope.payload.command)?;
+        let project_transaction = this
+            .update(&mut cx, |this, cx| {
+                this.execute_lsp_command(server_id, command, cx)
+            })?
+            .await?;
+        let project_transaction = this.update(&mut cx, |this, cx| {
+            this.serialize_project_transaction_for_peer(project_transaction, sender_id, cx)
+        })?;
+        Ok(proto::ExecuteCodeLensResponse {
+            transaction: Some(project_transaction),
+        })
+    }
+}
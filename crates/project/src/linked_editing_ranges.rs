@@ -0,0 +1,179 @@
+use crate::{lsp_command::LspCommand, LanguageServerToQuery, Project};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    point_from_lsp, point_to_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Bias, Buffer, PointUtf16, ToPointUtf16,
+};
+use lsp::{LanguageServer, LanguageServerId, ServerCapabilities};
+use std::{ops::Range, path::Path, sync::Arc};
+
+fn supports_linked_editing_ranges(capabilities: &ServerCapabilities) -> bool {
+    !matches!(
+        capabilities.linked_editing_range_provider,
+        None | Some(lsp::LinkedEditingRangeServerCapabilities::Simple(false))
+    )
+}
+
+pub(crate) struct GetLinkedEditingRanges {
+    pub position: PointUtf16,
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetLinkedEditingRanges {
+    type Response = Vec<Range<text::Anchor>>;
+    type LspRequest = lsp::request::LinkedEditingRange;
+    type ProtoRequest = proto::GetLinkedEditingRanges;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_linked_editing_ranges(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::LinkedEditingRangeParams {
+        lsp::LinkedEditingRangeParams {
+            text_document_position_params: lsp::TextDocumentPositionParams::new(
+                lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(path).unwrap()),
+                point_to_lsp(self.position),
+            ),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        response: Option<lsp::LinkedEditingRanges>,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<text::Anchor>>> {
+        let Some(response) = response else {
+            return Ok(Vec::new());
+        };
+        buffer.update(&mut cx, |buffer, _| {
+            response
+                .ranges
+                .into_iter()
+                .map(|range| {
+                    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    // The ranges grow along with the text that's typed at their edges.
+                    buffer.anchor_before(start)..buffer.anchor_after(end)
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetLinkedEditingRanges {
+        proto::GetLinkedEditingRanges {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetLinkedEditingRanges,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        ranges: Vec<Range<text::Anchor>>,
+        _: &mut Project,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetLinkedEditingRangesResponse {
+        proto::GetLinkedEditingRangesResponse {
+            ranges: ranges
+                .into_iter()
+                .map(|range| proto::LinkedEditingRange {
+                    start: Some(serialize_anchor(&range.start)),
+                    end: Some(serialize_anchor(&range.end)),
+                })
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetLinkedEditingRangesResponse,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<Range<text::Anchor>>> {
+        let mut ranges = Vec::new();
+        for range in message.ranges {
+            let start = range
+                .start
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing range start"))?;
+            let end = range
+                .end
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing range end"))?;
+            buffer
+                .update(&mut cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+                .await?;
+            ranges.push(start..end);
+        }
+        Ok(ranges)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetLinkedEditingRanges) -> u64 {
+        message.buffer_id
+    }
+}
+
+impl Project {
+    /// Requests the ranges of the buffer that have the same content as the one at the given
+    /// position and are meant to be edited together, such as the names of an HTML element's
+    /// opening and closing tags. The ranges include the one at the position.
+    pub fn linked_editing_ranges<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<text::Anchor>>>> {
+        let buffer = buffer_handle.read(cx);
+        let position = position.to_point_utf16(buffer);
+        // The servers of remote projects are picked by the host.
+        let server = self
+            .language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| supports_linked_editing_ranges(&server.capabilities()))
+            .map_or(LanguageServerToQuery::Primary, |(_, server)| {
+                LanguageServerToQuery::Other(server.server_id())
+            });
+        self.request_lsp(
+            buffer_handle.clone(),
+            server,
+            GetLinkedEditingRanges { position },
+            cx,
+        )
+    }
+}
@@ -592,7 +592,7 @@ impl LspCommand for GetTypeDefinition {
     }
 }
 
-fn language_server_for_buffer(
+pub(crate) fn language_server_for_buffer(
     project: &Model<Project>,
     buffer: &Model<Buffer>,
     server_id: LanguageServerId,
@@ -607,6 +607,82 @@ fn language_server_for_buffer(
         .ok_or_else(|| anyhow!("no language server found for buffer"))
 }
 
+/// Opens the buffer of the document that a language server refers to by `uri`, and resolves
+/// `range` in it.
+pub(crate) async fn location_from_lsp(
+    uri: lsp::Url,
+    range: lsp::Range,
+    project: &Model<Project>,
+    lsp_adapter: &CachedLspAdapter,
+    language_server: &LanguageServer,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_via_lsp(
+                uri,
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    location_in_buffer(buffer, range, cx)
+}
+
+pub(crate) fn location_in_buffer(
+    buffer: Model<Buffer>,
+    range: lsp::Range,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let range = buffer.update(cx, |buffer, _| {
+        let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+        buffer.anchor_after(start)..buffer.anchor_before(end)
+    })?;
+    Ok(Location { buffer, range })
+}
+
+pub(crate) fn location_to_proto(
+    location: Location,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::Location {
+    proto::Location {
+        buffer_id: project.create_buffer_for_peer(&location.buffer, peer_id, cx),
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+    }
+}
+
+pub(crate) async fn location_from_proto(
+    location: proto::Location,
+    project: &Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.wait_for_remote_buffer(location.buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location start"))?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location end"))?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
 async fn location_links_from_proto(
     proto_links: Vec<proto::LocationLink>,
     project: Model<Project>,
@@ -2262,7 +2338,7 @@ impl LspCommand for InlayHints {
             lsp_adapter.name.0.as_ref() == "typescript-language-server";
 
         let hints = message.unwrap_or_default().into_iter().map(|lsp_hint| {
-            let resolve_state = if InlayHints::can_resolve_inlays(lsp_server.capabilities()) {
+            let resolve_state = if InlayHints::can_resolve_inlays(&lsp_server.capabilities()) {
                 ResolveState::CanResolve(lsp_server.server_id(), lsp_hint.data.clone())
             } else {
                 ResolveState::Resolved
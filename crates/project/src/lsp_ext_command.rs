@@ -135,3 +135,102 @@ impl LspCommand for ExpandMacro {
         message.buffer_id
     }
 }
+
+/// Asks texlab to build the document, or the root document that includes it, with the build
+/// tool it's configured with.
+pub enum TexlabBuild {}
+
+impl lsp::request::Request for TexlabBuild {
+    type Params = TexlabBuildParams;
+    type Result = TexlabBuildResult;
+    const METHOD: &'static str = "textDocument/build";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TexlabBuildParams {
+    pub text_document: lsp::TextDocumentIdentifier,
+    pub position: Option<lsp::Position>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TexlabBuildResult {
+    pub status: TexlabBuildStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "u8", into = "u8")]
+pub enum TexlabBuildStatus {
+    Success,
+    Error,
+    Failure,
+    Cancelled,
+}
+
+impl From<u8> for TexlabBuildStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            0 => Self::Success,
+            1 => Self::Error,
+            3 => Self::Cancelled,
+            _ => Self::Failure,
+        }
+    }
+}
+
+impl From<TexlabBuildStatus> for u8 {
+    fn from(status: TexlabBuildStatus) -> Self {
+        match status {
+            TexlabBuildStatus::Success => 0,
+            TexlabBuildStatus::Error => 1,
+            TexlabBuildStatus::Failure => 2,
+            TexlabBuildStatus::Cancelled => 3,
+        }
+    }
+}
+
+/// Asks texlab to show the position in the PDF viewer it's configured with, which scrolls to
+/// the part of the output that the position was typeset into.
+pub enum TexlabForwardSearch {}
+
+impl lsp::request::Request for TexlabForwardSearch {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = TexlabForwardSearchResult;
+    const METHOD: &'static str = "textDocument/forwardSearch";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TexlabForwardSearchResult {
+    pub status: TexlabForwardSearchStatus,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "u8", into = "u8")]
+pub enum TexlabForwardSearchStatus {
+    Success,
+    Error,
+    Failure,
+    Unconfigured,
+}
+
+impl From<u8> for TexlabForwardSearchStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            0 => Self::Success,
+            1 => Self::Error,
+            3 => Self::Unconfigured,
+            _ => Self::Failure,
+        }
+    }
+}
+
+impl From<TexlabForwardSearchStatus> for u8 {
+    fn from(status: TexlabForwardSearchStatus) -> Self {
+        match status {
+            TexlabForwardSearchStatus::Success => 0,
+            TexlabForwardSearchStatus::Error => 1,
+            TexlabForwardSearchStatus::Failure => 2,
+            TexlabForwardSearchStatus::Unconfigured => 3,
+        }
+    }
+}
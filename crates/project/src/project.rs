@@ -1,10 +1,17 @@
+mod call_hierarchy;
+mod closing_labels;
+mod code_lens;
 mod ignore;
+mod linked_editing_ranges;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+mod pull_diagnostics;
 pub mod search;
+mod semantic_tokens;
 pub mod terminals;
+mod type_hierarchy;
 pub mod worktree;
 
 #[cfg(test)]
@@ -13,8 +20,14 @@ mod project_tests;
 mod worktree_tests;
 
 use anyhow::{anyhow, Context as _, Result};
-use client::{proto, Client, Collaborator, TypedEnvelope, UserStore};
+use async_trait::async_trait;
+use client::{
+    proto,
+    telemetry::{register_memory_accounting, LanguageServerFailureKind, MemoryAccounting},
+    Client, Collaborator, TypedEnvelope, UserStore,
+};
 use clock::ReplicaId;
+use closing_labels::{ClosingLabel, PublishClosingLabels};
 use collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use copilot::Copilot;
 use futures::{
@@ -33,7 +46,9 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
+    language_settings::{
+        language_settings, FormatOnSave, Formatter, InlayHintKind, LanguageServerMergeStrategy,
+    },
     markdown, point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_fingerprint, deserialize_line_ending, deserialize_version,
@@ -41,9 +56,10 @@ use language::{
     },
     range_from_lsp, range_to_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability,
     CodeAction, CodeLabel, Completion, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff,
-    Documentation, Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName,
-    LocalFile, LspAdapterDelegate, OffsetRangeExt, Operation, Patch, PendingLanguageServer,
-    PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
+    Documentation, Event as BufferEvent, File as _, InstallProgress, Language, LanguageRegistry,
+    LanguageServerBinaryError, LanguageServerName, LocalFile, LspAdapterDelegate, OffsetRangeExt,
+    Operation, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
+    ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -56,8 +72,10 @@ use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{LspSettings, ProjectSettings};
+use pull_diagnostics::{ProgressOrPartialResult, PulledDiagnostics};
 use rand::prelude::*;
 use search::SearchQuery;
+use semantic_tokens::{semantic_tokens_options, LspSemanticTokens, SemanticTokensResponse};
 use serde::Serialize;
 use settings::{Settings, SettingsStore};
 use sha2::{Digest, Sha256};
@@ -83,16 +101,31 @@ use std::{
 use terminals::Terminals;
 use text::Anchor;
 use util::{
-    debug_panic, defer, http::HttpClient, merge_json_value_into,
-    paths::LOCAL_SETTINGS_RELATIVE_PATH, post_inc, ResultExt, TryFutureExt as _,
+    debug_panic, defer,
+    fs::find_executable,
+    http::{HttpClient, MirroredHttpClient},
+    merge_json_value_into,
+    paths::LOCAL_SETTINGS_RELATIVE_PATH,
+    post_inc, ResultExt, TryFutureExt as _,
 };
 
+pub use call_hierarchy::{CallHierarchyCall, CallHierarchyDirection, CallHierarchyItem};
+pub use code_lens::CodeLens;
 pub use fs::*;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
+pub use semantic_tokens::SemanticToken;
+pub use type_hierarchy::{TypeHierarchyDirection, TypeHierarchyItem};
 pub use worktree::*;
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
+/// How many times in a row a language server that crashes is restarted before giving up.
+const MAX_SERVER_CRASH_RESTART_COUNT: u32 = 5;
+/// How long to wait before restarting a language server after its first crash, which
+/// doubles after each crash that follows.
+const SERVER_CRASH_RESTART_DELAY: Duration = Duration::from_secs(1);
+/// How long a language server has to run without crashing to reset its crash count.
+const SERVER_CRASH_RESET_UPTIME: Duration = Duration::from_secs(5 * 60);
 
 pub trait Item {
     fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId>;
@@ -108,6 +141,7 @@ pub struct Project {
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
+    language_server_project_roots: HashMap<(WorktreeId, LanguageServerName), Option<Arc<Path>>>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     client: Arc<client::Client>,
@@ -138,6 +172,9 @@ pub struct Project {
     /// Used for re-issuing buffer requests when peers temporarily disconnect
     incomplete_remote_buffers: HashMap<u64, Option<Model<Buffer>>>,
     buffer_snapshots: HashMap<u64, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    semantic_tokens: HashMap<u64, HashMap<LanguageServerId, LspSemanticTokens>>,
+    closing_labels: HashMap<u64, HashMap<LanguageServerId, Vec<ClosingLabel>>>,
+    pulled_diagnostics: PulledDiagnostics,
     buffers_being_formatted: HashSet<u64>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DelayedDebounced,
@@ -148,6 +185,10 @@ pub struct Project {
     copilot_lsp_subscription: Option<gpui::Subscription>,
     copilot_log_subscription: Option<lsp::Subscription>,
     current_lsp_settings: HashMap<Arc<str>, LspSettings>,
+    language_server_crash_counts: HashMap<(WorktreeId, LanguageServerName), u32>,
+    /// The language servers the user stopped, which aren't started again until they are
+    /// restarted.
+    stopped_language_servers: HashSet<(WorktreeId, LanguageServerName)>,
     node: Option<Arc<dyn NodeRuntime>>,
     default_prettier: DefaultPrettier,
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
@@ -261,6 +302,12 @@ pub enum Event {
     LanguageServerAdded(LanguageServerId),
     LanguageServerRemoved(LanguageServerId),
     LanguageServerLog(LanguageServerId, String),
+    /// A language server kept crashing, and won't be restarted again.
+    LanguageServerCrashed {
+        language_server_id: LanguageServerId,
+        name: LanguageServerName,
+        worktree_id: WorktreeId,
+    },
     Notification(String),
     ActiveEntryChanged(Option<ProjectEntryId>),
     ActivateProjectPanel,
@@ -288,6 +335,10 @@ pub enum Event {
     CollaboratorJoined(proto::PeerId),
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
+    /// A language server asked for the semantic tokens of its buffers to be requested again.
+    RefreshSemanticTokens,
+    /// A language server asked for the code lenses of its buffers to be requested again.
+    RefreshCodeLenses,
     RevealInProjectPanel(ProjectEntryId),
 }
 
@@ -298,11 +349,70 @@ pub enum LanguageServerState {
         language: Arc<Language>,
         adapter: Arc<CachedLspAdapter>,
         server: Arc<LanguageServer>,
-        watched_paths: HashMap<WorktreeId, GlobSet>,
+        /// The file watchers that the server registered, by the id of their registration.
+        watcher_registrations: HashMap<String, Vec<lsp::FileSystemWatcher>>,
+        watched_paths: HashMap<WorktreeId, LanguageServerWatchedPaths>,
         simulate_disk_based_diagnostics_completion: Option<Task<()>>,
     },
 }
 
+/// The paths of a worktree that a language server watches, for each kind of change it wants
+/// to be notified about.
+pub struct LanguageServerWatchedPaths {
+    created: GlobSet,
+    changed: GlobSet,
+    deleted: GlobSet,
+}
+
+impl LanguageServerWatchedPaths {
+    fn is_match(&self, path: &Path, change: lsp::FileChangeType) -> bool {
+        let globs = match change {
+            lsp::FileChangeType::CREATED => &self.created,
+            lsp::FileChangeType::DELETED => &self.deleted,
+            _ => &self.changed,
+        };
+        globs.is_match(path)
+    }
+}
+
+#[derive(Default)]
+struct LanguageServerWatchedPathsBuilder {
+    created: Vec<Glob>,
+    changed: Vec<Glob>,
+    deleted: Vec<Glob>,
+}
+
+impl LanguageServerWatchedPathsBuilder {
+    fn add(&mut self, glob: Glob, kind: Option<lsp::WatchKind>) {
+        // Watchers without a kind are notified about every kind of change.
+        let kind = kind.unwrap_or(lsp::WatchKind::all());
+        if kind.contains(lsp::WatchKind::Create) {
+            self.created.push(glob.clone());
+        }
+        if kind.contains(lsp::WatchKind::Change) {
+            self.changed.push(glob.clone());
+        }
+        if kind.contains(lsp::WatchKind::Delete) {
+            self.deleted.push(glob);
+        }
+    }
+
+    fn build(self) -> Result<LanguageServerWatchedPaths> {
+        let build_glob_set = |globs: Vec<Glob>| {
+            let mut builder = GlobSetBuilder::new();
+            for glob in globs {
+                builder.add(glob);
+            }
+            builder.build()
+        };
+        Ok(LanguageServerWatchedPaths {
+            created: build_glob_set(self.created)?,
+            changed: build_glob_set(self.changed)?,
+            deleted: build_glob_set(self.deleted)?,
+        })
+    }
+}
+
 #[derive(Serialize)]
 pub struct LanguageServerStatus {
     pub name: String,
@@ -315,6 +425,8 @@ pub struct LanguageServerStatus {
 pub struct LanguageServerProgress {
     pub message: Option<String>,
     pub percentage: Option<usize>,
+    /// Whether the server accepts requests to cancel the work.
+    pub is_cancellable: bool,
     #[serde(skip_serializing)]
     pub last_update_at: Instant,
 }
@@ -516,8 +628,12 @@ pub enum FormatTrigger {
 }
 
 struct ProjectLspAdapterDelegate {
-    project: Model<Project>,
+    notifications_tx: mpsc::UnboundedSender<String>,
     http_client: Arc<dyn HttpClient>,
+    pinned_server_version: Option<String>,
+    prefer_system_binary: bool,
+    languages: Arc<LanguageRegistry>,
+    language: Arc<Language>,
 }
 
 // Currently, formatting operations are represented differently depending on
@@ -616,6 +732,25 @@ impl Project {
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
+        client.add_model_request_handler(
+            Self::handle_lsp_command::<call_hierarchy::PrepareCallHierarchy>,
+        );
+        client.add_model_request_handler(
+            Self::handle_lsp_command::<call_hierarchy::GetIncomingCalls>,
+        );
+        client.add_model_request_handler(
+            Self::handle_lsp_command::<call_hierarchy::GetOutgoingCalls>,
+        );
+        client.add_model_request_handler(
+            Self::handle_lsp_command::<type_hierarchy::PrepareTypeHierarchy>,
+        );
+        client.add_model_request_handler(Self::handle_lsp_command::<type_hierarchy::GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<type_hierarchy::GetSubtypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<code_lens::GetCodeLenses>);
+        client.add_model_request_handler(Self::handle_execute_code_lens);
+        client.add_model_request_handler(
+            Self::handle_lsp_command::<linked_editing_ranges::GetLinkedEditingRanges>,
+        );
     }
 
     pub fn local(
@@ -626,7 +761,7 @@ impl Project {
         fs: Arc<dyn Fs>,
         cx: &mut AppContext,
     ) -> Model<Self> {
-        cx.new_model(|cx: &mut ModelContext<Self>| {
+        let project = cx.new_model(|cx: &mut ModelContext<Self>| {
             let (tx, rx) = mpsc::unbounded();
             cx.spawn(move |this, cx| Self::send_buffer_ordered_messages(this, rx, cx))
                 .detach();
@@ -645,6 +780,9 @@ impl Project {
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens: Default::default(),
+                closing_labels: Default::default(),
+                pulled_diagnostics: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 opened_buffer: watch::channel(),
@@ -666,6 +804,7 @@ impl Project {
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
+                language_server_project_roots: HashMap::default(),
                 language_server_statuses: Default::default(),
                 last_workspace_edits_by_language_server: Default::default(),
                 buffers_being_formatted: Default::default(),
@@ -678,12 +817,16 @@ impl Project {
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
+                language_server_crash_counts: HashMap::default(),
+                stopped_language_servers: HashSet::default(),
                 node: Some(node),
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
             }
-        })
+        });
+        register_memory_accounting(BufferStoreAccounting(project.downgrade()), cx);
+        project
     }
 
     pub async fn remote(
@@ -754,6 +897,7 @@ impl Project {
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
+                language_server_project_roots: HashMap::default(),
                 language_server_statuses: response
                     .payload
                     .language_servers
@@ -776,6 +920,9 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DelayedDebounced::new(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens: Default::default(),
+                closing_labels: Default::default(),
+                pulled_diagnostics: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
@@ -783,6 +930,8 @@ impl Project {
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
+                language_server_crash_counts: HashMap::default(),
+                stopped_language_servers: HashSet::default(),
                 node: None,
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
@@ -795,6 +944,7 @@ impl Project {
             this
         })?;
         let subscription = subscription.set_model(&this, &mut cx);
+        cx.update(|cx| register_memory_accounting(BufferStoreAccounting(this.downgrade()), cx))?;
 
         let user_ids = response
             .payload
@@ -929,7 +1079,9 @@ impl Project {
                 let file = worktree.as_ref().and_then(|tree| {
                     tree.update(cx, |tree, cx| tree.root_file(cx).map(|f| f as _))
                 });
-                if !language_settings(Some(language), file.as_ref(), cx).enable_language_server {
+                if !language_settings(Some(language), file.as_ref(), cx).enable_language_server
+                    || !ProjectSettings::get_global(cx).is_language_server_enabled(&adapter.name.0)
+                {
                     language_servers_to_stop.push((*worktree_id, started_lsp_name.clone()));
                 } else if let Some(worktree) = worktree {
                     let server_name = &adapter.name.0;
@@ -942,11 +1094,26 @@ impl Project {
                             language_servers_to_restart.push((worktree, Arc::clone(language)));
                         }
                         (Some(current_lsp_settings), Some(new_lsp_settings)) => {
-                            if current_lsp_settings != new_lsp_settings {
+                            // Changes to `settings` are sent to the running server instead.
+                            let without_settings = |lsp_settings: &LspSettings| LspSettings {
+                                settings: None,
+                                ..lsp_settings.clone()
+                            };
+                            if without_settings(current_lsp_settings)
+                                != without_settings(new_lsp_settings)
+                            {
                                 language_servers_to_restart.push((worktree, Arc::clone(language)));
                             }
                         }
                     }
+                    let binary_settings = |lsp_settings: &HashMap<Arc<str>, LspSettings>| {
+                        lsp_settings
+                            .get(server_name)
+                            .map(|s| (s.version.clone(), s.prefer_system_binary))
+                    };
+                    if binary_settings(current_lsp_settings) != binary_settings(&new_lsp_settings) {
+                        self.languages.forget_server_binary(&adapter.name);
+                    }
                 }
             }
         }
@@ -2163,6 +2330,7 @@ impl Project {
                         .entry(buffer_id)
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
+                    self.pull_document_diagnostics(buffer_handle, server.server_id(), cx);
                 }
             }
         }
@@ -2190,6 +2358,9 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.semantic_tokens.remove(&buffer.remote_id());
+            self.closing_labels.remove(&buffer.remote_id());
+            self.pulled_diagnostics.forget_buffer(buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
@@ -2345,6 +2516,7 @@ impl Project {
             }
 
             BufferEvent::Edited { .. } => {
+                let buffer_handle = buffer.clone();
                 let buffer = buffer.read(cx);
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2356,7 +2528,7 @@ impl Project {
                     .map(|i| i.1.clone())
                     .collect();
 
-                for language_server in language_servers {
+                for language_server in &language_servers {
                     let language_server = language_server.clone();
 
                     let buffer_snapshots = self
@@ -2438,6 +2610,10 @@ impl Project {
                         )
                         .log_err();
                 }
+
+                for language_server in language_servers {
+                    self.pull_document_diagnostics(&buffer_handle, language_server.server_id(), cx);
+                }
             }
 
             BufferEvent::Saved => {
@@ -2448,6 +2624,7 @@ impl Project {
                     uri: lsp::Url::from_file_path(abs_path).unwrap(),
                 };
 
+                let mut saved_server_ids = Vec::new();
                 for (_, _, server) in self.language_servers_for_worktree(worktree_id) {
                     let text = include_text(server.as_ref()).then(|| buffer.read(cx).text());
 
@@ -2459,6 +2636,12 @@ impl Project {
                             },
                         )
                         .log_err();
+                    saved_server_ids.push(server.server_id());
+                }
+                // Saving can change the diagnostics of other files, e.g. the files that
+                // depend on the saved one.
+                for server_id in saved_server_ids {
+                    self.pull_workspace_diagnostics(server_id, cx);
                 }
 
                 let language_server_ids = self.language_server_ids_for_buffer(buffer.read(cx), cx);
@@ -2702,20 +2885,23 @@ impl Project {
         cx.spawn(move |this, mut cx| async move {
             while let Some(()) = settings_changed_rx.next().await {
                 let servers: Vec<_> = this.update(&mut cx, |this, _| {
-                    this.language_servers
-                        .values()
-                        .filter_map(|state| match state {
-                            LanguageServerState::Starting(_) => None,
-                            LanguageServerState::Running {
-                                adapter, server, ..
-                            } => Some((adapter.clone(), server.clone())),
+                    this.language_server_ids
+                        .iter()
+                        .filter_map(|((worktree_id, _), server_id)| {
+                            match this.language_servers.get(server_id)? {
+                                LanguageServerState::Starting(_) => None,
+                                LanguageServerState::Running {
+                                    adapter, server, ..
+                                } => Some((*worktree_id, adapter.clone(), server.clone())),
+                            }
                         })
                         .collect()
                 })?;
 
-                for (adapter, server) in servers {
-                    let workspace_config =
-                        cx.update(|cx| adapter.workspace_configuration(server.root_path(), cx))?;
+                for (worktree_id, adapter, server) in servers {
+                    let workspace_config = cx.update(|cx| {
+                        Self::workspace_configuration(&adapter, worktree_id, server.root_path(), cx)
+                    })?;
                     server
                         .notify::<lsp::notification::DidChangeConfiguration>(
                             lsp::DidChangeConfigurationParams {
@@ -2793,7 +2979,9 @@ impl Project {
         }
 
         for adapter in language.lsp_adapters() {
-            self.start_language_server(worktree, adapter.clone(), language.clone(), cx);
+            if ProjectSettings::get_global(cx).is_language_server_enabled(&adapter.name.0) {
+                self.start_language_server(worktree, adapter.clone(), language.clone(), cx);
+            }
         }
     }
 
@@ -2808,31 +2996,65 @@ impl Project {
             return;
         }
 
-        let worktree = worktree.read(cx);
-        let worktree_id = worktree.id();
-        let worktree_path = worktree.abs_path();
+        let worktree_id = worktree.read(cx).id();
         let key = (worktree_id, adapter.name.clone());
-        if self.language_server_ids.contains_key(&key) {
+        if self.language_server_ids.contains_key(&key)
+            || self.stopped_language_servers.contains(&key)
+        {
+            return;
+        }
+
+        let worktree_path = match self.language_server_project_roots.get(&key) {
+            Some(Some(project_root)) => project_root.clone(),
+            // The server is started once its project root has been found.
+            Some(None) => return,
+            None => {
+                self.find_language_server_project_root(worktree, adapter, language, cx);
+                return;
+            }
+        };
+        if let Some(server_id) = self.language_server_for_project_root(&adapter, &worktree_path) {
+            self.language_server_ids.insert(key, server_id);
             return;
         }
 
+        let project_settings = ProjectSettings::get_global(cx);
+        let lsp = project_settings.lsp.get(&adapter.name.0);
+        // Unlike the binary to run, initialization options can also be set in the
+        // worktree's own settings.
+        let override_options =
+            ProjectSettings::get(Some((worktree_id.to_usize(), Path::new(""))), cx)
+                .lsp
+                .get(&adapter.name.0)
+                .and_then(|s| s.initialization_options.clone());
+        let override_binary = lsp
+            .and_then(|s| s.binary.as_ref())
+            .and_then(|binary| binary.binary());
+        let pinned_server_version = lsp.and_then(|s| s.version.clone());
+        let prefer_system_binary = lsp.and_then(|s| s.prefer_system_binary).unwrap_or(false);
+        let override_env = lsp.and_then(|s| s.env());
+
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let pending_server = match self.languages.create_pending_language_server(
             stderr_capture.clone(),
             language.clone(),
             adapter.clone(),
             Arc::clone(&worktree_path),
-            ProjectLspAdapterDelegate::new(self, cx),
+            override_binary,
+            override_env,
+            ProjectLspAdapterDelegate::new(
+                self,
+                language.clone(),
+                pinned_server_version,
+                prefer_system_binary,
+                cx,
+            ),
             cx,
         ) {
             Some(pending_server) => pending_server,
             None => return,
         };
 
-        let project_settings = ProjectSettings::get_global(cx);
-        let lsp = project_settings.lsp.get(&adapter.name.0);
-        let override_options = lsp.map(|s| s.initialization_options.clone()).flatten();
-
         let server_id = pending_server.server_id;
         let container_dir = pending_server.container_dir.clone();
         let state = LanguageServerState::Starting({
@@ -2866,6 +3088,20 @@ impl Project {
                         log::error!("server stderr: {:?}", stderr_capture.lock().take());
 
                         let this = this.upgrade()?;
+                        let failure_kind =
+                            if err.downcast_ref::<LanguageServerBinaryError>().is_some() {
+                                LanguageServerFailureKind::DownloadFailed
+                            } else {
+                                LanguageServerFailureKind::Crashed
+                            };
+                        this.update(&mut cx, |this, _| {
+                            this.client.telemetry().report_language_server_event(
+                                &server_name,
+                                None,
+                                failure_kind,
+                            );
+                        })
+                        .ok();
                         let container_dir = container_dir?;
 
                         let attempt_count = adapter.reinstall_attempt_count.fetch_add(1, SeqCst);
@@ -2900,6 +3136,56 @@ impl Project {
         self.language_server_ids.insert(key, server_id);
     }
 
+    /// Looks for the directory that the adapter starts its server in for the worktree, in
+    /// the background, and then starts the server.
+    fn find_language_server_project_root(
+        &mut self,
+        worktree: &Model<Worktree>,
+        adapter: Arc<CachedLspAdapter>,
+        language: Arc<Language>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let key = (worktree.read(cx).id(), adapter.name.clone());
+        let worktree_path = worktree.read(cx).abs_path();
+        let worktree = worktree.downgrade();
+        let fs = self.fs.clone();
+        self.language_server_project_roots.insert(key.clone(), None);
+        cx.spawn(move |this, mut cx| async move {
+            let project_root: Arc<Path> =
+                match adapter.project_root(&worktree_path, fs.as_ref()).await {
+                    Some(project_root) => project_root.into(),
+                    None => worktree_path,
+                };
+            this.update(&mut cx, |this, cx| {
+                // The worktree was removed, or its server stopped, in the meantime.
+                let Some(root) = this.language_server_project_roots.get_mut(&key) else {
+                    return;
+                };
+                *root = Some(project_root);
+                if let Some(worktree) = worktree.upgrade() {
+                    this.start_language_server(&worktree, adapter, language, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Finds the server that was started for another worktree in the given project root, so
+    /// that it can be shared instead of starting one more instance.
+    fn language_server_for_project_root(
+        &self,
+        adapter: &CachedLspAdapter,
+        project_root: &Path,
+    ) -> Option<LanguageServerId> {
+        self.language_server_ids
+            .iter()
+            .find_map(|(key, server_id)| {
+                let root = self.language_server_project_roots.get(key)?.as_deref()?;
+                (key.1 == adapter.name && root == project_root).then_some(*server_id)
+            })
+    }
+
     fn reinstall_language_server(
         &mut self,
         language: Arc<Language>,
@@ -2972,6 +3258,7 @@ impl Project {
             this.clone(),
             override_initialization_options,
             pending_server,
+            key.0,
             worktree_path,
             adapter.clone(),
             server_id,
@@ -3002,13 +3289,14 @@ impl Project {
         this: WeakModel<Self>,
         override_options: Option<serde_json::Value>,
         pending_server: PendingLanguageServer,
+        worktree_id: WorktreeId,
         worktree_path: &Path,
         adapter: Arc<CachedLspAdapter>,
         server_id: LanguageServerId,
         cx: &mut AsyncAppContext,
     ) -> Result<Arc<LanguageServer>> {
-        let workspace_config =
-            cx.update(|cx| adapter.workspace_configuration(worktree_path, cx))?;
+        let workspace_config = cx
+            .update(|cx| Self::workspace_configuration(&adapter, worktree_id, worktree_path, cx))?;
         let language_server = pending_server.task.await?;
 
         language_server
@@ -3042,8 +3330,9 @@ impl Project {
                     let adapter = adapter.clone();
                     let worktree_path = worktree_path.clone();
                     async move {
-                        let workspace_config =
-                            cx.update(|cx| adapter.workspace_configuration(&worktree_path, cx))?;
+                        let workspace_config = cx.update(|cx| {
+                            Self::workspace_configuration(&adapter, worktree_id, &worktree_path, cx)
+                        })?;
                         Ok(params
                             .items
                             .into_iter()
@@ -3093,17 +3382,27 @@ impl Project {
                 move |params, mut cx| {
                     let this = this.clone();
                     async move {
-                        for reg in params.registrations {
-                            if reg.method == "workspace/didChangeWatchedFiles" {
-                                if let Some(options) = reg.register_options {
-                                    let options = serde_json::from_value(options)?;
-                                    this.update(&mut cx, |this, cx| {
-                                        this.on_lsp_did_change_watched_files(
-                                            server_id, options, cx,
-                                        );
-                                    })?;
-                                }
-                            }
+                        for registration in params.registrations {
+                            this.update(&mut cx, |this, cx| {
+                                this.on_lsp_register_capability(server_id, registration, cx)
+                            })??;
+                        }
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::UnregisterCapability, _, _>({
+                let this = this.clone();
+                move |params, mut cx| {
+                    let this = this.clone();
+                    async move {
+                        for unregistration in params.unregisterations {
+                            this.update(&mut cx, |this, cx| {
+                                this.on_lsp_unregister_capability(server_id, unregistration, cx)
+                            })?;
                         }
                         Ok(())
                     }
@@ -3127,6 +3426,46 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |_, cx| cx.emit(Event::RefreshSemanticTokens))?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |_, cx| cx.emit(Event::RefreshCodeLenses))?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_notification::<PublishClosingLabels, _>({
+                let this = this.clone();
+                move |params, mut cx| {
+                    if let Some(this) = this.upgrade() {
+                        this.update(&mut cx, |this, cx| {
+                            this.update_closing_labels(server_id, params, cx).log_err();
+                        })
+                        .ok();
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::InlayHintRefreshRequest, _, _>({
                 let this = this.clone();
@@ -3150,15 +3489,38 @@ impl Project {
             adapter.disk_based_diagnostics_progress_token.clone();
 
         language_server
-            .on_notification::<lsp::notification::Progress, _>(move |params, mut cx| {
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_notification::<ProgressOrPartialResult, _>(move |params, mut cx| {
                 if let Some(this) = this.upgrade() {
                     this.update(&mut cx, |this, cx| {
-                        this.on_lsp_progress(
-                            params,
-                            server_id,
-                            disk_based_diagnostics_progress_token.clone(),
-                            cx,
-                        );
+                        if this.on_partial_diagnostics(&params.token, params.value.clone(), cx) {
+                            return;
+                        }
+                        if let Some(value) = serde_json::from_value(params.value).log_err() {
+                            this.on_lsp_progress(
+                                lsp::ProgressParams {
+                                    token: params.token,
+                                    value,
+                                },
+                                server_id,
+                                disk_based_diagnostics_progress_token.clone(),
+                                cx,
+                            );
+                        }
                     })
                     .ok();
                 }
@@ -3185,6 +3547,26 @@ impl Project {
         Ok(language_server)
     }
 
+    /// The configuration sent to a language server running in the given worktree, which
+    /// is the adapter's own configuration with the server's `settings` from the worktree's
+    /// settings merged into it.
+    fn workspace_configuration(
+        adapter: &CachedLspAdapter,
+        worktree_id: WorktreeId,
+        worktree_path: &Path,
+        cx: &mut AppContext,
+    ) -> serde_json::Value {
+        let mut workspace_config = adapter.workspace_configuration(worktree_path, cx);
+        let settings = ProjectSettings::get(Some((worktree_id.to_usize(), Path::new(""))), cx)
+            .lsp
+            .get(&adapter.name.0)
+            .and_then(|s| s.settings.clone());
+        if let Some(settings) = settings {
+            merge_json_value_into(settings, &mut workspace_config);
+        }
+        workspace_config
+    }
+
     fn insert_newly_running_language_server(
         &mut self,
         language: Arc<Language>,
@@ -3212,6 +3594,7 @@ impl Project {
             LanguageServerState::Running {
                 adapter: adapter.clone(),
                 language: language.clone(),
+                watcher_registrations: Default::default(),
                 watched_paths: Default::default(),
                 server: language_server.clone(),
                 simulate_disk_based_diagnostics_completion: None,
@@ -3230,6 +3613,28 @@ impl Project {
 
         cx.emit(Event::LanguageServerAdded(server_id));
 
+        let exited = language_server.exited();
+        let started_at = Instant::now();
+        cx.spawn({
+            let key = key.clone();
+            move |this, mut cx| async move {
+                exited.await;
+                this.update(&mut cx, |this, cx| {
+                    this.handle_language_server_exit(server_id, key, started_at.elapsed(), cx)
+                })
+                .ok();
+            }
+        })
+        .detach();
+
+        if let Some(process_id) = language_server.process_id() {
+            self.client.telemetry().watch_language_server_memory(
+                process_id,
+                &adapter.name.0,
+                language_server.version(),
+            );
+        }
+
         if let Some(project_id) = self.remote_id() {
             self.client.send(proto::StartLanguageServer {
                 project_id,
@@ -3253,7 +3658,10 @@ impl Project {
                     None => continue,
                 };
 
-                if file.worktree.read(cx).id() != key.0
+                // Servers that are shared between the worktrees in a project root are told
+                // about the buffers of all of them.
+                let buffer_key = (file.worktree.read(cx).id(), key.1.clone());
+                if self.language_server_ids.get(&buffer_key) != Some(&server_id)
                     || !language.lsp_adapters().iter().any(|a| a.name == key.1)
                 {
                     continue;
@@ -3308,11 +3716,178 @@ impl Project {
                 });
             }
         }
+        self.refresh_pulled_diagnostics(server_id, cx);
 
         cx.notify();
         Ok(())
     }
 
+    /// Restarts a language server that exited without being stopped, waiting longer after
+    /// each crash, until it has crashed [`MAX_SERVER_CRASH_RESTART_COUNT`] times in a row.
+    ///
+    /// The restarted server is told about the open buffers like any new server, so it
+    /// picks up where the crashed one left off.
+    fn handle_language_server_exit(
+        &mut self,
+        server_id: LanguageServerId,
+        key: (WorktreeId, LanguageServerName),
+        uptime: Duration,
+        cx: &mut ModelContext<Self>,
+    ) {
+        // Servers that are stopped on purpose are removed before they exit.
+        let Some(LanguageServerState::Running {
+            adapter,
+            language,
+            server,
+            ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        if self.language_server_ids.get(&key) != Some(&server_id) {
+            return;
+        }
+        let (adapter, language) = (adapter.clone(), language.clone());
+        log::error!("language server {:?} exited unexpectedly", adapter.name.0);
+        self.client.telemetry().report_language_server_event(
+            &adapter.name.0,
+            server.version(),
+            LanguageServerFailureKind::Crashed,
+        );
+
+        let crash_count = self
+            .language_server_crash_counts
+            .entry(key.clone())
+            .or_default();
+        if uptime >= SERVER_CRASH_RESET_UPTIME {
+            *crash_count = 0;
+        }
+        *crash_count += 1;
+        let crash_count = *crash_count;
+
+        if crash_count > MAX_SERVER_CRASH_RESTART_COUNT {
+            log::error!(
+                "not restarting language server {:?} after {MAX_SERVER_CRASH_RESTART_COUNT} crashes",
+                adapter.name.0
+            );
+            self.language_server_crash_counts.remove(&key);
+            cx.emit(Event::LanguageServerCrashed {
+                language_server_id: server_id,
+                name: key.1.clone(),
+                worktree_id: key.0,
+            });
+            self.stop_language_server(key.0, key.1, cx).detach();
+            return;
+        }
+
+        let delay = SERVER_CRASH_RESTART_DELAY * 2u32.pow(crash_count - 1);
+        self.restart_language_server_after(key, adapter, language, delay, cx);
+    }
+
+    /// Stops the language server for `key` and starts it again once `delay` has elapsed.
+    fn restart_language_server_after(
+        &mut self,
+        key: (WorktreeId, LanguageServerName),
+        adapter: Arc<CachedLspAdapter>,
+        language: Arc<Language>,
+        delay: Duration,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(worktree) = self.worktree_for_id(key.0, cx) else {
+            return;
+        };
+        let worktree = worktree.downgrade();
+        let stop = self.stop_language_server(key.0, key.1.clone(), cx);
+        cx.spawn(move |this, mut cx| async move {
+            let orphaned_worktrees = stop.await;
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+
+            this.update(&mut cx, |this, cx| {
+                let Some(worktree) = worktree.upgrade() else {
+                    return;
+                };
+                if !ProjectSettings::get_global(cx).is_language_server_enabled(&adapter.name.0) {
+                    return;
+                }
+                log::info!("restarting language server {:?}", adapter.name.0);
+                this.start_language_server(&worktree, adapter, language, cx);
+
+                // The worktrees that shared the old server share the new one.
+                if let Some(new_server_id) = this.language_server_ids.get(&key).copied() {
+                    for worktree_id in orphaned_worktrees {
+                        this.language_server_ids
+                            .insert((worktree_id, key.1.clone()), new_server_id);
+                    }
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Restarts a single running language server, leaving the other servers for its
+    /// language alone.
+    pub fn restart_language_server(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            adapter,
+            language,
+            server,
+            ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(key) = self.language_server_key(server_id) else {
+            return;
+        };
+        self.client.telemetry().report_language_server_event(
+            &adapter.name.0,
+            server.version(),
+            LanguageServerFailureKind::Restarted,
+        );
+        let (adapter, language) = (adapter.clone(), language.clone());
+        self.restart_language_server_after(key, adapter, language, Duration::ZERO, cx);
+    }
+
+    /// Stops a running language server, which isn't started again for new buffers until
+    /// it's restarted.
+    pub fn stop_language_server_with_id(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let Some(key) = self.language_server_key(server_id) else {
+            return Task::ready(());
+        };
+        let stop = self.stop_language_server(key.0, key.1.clone(), cx);
+        cx.spawn(move |this, mut cx| async move {
+            let stopped_worktrees = stop.await;
+            this.update(&mut cx, |this, _| {
+                for worktree_id in stopped_worktrees {
+                    this.stopped_language_servers
+                        .insert((worktree_id, key.1.clone()));
+                }
+            })
+            .ok();
+        })
+    }
+
+    fn language_server_key(
+        &self,
+        server_id: LanguageServerId,
+    ) -> Option<(WorktreeId, LanguageServerName)> {
+        self.language_server_ids
+            .iter()
+            .find(|(_, id)| **id == server_id)
+            .map(|(key, _)| key.clone())
+    }
+
     // Returns a list of all of the worktrees which no longer have a language server and the root path
     // for the stopped server
     fn stop_language_server(
@@ -3322,6 +3897,7 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Vec<WorktreeId>> {
         let key = (worktree_id, adapter_name);
+        self.language_server_project_roots.remove(&key);
         if let Some(server_id) = self.language_server_ids.remove(&key) {
             log::info!("stopping language server {}", key.1 .0);
 
@@ -3331,6 +3907,7 @@ impl Project {
             for other_key in other_keys {
                 if self.language_server_ids.get(&other_key) == Some(&server_id) {
                     self.language_server_ids.remove(&other_key);
+                    self.language_server_project_roots.remove(&other_key);
                     orphaned_worktrees.push(other_key.0);
                 }
             }
@@ -3353,6 +3930,7 @@ impl Project {
             }
 
             self.language_server_statuses.remove(&server_id);
+            self.pulled_diagnostics.forget_server(server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -3418,11 +3996,27 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) {
         let worktree_id = worktree.read(cx).id();
+        for adapter in language.lsp_adapters() {
+            self.stopped_language_servers
+                .remove(&(worktree_id, adapter.name.clone()));
+        }
 
         let stop_tasks = language
             .lsp_adapters()
             .iter()
             .map(|adapter| {
+                let running_server = self
+                    .language_server_ids
+                    .get(&(worktree_id, adapter.name.clone()))
+                    .and_then(|server_id| self.language_servers.get(server_id));
+                if let Some(LanguageServerState::Running { server, .. }) = running_server {
+                    self.client.telemetry().report_language_server_event(
+                        &adapter.name.0,
+                        server.version(),
+                        LanguageServerFailureKind::Restarted,
+                    );
+                }
+
                 let stop_task = self.stop_language_server(worktree_id, adapter.name.clone(), cx);
                 (stop_task, adapter.name.clone())
             })
@@ -3581,6 +4175,7 @@ impl Project {
                         LanguageServerProgress {
                             message: report.message.clone(),
                             percentage: report.percentage.map(|p| p as usize),
+                            is_cancellable: report.cancellable.unwrap_or(false),
                             last_update_at: Instant::now(),
                         },
                         cx,
@@ -3601,12 +4196,20 @@ impl Project {
             }
             lsp::WorkDoneProgress::Report(report) => {
                 if !is_disk_based_diagnostics_progress {
+                    // Reports that don't mention cancellation keep it as it was.
+                    let is_cancellable = report.cancellable.unwrap_or_else(|| {
+                        language_server_status
+                            .pending_work
+                            .get(&token)
+                            .map_or(false, |work| work.is_cancellable)
+                    });
                     self.on_lsp_work_progress(
                         language_server_id,
                         token.clone(),
                         LanguageServerProgress {
                             message: report.message.clone(),
                             percentage: report.percentage.map(|p| p as usize),
+                            is_cancellable,
                             last_update_at: Instant::now(),
                         },
                         cx,
@@ -3682,6 +4285,7 @@ impl Project {
                 .or_insert(LanguageServerProgress {
                     message: Default::default(),
                     percentage: Default::default(),
+                    is_cancellable: false,
                     last_update_at: progress.last_update_at,
                 });
             if progress.message.is_some() {
@@ -3690,6 +4294,7 @@ impl Project {
             if progress.percentage.is_some() {
                 entry.percentage = progress.percentage;
             }
+            entry.is_cancellable = progress.is_cancellable;
             entry.last_update_at = progress.last_update_at;
             cx.notify();
         }
@@ -3703,70 +4308,223 @@ impl Project {
     ) {
         if let Some(status) = self.language_server_statuses.get_mut(&language_server_id) {
             cx.emit(Event::RefreshInlayHints);
+            cx.emit(Event::RefreshSemanticTokens);
             status.pending_work.remove(&token);
             cx.notify();
         }
     }
 
+    /// Asks the language server to cancel the work reported under the given progress token.
+    /// The work stays pending until the server reports that it has ended.
+    pub fn cancel_language_server_work(
+        &mut self,
+        language_server_id: LanguageServerId,
+        token: String,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let is_cancellable = self
+            .language_server_statuses
+            .get(&language_server_id)
+            .and_then(|status| status.pending_work.get(&token))
+            .map_or(false, |progress| progress.is_cancellable);
+        if !is_cancellable {
+            return;
+        }
+        if let Some(server) = self.language_server_for_id(language_server_id) {
+            server
+                .notify::<lsp::notification::WorkDoneProgressCancel>(
+                    lsp::WorkDoneProgressCancelParams {
+                        token: lsp::NumberOrString::String(token),
+                    },
+                )
+                .log_err();
+        }
+        cx.notify();
+    }
+
+    fn on_lsp_register_capability(
+        &mut self,
+        language_server_id: LanguageServerId,
+        registration: lsp::Registration,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if registration.method == "workspace/didChangeWatchedFiles" {
+            if let Some(options) = registration.register_options {
+                let options = serde_json::from_value(options)?;
+                self.on_lsp_did_change_watched_files(
+                    language_server_id,
+                    registration.id,
+                    options,
+                    cx,
+                );
+            }
+            return Ok(());
+        }
+
+        let Some(server) = self.language_server_for_id(language_server_id) else {
+            return Ok(());
+        };
+        if server.register_capability(
+            &registration.id,
+            &registration.method,
+            registration.register_options,
+        )? {
+            self.on_lsp_capabilities_changed(language_server_id, &registration.method, cx);
+        } else {
+            log::warn!(
+                "language server {} registered unsupported capability {}",
+                server.name(),
+                registration.method
+            );
+        }
+        Ok(())
+    }
+
+    fn on_lsp_unregister_capability(
+        &mut self,
+        language_server_id: LanguageServerId,
+        unregistration: lsp::Unregistration,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if unregistration.method == "workspace/didChangeWatchedFiles" {
+            self.on_lsp_unregister_did_change_watched_files(
+                language_server_id,
+                &unregistration.id,
+                cx,
+            );
+            return;
+        }
+
+        if let Some(server) = self.language_server_for_id(language_server_id) {
+            if server.unregister_capability(&unregistration.id) {
+                self.on_lsp_capabilities_changed(language_server_id, &unregistration.method, cx);
+            }
+        }
+    }
+
+    /// Updates the state that was derived from a server's capabilities after it registered or
+    /// unregistered the capability for the given method.
+    fn on_lsp_capabilities_changed(
+        &mut self,
+        language_server_id: LanguageServerId,
+        method: &str,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match method {
+            "textDocument/completion" => {
+                let Some(server) = self.language_server_for_id(language_server_id) else {
+                    return;
+                };
+                let completion_triggers = server
+                    .capabilities()
+                    .completion_provider
+                    .as_ref()
+                    .and_then(|provider| provider.trigger_characters.clone())
+                    .unwrap_or_default();
+                for buffer in self.opened_buffers.values() {
+                    let Some(buffer) = buffer.upgrade() else {
+                        continue;
+                    };
+                    let is_served_by_server = self
+                        .buffer_snapshots
+                        .get(&buffer.read(cx).remote_id())
+                        .map_or(false, |snapshots| {
+                            snapshots.contains_key(&language_server_id)
+                        });
+                    if is_served_by_server {
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.set_completion_triggers(completion_triggers.clone(), cx)
+                        });
+                    }
+                }
+            }
+            "textDocument/semanticTokens" => cx.emit(Event::RefreshSemanticTokens),
+            "textDocument/codeLens" => cx.emit(Event::RefreshCodeLenses),
+            "textDocument/diagnostic" => self.refresh_pulled_diagnostics(language_server_id, cx),
+            _ => {}
+        }
+    }
+
     fn on_lsp_did_change_watched_files(
         &mut self,
         language_server_id: LanguageServerId,
+        registration_id: String,
         params: DidChangeWatchedFilesRegistrationOptions,
         cx: &mut ModelContext<Self>,
     ) {
-        if let Some(LanguageServerState::Running { watched_paths, .. }) =
-            self.language_servers.get_mut(&language_server_id)
+        if let Some(LanguageServerState::Running {
+            watcher_registrations,
+            ..
+        }) = self.language_servers.get_mut(&language_server_id)
         {
-            let mut builders = HashMap::default();
-            for watcher in params.watchers {
-                for worktree in &self.worktrees {
-                    if let Some(worktree) = worktree.upgrade() {
-                        let glob_is_inside_worktree = worktree.update(cx, |tree, _| {
-                            if let Some(abs_path) = tree.abs_path().to_str() {
-                                let relative_glob_pattern = match &watcher.glob_pattern {
-                                    lsp::GlobPattern::String(s) => s
-                                        .strip_prefix(abs_path)
-                                        .and_then(|s| s.strip_prefix(std::path::MAIN_SEPARATOR)),
-                                    lsp::GlobPattern::Relative(rp) => {
-                                        let base_uri = match &rp.base_uri {
-                                            lsp::OneOf::Left(workspace_folder) => {
-                                                &workspace_folder.uri
-                                            }
-                                            lsp::OneOf::Right(base_uri) => base_uri,
-                                        };
-                                        base_uri.to_file_path().ok().and_then(|file_path| {
-                                            (file_path.to_str() == Some(abs_path))
-                                                .then_some(rp.pattern.as_str())
-                                        })
-                                    }
-                                };
-                                if let Some(relative_glob_pattern) = relative_glob_pattern {
-                                    let literal_prefix = glob_literal_prefix(relative_glob_pattern);
-                                    tree.as_local_mut()
-                                        .unwrap()
-                                        .add_path_prefix_to_scan(Path::new(literal_prefix).into());
-                                    if let Some(glob) = Glob::new(relative_glob_pattern).log_err() {
-                                        builders
-                                            .entry(tree.id())
-                                            .or_insert_with(|| GlobSetBuilder::new())
-                                            .add(glob);
-                                    }
-                                    return true;
-                                }
-                            }
-                            false
-                        });
-                        if glob_is_inside_worktree {
-                            break;
-                        }
+            watcher_registrations.insert(registration_id, params.watchers);
+            self.rebuild_watched_paths(language_server_id, cx);
+        }
+    }
+
+    fn on_lsp_unregister_did_change_watched_files(
+        &mut self,
+        language_server_id: LanguageServerId,
+        registration_id: &str,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(LanguageServerState::Running {
+            watcher_registrations,
+            ..
+        }) = self.language_servers.get_mut(&language_server_id)
+        {
+            if watcher_registrations.remove(registration_id).is_some() {
+                self.rebuild_watched_paths(language_server_id, cx);
+            }
+        }
+    }
+
+    /// Translates the globs of all of the server's file watchers into globs relative to each
+    /// worktree, and makes the worktrees load the ignored directories that they refer to.
+    fn rebuild_watched_paths(
+        &mut self,
+        language_server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if let Some(LanguageServerState::Running {
+            watcher_registrations,
+            watched_paths,
+            ..
+        }) = self.language_servers.get_mut(&language_server_id)
+        {
+            let mut builders = HashMap::<_, LanguageServerWatchedPathsBuilder>::default();
+            for watcher in watcher_registrations.values().flatten() {
+                for worktree in &self.worktrees {
+                    let Some(worktree) = worktree.upgrade() else {
+                        continue;
+                    };
+                    let Some(tree) = worktree.read(cx).as_local() else {
+                        continue;
+                    };
+                    let Some(relative_glob_pattern) =
+                        glob_pattern_relative_to(&watcher.glob_pattern, tree.abs_path())
+                    else {
+                        continue;
+                    };
+                    // Globs that aren't limited to a directory only match the paths that
+                    // the worktree loads anyway.
+                    let literal_prefix = glob_literal_prefix(&relative_glob_pattern);
+                    if !literal_prefix.is_empty() {
+                        tree.add_path_prefix_to_scan(Path::new(literal_prefix).into());
+                    }
+                    if let Some(glob) = Glob::new(&relative_glob_pattern).log_err() {
+                        builders
+                            .entry(tree.id())
+                            .or_default()
+                            .add(glob, watcher.kind);
                     }
                 }
             }
 
             watched_paths.clear();
             for (worktree_id, builder) in builders {
-                if let Ok(globset) = builder.build() {
-                    watched_paths.insert(worktree_id, globset);
+                if let Some(paths) = builder.build().log_err() {
+                    watched_paths.insert(worktree_id, paths);
                 }
             }
 
@@ -3812,8 +4570,10 @@ impl Project {
 
     pub fn language_server_statuses(
         &self,
-    ) -> impl DoubleEndedIterator<Item = &LanguageServerStatus> {
-        self.language_server_statuses.values()
+    ) -> impl DoubleEndedIterator<Item = (LanguageServerId, &LanguageServerStatus)> {
+        self.language_server_statuses
+            .iter()
+            .map(|(id, status)| (*id, status))
     }
 
     pub fn update_diagnostics(
@@ -3823,6 +4583,10 @@ impl Project {
         disk_based_sources: &[String],
         cx: &mut ModelContext<Self>,
     ) -> Result<()> {
+        if !self.shows_diagnostics_from(language_server_id, cx) {
+            return Ok(());
+        }
+
         let abs_path = params
             .uri
             .to_file_path()
@@ -3936,6 +4700,43 @@ impl Project {
         Ok(())
     }
 
+    /// Whether to show the diagnostics published by a language server, which are ignored
+    /// when it isn't the primary language server of a language that only shows the
+    /// diagnostics of its primary language server.
+    fn shows_diagnostics_from(&self, server_id: LanguageServerId, cx: &AppContext) -> bool {
+        let Some(LanguageServerState::Running {
+            language, adapter, ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return true;
+        };
+        let merge_strategy = language_settings(Some(language), None, cx)
+            .language_server_merging
+            .diagnostics;
+        if merge_strategy == LanguageServerMergeStrategy::All {
+            return true;
+        }
+
+        let Some(worktree_id) = self
+            .language_server_ids
+            .iter()
+            .find_map(|((worktree_id, _), id)| (*id == server_id).then_some(*worktree_id))
+        else {
+            return true;
+        };
+        let primary_adapter = language.lsp_adapters().iter().find(|adapter| {
+            self.language_server_ids
+                .get(&(worktree_id, adapter.name.clone()))
+                .map_or(false, |id| {
+                    matches!(
+                        self.language_servers.get(id),
+                        Some(LanguageServerState::Running { .. })
+                    )
+                })
+        });
+        primary_adapter.map_or(true, |primary_adapter| primary_adapter.name == adapter.name)
+    }
+
     pub fn update_diagnostic_entries(
         &mut self,
         server_id: LanguageServerId,
@@ -4514,136 +5315,13 @@ impl Project {
 
     pub fn symbols(&self, query: &str, cx: &mut ModelContext<Self>) -> Task<Result<Vec<Symbol>>> {
         if self.is_local() {
-            let mut requests = Vec::new();
-            for ((worktree_id, _), server_id) in self.language_server_ids.iter() {
-                let worktree_id = *worktree_id;
-                let worktree_handle = self.worktree_for_id(worktree_id, cx);
-                let worktree = match worktree_handle.and_then(|tree| tree.read(cx).as_local()) {
-                    Some(worktree) => worktree,
-                    None => continue,
-                };
-                let worktree_abs_path = worktree.abs_path().clone();
-
-                let (adapter, language, server) = match self.language_servers.get(server_id) {
-                    Some(LanguageServerState::Running {
-                        adapter,
-                        language,
-                        server,
-                        ..
-                    }) => (adapter.clone(), language.clone(), server),
-
-                    _ => continue,
-                };
-
-                requests.push(
-                    server
-                        .request::<lsp::request::WorkspaceSymbolRequest>(
-                            lsp::WorkspaceSymbolParams {
-                                query: query.to_string(),
-                                ..Default::default()
-                            },
-                        )
-                        .log_err()
-                        .map(move |response| {
-                            let lsp_symbols = response.flatten().map(|symbol_response| match symbol_response {
-                                lsp::WorkspaceSymbolResponse::Flat(flat_responses) => {
-                                    flat_responses.into_iter().map(|lsp_symbol| {
-                                        (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.location)
-                                    }).collect::<Vec<_>>()
-                                }
-                                lsp::WorkspaceSymbolResponse::Nested(nested_responses) => {
-                                    nested_responses.into_iter().filter_map(|lsp_symbol| {
-                                        let location = match lsp_symbol.location {
-                                            OneOf::Left(location) => location,
-                                            OneOf::Right(_) => {
-                                                error!("Unexpected: client capabilities forbid symbol resolutions in workspace.symbol.resolveSupport");
-                                                return None
-                                            }
-                                        };
-                                        Some((lsp_symbol.name, lsp_symbol.kind, location))
-                                    }).collect::<Vec<_>>()
-                                }
-                            }).unwrap_or_default();
-
-                            (
-                                adapter,
-                                language,
-                                worktree_id,
-                                worktree_abs_path,
-                                lsp_symbols,
-                            )
-                        }),
-                );
-            }
-
-            cx.spawn(move |this, mut cx| async move {
-                let responses = futures::future::join_all(requests).await;
-                let this = match this.upgrade() {
-                    Some(this) => this,
-                    None => return Ok(Vec::new()),
-                };
-
-                let symbols = this.update(&mut cx, |this, cx| {
-                    let mut symbols = Vec::new();
-                    for (
-                        adapter,
-                        adapter_language,
-                        source_worktree_id,
-                        worktree_abs_path,
-                        lsp_symbols,
-                    ) in responses
-                    {
-                        symbols.extend(lsp_symbols.into_iter().filter_map(
-                            |(symbol_name, symbol_kind, symbol_location)| {
-                                let abs_path = symbol_location.uri.to_file_path().ok()?;
-                                let mut worktree_id = source_worktree_id;
-                                let path;
-                                if let Some((worktree, rel_path)) =
-                                    this.find_local_worktree(&abs_path, cx)
-                                {
-                                    worktree_id = worktree.read(cx).id();
-                                    path = rel_path;
-                                } else {
-                                    path = relativize_path(&worktree_abs_path, &abs_path);
-                                }
-
-                                let project_path = ProjectPath {
-                                    worktree_id,
-                                    path: path.into(),
-                                };
-                                let signature = this.symbol_signature(&project_path);
-                                let adapter_language = adapter_language.clone();
-                                let language = this
-                                    .languages
-                                    .language_for_file(&project_path.path, None)
-                                    .unwrap_or_else(move |_| adapter_language);
-                                let language_server_name = adapter.name.clone();
-                                Some(async move {
-                                    let language = language.await;
-                                    let label =
-                                        language.label_for_symbol(&symbol_name, symbol_kind).await;
-
-                                    Symbol {
-                                        language_server_name,
-                                        source_worktree_id,
-                                        path: project_path,
-                                        label: label.unwrap_or_else(|| {
-                                            CodeLabel::plain(symbol_name.clone(), None)
-                                        }),
-                                        kind: symbol_kind,
-                                        name: symbol_name,
-                                        range: range_from_lsp(symbol_location.range),
-                                        signature,
-                                    }
-                                })
-                            },
-                        ));
-                    }
-
-                    symbols
-                })?;
-
-                Ok(futures::future::join_all(symbols).await)
+            let symbols = self.search_local_symbols(query, cx);
+            cx.background_executor().spawn(async move {
+                let mut all_symbols = Vec::new();
+                while let Ok(symbols) = symbols.recv().await {
+                    all_symbols.extend(symbols);
+                }
+                Ok(all_symbols)
             })
         } else if let Some(project_id) = self.remote_id() {
             let request = self.client.request(proto::GetProjectSymbols {
@@ -4674,6 +5352,173 @@ impl Project {
         }
     }
 
+    /// Searches the symbols of the workspace like [`Project::symbols`], but sends the symbols
+    /// of each language server through the returned channel as soon as the server responds,
+    /// so that slow servers don't hold back the results of the others.
+    pub fn search_symbols(
+        &self,
+        query: &str,
+        cx: &mut ModelContext<Self>,
+    ) -> Receiver<Vec<Symbol>> {
+        if self.is_local() {
+            return self.search_local_symbols(query, cx);
+        }
+        let (tx, rx) = smol::channel::bounded(1);
+        let symbols = self.symbols(query, cx);
+        cx.background_executor()
+            .spawn(async move {
+                if let Some(symbols) = symbols.await.log_err() {
+                    tx.send(symbols).await.ok();
+                }
+            })
+            .detach();
+        rx
+    }
+
+    fn search_local_symbols(
+        &self,
+        query: &str,
+        cx: &mut ModelContext<Self>,
+    ) -> Receiver<Vec<Symbol>> {
+        let (tx, rx) = smol::channel::unbounded();
+        let mut requests = Vec::new();
+        for ((worktree_id, _), server_id) in self.language_server_ids.iter() {
+            let worktree_id = *worktree_id;
+            let worktree_handle = self.worktree_for_id(worktree_id, cx);
+            let worktree = match worktree_handle.and_then(|tree| tree.read(cx).as_local()) {
+                Some(worktree) => worktree,
+                None => continue,
+            };
+            let worktree_abs_path = worktree.abs_path().clone();
+
+            let (adapter, language, server) = match self.language_servers.get(server_id) {
+                Some(LanguageServerState::Running {
+                    adapter,
+                    language,
+                    server,
+                    ..
+                }) => (adapter.clone(), language.clone(), server),
+
+                _ => continue,
+            };
+            if matches!(
+                server.capabilities().workspace_symbol_provider,
+                None | Some(OneOf::Left(false))
+            ) {
+                continue;
+            }
+
+            requests.push(
+                server
+                    .request::<lsp::request::WorkspaceSymbolRequest>(
+                        lsp::WorkspaceSymbolParams {
+                            query: query.to_string(),
+                            ..Default::default()
+                        },
+                    )
+                    .log_err()
+                    .map(move |response| {
+                        let lsp_symbols = response.flatten().map(|symbol_response| match symbol_response {
+                            lsp::WorkspaceSymbolResponse::Flat(flat_responses) => {
+                                flat_responses.into_iter().map(|lsp_symbol| {
+                                    (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.location)
+                                }).collect::<Vec<_>>()
+                            }
+                            lsp::WorkspaceSymbolResponse::Nested(nested_responses) => {
+                                nested_responses.into_iter().filter_map(|lsp_symbol| {
+                                    let location = match lsp_symbol.location {
+                                        OneOf::Left(location) => location,
+                                        OneOf::Right(_) => {
+                                            error!("Unexpected: client capabilities forbid symbol resolutions in workspace.symbol.resolveSupport");
+                                            return None
+                                        }
+                                    };
+                                    Some((lsp_symbol.name, lsp_symbol.kind, location))
+                                }).collect::<Vec<_>>()
+                            }
+                        }).unwrap_or_default();
+
+                        (
+                            adapter,
+                            language,
+                            worktree_id,
+                            worktree_abs_path,
+                            lsp_symbols,
+                        )
+                    }),
+            );
+        }
+
+        cx.spawn(move |this, mut cx| async move {
+            let mut responses = requests.into_iter().collect::<FuturesUnordered<_>>();
+            while let Some((
+                adapter,
+                adapter_language,
+                source_worktree_id,
+                worktree_abs_path,
+                lsp_symbols,
+            )) = responses.next().await
+            {
+                let Ok(symbols) = this.update(&mut cx, |this, cx| {
+                    lsp_symbols
+                        .into_iter()
+                        .filter_map(|(symbol_name, symbol_kind, symbol_location)| {
+                            let abs_path = symbol_location.uri.to_file_path().ok()?;
+                            let mut worktree_id = source_worktree_id;
+                            let path;
+                            if let Some((worktree, rel_path)) =
+                                this.find_local_worktree(&abs_path, cx)
+                            {
+                                worktree_id = worktree.read(cx).id();
+                                path = rel_path;
+                            } else {
+                                path = relativize_path(&worktree_abs_path, &abs_path);
+                            }
+
+                            let project_path = ProjectPath {
+                                worktree_id,
+                                path: path.into(),
+                            };
+                            let signature = this.symbol_signature(&project_path);
+                            let adapter_language = adapter_language.clone();
+                            let language = this
+                                .languages
+                                .language_for_file(&project_path.path, None)
+                                .unwrap_or_else(move |_| adapter_language);
+                            let language_server_name = adapter.name.clone();
+                            Some(async move {
+                                let language = language.await;
+                                let label =
+                                    language.label_for_symbol(&symbol_name, symbol_kind).await;
+
+                                Symbol {
+                                    language_server_name,
+                                    source_worktree_id,
+                                    path: project_path,
+                                    label: label.unwrap_or_else(|| {
+                                        CodeLabel::plain(symbol_name.clone(), None)
+                                    }),
+                                    kind: symbol_kind,
+                                    name: symbol_name,
+                                    range: range_from_lsp(symbol_location.range),
+                                    signature,
+                                }
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                }) else {
+                    break;
+                };
+                let symbols = futures::future::join_all(symbols).await;
+                if tx.send(symbols).await.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+        rx
+    }
+
     pub fn open_buffer_for_symbol(
         &mut self,
         symbol: &Symbol,
@@ -4738,12 +5583,54 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Option<Hover>>> {
         let position = position.to_point_utf16(buffer.read(cx));
-        self.request_lsp(
-            buffer.clone(),
-            LanguageServerToQuery::Primary,
-            GetHover { position },
-            cx,
-        )
+        let merge_strategy = {
+            let buffer = buffer.read(cx);
+            language_settings(buffer.language(), buffer.file(), cx)
+                .language_server_merging
+                .hovers
+        };
+        if !self.is_local() {
+            return self.request_lsp(
+                buffer.clone(),
+                LanguageServerToQuery::Primary,
+                GetHover { position },
+                cx,
+            );
+        }
+        let server_count = match merge_strategy {
+            LanguageServerMergeStrategy::Primary => 1,
+            LanguageServerMergeStrategy::All => usize::MAX,
+        };
+
+        let server_ids: Vec<_> = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .filter(|(_, server)| server.capabilities().hover_provider.is_some())
+            .take(server_count)
+            .map(|(_, server)| server.server_id())
+            .collect();
+        let tasks: Vec<_> = server_ids
+            .into_iter()
+            .map(|server_id| {
+                self.request_lsp(
+                    buffer.clone(),
+                    LanguageServerToQuery::Other(server_id),
+                    GetHover { position },
+                    cx,
+                )
+            })
+            .collect();
+        cx.spawn(|_, _| async move {
+            let mut hover: Option<Hover> = None;
+            for task in tasks {
+                if let Ok(Some(new_hover)) = task.await {
+                    match &mut hover {
+                        Some(hover) => hover.contents.extend(new_hover.contents),
+                        None => hover = Some(new_hover),
+                    }
+                }
+            }
+            Ok(hover)
+        })
     }
 
     pub fn completions<T: ToOffset + ToPointUtf16>(
@@ -4757,6 +5644,13 @@ impl Project {
             let snapshot = buffer.read(cx).snapshot();
             let offset = position.to_offset(&snapshot);
             let scope = snapshot.language_scope_at(offset);
+            let merge_strategy = language_settings(buffer.read(cx).language(), snapshot.file(), cx)
+                .language_server_merging
+                .completions;
+            let server_count = match merge_strategy {
+                LanguageServerMergeStrategy::Primary => 1,
+                LanguageServerMergeStrategy::All => usize::MAX,
+            };
 
             let server_ids: Vec<_> = self
                 .language_servers_for_buffer(buffer.read(cx), cx)
@@ -4767,6 +5661,7 @@ impl Project {
                         .map(|scope| scope.language_allowed(&adapter.name))
                         .unwrap_or(true)
                 })
+                .take(server_count)
                 .map(|(_, server)| server.server_id())
                 .collect();
 
@@ -5543,14 +6438,18 @@ impl Project {
                 lsp_request,
                 cx,
             );
-            cx.spawn(move |_, mut cx| async move {
+            cx.spawn(move |project, mut cx| async move {
                 buffer_handle
                     .update(&mut cx, |buffer, _| {
                         buffer.wait_for_edits(vec![range_start.timestamp, range_end.timestamp])
                     })?
                     .await
                     .context("waiting for inlay hint request range edits")?;
-                lsp_request_task.await.context("inlay hints LSP request")
+                let mut hints = lsp_request_task.await.context("inlay hints LSP request")?;
+                hints.extend(project.update(&mut cx, |project, cx| {
+                    project.closing_label_hints(&buffer_handle, range_start..range_end, cx)
+                })?);
+                Ok(hints)
             })
         } else if let Some(project_id) = self.remote_id() {
             let client = self.client.clone();
@@ -5598,7 +6497,7 @@ impl Project {
             } else {
                 return Task::ready(Ok(hint));
             };
-            if !InlayHints::can_resolve_inlays(lang_server.capabilities()) {
+            if !InlayHints::can_resolve_inlays(&lang_server.capabilities()) {
                 return Task::ready(Ok(hint));
             }
 
@@ -5645,6 +6544,120 @@ impl Project {
         }
     }
 
+    /// Requests the semantic tokens of the buffer from its primary language server,
+    /// as a delta against the previous response when the server supports it. Returns
+    /// `None` when the server doesn't provide semantic tokens, or the project is remote.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Vec<SemanticToken>>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(None));
+        }
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some((_, language_server)) = self.primary_language_server_for_buffer(buffer, cx) else {
+            return Task::ready(Ok(None));
+        };
+        let language_server = language_server.clone();
+        let capabilities = language_server.capabilities();
+        let Some((legend, supports_delta)) = semantic_tokens_options(&capabilities) else {
+            return Task::ready(Ok(None));
+        };
+        let legend = legend.clone();
+        let Some(abs_path) = File::from_dyn(buffer.file()).map(|file| file.abs_path(cx)) else {
+            return Task::ready(Ok(None));
+        };
+        let Ok(uri) = lsp::Url::from_file_path(abs_path) else {
+            return Task::ready(Err(anyhow!("invalid buffer path")));
+        };
+        let text_document = lsp::TextDocumentIdentifier::new(uri);
+        // The server has already been told about every edit of the buffer, so its tokens
+        // refer to the current snapshot.
+        let snapshot = buffer.snapshot();
+        let server_id = language_server.server_id();
+        let previous_result_id = self
+            .semantic_tokens
+            .get(&buffer_id)
+            .and_then(|tokens| tokens.get(&server_id))
+            .and_then(|tokens| tokens.result_id.clone())
+            .filter(|_| supports_delta);
+
+        cx.spawn(move |this, mut cx| async move {
+            let response = match previous_result_id {
+                Some(previous_result_id) => language_server
+                    .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                        lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?
+                    .map(|response| match response {
+                        lsp::SemanticTokensFullDeltaResult::Tokens(tokens) => {
+                            SemanticTokensResponse::Full(tokens)
+                        }
+                        lsp::SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                            SemanticTokensResponse::Delta(delta)
+                        }
+                        lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+                            SemanticTokensResponse::Delta(lsp::SemanticTokensDelta {
+                                result_id: None,
+                                edits,
+                            })
+                        }
+                    }),
+                None => language_server
+                    .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await?
+                    .map(|response| match response {
+                        lsp::SemanticTokensResult::Tokens(tokens) => {
+                            SemanticTokensResponse::Full(tokens)
+                        }
+                        lsp::SemanticTokensResult::Partial(partial) => {
+                            SemanticTokensResponse::Full(lsp::SemanticTokens {
+                                result_id: None,
+                                data: partial.data,
+                            })
+                        }
+                    }),
+            };
+
+            this.update(&mut cx, |this, _| {
+                let buffer_tokens = this.semantic_tokens.entry(buffer_id).or_default();
+                let tokens: &LspSemanticTokens = match response {
+                    Some(SemanticTokensResponse::Full(tokens)) => {
+                        buffer_tokens.insert(server_id, LspSemanticTokens::new(tokens));
+                        &buffer_tokens[&server_id]
+                    }
+                    Some(SemanticTokensResponse::Delta(delta)) => {
+                        let tokens = buffer_tokens
+                            .get_mut(&server_id)
+                            .ok_or_else(|| anyhow!("no semantic tokens to apply a delta to"))?;
+                        if let Err(error) = tokens.apply_delta(delta) {
+                            // Request all of the tokens the next time.
+                            buffer_tokens.remove(&server_id);
+                            return Err(error);
+                        }
+                        tokens
+                    }
+                    None => {
+                        buffer_tokens.remove(&server_id);
+                        return Ok(None);
+                    }
+                };
+                Ok(Some(tokens.decode(&legend, &snapshot)))
+            })?
+        })
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn search(
         &self,
@@ -6121,7 +7134,7 @@ impl Project {
             if let (Some(file), Some(language_server)) = (file, language_server) {
                 let lsp_params = request.to_lsp(&file.abs_path(cx), buffer, &language_server, cx);
                 return cx.spawn(move |this, cx| async move {
-                    if !request.check_capabilities(language_server.capabilities()) {
+                    if !request.check_capabilities(&language_server.capabilities()) {
                         return Ok(Default::default());
                     }
 
@@ -6353,10 +7366,13 @@ impl Project {
             }
         }
         servers_to_remove.retain(|server_id, _| !servers_to_preserve.contains(server_id));
+        self.language_server_project_roots
+            .retain(|(worktree_id, _), _| *worktree_id != id_to_remove);
         for (server_id_to_remove, server_name) in servers_to_remove {
             self.language_server_ids
                 .remove(&(id_to_remove, server_name));
             self.language_server_statuses.remove(&server_id_to_remove);
+            self.pulled_diagnostics.forget_server(server_id_to_remove);
             self.last_workspace_edits_by_language_server
                 .remove(&server_id_to_remove);
             self.language_servers.remove(&server_id_to_remove);
@@ -6609,9 +7625,6 @@ impl Project {
                         changes: changes
                             .iter()
                             .filter_map(|(path, _, change)| {
-                                if !watched_paths.is_match(&path) {
-                                    return None;
-                                }
                                 let typ = match change {
                                     PathChange::Loaded => return None,
                                     PathChange::Added => lsp::FileChangeType::CREATED,
@@ -6619,6 +7632,9 @@ impl Project {
                                     PathChange::Updated => lsp::FileChangeType::CHANGED,
                                     PathChange::AddedOrUpdated => lsp::FileChangeType::CHANGED,
                                 };
+                                if !watched_paths.is_match(&path, typ) {
+                                    return None;
+                                }
                                 Some(lsp::FileEvent {
                                     uri: lsp::Url::from_file_path(abs_path.join(path)).unwrap(),
                                     typ,
@@ -7310,6 +8326,7 @@ impl Project {
                         LanguageServerProgress {
                             message: payload.message,
                             percentage: payload.percentage.map(|p| p as usize),
+                            is_cancellable: false,
                             last_update_at: Instant::now(),
                         },
                         cx,
@@ -7323,6 +8340,7 @@ impl Project {
                         LanguageServerProgress {
                             message: payload.message,
                             percentage: payload.percentage.map(|p| p as usize),
+                            is_cancellable: false,
                             last_update_at: Instant::now(),
                         },
                         cx,
@@ -8637,6 +9655,23 @@ impl Project {
         }
     }
 
+    /// The language servers that have finished starting, along with the worktree and the
+    /// language they were started for.
+    pub fn running_language_servers(
+        &self,
+    ) -> impl '_ + Iterator<Item = (WorktreeId, Arc<Language>, Arc<LanguageServer>)> {
+        self.language_server_ids
+            .iter()
+            .filter_map(|((worktree_id, _), server_id)| {
+                match self.language_servers.get(server_id)? {
+                    LanguageServerState::Starting(_) => None,
+                    LanguageServerState::Running {
+                        language, server, ..
+                    } => Some((*worktree_id, language.clone(), server.clone())),
+                }
+            })
+    }
+
     pub fn language_servers(
         &self,
     ) -> impl '_ + Iterator<Item = (LanguageServerId, LanguageServerName, WorktreeId)> {
@@ -8760,6 +9795,40 @@ fn subscribe_for_copilot_events(
     )
 }
 
+/// Converts the pattern of a file watcher into a glob relative to the worktree at the given
+/// path. Absolute patterns and relative patterns with a base inside the worktree only match
+/// paths in that worktree, while other patterns, such as `**/*.go`, match paths in any of them.
+fn glob_pattern_relative_to(
+    pattern: &lsp::GlobPattern,
+    worktree_abs_path: &Path,
+) -> Option<String> {
+    match pattern {
+        lsp::GlobPattern::String(pattern) => {
+            let path = Path::new(pattern);
+            if path.is_absolute() {
+                let relative_path = path.strip_prefix(worktree_abs_path).ok()?;
+                Some(relative_path.to_str()?.to_string())
+            } else {
+                Some(pattern.clone())
+            }
+        }
+        lsp::GlobPattern::Relative(pattern) => {
+            let base_uri = match &pattern.base_uri {
+                lsp::OneOf::Left(workspace_folder) => &workspace_folder.uri,
+                lsp::OneOf::Right(base_uri) => base_uri,
+            };
+            let base_path = base_uri.to_file_path().ok()?;
+            let relative_base_path = base_path.strip_prefix(worktree_abs_path).ok()?;
+            Some(
+                relative_base_path
+                    .join(&pattern.pattern)
+                    .to_str()?
+                    .to_string(),
+            )
+        }
+    }
+}
+
 fn glob_literal_prefix<'a>(glob: &'a str) -> &'a str {
     let mut literal_end = 0;
     for (i, part) in glob.split(path::MAIN_SEPARATOR).enumerate() {
@@ -8792,6 +9861,26 @@ impl WorktreeHandle {
     }
 }
 
+/// Estimates the memory held by a project's open buffers from the length of their text.
+struct BufferStoreAccounting(WeakModel<Project>);
+
+impl MemoryAccounting for BufferStoreAccounting {
+    fn name(&self) -> &'static str {
+        "buffer_store"
+    }
+
+    fn estimated_size_in_bytes(&self, cx: &AppContext) -> Option<u64> {
+        let project = self.0.upgrade()?;
+        let size = project
+            .read(cx)
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| Some(buffer.upgrade()?.read(cx).len() as u64))
+            .sum();
+        Some(size)
+    }
+}
+
 impl OpenBuffer {
     pub fn upgrade(&self) -> Option<Model<Buffer>> {
         match self {
@@ -8873,23 +9962,86 @@ impl<P: AsRef<Path>> From<(WorktreeId, P)> for ProjectPath {
 }
 
 impl ProjectLspAdapterDelegate {
-    fn new(project: &Project, cx: &ModelContext<Project>) -> Arc<Self> {
+    fn new(
+        project: &Project,
+        language: Arc<Language>,
+        pinned_server_version: Option<String>,
+        prefer_system_binary: bool,
+        cx: &ModelContext<Project>,
+    ) -> Arc<Self> {
+        // Adapters show notifications while fetching their servers in the background, so
+        // they are forwarded to the project until the delegate is dropped.
+        let (notifications_tx, mut notifications_rx) = mpsc::unbounded::<String>();
+        cx.spawn(|this, mut cx| async move {
+            while let Some(message) = notifications_rx.next().await {
+                this.update(&mut cx, |_, cx| cx.emit(Event::Notification(message)))
+                    .ok();
+            }
+        })
+        .detach();
+
+        let http_client = match ProjectSettings::get_global(cx).proxy.as_deref() {
+            Some(proxy) => match proxy.parse() {
+                Ok(proxy) => util::http::client_with_proxy(Some(proxy)),
+                Err(error) => {
+                    log::error!("invalid proxy {proxy:?}: {error}");
+                    project.client.http_client()
+                }
+            },
+            None => project.client.http_client(),
+        };
+        let http_client = match ProjectSettings::get_global(cx).download_mirror.clone() {
+            Some(mirror) => match MirroredHttpClient::new(http_client.clone(), mirror) {
+                Ok(client) => client,
+                Err(error) => {
+                    log::error!("invalid download mirror: {error}");
+                    http_client
+                }
+            },
+            None => http_client,
+        };
+
         Arc::new(Self {
-            project: cx.handle(),
-            http_client: project.client.http_client(),
+            notifications_tx,
+            http_client,
+            pinned_server_version,
+            prefer_system_binary,
+            languages: project.languages.clone(),
+            language,
         })
     }
 }
 
+#[async_trait]
 impl LspAdapterDelegate for ProjectLspAdapterDelegate {
-    fn show_notification(&self, message: &str, cx: &mut AppContext) {
-        self.project
-            .update(cx, |_, cx| cx.emit(Event::Notification(message.to_owned())));
+    fn show_notification(&self, message: &str) {
+        self.notifications_tx
+            .unbounded_send(message.to_owned())
+            .ok();
     }
 
     fn http_client(&self) -> Arc<dyn HttpClient> {
         self.http_client.clone()
     }
+
+    fn pinned_server_version(&self) -> Option<String> {
+        self.pinned_server_version.clone()
+    }
+
+    fn prefers_system_binary(&self) -> bool {
+        self.prefer_system_binary
+    }
+
+    fn report_install_progress(&self, progress: InstallProgress) {
+        self.languages
+            .report_install_progress(self.language.clone(), progress);
+    }
+
+    async fn which(&self, name: &str) -> Option<PathBuf> {
+        // Language servers are only fetched once the login shell's environment has been
+        // loaded into Zed's own.
+        find_executable(name, std::env::var_os("PATH").as_deref(), &[]).await
+    }
 }
 
 fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
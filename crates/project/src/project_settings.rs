@@ -1,16 +1,24 @@
 use collections::HashMap;
 use gpui::AppContext;
+use lsp::{LanguageServerBinary, LanguageServerSocket};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
+use util::ResultExt;
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectSettings {
     /// Configuration for language servers.
     ///
     /// The following settings can be overridden for specific language servers:
+    /// - enable
+    /// - binary
+    /// - env
     /// - initialization_options
+    /// - prefer_system_binary
+    /// - settings
+    /// - version
     /// To override settings for a language, add an entry for that language server's
     /// name to the lsp value.
     /// Default: null
@@ -34,6 +42,23 @@ pub struct ProjectSettings {
     /// ]
     #[serde(default)]
     pub file_scan_exclusions: Option<Vec<String>>,
+
+    /// The proxy to download language servers through, such as `http://proxy:8080` or
+    /// `socks5://localhost:1080`. When unset, the standard `HTTPS_PROXY`, `ALL_PROXY` and
+    /// `NO_PROXY` environment variables are used.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub proxy: Option<String>,
+
+    /// A mirror to download language servers from GitHub through, for networks that GitHub
+    /// can't be reached from. In this URL, `{url}` is replaced with the URL being
+    /// downloaded, `{host}` with its host, and `{path}` with its path and query, like
+    /// `https://mirror.example.com/{url}` or `https://mirror.example.com/{host}{path}`.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub download_mirror: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -55,10 +80,107 @@ pub enum GitGutterSetting {
     Hide,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct LspSettings {
+    /// Whether to start the language server, for languages that have it.
+    ///
+    /// Default: true
+    pub enable: Option<bool>,
+    /// An existing executable to run instead of the language server Zed installs.
+    pub binary: Option<LspBinarySettings>,
+    /// The version of the language server to install instead of its latest release.
+    ///
+    /// Default: null
+    pub version: Option<String>,
+    /// Whether to run a copy of the language server that's already installed, e.g. one on
+    /// the `PATH`, instead of installing one. Zed installs its own copy when none is found.
+    ///
+    /// Default: false
+    pub prefer_system_binary: Option<bool>,
+    /// Environment variables to set for the language server, like `JAVA_HOME` or
+    /// `GOFLAGS`, in whose values `~` and environment variables are expanded. These are
+    /// merged into the ones the language server's adapter sets.
+    ///
+    /// Default: null
+    pub env: Option<HashMap<String, String>>,
+    /// Options sent to the language server when it starts, merged into the ones Zed sends.
+    /// These can also be set in a project's settings.
+    ///
+    /// Default: null
     pub initialization_options: Option<serde_json::Value>,
+    /// Configuration sent to the language server whenever it asks for it, merged into
+    /// the configuration Zed sends. Unlike `initialization_options`, changing this
+    /// doesn't restart the language server. These can also be set in a project's settings.
+    ///
+    /// Default: null
+    pub settings: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct LspBinarySettings {
+    /// The path to the executable, in which `~` and environment variables are expanded.
+    pub path: Option<String>,
+    /// The arguments to launch the executable with.
+    ///
+    /// Default: []
+    pub arguments: Option<Vec<String>>,
+    /// The socket to talk to the language server over instead of its stdio, either a TCP
+    /// address like `127.0.0.1:9257` or `unix:` followed by the path of a Unix socket.
+    /// Without a `path`, the language server is expected to be running already.
+    pub socket: Option<String>,
+}
+
+impl ProjectSettings {
+    /// Whether the language server with the given name is started for the languages that
+    /// have it.
+    pub fn is_language_server_enabled(&self, server_name: &str) -> bool {
+        self.lsp
+            .get(server_name)
+            .and_then(|settings| settings.enable)
+            .unwrap_or(true)
+    }
+}
+
+impl LspSettings {
+    /// The environment variables to set for the language server, with their values expanded.
+    pub fn env(&self) -> Option<HashMap<String, String>> {
+        let env = self.env.as_ref()?;
+        Some(
+            env.iter()
+                .filter_map(|(name, value)| {
+                    let value = shellexpand::full(value).log_err()?;
+                    Some((name.clone(), value.into_owned()))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl LspBinarySettings {
+    /// The binary to launch, which is only overridden once a path or socket is configured.
+    pub fn binary(&self) -> Option<LanguageServerBinary> {
+        let socket = match &self.socket {
+            Some(socket) => Some(socket.parse::<LanguageServerSocket>().log_err()?),
+            None => None,
+        };
+        let path = match &self.path {
+            Some(path) => PathBuf::from(shellexpand::full(path).log_err()?.as_ref()),
+            None if socket.is_some() => PathBuf::new(),
+            None => return None,
+        };
+        Some(LanguageServerBinary {
+            path,
+            arguments: self
+                .arguments
+                .iter()
+                .flatten()
+                .map(|argument| argument.into())
+                .collect(),
+            socket,
+            env: None,
+        })
+    }
 }
 
 impl Settings for ProjectSettings {
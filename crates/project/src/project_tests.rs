@@ -3,7 +3,10 @@ use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::AppContext;
 use language::{
-    language_settings::{AllLanguageSettings, LanguageSettingsContent},
+    language_settings::{
+        AllLanguageSettings, LanguageServerMergeStrategy, LanguageServerMergingSettings,
+        LanguageSettingsContent,
+    },
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
     LineEnding, OffsetRangeExt, Point, ToPoint,
 };
@@ -730,6 +733,228 @@ async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppCon
     );
 }
 
+#[gpui::test]
+async fn test_language_server_watched_file_registrations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "Cargo.toml": "",
+            "src": {
+                "a.rs": "",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    project.update(cx, |project, _| {
+        project.languages.add(Arc::new(language));
+    });
+    cx.executor().run_until_parked();
+    let _buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/src/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    // Register a glob without a base, which applies to every worktree, for created and
+    // deleted files only, and a glob relative to a directory of the worktree.
+    let fake_server = fake_servers.next().await.unwrap();
+    let register = |id: &str, watcher: lsp::FileSystemWatcher| {
+        fake_server.request::<lsp::request::RegisterCapability>(lsp::RegistrationParams {
+            registrations: vec![lsp::Registration {
+                id: id.to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+                register_options: serde_json::to_value(
+                    lsp::DidChangeWatchedFilesRegistrationOptions {
+                        watchers: vec![watcher],
+                    },
+                )
+                .ok(),
+            }],
+        })
+    };
+    register(
+        "toml-files",
+        lsp::FileSystemWatcher {
+            glob_pattern: lsp::GlobPattern::String("**/*.toml".to_string()),
+            kind: Some(lsp::WatchKind::Create | lsp::WatchKind::Delete),
+        },
+    )
+    .await
+    .unwrap();
+    register(
+        "source-files",
+        lsp::FileSystemWatcher {
+            glob_pattern: lsp::GlobPattern::Relative(lsp::RelativePattern {
+                base_uri: lsp::OneOf::Right(Url::from_file_path("/the-root/src").unwrap()),
+                pattern: "*.rs".to_string(),
+            }),
+            kind: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let file_changes = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_notification::<lsp::notification::DidChangeWatchedFiles, _>({
+        let file_changes = file_changes.clone();
+        move |params, _| {
+            let mut file_changes = file_changes.lock();
+            file_changes.extend(params.changes);
+            file_changes.sort_by(|a, b| a.uri.cmp(&b.uri));
+        }
+    });
+    cx.executor().run_until_parked();
+
+    fs.save(
+        "/the-root/Cargo.toml".as_ref(),
+        &"[package]".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    fs.create_file("/the-root/src/b.toml".as_ref(), Default::default())
+        .await
+        .unwrap();
+    fs.create_file("/the-root/src/b.rs".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        mem::take(&mut *file_changes.lock()),
+        &[
+            lsp::FileEvent {
+                uri: lsp::Url::from_file_path("/the-root/src/b.rs").unwrap(),
+                typ: lsp::FileChangeType::CREATED,
+            },
+            lsp::FileEvent {
+                uri: lsp::Url::from_file_path("/the-root/src/b.toml").unwrap(),
+                typ: lsp::FileChangeType::CREATED,
+            },
+        ]
+    );
+
+    // After a watcher is unregistered, changes to its files are no longer reported.
+    fake_server
+        .request::<lsp::request::UnregisterCapability>(lsp::UnregistrationParams {
+            unregisterations: vec![lsp::Unregistration {
+                id: "source-files".to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+            }],
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    fs.remove_file("/the-root/src/b.rs".as_ref(), Default::default())
+        .await
+        .unwrap();
+    fs.remove_file("/the-root/src/b.toml".as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        mem::take(&mut *file_changes.lock()),
+        &[lsp::FileEvent {
+            uri: lsp::Url::from_file_path("/the-root/src/b.toml").unwrap(),
+            typ: lsp::FileChangeType::DELETED,
+        }]
+    );
+}
+
+#[gpui::test]
+async fn test_dynamic_capability_registration(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/the-root", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    project.update(cx, |project, _| {
+        project.languages.add(Arc::new(language));
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert!(buffer.completion_triggers().is_empty())
+    });
+
+    // The server provides completions only after registering them.
+    fake_server
+        .request::<lsp::request::RegisterCapability>(lsp::RegistrationParams {
+            registrations: vec![lsp::Registration {
+                id: "completions".to_string(),
+                method: "textDocument/completion".to_string(),
+                register_options: serde_json::to_value(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+                    ..Default::default()
+                })
+                .ok(),
+            }],
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.completion_triggers(), &[".", ":"])
+    });
+
+    fake_server
+        .request::<lsp::request::UnregisterCapability>(lsp::UnregistrationParams {
+            unregisterations: vec![lsp::Unregistration {
+                id: "completions".to_string(),
+                method: "textDocument/completion".to_string(),
+            }],
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert!(buffer.completion_triggers().is_empty())
+    });
+}
+
 #[gpui::test]
 async fn test_single_file_worktrees_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1177,6 +1402,140 @@ async fn test_restarting_server_with_diagnostics_running(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_cancelling_language_server_work(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter::default()))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let cancelled_tokens = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_notification::<lsp::notification::WorkDoneProgressCancel, _>({
+        let cancelled_tokens = cancelled_tokens.clone();
+        move |params, _| cancelled_tokens.lock().push(params.token)
+    });
+
+    for (token, cancellable) in [("indexing", true), ("checking", false)] {
+        fake_server
+            .request::<lsp::request::WorkDoneProgressCreate>(lsp::WorkDoneProgressCreateParams {
+                token: lsp::NumberOrString::String(token.into()),
+            })
+            .await
+            .unwrap();
+        fake_server.notify::<lsp::notification::Progress>(lsp::ProgressParams {
+            token: lsp::NumberOrString::String(token.into()),
+            value: lsp::ProgressParamsValue::WorkDone(lsp::WorkDoneProgress::Begin(
+                lsp::WorkDoneProgressBegin {
+                    title: token.into(),
+                    cancellable: Some(cancellable),
+                    ..Default::default()
+                },
+            )),
+        });
+    }
+    // A report that doesn't mention cancellation keeps the work cancellable.
+    fake_server.notify::<lsp::notification::Progress>(lsp::ProgressParams {
+        token: lsp::NumberOrString::String("indexing".into()),
+        value: lsp::ProgressParamsValue::WorkDone(lsp::WorkDoneProgress::Report(
+            lsp::WorkDoneProgressReport {
+                percentage: Some(50),
+                ..Default::default()
+            },
+        )),
+    });
+    cx.executor().run_until_parked();
+
+    project.update(cx, |project, cx| {
+        let (language_server_id, status) = project.language_server_statuses().next().unwrap();
+        assert!(status.pending_work["indexing"].is_cancellable);
+        assert_eq!(status.pending_work["indexing"].percentage, Some(50));
+        assert!(!status.pending_work["checking"].is_cancellable);
+
+        project.cancel_language_server_work(language_server_id, "indexing".into(), cx);
+        project.cancel_language_server_work(language_server_id, "checking".into(), cx);
+    });
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        *cancelled_tokens.lock(),
+        [lsp::NumberOrString::String("indexing".into())]
+    );
+}
+
+#[gpui::test]
+async fn test_closing_labels_as_inlay_hints(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            path_suffixes: vec!["dart".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter::default()))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.dart": "Column(\n  children: [],\n)" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.dart", cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.notify::<closing_labels::PublishClosingLabels>(
+        closing_labels::PublishClosingLabelsParams {
+            uri: Url::from_file_path("/dir/a.dart").unwrap(),
+            labels: vec![closing_labels::LspClosingLabel {
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(2, 1)),
+                label: "Column".to_string(),
+            }],
+        },
+    );
+    cx.executor().run_until_parked();
+
+    let hints = project
+        .update(cx, |project, cx| {
+            let len = buffer.read(cx).len();
+            project.inlay_hints(buffer.clone(), 0..len, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        hints.iter().map(|hint| hint.text()).collect::<Vec<_>>(),
+        ["// Column"]
+    );
+}
+
 #[gpui::test]
 async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1444,6 +1803,276 @@ async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
         .await;
 }
 
+#[gpui::test]
+async fn test_toggling_individual_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_rust_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+
+    let _rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_rust_server_1 = fake_rust_servers.next().await.unwrap();
+
+    // Disable the language server by name, leaving the language's other servers alone.
+    let set_enabled = |enable: bool, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            cx.update_global(|settings: &mut SettingsStore, cx| {
+                settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                    settings.lsp.insert(
+                        Arc::from("rust-lsp"),
+                        LspSettings {
+                            enable: Some(enable),
+                            ..Default::default()
+                        },
+                    );
+                });
+            })
+        });
+    };
+    set_enabled(false, cx);
+    fake_rust_server_1
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+
+    set_enabled(true, cx);
+    let mut fake_rust_server_2 = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server_2
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/a.rs"
+    );
+}
+
+#[gpui::test]
+async fn test_project_specific_language_server_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let initialization_options = Arc::new(Mutex::new(None));
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_rust_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            initializer: Some(Box::new({
+                let initialization_options = initialization_options.clone();
+                move |fake_server| {
+                    let initialization_options = initialization_options.clone();
+                    fake_server.handle_request::<lsp::request::Initialize, _, _>(
+                        move |params, _| {
+                            *initialization_options.lock() = params.initialization_options;
+                            async move { Ok(Default::default()) }
+                        },
+                    );
+                }
+            })),
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "lsp": {
+                        "rust-lsp": {
+                            "initialization_options": { "a": 1 },
+                            "settings": { "b": 2 }
+                        }
+                    }
+                }"#
+            },
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+    cx.executor().run_until_parked();
+
+    let _rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidChangeConfiguration>()
+            .await
+            .settings,
+        json!({ "b": 2 })
+    );
+    assert_eq!(*initialization_options.lock(), Some(json!({ "a": 1 })));
+}
+
+#[gpui::test]
+async fn test_stopping_and_restarting_a_language_server(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_rust_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "", "b.rs": "" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+
+    let rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+    let server_id = project.update(cx, |project, _| {
+        let (_, _, server) = project.running_language_servers().next().unwrap();
+        server.server_id()
+    });
+
+    project
+        .update(cx, |project, cx| {
+            project.stop_language_server_with_id(server_id, cx)
+        })
+        .await;
+    fake_rust_server
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+
+    // A stopped server isn't started for newly-opened buffers.
+    let _other_rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(fake_rust_servers.try_next().is_err());
+
+    project.update(cx, |project, cx| {
+        project.restart_language_servers_for_buffers([rs_buffer], cx);
+    });
+    fake_rust_server = fake_rust_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+    let new_server_id = project.update(cx, |project, _| {
+        let (_, _, server) = project.running_language_servers().next().unwrap();
+        server.server_id()
+    });
+    assert_ne!(new_server_id, server_id);
+
+    project.update(cx, |project, cx| {
+        project.restart_language_server(new_server_id, cx);
+    });
+    fake_rust_server
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+    fake_rust_servers.next().await.unwrap();
+}
+
+#[gpui::test]
+async fn test_restarting_crashed_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_rust_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() {}" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+
+    let _rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+
+    // Dropping a fake server closes its output, as if its process had exited.
+    for crash_count in 1..=MAX_SERVER_CRASH_RESTART_COUNT {
+        drop(fake_rust_server);
+        cx.executor().run_until_parked();
+        assert!(fake_rust_servers.try_next().is_err());
+
+        cx.executor()
+            .advance_clock(SERVER_CRASH_RESTART_DELAY * 2u32.pow(crash_count - 1));
+        fake_rust_server = fake_rust_servers.next().await.unwrap();
+        assert_eq!(
+            fake_rust_server
+                .receive_notification::<lsp::notification::DidOpenTextDocument>()
+                .await
+                .text_document
+                .text,
+            "fn a() {}"
+        );
+    }
+
+    // The server isn't restarted again once it has crashed too many times in a row.
+    drop(fake_rust_server);
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(60 * 60));
+    cx.executor().run_until_parked();
+    assert!(fake_rust_servers.try_next().is_err());
+    project.update(cx, |project, _| {
+        assert_eq!(project.language_servers().count(), 0);
+    });
+}
+
 #[gpui::test(iterations = 3)]
 async fn test_transforming_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1799,6 +2428,122 @@ async fn test_empty_diagnostic_ranges(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_pulling_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "let one = ;" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    let mut requests =
+        fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+            let previous_result_ids = previous_result_ids.clone();
+            move |params, _| {
+                let previous_result_id = params.previous_result_id;
+                previous_result_ids.lock().push(previous_result_id.clone());
+                async move {
+                    let report = match previous_result_id {
+                        Some(result_id) => lsp::DocumentDiagnosticReport::Unchanged(
+                            lsp::RelatedUnchangedDocumentDiagnosticReport {
+                                related_documents: None,
+                                unchanged_document_diagnostic_report:
+                                    lsp::UnchangedDocumentDiagnosticReport { result_id },
+                            },
+                        ),
+                        None => lsp::DocumentDiagnosticReport::Full(
+                            lsp::RelatedFullDocumentDiagnosticReport {
+                                related_documents: None,
+                                full_document_diagnostic_report:
+                                    lsp::FullDocumentDiagnosticReport {
+                                        result_id: Some("the-result-id".into()),
+                                        items: vec![lsp::Diagnostic {
+                                            range: lsp::Range::new(
+                                                lsp::Position::new(0, 10),
+                                                lsp::Position::new(0, 11),
+                                            ),
+                                            severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                            message: "syntax error".to_string(),
+                                            ..Default::default()
+                                        }],
+                                    },
+                            },
+                        ),
+                    };
+                    Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+                }
+            }
+        });
+
+    // The diagnostics are pulled once the buffer is opened in the server...
+    cx.executor().advance_clock(Duration::from_secs(1));
+    requests.next().await.unwrap();
+    cx.executor().run_until_parked();
+    let expected_chunks = [("let one = ", None), (";", Some(DiagnosticSeverity::ERROR))];
+    buffer.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            expected_chunks
+        );
+    });
+
+    // ...and again after it's edited, when the server can report that they haven't changed.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    requests.next().await.unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        *previous_result_ids.lock(),
+        [None, Some("the-result-id".to_string())]
+    );
+    buffer.update(cx, |buffer, _| {
+        let chunks = chunks_with_diagnostics(buffer, 0..buffer.len());
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|(s, d)| (s.as_str(), *d))
+                .collect::<Vec<_>>(),
+            [
+                ("\nlet one = ", None),
+                (";", Some(DiagnosticSeverity::ERROR)),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2343,6 +3088,213 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() { crate::a() }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let item = |name: &str, path: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let a_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    let b_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    let call_range = lsp::Range::new(lsp::Position::new(0, 16), lsp::Position::new(0, 17));
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let a = item("a", "/dir/a.rs", a_range);
+        move |params, _| {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 3)
+            );
+            let a = a.clone();
+            async move { Ok(Some(vec![a])) }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let b = item("b", "/dir/b.rs", b_range);
+        move |params, _| {
+            assert_eq!(params.item.name, "a");
+            let b = b.clone();
+            async move {
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: b,
+                    from_ranges: vec![call_range],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(0, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        ["a"]
+    );
+
+    let calls = project
+        .update(cx, |project, cx| {
+            project.call_hierarchy_calls(&items[0], CallHierarchyDirection::Incoming, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name, "b");
+    // The caller's buffer is opened, and the calls are made in it.
+    let caller_location = &calls[0].item.location;
+    assert_eq!(calls[0].call_locations.len(), 1);
+    assert_eq!(calls[0].call_locations[0].buffer, caller_location.buffer);
+    caller_location.buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "fn b() { crate::a() }");
+        assert_eq!(caller_location.range.to_offset(buffer), 3..4);
+        assert_eq!(calls[0].call_locations[0].range.to_offset(buffer), 16..17);
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    use crate::type_hierarchy::{
+        LspPrepareTypeHierarchy, LspTypeHierarchyItem, LspTypeHierarchySupertypes,
+    };
+
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language.set_fake_lsp_adapter(Default::default()).await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "struct Dog; impl Animal for Dog {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let item = |name: &str, kind: lsp::SymbolKind| LspTypeHierarchyItem {
+        name: name.to_string(),
+        kind,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+        range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+        selection_range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 10)),
+        data: Some(json!({ "id": name })),
+    };
+    fake_server.handle_request::<LspPrepareTypeHierarchy, _, _>({
+        let dog = item("Dog", lsp::SymbolKind::STRUCT);
+        move |params, _| {
+            assert_eq!(params.position, lsp::Position::new(0, 8));
+            let dog = dog.clone();
+            async move { Ok(Some(vec![dog])) }
+        }
+    });
+    fake_server.handle_request::<LspTypeHierarchySupertypes, _, _>({
+        let animal = item("Animal", lsp::SymbolKind::INTERFACE);
+        move |params, _| {
+            // The item is sent back to the server as it reported it.
+            assert_eq!(params.item.data, Some(json!({ "id": "Dog" })));
+            let animal = animal.clone();
+            async move { Ok(Some(vec![animal])) }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, Point::new(0, 8), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        ["Dog"]
+    );
+    assert_eq!(items[0].location.buffer, buffer);
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(items[0].location.range.to_offset(buffer), 7..10);
+    });
+
+    let supertypes = project
+        .update(cx, |project, cx| {
+            project.type_hierarchy_related_types(&items[0], TypeHierarchyDirection::Supertypes, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        supertypes
+            .iter()
+            .map(|item| (item.name.as_str(), item.kind))
+            .collect::<Vec<_>>(),
+        [("Animal", lsp::SymbolKind::INTERFACE)]
+    );
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2506,6 +3458,82 @@ async fn test_completions_with_carriage_returns(cx: &mut gpui::TestAppContext) {
     assert_eq!(completions[0].new_text, "fully\nQualified\nName");
 }
 
+#[gpui::test]
+async fn test_primary_completions_skip_servers_without_completions(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.language_server_merging = Some(LanguageServerMergingSettings {
+                    completions: LanguageServerMergeStrategy::Primary,
+                    diagnostics: LanguageServerMergeStrategy::All,
+                    hovers: LanguageServerMergeStrategy::Primary,
+                });
+            });
+        })
+    });
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            path_suffixes: vec!["ts".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::language_typescript()),
+    );
+    let mut fake_linter_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-linter-language-server",
+            capabilities: lsp::ServerCapabilities::default(),
+            ..Default::default()
+        }))
+        .await;
+    let mut fake_completion_servers = language
+        .add_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-completion-language-server",
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.ts": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let _fake_linter_server = fake_linter_servers.next().await.unwrap();
+    let fake_completion_server = fake_completion_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), cx)
+    });
+
+    fake_completion_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName".into(),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fullyQualifiedName");
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
     init_test(cx);
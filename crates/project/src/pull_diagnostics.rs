@@ -0,0 +1,464 @@
+use crate::{File, LanguageServerState, Project};
+use collections::HashMap;
+use gpui::{Model, ModelContext, Task};
+use language::{Buffer, LocalFile as _};
+use lsp::{LanguageServer, LanguageServerId};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+use util::{post_inc, ResultExt};
+
+/// How long to wait after a buffer changes before pulling its diagnostics.
+const DOCUMENT_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// The diagnostics requests sent to the language servers that support pulling diagnostics,
+/// rather than publishing them whenever they change.
+#[derive(Default)]
+pub(crate) struct PulledDiagnostics {
+    /// The result id of the last report for each document, which lets a server respond
+    /// that the document's diagnostics haven't changed since.
+    result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
+    document_pulls: HashMap<(u64, LanguageServerId), Task<()>>,
+    workspace_pulls: HashMap<LanguageServerId, Task<()>>,
+    partial_result_tokens: HashMap<String, PartialResultTarget>,
+    next_partial_result_token: usize,
+}
+
+/// What the partial results reported with a token are for.
+enum PartialResultTarget {
+    Document {
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: i32,
+    },
+    Workspace {
+        server_id: LanguageServerId,
+    },
+}
+
+impl PartialResultTarget {
+    fn server_id(&self) -> LanguageServerId {
+        match self {
+            Self::Document { server_id, .. } | Self::Workspace { server_id } => *server_id,
+        }
+    }
+}
+
+impl PulledDiagnostics {
+    pub fn forget_server(&mut self, server_id: LanguageServerId) {
+        self.result_ids.remove(&server_id);
+        self.document_pulls.retain(|(_, id), _| *id != server_id);
+        self.workspace_pulls.remove(&server_id);
+        self.partial_result_tokens
+            .retain(|_, target| target.server_id() != server_id);
+    }
+
+    pub fn forget_buffer(&mut self, buffer_id: u64) {
+        self.document_pulls.retain(|(id, _), _| *id != buffer_id);
+    }
+
+    fn partial_result_params(&mut self, target: PartialResultTarget) -> lsp::PartialResultParams {
+        let token = format!(
+            "zed/diagnostics/{}",
+            post_inc(&mut self.next_partial_result_token)
+        );
+        self.partial_result_tokens.insert(token.clone(), target);
+        lsp::PartialResultParams {
+            partial_result_token: Some(lsp::NumberOrString::String(token)),
+        }
+    }
+
+    fn finish_request(&mut self, params: &lsp::PartialResultParams) {
+        if let Some(lsp::NumberOrString::String(token)) = &params.partial_result_token {
+            self.partial_result_tokens.remove(token);
+        }
+    }
+}
+
+/// A `$/progress` notification, which reports either the progress of some work or a
+/// partial result of a request.
+pub(crate) enum ProgressOrPartialResult {}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ProgressOrPartialResultParams {
+    pub token: lsp::ProgressToken,
+    pub value: serde_json::Value,
+}
+
+impl lsp::notification::Notification for ProgressOrPartialResult {
+    type Params = ProgressOrPartialResultParams;
+    const METHOD: &'static str = "$/progress";
+}
+
+fn diagnostic_options(server: &LanguageServer) -> Option<lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider.clone()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options)
+        }
+    }
+}
+
+impl Project {
+    fn running_language_server(&self, server_id: LanguageServerId) -> Option<&Arc<LanguageServer>> {
+        match self.language_servers.get(&server_id)? {
+            LanguageServerState::Running { server, .. } => Some(server),
+            LanguageServerState::Starting(_) => None,
+        }
+    }
+
+    /// Pulls the diagnostics of the buffer from the language server shortly, if the server
+    /// supports pulling diagnostics. Servers that don't keep publishing them instead.
+    pub(crate) fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(server) = self.running_language_server(server_id) else {
+            return;
+        };
+        let Some(options) = diagnostic_options(server) else {
+            return;
+        };
+        let identifier = options.identifier.clone();
+        let server = server.clone();
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(file) = File::from_dyn(buffer.file()).and_then(|file| file.as_local()) else {
+            return;
+        };
+        let Ok(uri) = lsp::Url::from_file_path(file.abs_path(cx)) else {
+            return;
+        };
+
+        let task = cx.spawn(move |this, mut cx| async move {
+            cx.background_executor()
+                .timer(DOCUMENT_DIAGNOSTICS_DEBOUNCE)
+                .await;
+
+            let Some((params, version)) = this
+                .update(&mut cx, |this, _| {
+                    // The server's diagnostics refer to the last version it was sent.
+                    let version = this
+                        .buffer_snapshots
+                        .get(&buffer_id)?
+                        .get(&server_id)?
+                        .last()?
+                        .version;
+                    let previous_result_id = this
+                        .pulled_diagnostics
+                        .result_ids
+                        .get(&server_id)
+                        .and_then(|result_ids| result_ids.get(&uri))
+                        .cloned();
+                    let partial_result_params = this.pulled_diagnostics.partial_result_params(
+                        PartialResultTarget::Document {
+                            server_id,
+                            uri: uri.clone(),
+                            version,
+                        },
+                    );
+                    let params = lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params,
+                    };
+                    Some((params, version))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            let partial_result_params = params.partial_result_params.clone();
+            let response = server
+                .request::<lsp::request::DocumentDiagnosticRequest>(params)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pulled_diagnostics
+                    .finish_request(&partial_result_params);
+                match response.log_err()? {
+                    lsp::DocumentDiagnosticReportResult::Report(report) => {
+                        this.apply_document_diagnostic_report(
+                            server_id,
+                            uri,
+                            Some(version),
+                            report,
+                            cx,
+                        );
+                    }
+                    lsp::DocumentDiagnosticReportResult::Partial(partial) => {
+                        this.apply_related_diagnostic_reports(
+                            server_id,
+                            partial.related_documents.into_iter().flatten(),
+                            cx,
+                        );
+                    }
+                }
+                Some(())
+            })
+            .ok();
+        });
+        self.pulled_diagnostics
+            .document_pulls
+            .insert((buffer_id, server_id), task);
+    }
+
+    /// Pulls the diagnostics of the whole workspace from the language server, if it
+    /// supports pulling them.
+    pub(crate) fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(server) = self.running_language_server(server_id) else {
+            return;
+        };
+        let Some(options) =
+            diagnostic_options(server).filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        let identifier = options.identifier.clone();
+        let server = server.clone();
+
+        let previous_result_ids = self
+            .pulled_diagnostics
+            .result_ids
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .map(|(uri, result_id)| lsp::PreviousResultId {
+                uri: uri.clone(),
+                value: result_id.clone(),
+            })
+            .collect();
+        let partial_result_params = self
+            .pulled_diagnostics
+            .partial_result_params(PartialResultTarget::Workspace { server_id });
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier,
+            previous_result_ids,
+            work_done_progress_params: Default::default(),
+            partial_result_params: partial_result_params.clone(),
+        };
+
+        let task = cx.spawn(move |this, mut cx| async move {
+            let response = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.pulled_diagnostics
+                    .finish_request(&partial_result_params);
+                let items = match response.log_err()? {
+                    lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                    lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+                };
+                this.apply_workspace_diagnostic_reports(server_id, items, cx);
+                Some(())
+            })
+            .ok();
+        });
+        self.pulled_diagnostics
+            .workspace_pulls
+            .insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of every open buffer and of the workspace again, e.g. when the
+    /// server asks for them to be refreshed.
+    pub(crate) fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| {
+                self.buffer_snapshots
+                    .get(&buffer.read(cx).remote_id())
+                    .map_or(false, |snapshots| snapshots.contains_key(&server_id))
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(&buffer, server_id, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Applies a partial result of a diagnostics request. Returns false if the token isn't
+    /// one of a diagnostics request.
+    pub(crate) fn on_partial_diagnostics(
+        &mut self,
+        token: &lsp::ProgressToken,
+        value: serde_json::Value,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        let lsp::NumberOrString::String(token) = token else {
+            return false;
+        };
+        let Some(target) = self.pulled_diagnostics.partial_result_tokens.get(token) else {
+            return false;
+        };
+
+        match target {
+            PartialResultTarget::Document {
+                server_id,
+                uri,
+                version,
+            } => {
+                let (server_id, uri, version) = (*server_id, uri.clone(), *version);
+                // The first partial result is the report of the document itself, and the
+                // rest are reports of related documents.
+                if let Ok(report) =
+                    serde_json::from_value::<lsp::DocumentDiagnosticReport>(value.clone())
+                {
+                    self.apply_document_diagnostic_report(
+                        server_id,
+                        uri,
+                        Some(version),
+                        report,
+                        cx,
+                    );
+                } else if let Some(partial) =
+                    serde_json::from_value::<lsp::DocumentDiagnosticReportPartialResult>(value)
+                        .log_err()
+                {
+                    self.apply_related_diagnostic_reports(
+                        server_id,
+                        partial.related_documents.into_iter().flatten(),
+                        cx,
+                    );
+                }
+            }
+            PartialResultTarget::Workspace { server_id } => {
+                let server_id = *server_id;
+                if let Some(partial) =
+                    serde_json::from_value::<lsp::WorkspaceDiagnosticReportPartialResult>(value)
+                        .log_err()
+                {
+                    self.apply_workspace_diagnostic_reports(server_id, partial.items, cx);
+                }
+            }
+        }
+        true
+    }
+
+    fn apply_document_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReport,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let (report, related_documents) = match report {
+            lsp::DocumentDiagnosticReport::Full(report) => (
+                lsp::DocumentDiagnosticReportKind::Full(report.full_document_diagnostic_report),
+                report.related_documents,
+            ),
+            lsp::DocumentDiagnosticReport::Unchanged(report) => (
+                lsp::DocumentDiagnosticReportKind::Unchanged(
+                    report.unchanged_document_diagnostic_report,
+                ),
+                report.related_documents,
+            ),
+        };
+        self.apply_diagnostic_report(server_id, uri, version, report, cx);
+        self.apply_related_diagnostic_reports(
+            server_id,
+            related_documents.into_iter().flatten(),
+            cx,
+        );
+    }
+
+    fn apply_related_diagnostic_reports(
+        &mut self,
+        server_id: LanguageServerId,
+        reports: impl IntoIterator<Item = (lsp::Url, lsp::DocumentDiagnosticReportKind)>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        for (uri, report) in reports {
+            self.apply_diagnostic_report(server_id, uri, None, report, cx);
+        }
+    }
+
+    fn apply_workspace_diagnostic_reports(
+        &mut self,
+        server_id: LanguageServerId,
+        reports: Vec<lsp::WorkspaceDocumentDiagnosticReport>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        for report in reports {
+            let (uri, version, report) = match report {
+                lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                    report.uri,
+                    report.version,
+                    lsp::DocumentDiagnosticReportKind::Full(report.full_document_diagnostic_report),
+                ),
+                lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                    report.uri,
+                    report.version,
+                    lsp::DocumentDiagnosticReportKind::Unchanged(
+                        report.unchanged_document_diagnostic_report,
+                    ),
+                ),
+            };
+            // Servers report the version of the documents that are open.
+            let version = version.and_then(|version| i32::try_from(version).ok());
+            self.apply_diagnostic_report(server_id, uri, version, report, cx);
+        }
+    }
+
+    fn apply_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let result_ids = self
+            .pulled_diagnostics
+            .result_ids
+            .entry(server_id)
+            .or_default();
+        let diagnostics = match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                match report.result_id {
+                    Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                    None => result_ids.remove(&uri),
+                };
+                report.items
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                result_ids.insert(uri, report.result_id);
+                return;
+            }
+        };
+
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let adapter = adapter.clone();
+        let mut params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        adapter.process_diagnostics(&mut params);
+        self.update_diagnostics(
+            server_id,
+            params,
+            &adapter.disk_based_diagnostic_sources,
+            cx,
+        )
+        .log_err();
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{anyhow, Result};
+use language::{Bias, PointUtf16, Unclipped};
+use std::{ops::Range, sync::Arc};
+use text::{Anchor, BufferSnapshot};
+
+/// A range of a buffer that a language server classified, e.g. as a mutable variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    /// The type of the token, such as `function` or `parameter`.
+    pub token_type: Arc<str>,
+    /// The modifiers of the token, such as `deprecated` or `readonly`.
+    pub modifiers: Vec<Arc<str>>,
+}
+
+/// A language server's response to a semantic tokens request.
+pub(crate) enum SemanticTokensResponse {
+    Full(lsp::SemanticTokens),
+    Delta(lsp::SemanticTokensDelta),
+}
+
+/// The semantic tokens a language server last reported for a buffer, which its next
+/// response can be a delta against.
+pub(crate) struct LspSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<lsp::SemanticToken>,
+}
+
+impl LspSemanticTokens {
+    pub fn new(tokens: lsp::SemanticTokens) -> Self {
+        Self {
+            result_id: tokens.result_id,
+            data: tokens.data,
+        }
+    }
+
+    /// Applies the edits of a delta response. The edits refer to the tokens' flat
+    /// representation, where each token is five integers long.
+    pub fn apply_delta(&mut self, delta: lsp::SemanticTokensDelta) -> Result<()> {
+        const TOKEN_LEN: u32 = 5;
+
+        let mut edits = delta.edits;
+        edits.sort_by_key(|edit| edit.start);
+        // Applying the edits from last to first keeps the earlier edits' offsets valid.
+        for edit in edits.into_iter().rev() {
+            if edit.start % TOKEN_LEN != 0 || edit.delete_count % TOKEN_LEN != 0 {
+                return Err(anyhow!("semantic tokens edit doesn't align with tokens"));
+            }
+            let start = (edit.start / TOKEN_LEN) as usize;
+            let end = start + (edit.delete_count / TOKEN_LEN) as usize;
+            if end > self.data.len() {
+                return Err(anyhow!("semantic tokens edit is out of bounds"));
+            }
+            self.data.splice(start..end, edit.data.unwrap_or_default());
+        }
+        self.result_id = delta.result_id;
+        Ok(())
+    }
+
+    /// Resolves the tokens' relative positions and legend indices against the buffer the
+    /// language server reported them for. Tokens of unknown types are skipped.
+    pub fn decode(
+        &self,
+        legend: &lsp::SemanticTokensLegend,
+        snapshot: &BufferSnapshot,
+    ) -> Vec<SemanticToken> {
+        let mut tokens = Vec::with_capacity(self.data.len());
+        let mut row = 0;
+        let mut column = 0;
+        for token in &self.data {
+            if token.delta_line > 0 {
+                row += token.delta_line;
+                column = 0;
+            }
+            column += token.delta_start;
+
+            let Some(token_type) = legend.token_types.get(token.token_type as usize) else {
+                continue;
+            };
+            let modifiers = legend
+                .token_modifiers
+                .iter()
+                .enumerate()
+                .filter(|(ix, _)| {
+                    *ix < u32::BITS as usize && token.token_modifiers_bitset & (1 << ix) != 0
+                })
+                .map(|(_, modifier)| Arc::from(modifier.as_str()))
+                .collect();
+
+            let start =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(row, column + token.length)),
+                Bias::Right,
+            );
+            if start == end {
+                continue;
+            }
+            tokens.push(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type: Arc::from(token_type.as_str()),
+                modifiers,
+            });
+        }
+        tokens
+    }
+}
+
+/// The legend of the server's semantic tokens, and whether it can respond with deltas,
+/// if it provides semantic tokens for whole documents.
+pub(crate) fn semantic_tokens_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<(&lsp::SemanticTokensLegend, bool)> {
+    let options = match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            &options.semantic_tokens_options
+        }
+    };
+    let supports_delta = match options.full.as_ref()? {
+        lsp::SemanticTokensFullOptions::Bool(false) => return None,
+        lsp::SemanticTokensFullOptions::Bool(true) => false,
+        lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+    };
+    Some((&options.legend, supports_delta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::Buffer;
+
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        token_type: u32,
+        token_modifiers_bitset: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset,
+        }
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let mut tokens = LspSemanticTokens {
+            result_id: Some("1".into()),
+            data: vec![
+                token(0, 0, 2, 0, 0),
+                token(0, 3, 4, 1, 0),
+                token(1, 0, 3, 2, 0),
+            ],
+        };
+        tokens
+            .apply_delta(lsp::SemanticTokensDelta {
+                result_id: Some("2".into()),
+                edits: vec![
+                    lsp::SemanticTokensEdit {
+                        start: 10,
+                        delete_count: 5,
+                        data: None,
+                    },
+                    lsp::SemanticTokensEdit {
+                        start: 0,
+                        delete_count: 0,
+                        data: Some(vec![token(0, 0, 1, 3, 0)]),
+                    },
+                ],
+            })
+            .unwrap();
+        assert_eq!(tokens.result_id.as_deref(), Some("2"));
+        assert_eq!(
+            tokens.data,
+            [
+                token(0, 0, 1, 3, 0),
+                token(0, 0, 2, 0, 0),
+                token(0, 3, 4, 1, 0)
+            ]
+        );
+
+        let misaligned = lsp::SemanticTokensDelta {
+            result_id: Some("3".into()),
+            edits: vec![lsp::SemanticTokensEdit {
+                start: 2,
+                delete_count: 5,
+                data: None,
+            }],
+        };
+        assert!(tokens.apply_delta(misaligned).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let buffer = Buffer::new(0, 0, "let mut x = 1;\nx += 😀 + x;".into());
+        let snapshot = buffer.snapshot();
+        let legend = lsp::SemanticTokensLegend {
+            token_types: vec![
+                lsp::SemanticTokenType::KEYWORD,
+                lsp::SemanticTokenType::VARIABLE,
+            ],
+            token_modifiers: vec![
+                lsp::SemanticTokenModifier::DECLARATION,
+                lsp::SemanticTokenModifier::new("mutable"),
+            ],
+        };
+        let tokens = LspSemanticTokens {
+            result_id: None,
+            data: vec![
+                token(0, 0, 3, 0, 0),
+                token(0, 8, 1, 1, 0b11),
+                token(1, 0, 1, 1, 0b10),
+                // The emoji is two UTF-16 code units long.
+                token(0, 10, 1, 1, 0b10),
+                token(0, 1, 1, 7, 0),
+            ],
+        };
+
+        let decoded = tokens
+            .decode(&legend, &snapshot)
+            .into_iter()
+            .map(|token| {
+                (
+                    snapshot.text_for_range(token.range).collect::<String>(),
+                    token.token_type.to_string(),
+                    token
+                        .modifiers
+                        .iter()
+                        .map(|modifier| modifier.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decoded,
+            [
+                ("let".into(), "keyword".into(), vec![]),
+                (
+                    "x".into(),
+                    "variable".into(),
+                    vec!["declaration".into(), "mutable".into()]
+                ),
+                ("x".into(), "variable".into(), vec!["mutable".into()]),
+                ("x".into(), "variable".into(), vec!["mutable".into()]),
+            ]
+        );
+    }
+}
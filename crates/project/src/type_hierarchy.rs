@@ -0,0 +1,538 @@
+use crate::{
+    lsp_command::{
+        language_server_for_buffer, location_from_lsp, location_from_proto, location_to_proto,
+        LspCommand,
+    },
+    LanguageServerToQuery, Location, Project,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{AppContext, AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    point_to_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Buffer, CachedLspAdapter, PointUtf16, ToPointUtf16,
+};
+use lsp::{LanguageServer, LanguageServerId};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+
+// The type hierarchy requests were added in version 3.17 of the protocol, and are declared
+// here rather than taken from `lsp-types`.
+
+pub(crate) enum LspPrepareTypeHierarchy {}
+
+impl lsp::request::Request for LspPrepareTypeHierarchy {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = Option<Vec<LspTypeHierarchyItem>>;
+    const METHOD: &'static str = "textDocument/prepareTypeHierarchy";
+}
+
+pub(crate) enum LspTypeHierarchySupertypes {}
+
+impl lsp::request::Request for LspTypeHierarchySupertypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<LspTypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/supertypes";
+}
+
+pub(crate) enum LspTypeHierarchySubtypes {}
+
+impl lsp::request::Request for LspTypeHierarchySubtypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<LspTypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/subtypes";
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LspTypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<lsp::SymbolTag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub uri: lsp::Url,
+    pub range: lsp::Range,
+    pub selection_range: lsp::Range,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct TypeHierarchyItemParams {
+    pub item: LspTypeHierarchyItem,
+}
+
+/// A type in a type hierarchy, such as a class or an interface.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub language_server_id: LanguageServerId,
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    /// More details about the type, such as the package it's declared in.
+    pub detail: Option<String>,
+    pub uri: lsp::Url,
+    /// The location of the type's name.
+    pub location: Location,
+    lsp_item: LspTypeHierarchyItem,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeHierarchyDirection {
+    /// The types that a type extends or implements.
+    Supertypes,
+    /// The types that extend or implement a type.
+    Subtypes,
+}
+
+impl TypeHierarchyItem {
+    fn new(
+        lsp_item: LspTypeHierarchyItem,
+        language_server_id: LanguageServerId,
+        location: Location,
+    ) -> Self {
+        Self {
+            language_server_id,
+            name: lsp_item.name.clone(),
+            kind: lsp_item.kind,
+            detail: lsp_item.detail.clone(),
+            uri: lsp_item.uri.clone(),
+            location,
+            lsp_item,
+        }
+    }
+
+    async fn from_lsp(
+        lsp_item: LspTypeHierarchyItem,
+        project: &Model<Project>,
+        lsp_adapter: &CachedLspAdapter,
+        language_server: &LanguageServer,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let location = location_from_lsp(
+            lsp_item.uri.clone(),
+            lsp_item.selection_range,
+            project,
+            lsp_adapter,
+            language_server,
+            cx,
+        )
+        .await?;
+        Ok(Self::new(lsp_item, language_server.server_id(), location))
+    }
+
+    fn to_proto(
+        &self,
+        project: &mut Project,
+        peer_id: PeerId,
+        cx: &mut AppContext,
+    ) -> proto::TypeHierarchyItem {
+        proto::TypeHierarchyItem {
+            server_id: self.language_server_id.0 as u64,
+            location: Some(location_to_proto(
+                self.location.clone(),
+                project,
+                peer_id,
+                cx,
+            )),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        item: proto::TypeHierarchyItem,
+        project: &Model<Project>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let location = item
+            .location
+            .ok_or_else(|| anyhow!("missing item location"))?;
+        let location = location_from_proto(location, project, cx).await?;
+        let lsp_item = serde_json::from_slice(&item.lsp_item)?;
+        Ok(Self::new(
+            lsp_item,
+            LanguageServerId(item.server_id as usize),
+            location,
+        ))
+    }
+}
+
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = LspPrepareTypeHierarchy;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TextDocumentPositionParams {
+        lsp::TextDocumentPositionParams::new(
+            lsp::TextDocumentIdentifier::new(lsp::Url::from_file_path(path).unwrap()),
+            point_to_lsp(self.position),
+        )
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<LspTypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_lsp(lsp_items, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: items_to_proto(items, project, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_proto(message.items, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> u64 {
+        message.buffer_id
+    }
+}
+
+/// Requests the types that an item extends or implements. It's sent along with the buffer
+/// that the item is declared in.
+pub(crate) struct GetSupertypes {
+    pub lsp_item: LspTypeHierarchyItem,
+}
+
+/// Requests the types that extend or implement an item. It's sent along with the buffer that
+/// the item is declared in.
+pub(crate) struct GetSubtypes {
+    pub lsp_item: LspTypeHierarchyItem,
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = LspTypeHierarchySupertypes;
+    type ProtoRequest = proto::TypeHierarchySupertypes;
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> TypeHierarchyItemParams {
+        TypeHierarchyItemParams {
+            item: self.lsp_item.clone(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<LspTypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_lsp(lsp_items, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::TypeHierarchySupertypes {
+        proto::TypeHierarchySupertypes {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::TypeHierarchySupertypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::TypeHierarchyRelatedTypesResponse {
+        proto::TypeHierarchyRelatedTypesResponse {
+            items: items_to_proto(items, project, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::TypeHierarchyRelatedTypesResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_proto(message.items, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::TypeHierarchySupertypes) -> u64 {
+        message.buffer_id
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = LspTypeHierarchySubtypes;
+    type ProtoRequest = proto::TypeHierarchySubtypes;
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> TypeHierarchyItemParams {
+        TypeHierarchyItemParams {
+            item: self.lsp_item.clone(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<LspTypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_lsp(lsp_items, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::TypeHierarchySubtypes {
+        proto::TypeHierarchySubtypes {
+            project_id,
+            buffer_id: buffer.remote_id(),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::TypeHierarchySubtypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            lsp_item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::TypeHierarchyRelatedTypesResponse {
+        proto::TypeHierarchyRelatedTypesResponse {
+            items: items_to_proto(items, project, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::TypeHierarchyRelatedTypesResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        items_from_proto(message.items, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::TypeHierarchySubtypes) -> u64 {
+        message.buffer_id
+    }
+}
+
+async fn items_from_lsp(
+    lsp_items: Option<Vec<LspTypeHierarchyItem>>,
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(&project, &buffer, server_id, &mut cx)?;
+    let mut items = Vec::new();
+    for lsp_item in lsp_items.unwrap_or_default() {
+        items.push(
+            TypeHierarchyItem::from_lsp(
+                lsp_item,
+                &project,
+                &lsp_adapter,
+                &language_server,
+                &mut cx,
+            )
+            .await?,
+        );
+    }
+    Ok(items)
+}
+
+fn items_to_proto(
+    items: Vec<TypeHierarchyItem>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> Vec<proto::TypeHierarchyItem> {
+    items
+        .iter()
+        .map(|item| item.to_proto(project, peer_id, cx))
+        .collect()
+}
+
+async fn items_from_proto(
+    items: Vec<proto::TypeHierarchyItem>,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut result = Vec::new();
+    for item in items {
+        result.push(TypeHierarchyItem::from_proto(item, &project, &mut cx).await?);
+    }
+    Ok(result)
+}
+
+impl Project {
+    /// Resolves the type at the given position into the items of a type hierarchy. Since
+    /// servers' support for type hierarchies isn't known up front, the buffer's language
+    /// servers are asked in turn until one of them responds. The servers of remote projects
+    /// are picked by the host.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer_handle: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let buffer = buffer_handle.read(cx);
+        let position = position.to_point_utf16(buffer);
+        if !self.is_local() {
+            return self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Primary,
+                PrepareTypeHierarchy { position },
+                cx,
+            );
+        }
+        let server_ids = self
+            .language_servers_for_buffer(buffer, cx)
+            .map(|(_, server)| server.server_id())
+            .collect::<Vec<_>>();
+
+        let buffer = buffer_handle.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut responded = false;
+            let mut first_error = None;
+            for server_id in server_ids {
+                let request = this.update(&mut cx, |this, cx| {
+                    this.request_lsp(
+                        buffer.clone(),
+                        LanguageServerToQuery::Other(server_id),
+                        PrepareTypeHierarchy { position },
+                        cx,
+                    )
+                })?;
+                match request.await {
+                    Ok(items) if !items.is_empty() => return Ok(items),
+                    Ok(_) => responded = true,
+                    Err(error) => {
+                        first_error.get_or_insert(error);
+                    }
+                }
+            }
+            match first_error {
+                Some(error) if !responded => Err(error),
+                _ => Ok(Vec::new()),
+            }
+        })
+    }
+
+    /// Requests the supertypes or the subtypes of the item, from the language server that
+    /// reported it.
+    pub fn type_hierarchy_related_types(
+        &self,
+        item: &TypeHierarchyItem,
+        direction: TypeHierarchyDirection,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        let lsp_item = item.lsp_item.clone();
+        match direction {
+            TypeHierarchyDirection::Supertypes => {
+                self.request_lsp(buffer, server, GetSupertypes { lsp_item }, cx)
+            }
+            TypeHierarchyDirection::Subtypes => {
+                self.request_lsp(buffer, server, GetSubtypes { lsp_item }, cx)
+            }
+        }
+    }
+}
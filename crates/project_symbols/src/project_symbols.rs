@@ -7,7 +7,7 @@ use gpui::{
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
 use project::{Project, Symbol};
-use std::{borrow::Cow, cmp::Reverse, sync::Arc};
+use std::{borrow::Cow, cmp::Reverse, mem, sync::Arc};
 use theme::ActiveTheme;
 use util::ResultExt;
 use workspace::{
@@ -102,6 +102,43 @@ impl ProjectSymbolsDelegate {
         self.matches = matches;
         self.set_selected_index(0, cx);
     }
+
+    fn clear_symbols(&mut self) {
+        self.symbols.clear();
+        self.visible_match_candidates.clear();
+        self.external_match_candidates.clear();
+    }
+
+    /// Adds the symbols of a language server, skipping the ones that other servers already
+    /// reported, and splits them into visible and external candidates depending on whether
+    /// they're in an ignored file.
+    fn add_symbols(&mut self, symbols: Vec<Symbol>, cx: &mut ViewContext<Picker<Self>>) {
+        let project = self.project.read(cx);
+        for symbol in symbols {
+            let is_duplicate = self.symbols.iter().any(|existing| {
+                existing.path == symbol.path
+                    && existing.range == symbol.range
+                    && existing.name == symbol.name
+            });
+            if is_duplicate {
+                continue;
+            }
+
+            let candidate = StringMatchCandidate::new(
+                self.symbols.len(),
+                symbol.label.text[symbol.label.filter_range.clone()].to_string(),
+            );
+            let is_visible = project
+                .entry_for_path(&symbol.path, cx)
+                .map_or(false, |entry| !entry.is_ignored);
+            if is_visible {
+                self.visible_match_candidates.push(candidate);
+            } else {
+                self.external_match_candidates.push(candidate);
+            }
+            self.symbols.push(symbol);
+        }
+    }
 }
 
 impl PickerDelegate for ProjectSymbolsDelegate {
@@ -166,32 +203,30 @@ impl PickerDelegate for ProjectSymbolsDelegate {
         self.show_worktree_root_name = self.project.read(cx).visible_worktrees(cx).count() > 1;
         let symbols = self
             .project
-            .update(cx, |project, cx| project.symbols(&query, cx));
+            .update(cx, |project, cx| project.search_symbols(&query, cx));
         cx.spawn(|this, mut cx| async move {
-            let symbols = symbols.await.log_err();
-            if let Some(symbols) = symbols {
-                this.update(&mut cx, |this, cx| {
+            // Replace the previous query's symbols once the first server responds, and add
+            // the symbols of the other servers as they respond.
+            let mut is_first_response = true;
+            while let Ok(new_symbols) = symbols.recv().await {
+                let is_first = mem::take(&mut is_first_response);
+                let updated = this.update(&mut cx, |this, cx| {
                     let delegate = &mut this.delegate;
-                    let project = delegate.project.read(cx);
-                    let (visible_match_candidates, external_match_candidates) = symbols
-                        .iter()
-                        .enumerate()
-                        .map(|(id, symbol)| {
-                            StringMatchCandidate::new(
-                                id,
-                                symbol.label.text[symbol.label.filter_range.clone()].to_string(),
-                            )
-                        })
-                        .partition(|candidate| {
-                            project
-                                .entry_for_path(&symbols[candidate.id].path, cx)
-                                .map_or(false, |e| !e.is_ignored)
-                        });
-
-                    delegate.visible_match_candidates = visible_match_candidates;
-                    delegate.external_match_candidates = external_match_candidates;
-                    delegate.symbols = symbols;
+                    if is_first {
+                        delegate.clear_symbols();
+                    }
+                    delegate.add_symbols(new_symbols, cx);
                     delegate.filter(&query, cx);
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            if is_first_response {
+                this.update(&mut cx, |this, cx| {
+                    this.delegate.clear_symbols();
+                    this.delegate.filter(&query, cx);
                 })
                 .log_err();
             }
@@ -264,7 +299,7 @@ mod tests {
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
-    use std::{path::Path, sync::Arc};
+    use std::{path::Path, sync::Arc, time::Duration};
 
     #[gpui::test]
     async fn test_project_symbols(cx: &mut TestAppContext) {
@@ -279,7 +314,10 @@ mod tests {
             None,
         );
         let mut fake_servers = language
-            .set_fake_lsp_adapter(Arc::<FakeLspAdapter>::default())
+            .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+                capabilities: lsp::LanguageServer::workspace_symbol_capabilities(),
+                ..Default::default()
+            }))
             .await;
 
         let fs = FakeFs::new(cx.executor());
@@ -387,6 +425,111 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_project_symbols_from_multiple_servers(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let mut rust = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            None,
+        );
+        let mut rust_servers = rust
+            .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+                name: "rust-language-server",
+                capabilities: lsp::LanguageServer::workspace_symbol_capabilities(),
+                ..Default::default()
+            }))
+            .await;
+        let mut toml = Language::new(
+            LanguageConfig {
+                name: "TOML".into(),
+                path_suffixes: vec!["toml".to_string()],
+                ..Default::default()
+            },
+            None,
+        );
+        let mut toml_servers = toml
+            .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+                name: "toml-language-server",
+                capabilities: lsp::LanguageServer::workspace_symbol_capabilities(),
+                ..Default::default()
+            }))
+            .await;
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/dir", json!({ "main.rs": "", "Cargo.toml": "" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        project.update(cx, |project, _| {
+            project.languages().add(Arc::new(rust));
+            project.languages().add(Arc::new(toml));
+        });
+        for path in ["/dir/main.rs", "/dir/Cargo.toml"] {
+            project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+        }
+
+        // The TOML server takes a while to respond.
+        let rust_server = rust_servers.next().await.unwrap();
+        rust_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(|_, _| async move {
+            Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol(
+                "package_name",
+                "/dir/main.rs",
+            )])))
+        });
+        let toml_server = toml_servers.next().await.unwrap();
+        toml_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(|_, cx| {
+            let executor = cx.background_executor().clone();
+            async move {
+                executor.timer(Duration::from_secs(1)).await;
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![
+                    symbol("package", "/dir/Cargo.toml"),
+                    symbol("package_name", "/dir/main.rs"),
+                ])))
+            }
+        });
+
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let symbols = cx.new_view(|cx| {
+            Picker::new(
+                ProjectSymbolsDelegate::new(workspace.downgrade(), project.clone()),
+                cx,
+            )
+        });
+        symbols.update(cx, |p, cx| p.update_matches("pack".to_string(), cx));
+
+        // The symbols of the first server to respond are shown right away.
+        cx.run_until_parked();
+        symbols.update(cx, |symbols, _| {
+            let delegate = &symbols.delegate;
+            let names = delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["package_name"]);
+        });
+
+        // The other server's symbols are merged with them, without duplicates.
+        cx.executor().advance_clock(Duration::from_secs(2));
+        cx.run_until_parked();
+        symbols.update(cx, |symbols, _| {
+            let delegate = &symbols.delegate;
+            let names = delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["package", "package_name"]);
+        });
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let store = SettingsStore::test(cx);
@@ -284,6 +284,22 @@ messages!(
     (LspExtExpandMacro, Background),
     (LspExtExpandMacroResponse, Background),
     (SetRoomParticipantRole, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (CallHierarchyIncomingCalls, Background),
+    (CallHierarchyOutgoingCalls, Background),
+    (CallHierarchyCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (TypeHierarchySupertypes, Background),
+    (TypeHierarchySubtypes, Background),
+    (TypeHierarchyRelatedTypesResponse, Background),
+    (GetCodeLenses, Background),
+    (GetCodeLensesResponse, Background),
+    (ExecuteCodeLens, Background),
+    (ExecuteCodeLensResponse, Background),
+    (GetLinkedEditingRanges, Background),
+    (GetLinkedEditingRangesResponse, Background),
 );
 
 request_messages!(
@@ -369,6 +385,15 @@ request_messages!(
     (UpdateWorktree, Ack),
     (LspExtExpandMacro, LspExtExpandMacroResponse),
     (SetRoomParticipantRole, Ack),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (CallHierarchyIncomingCalls, CallHierarchyCallsResponse),
+    (CallHierarchyOutgoingCalls, CallHierarchyCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (TypeHierarchySupertypes, TypeHierarchyRelatedTypesResponse),
+    (TypeHierarchySubtypes, TypeHierarchyRelatedTypesResponse),
+    (GetCodeLenses, GetCodeLensesResponse),
+    (ExecuteCodeLens, ExecuteCodeLensResponse),
+    (GetLinkedEditingRanges, GetLinkedEditingRangesResponse),
 );
 
 entity_messages!(
@@ -422,6 +447,15 @@ entity_messages!(
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
+    PrepareCallHierarchy,
+    CallHierarchyIncomingCalls,
+    CallHierarchyOutgoingCalls,
+    PrepareTypeHierarchy,
+    TypeHierarchySupertypes,
+    TypeHierarchySubtypes,
+    GetCodeLenses,
+    ExecuteCodeLens,
+    GetLinkedEditingRanges,
 );
 
 entity_messages!(
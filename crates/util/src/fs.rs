@@ -1,4 +1,9 @@
-use std::path::Path;
+use std::{
+    cmp::Reverse,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 use smol::{fs, stream::StreamExt};
 
@@ -26,3 +31,149 @@ where
         }
     }
 }
+
+/// Removes the entries of `dir` whose names start with `prefix`, except for the `keep` most
+/// recently modified ones. The entry containing `in_use` is always kept, and counts
+/// towards `keep`.
+pub async fn remove_stale_versions(dir: &Path, prefix: &str, in_use: &Path, keep: usize) {
+    let Some(mut entries) = fs::read_dir(dir).await.log_err() else {
+        return;
+    };
+
+    let mut versions = Vec::new();
+    while let Some(entry) = entries.next().await {
+        let Some(entry) = entry.log_err() else {
+            continue;
+        };
+        if !entry.file_name().to_string_lossy().starts_with(prefix) {
+            continue;
+        }
+        let Some(metadata) = entry.metadata().await.log_err() else {
+            continue;
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        versions.push((entry.path(), metadata.is_file(), modified));
+    }
+
+    let mut kept = 0;
+    if let Some(ix) = versions
+        .iter()
+        .position(|(path, _, _)| in_use.starts_with(path))
+    {
+        versions.remove(ix);
+        kept += 1;
+    }
+
+    versions.sort_by_key(|(_, _, modified)| Reverse(*modified));
+    for (path, is_file, _) in versions {
+        if kept < keep {
+            kept += 1;
+        } else if is_file {
+            fs::remove_file(&path).await.log_err();
+        } else {
+            fs::remove_dir_all(&path).await.log_err();
+        }
+    }
+}
+
+/// Finds the executable named `name` in the directories of `path_var`, a list of paths in
+/// the format of the `PATH` environment variable, or else in `extra_dirs`.
+pub async fn find_executable(
+    name: &str,
+    path_var: Option<&OsStr>,
+    extra_dirs: &[PathBuf],
+) -> Option<PathBuf> {
+    let dirs = path_var
+        .into_iter()
+        .flat_map(std::env::split_paths)
+        .chain(extra_dirs.iter().cloned());
+    for dir in dirs {
+        let candidate = dir.join(name);
+        if let Ok(metadata) = fs::metadata(&candidate).await {
+            if metadata.is_file() && is_executable(&metadata) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_: &std::fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_remove_stale_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        for (ix, name) in ["server-1", "server-2", "server-3", "server-4", "other"]
+            .into_iter()
+            .enumerate()
+        {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(ix as u64 * 60))
+                .unwrap();
+        }
+
+        smol::block_on(remove_stale_versions(
+            dir,
+            "server-",
+            &dir.join("server-1"),
+            2,
+        ));
+
+        let mut names = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["other", "server-1", "server-4"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        for name in ["first", "second", "extra"] {
+            std::fs::create_dir(dir.join(name)).unwrap();
+        }
+        let create = |path: PathBuf, mode: u32| {
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        // A file that isn't executable doesn't shadow the executables after it.
+        create(dir.join("first/server"), 0o644);
+        let server = create(dir.join("second/server"), 0o755);
+        let extra_server = create(dir.join("extra/extra-server"), 0o755);
+
+        let path_var = std::env::join_paths([dir.join("first"), dir.join("second")]).unwrap();
+        let extra_dirs = [dir.join("extra")];
+        let find = |name: &str| {
+            smol::block_on(find_executable(
+                name,
+                Some(path_var.as_os_str()),
+                &extra_dirs,
+            ))
+        };
+        assert_eq!(find("server"), Some(server));
+        assert_eq!(find("extra-server"), Some(extra_server));
+        assert_eq!(find("missing"), None);
+        assert_eq!(smol::block_on(find_executable("server", None, &[])), None);
+    }
+}
@@ -1,17 +1,59 @@
-use crate::http::HttpClient;
+use crate::{
+    http::{AsyncBody, HttpClient, Method, Request, RetryAttempt, StatusCode},
+    retry_delay, ResultExt,
+};
 use anyhow::{anyhow, bail, Context, Result};
-use futures::AsyncReadExt;
+use futures::{future::BoxFuture, AsyncReadExt, AsyncWriteExt};
+use isahc::config::{Configurable, RedirectPolicy};
 use serde::Deserialize;
-use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use smol::fs::{self, File};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// Names of release assets that list the SHA-256 checksums of the other assets, in the
+/// format written by `sha256sum`.
+const CHECKSUM_MANIFEST_NAMES: &[&str] = &[
+    "SHA256SUMS",
+    "SHA256SUMS.txt",
+    "sha256sums.txt",
+    "checksums.txt",
+    "sha256.txt",
+];
+
+/// Suffixes of release assets that accompany other assets, like their checksums or
+/// signatures, rather than being installable themselves.
+const ANCILLARY_ASSET_SUFFIXES: &[&str] = &[
+    ".sha256",
+    ".sha256sum",
+    ".sig",
+    ".asc",
+    ".minisig",
+    ".pem",
+    ".sbom",
+    ".intoto.jsonl",
+];
+
+/// How many times a download is attempted before giving up on it.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 5;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 pub struct GitHubLspBinaryVersion {
     pub name: String,
     pub url: String,
+    /// The SHA-256 checksum of the asset at `url` as lowercase hex, if the release publishes one.
+    pub sha256: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct GithubRelease {
     pub name: String,
+    #[serde(default)]
+    pub tag_name: String,
     #[serde(rename = "prerelease")]
     pub pre_release: bool,
     pub assets: Vec<GithubReleaseAsset>,
@@ -23,6 +65,22 @@ pub struct GithubRelease {
 pub struct GithubReleaseAsset {
     pub name: String,
     pub browser_download_url: String,
+    /// The checksum GitHub computed when the asset was uploaded, e.g. `sha256:<hex>`.
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl GithubRelease {
+    /// Whether this is the release of `version`, which matches tags with or without a
+    /// leading `v`, including tags prefixed with a path like `gopls/v0.14.2`.
+    pub fn is_version(&self, version: &str) -> bool {
+        let normalize = |name: &str| {
+            let name = name.rsplit('/').next().unwrap_or(name);
+            name.strip_prefix('v').unwrap_or(name).to_string()
+        };
+        let version = normalize(version);
+        normalize(&self.tag_name) == version || normalize(&self.name) == version
+    }
 }
 
 pub async fn latest_github_release(
@@ -30,21 +88,58 @@ pub async fn latest_github_release(
     pre_release: bool,
     http: Arc<dyn HttpClient>,
 ) -> Result<GithubRelease, anyhow::Error> {
-    let mut response = http
-        .get(
-            &format!("https://api.github.com/repos/{repo_name_with_owner}/releases"),
-            Default::default(),
-            true,
-        )
-        .await
-        .context("error fetching latest release")?;
+    let releases: Vec<GithubRelease> = fetch_from_github_api(
+        &format!("https://api.github.com/repos/{repo_name_with_owner}/releases"),
+        http,
+    )
+    .await
+    .context("error fetching latest release")?;
+
+    releases
+        .into_iter()
+        .find(|release| release.pre_release == pre_release)
+        .ok_or(anyhow!("Failed to find a release"))
+}
+
+/// Fetches the release of a specific version, whose tag may or may not start with a `v`.
+pub async fn github_release_for_version(
+    repo_name_with_owner: &str,
+    version: &str,
+    http: Arc<dyn HttpClient>,
+) -> Result<GithubRelease> {
+    let mut tags = vec![version.to_string()];
+    if !version.starts_with('v') {
+        tags.push(format!("v{version}"));
+    }
+
+    let mut last_error = None;
+    for tag in tags {
+        let url =
+            format!("https://api.github.com/repos/{repo_name_with_owner}/releases/tags/{tag}");
+        match fetch_from_github_api(&url, http.clone()).await {
+            Ok(release) => return Ok(release),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| anyhow!("no tags to look up"))
+        .context(format!(
+            "error fetching release {version} of {repo_name_with_owner}"
+        )))
+}
+
+async fn fetch_from_github_api<T: for<'de> Deserialize<'de>>(
+    url: &str,
+    http: Arc<dyn HttpClient>,
+) -> Result<T> {
+    let mut response = http.get(url, Default::default(), true).await?;
 
     let mut body = Vec::new();
     response
         .body_mut()
         .read_to_end(&mut body)
         .await
-        .context("error reading latest release")?;
+        .context("error reading response")?;
 
     if response.status().is_client_error() {
         let text = String::from_utf8_lossy(body.as_slice());
@@ -54,20 +149,901 @@ pub async fn latest_github_release(
         );
     }
 
-    let releases = match serde_json::from_slice::<Vec<GithubRelease>>(body.as_slice()) {
-        Ok(releases) => releases,
-
+    match serde_json::from_slice::<T>(body.as_slice()) {
+        Ok(value) => Ok(value),
         Err(_) => {
             log::error!(
                 "Error deserializing GitHub API response text: {:?}",
                 String::from_utf8_lossy(body.as_slice())
             );
-            return Err(anyhow!("error deserializing latest release"));
+            Err(anyhow!("error deserializing GitHub API response"))
         }
+    }
+}
+
+/// An operating system that release assets are built for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Os {
+    Mac,
+    Linux,
+    Windows,
+}
+
+/// A CPU architecture that release assets are built for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+}
+
+/// The C library that Linux release assets are linked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Libc {
+    Gnu,
+    Musl,
+}
+
+/// The platform to pick release assets for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Platform {
+    pub os: Os,
+    pub arch: Arch,
+    /// Only used on Linux.
+    pub libc: Libc,
+}
+
+impl Platform {
+    /// The platform Zed is running on, or `None` if language servers aren't published
+    /// for it.
+    pub fn current() -> Option<Self> {
+        let os = match std::env::consts::OS {
+            "macos" => Os::Mac,
+            "linux" => Os::Linux,
+            "windows" => Os::Windows,
+            _ => return None,
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => Arch::X86_64,
+            "aarch64" => Arch::Aarch64,
+            _ => return None,
+        };
+        let libc = if cfg!(target_env = "musl") {
+            Libc::Musl
+        } else {
+            Libc::Gnu
+        };
+        Some(Self { os, arch, libc })
+    }
+
+    /// Finds the asset built for this platform among those whose names start with `prefix`
+    /// and end with `extension`.
+    ///
+    /// Projects name their assets differently, so any of the common spellings of the OS
+    /// and architecture are accepted, like `darwin`, `apple` or `macos`, and `x64` or
+    /// `amd64`. Assets that don't name an architecture are assumed to be universal. On
+    /// glibc systems, statically-linked musl builds are used when there is no glibc build.
+    pub fn find_asset<'a>(
+        &self,
+        assets: &'a [GithubReleaseAsset],
+        prefix: &str,
+        extension: &str,
+    ) -> Option<&'a GithubReleaseAsset> {
+        const OTHER_ARCHES: &[&str] = &[
+            "x86", "i386", "i686", "arm", "armv7", "armhf", "riscv64", "ppc64le", "s390x",
+        ];
+
+        let os_names: &[&str] = match self.os {
+            Os::Mac => &["darwin", "apple", "macos", "mac", "osx"],
+            Os::Linux => &["linux"],
+            Os::Windows => &["windows", "win32", "win64", "win"],
+        };
+        let arch_names = |arch: Arch| -> &'static [&'static str] {
+            match arch {
+                Arch::X86_64 => &["x86_64", "x64", "amd64"],
+                Arch::Aarch64 => &["aarch64", "arm64"],
+            }
+        };
+        let other_arch = match self.arch {
+            Arch::X86_64 => Arch::Aarch64,
+            Arch::Aarch64 => Arch::X86_64,
+        };
+
+        assets
+            .iter()
+            .filter_map(|asset| {
+                let name = asset.name.to_ascii_lowercase();
+                if !name.starts_with(&prefix.to_ascii_lowercase())
+                    || !name.ends_with(&extension.to_ascii_lowercase())
+                    || ANCILLARY_ASSET_SUFFIXES
+                        .iter()
+                        .any(|suffix| name.ends_with(suffix))
+                {
+                    return None;
+                }
+                let has_word = |words: &[&str]| words.iter().any(|word| contains_word(&name, word));
+                if !has_word(os_names) {
+                    return None;
+                }
+
+                let mut score = 0;
+                if has_word(arch_names(self.arch)) {
+                    score += 2;
+                } else if has_word(arch_names(other_arch)) || has_word(OTHER_ARCHES) {
+                    return None;
+                }
+
+                if self.os == Os::Linux {
+                    match (self.libc, has_word(&["gnu"]), has_word(&["musl"])) {
+                        (Libc::Gnu, true, _) | (Libc::Musl, _, true) => score += 2,
+                        (_, false, false) => score += 1,
+                        (Libc::Gnu, false, true) => {}
+                        (Libc::Musl, true, false) => return None,
+                    }
+                }
+                Some((score, asset))
+            })
+            // `max_by_key` returns the last of the best matches, so reverse the assets to
+            // prefer the first.
+            .rev()
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, asset)| asset)
+    }
+}
+
+/// Whether `word` appears in `name` delimited by the start or end of `name`, or by one of
+/// the punctuation characters that asset names are separated with.
+fn contains_word(name: &str, word: &str) -> bool {
+    let is_separator = |c: char| matches!(c, '-' | '_' | '.');
+    name.match_indices(word).any(|(ix, _)| {
+        let before = name[..ix].chars().next_back();
+        let after = name[ix + word.len()..].chars().next();
+        before.map_or(true, is_separator) && after.map_or(true, is_separator)
+    })
+}
+
+/// Picks the asset of `release` that's built for the current platform, as described in
+/// [`Platform::find_asset`], along with its checksum.
+pub async fn platform_asset_version(
+    release: GithubRelease,
+    prefix: &str,
+    extension: &str,
+    http: Arc<dyn HttpClient>,
+) -> Result<GitHubLspBinaryVersion> {
+    let platform = Platform::current().ok_or_else(|| {
+        anyhow!(
+            "language servers aren't published for {} on {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let asset = platform
+        .find_asset(&release.assets, prefix, extension)
+        .ok_or_else(|| {
+            anyhow!(
+                "no asset of release {} matches {prefix}*{extension} for {platform:?}",
+                release.name
+            )
+        })?;
+    Ok(GitHubLspBinaryVersion {
+        sha256: asset_sha256(&release, asset, http).await?,
+        url: asset.browser_download_url.clone(),
+        name: release.name.clone(),
+    })
+}
+
+/// Finds the published SHA-256 checksum of a release asset, from the digest GitHub reports,
+/// a `<asset>.sha256` file, or a checksum manifest attached to the same release.
+///
+/// Returns `None` when the release publishes no checksum for the asset.
+pub async fn asset_sha256(
+    release: &GithubRelease,
+    asset: &GithubReleaseAsset,
+    http: Arc<dyn HttpClient>,
+) -> Result<Option<String>> {
+    if let Some(sha256) = asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+    {
+        return Ok(Some(sha256.to_ascii_lowercase()));
+    }
+
+    let sidecar_names = [
+        format!("{}.sha256", asset.name),
+        format!("{}.sha256sum", asset.name),
+    ];
+    let checksum_asset = release.assets.iter().find(|candidate| {
+        sidecar_names.contains(&candidate.name)
+            || CHECKSUM_MANIFEST_NAMES.contains(&candidate.name.as_str())
+    });
+    let Some(checksum_asset) = checksum_asset else {
+        return Ok(None);
     };
 
-    releases
-        .into_iter()
-        .find(|release| release.pre_release == pre_release)
-        .ok_or(anyhow!("Failed to find a release"))
+    let contents = download(http, &checksum_asset.browser_download_url, &|_, _| {})
+        .await
+        .with_context(|| format!("error downloading {}", checksum_asset.name))?;
+    let contents = String::from_utf8_lossy(&contents);
+    let sha256 = parse_checksum_manifest(&contents, &asset.name)
+        .with_context(|| format!("{} has no checksum for {}", checksum_asset.name, asset.name))?;
+    Ok(Some(sha256))
+}
+
+/// Downloads a release asset to `destination`, failing if its contents don't match the
+/// expected checksum.
+///
+/// The asset is hashed as it's written to a file next to `destination`, which is only
+/// moved into place once it has been verified, so nothing from a tampered or truncated
+/// download is left at `destination`.
+///
+/// `on_progress` is called with the number of bytes downloaded so far and the total size,
+/// when the server reports it.
+pub async fn download_verified(
+    http: Arc<dyn HttpClient>,
+    url: &str,
+    sha256: Option<&str>,
+    destination: &Path,
+    on_progress: &(dyn Fn(u64, Option<u64>) + Send + Sync),
+) -> Result<()> {
+    let mut partial_path = destination.as_os_str().to_owned();
+    partial_path.push(".partial");
+    let partial_path = PathBuf::from(partial_path);
+
+    let mut download = FileDownload::create(partial_path.clone()).await?;
+    let result = async {
+        download_with_retries(http, url, &mut download, on_progress, INITIAL_RETRY_DELAY).await?;
+        download
+            .file
+            .flush()
+            .await
+            .with_context(|| format!("failed to write to {}", partial_path.display()))?;
+        match sha256 {
+            Some(sha256) => verify_sha256(download.sha256.clone(), sha256)
+                .with_context(|| format!("verifying {url}"))?,
+            None => log::warn!("no checksum is published for {url}, installing it unverified"),
+        }
+        anyhow::Ok(())
+    }
+    .await;
+    drop(download);
+
+    if let Err(error) = result {
+        fs::remove_file(&partial_path).await.log_err();
+        return Err(error);
+    }
+    fs::rename(&partial_path, destination)
+        .await
+        .with_context(|| format!("failed to move download to {}", destination.display()))
+}
+
+/// Downloads the contents of `url` into memory, retrying with backoff when the connection
+/// fails or the response is cut short. Retries ask the server for the rest of the file, so
+/// a download that was interrupted partway through doesn't need to start over.
+async fn download(
+    http: Arc<dyn HttpClient>,
+    url: &str,
+    on_progress: &(dyn Fn(u64, Option<u64>) + Send + Sync),
+) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    download_with_retries(http, url, &mut bytes, on_progress, INITIAL_RETRY_DELAY).await?;
+    Ok(bytes)
+}
+
+/// Where the contents of a download are written as they're received.
+trait DownloadSink: Send {
+    fn written_bytes(&self) -> u64;
+
+    /// Discards what was written so far, when the server sends the whole file again.
+    fn restart(&mut self) -> BoxFuture<'_, Result<()>>;
+
+    fn append<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>>;
+}
+
+impl DownloadSink for Vec<u8> {
+    fn written_bytes(&self) -> u64 {
+        self.len() as u64
+    }
+
+    fn restart(&mut self) -> BoxFuture<'_, Result<()>> {
+        self.clear();
+        Box::pin(async { Ok(()) })
+    }
+
+    fn append<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        self.extend_from_slice(chunk);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Streams a download to a file, hashing it on the way.
+struct FileDownload {
+    path: PathBuf,
+    file: File,
+    written_bytes: u64,
+    sha256: Sha256,
+}
+
+impl FileDownload {
+    async fn create(path: PathBuf) -> Result<Self> {
+        let file = File::create(&path)
+            .await
+            .with_context(|| format!("failed to create file {}", path.display()))?;
+        Ok(Self {
+            path,
+            file,
+            written_bytes: 0,
+            sha256: Sha256::new(),
+        })
+    }
+}
+
+impl DownloadSink for FileDownload {
+    fn written_bytes(&self) -> u64 {
+        self.written_bytes
+    }
+
+    fn restart(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.file = File::create(&self.path)
+                .await
+                .with_context(|| format!("failed to create file {}", self.path.display()))?;
+            self.written_bytes = 0;
+            self.sha256 = Sha256::new();
+            Ok(())
+        })
+    }
+
+    fn append<'a>(&'a mut self, chunk: &'a [u8]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.file
+                .write_all(chunk)
+                .await
+                .with_context(|| format!("failed to write to {}", self.path.display()))?;
+            self.sha256.update(chunk);
+            self.written_bytes += chunk.len() as u64;
+            Ok(())
+        })
+    }
+}
+
+async fn download_with_retries(
+    http: Arc<dyn HttpClient>,
+    url: &str,
+    sink: &mut dyn DownloadSink,
+    on_progress: &(dyn Fn(u64, Option<u64>) + Send + Sync),
+    initial_retry_delay: Duration,
+) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        let error = match download_attempt(http.as_ref(), url, attempt, sink, on_progress).await {
+            Ok(()) => return Ok(()),
+            Err(DownloadError::Permanent(error)) => return Err(error),
+            Err(DownloadError::Transient(error)) => error,
+        };
+
+        attempt += 1;
+        if attempt >= MAX_DOWNLOAD_ATTEMPTS {
+            return Err(error.context(format!("giving up after {attempt} attempts")));
+        }
+        log::warn!(
+            "download of {url} failed after {} bytes, retrying: {error:#}",
+            sink.written_bytes()
+        );
+        smol::Timer::after(retry_delay(initial_retry_delay, MAX_RETRY_DELAY, attempt)).await;
+    }
+}
+
+enum DownloadError {
+    /// The download may succeed if it's attempted again.
+    Transient(anyhow::Error),
+    Permanent(anyhow::Error),
+}
+
+/// Downloads the part of `url` that isn't in `sink` yet, appending it to `sink`.
+async fn download_attempt(
+    http: &dyn HttpClient,
+    url: &str,
+    attempt: u32,
+    sink: &mut dyn DownloadSink,
+    on_progress: &(dyn Fn(u64, Option<u64>) + Send + Sync),
+) -> Result<(), DownloadError> {
+    /// How much is downloaded between progress reports when the total size is unknown.
+    const PROGRESS_INTERVAL_IN_BYTES: u64 = 1024 * 1024;
+
+    let mut request = Request::builder()
+        .redirect_policy(RedirectPolicy::Follow)
+        .method(Method::GET)
+        .uri(url)
+        .extension(RetryAttempt(attempt));
+    let written_bytes = sink.written_bytes();
+    if written_bytes > 0 {
+        request = request.header("Range", format!("bytes={written_bytes}-"));
+    }
+    let request = request
+        .body(AsyncBody::empty())
+        .map_err(|error| DownloadError::Permanent(error.into()))?;
+
+    let mut response = http
+        .send(request)
+        .await
+        .context("error downloading release")
+        .map_err(DownloadError::Transient)?;
+
+    let status = response.status();
+    if status == StatusCode::PARTIAL_CONTENT && written_bytes > 0 {
+        let resumes_at_offset = response
+            .headers()
+            .get("Content-Range")
+            .and_then(|range| range.to_str().ok())
+            .map_or(false, |range| {
+                range.starts_with(&format!("bytes {written_bytes}-"))
+            });
+        if !resumes_at_offset {
+            sink.restart().await.map_err(DownloadError::Permanent)?;
+            return Err(DownloadError::Transient(anyhow!(
+                "server resumed the download at the wrong offset"
+            )));
+        }
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE && written_bytes > 0 {
+        sink.restart().await.map_err(DownloadError::Permanent)?;
+        return Err(DownloadError::Transient(anyhow!(
+            "server couldn't resume the download"
+        )));
+    } else if status.is_success() {
+        // The server sent the whole file, either because this is the first attempt or
+        // because it doesn't support resuming.
+        sink.restart().await.map_err(DownloadError::Permanent)?;
+    } else if status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+    {
+        return Err(DownloadError::Transient(anyhow!(
+            "download failed with status {status}"
+        )));
+    } else {
+        return Err(DownloadError::Permanent(anyhow!(
+            "download failed with status {status}"
+        )));
+    }
+
+    let remaining_bytes = response.body().len().or_else(|| {
+        response
+            .headers()
+            .get("Content-Length")
+            .and_then(|length| length.to_str().ok()?.parse().ok())
+    });
+    let total_bytes = remaining_bytes.map(|remaining_bytes| sink.written_bytes() + remaining_bytes);
+    let progress_interval = total_bytes.map_or(PROGRESS_INTERVAL_IN_BYTES, |total_bytes| {
+        (total_bytes / 100).max(1)
+    });
+    on_progress(sink.written_bytes(), total_bytes);
+
+    let mut chunk = vec![0; 64 * 1024];
+    let mut reported_bytes = sink.written_bytes();
+    loop {
+        let read = response
+            .body_mut()
+            .read(&mut chunk)
+            .await
+            .context("error reading release")
+            .map_err(DownloadError::Transient)?;
+        if read == 0 {
+            break;
+        }
+        sink.append(&chunk[..read])
+            .await
+            .map_err(DownloadError::Permanent)?;
+
+        let downloaded_bytes = sink.written_bytes();
+        if downloaded_bytes - reported_bytes >= progress_interval {
+            on_progress(downloaded_bytes, total_bytes);
+            reported_bytes = downloaded_bytes;
+        }
+    }
+    on_progress(sink.written_bytes(), total_bytes);
+
+    if let Some(total_bytes) = total_bytes {
+        if sink.written_bytes() < total_bytes {
+            return Err(DownloadError::Transient(anyhow!(
+                "download was truncated at {} of {total_bytes} bytes",
+                sink.written_bytes()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn verify_sha256(sha256: Sha256, expected: &str) -> Result<()> {
+    let actual = format!("{:x}", sha256.finalize());
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("checksum mismatch: expected {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+/// Finds the checksum of `asset_name` in the output of `sha256sum`, or the lone checksum in
+/// a file that only contains one.
+pub fn parse_checksum_manifest(contents: &str, asset_name: &str) -> Option<String> {
+    let is_sha256 = |field: &str| field.len() == 64 && field.chars().all(|c| c.is_ascii_hexdigit());
+    let lines = contents
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|fields| !fields.is_empty())
+        .collect::<Vec<_>>();
+
+    if let [fields] = lines.as_slice() {
+        if let [sha256] = fields.as_slice() {
+            return is_sha256(sha256).then(|| sha256.to_ascii_lowercase());
+        }
+    }
+
+    lines.iter().find_map(|fields| {
+        let [sha256, name, ..] = fields.as_slice() else {
+            return None;
+        };
+        // `sha256sum` marks files that were read in binary mode with a leading `*`.
+        let name = name.trim_start_matches('*');
+        let name = name.rsplit('/').next().unwrap_or(name);
+        (is_sha256(sha256) && name == asset_name).then(|| sha256.to_ascii_lowercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{Error, Response};
+    use futures::future::BoxFuture;
+    use std::sync::Mutex;
+
+    /// Responds to each request with the next of `responses`, recording the `Range` header
+    /// of each request.
+    struct ScriptedHttpClient {
+        responses: Mutex<Vec<Response<AsyncBody>>>,
+        ranges: Mutex<Vec<Option<String>>>,
+    }
+
+    impl ScriptedHttpClient {
+        fn new(mut responses: Vec<Response<AsyncBody>>) -> Arc<Self> {
+            responses.reverse();
+            Arc::new(Self {
+                responses: Mutex::new(responses),
+                ranges: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl HttpClient for ScriptedHttpClient {
+        fn send(&self, req: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+            self.ranges.lock().unwrap().push(
+                req.headers()
+                    .get("Range")
+                    .map(|range| range.to_str().unwrap().to_string()),
+            );
+            let response = self.responses.lock().unwrap().pop().unwrap();
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    /// A response whose body ends before the length it claims.
+    fn truncated_response(status: u16, body: &[u8], content_length: usize) -> Response<AsyncBody> {
+        Response::builder()
+            .status(status)
+            .header("Content-Length", content_length)
+            .body(AsyncBody::from_reader(futures::io::Cursor::new(
+                body.to_vec(),
+            )))
+            .unwrap()
+    }
+
+    const SHA256_OF_HELLO: &str =
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_is_version() {
+        let release = |name: &str, tag_name: &str| GithubRelease {
+            name: name.to_string(),
+            tag_name: tag_name.to_string(),
+            pre_release: false,
+            assets: Vec::new(),
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+        };
+
+        assert!(release("Release 0.17.10", "v0.17.10").is_version("0.17.10"));
+        assert!(release("Release 0.17.10", "v0.17.10").is_version("v0.17.10"));
+        assert!(release("2023-12-18", "2023-12-18").is_version("2023-12-18"));
+        assert!(release("gopls/v0.14.2", "gopls/v0.14.2").is_version("0.14.2"));
+        assert!(!release("Release 0.17.10", "v0.17.10").is_version("0.17.1"));
+    }
+
+    #[test]
+    fn test_download_resumes_truncated_responses() {
+        let http = ScriptedHttpClient::new(vec![
+            truncated_response(200, b"hel", 5),
+            Response::builder()
+                .status(206)
+                .header("Content-Range", "bytes 3-4/5")
+                .body(AsyncBody::from(b"lo".to_vec()))
+                .unwrap(),
+        ]);
+        let mut bytes = Vec::new();
+        smol::block_on(download_with_retries(
+            http.clone(),
+            "https://example.com/server.tar.gz",
+            &mut bytes,
+            &|_, _| {},
+            Duration::from_millis(1),
+        ))
+        .unwrap();
+        assert_eq!(bytes, b"hello");
+        assert_eq!(
+            *http.ranges.lock().unwrap(),
+            [None, Some("bytes=3-".to_string())]
+        );
+
+        // Servers that don't support ranges send the whole file again.
+        let http = ScriptedHttpClient::new(vec![
+            truncated_response(200, b"hel", 5),
+            Response::builder()
+                .status(200)
+                .body(AsyncBody::from(b"hello".to_vec()))
+                .unwrap(),
+        ]);
+        let mut bytes = Vec::new();
+        smol::block_on(download_with_retries(
+            http,
+            "https://example.com/server.tar.gz",
+            &mut bytes,
+            &|_, _| {},
+            Duration::from_millis(1),
+        ))
+        .unwrap();
+        assert_eq!(bytes, b"hello");
+    }
+
+    #[test]
+    fn test_download_retries() {
+        let http = ScriptedHttpClient::new(vec![
+            truncated_response(503, b"", 0),
+            Response::builder()
+                .status(200)
+                .body(AsyncBody::from(b"hello".to_vec()))
+                .unwrap(),
+        ]);
+        let mut bytes = Vec::new();
+        smol::block_on(download_with_retries(
+            http.clone(),
+            "https://example.com/server.tar.gz",
+            &mut bytes,
+            &|_, _| {},
+            Duration::from_millis(1),
+        ))
+        .unwrap();
+        assert_eq!(bytes, b"hello");
+        assert_eq!(http.ranges.lock().unwrap().len(), 2);
+
+        // Client errors won't go away by retrying.
+        let http = ScriptedHttpClient::new(vec![truncated_response(404, b"", 0)]);
+        assert!(smol::block_on(download_with_retries(
+            http.clone(),
+            "https://example.com/server.tar.gz",
+            &mut Vec::new(),
+            &|_, _| {},
+            Duration::from_millis(1),
+        ))
+        .is_err());
+        assert_eq!(http.ranges.lock().unwrap().len(), 1);
+
+        let http = ScriptedHttpClient::new(
+            (0..MAX_DOWNLOAD_ATTEMPTS)
+                .map(|_| truncated_response(200, b"hel", 5))
+                .collect(),
+        );
+        assert!(smol::block_on(download_with_retries(
+            http.clone(),
+            "https://example.com/server.tar.gz",
+            &mut Vec::new(),
+            &|_, _| {},
+            Duration::from_millis(1),
+        ))
+        .is_err());
+        assert_eq!(
+            http.ranges.lock().unwrap().len(),
+            MAX_DOWNLOAD_ATTEMPTS as usize
+        );
+    }
+
+    #[test]
+    fn test_download_verified() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // The checksum covers the parts of a download that was resumed.
+        let destination = dir.path().join("server.tar.gz");
+        let http = ScriptedHttpClient::new(vec![
+            truncated_response(200, b"hel", 5),
+            Response::builder()
+                .status(206)
+                .header("Content-Range", "bytes 3-4/5")
+                .body(AsyncBody::from(b"lo".to_vec()))
+                .unwrap(),
+        ]);
+        smol::block_on(download_verified(
+            http,
+            "https://example.com/server.tar.gz",
+            Some(SHA256_OF_HELLO),
+            &destination,
+            &|_, _| {},
+        ))
+        .unwrap();
+        assert_eq!(std::fs::read(&destination).unwrap(), b"hello");
+
+        // Nothing is left behind by a download that doesn't match its checksum.
+        let http = ScriptedHttpClient::new(vec![Response::builder()
+            .status(200)
+            .body(AsyncBody::from(b"hell!".to_vec()))
+            .unwrap()]);
+        assert!(smol::block_on(download_verified(
+            http,
+            "https://example.com/tampered.tar.gz",
+            Some(SHA256_OF_HELLO),
+            &dir.path().join("tampered.tar.gz"),
+            &|_, _| {},
+        ))
+        .is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_find_platform_asset() {
+        let assets = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| GithubReleaseAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{name}"),
+                    digest: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let find = |assets: &[GithubReleaseAsset], platform, prefix, extension| {
+            Platform::find_asset(&platform, assets, prefix, extension)
+                .map(|asset| asset.name.clone())
+        };
+        let platform = |os, arch, libc| Platform { os, arch, libc };
+
+        let rust_analyzer = assets(&[
+            "rust-analyzer-aarch64-apple-darwin.gz",
+            "rust-analyzer-aarch64-unknown-linux-gnu.gz",
+            "rust-analyzer-linux-x64.vsix",
+            "rust-analyzer-x86_64-apple-darwin.gz",
+            "rust-analyzer-x86_64-unknown-linux-gnu.gz",
+            "rust-analyzer-x86_64-unknown-linux-musl.gz",
+            "rust-analyzer-x86_64-unknown-linux-musl.gz.sha256",
+        ]);
+        assert_eq!(
+            find(
+                &rust_analyzer,
+                platform(Os::Mac, Arch::Aarch64, Libc::Gnu),
+                "rust-analyzer-",
+                ".gz"
+            )
+            .as_deref(),
+            Some("rust-analyzer-aarch64-apple-darwin.gz")
+        );
+        assert_eq!(
+            find(
+                &rust_analyzer,
+                platform(Os::Linux, Arch::X86_64, Libc::Gnu),
+                "rust-analyzer-",
+                ".gz"
+            )
+            .as_deref(),
+            Some("rust-analyzer-x86_64-unknown-linux-gnu.gz")
+        );
+        assert_eq!(
+            find(
+                &rust_analyzer,
+                platform(Os::Linux, Arch::X86_64, Libc::Musl),
+                "rust-analyzer-",
+                ".gz"
+            )
+            .as_deref(),
+            Some("rust-analyzer-x86_64-unknown-linux-musl.gz")
+        );
+        assert_eq!(
+            find(
+                &rust_analyzer,
+                platform(Os::Linux, Arch::Aarch64, Libc::Musl),
+                "rust-analyzer-",
+                ".gz"
+            ),
+            None
+        );
+
+        // Static musl builds are used on glibc systems when there's no glibc build.
+        let gleam = assets(&[
+            "gleam-v0.33.0-aarch64-apple-darwin.tar.gz",
+            "gleam-v0.33.0-x86_64-unknown-linux-musl.tar.gz",
+            "gleam-v0.33.0-x86_64-unknown-linux-musl.tar.gz.sig",
+        ]);
+        assert_eq!(
+            find(
+                &gleam,
+                platform(Os::Linux, Arch::X86_64, Libc::Gnu),
+                "gleam-",
+                ".tar.gz"
+            )
+            .as_deref(),
+            Some("gleam-v0.33.0-x86_64-unknown-linux-musl.tar.gz")
+        );
+
+        // Assets that don't name an architecture are universal.
+        let clangd = assets(&[
+            "clangd-linux-17.0.3.zip",
+            "clangd-mac-17.0.3.zip",
+            "clangd-windows-17.0.3.zip",
+            "clangd_indexing_tools-mac-17.0.3.zip",
+        ]);
+        assert_eq!(
+            find(
+                &clangd,
+                platform(Os::Mac, Arch::X86_64, Libc::Gnu),
+                "clangd-",
+                ".zip"
+            )
+            .as_deref(),
+            Some("clangd-mac-17.0.3.zip")
+        );
+
+        let next_ls = assets(&[
+            "next_ls_darwin_amd64",
+            "next_ls_darwin_arm64",
+            "next_ls_linux_amd64",
+            "next_ls_linux_arm64",
+        ]);
+        assert_eq!(
+            find(
+                &next_ls,
+                platform(Os::Linux, Arch::Aarch64, Libc::Gnu),
+                "next_ls_",
+                ""
+            )
+            .as_deref(),
+            Some("next_ls_linux_arm64")
+        );
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let sha256 = |bytes: &[u8]| Sha256::new_with_prefix(bytes);
+        assert!(verify_sha256(sha256(b"hello"), SHA256_OF_HELLO).is_ok());
+        assert!(verify_sha256(sha256(b"hello"), &SHA256_OF_HELLO.to_uppercase()).is_ok());
+        assert!(verify_sha256(sha256(b"hell"), SHA256_OF_HELLO).is_err());
+    }
+
+    #[test]
+    fn test_parse_checksum_manifest() {
+        let other = "a".repeat(64);
+        let manifest = format!(
+            "{other}  zls-aarch64-macos.tar.gz\n{SHA256_OF_HELLO} *dist/zls-x86_64-macos.tar.gz\n"
+        );
+        assert_eq!(
+            parse_checksum_manifest(&manifest, "zls-x86_64-macos.tar.gz").as_deref(),
+            Some(SHA256_OF_HELLO)
+        );
+        assert_eq!(
+            parse_checksum_manifest(&manifest, "zls-aarch64-macos.tar.gz"),
+            Some(other)
+        );
+        assert_eq!(parse_checksum_manifest(&manifest, "zls.zip"), None);
+
+        assert_eq!(
+            parse_checksum_manifest(&format!("{SHA256_OF_HELLO}\n"), "anything").as_deref(),
+            Some(SHA256_OF_HELLO)
+        );
+        assert_eq!(
+            parse_checksum_manifest("not a checksum\n", "anything"),
+            None
+        );
+    }
 }
@@ -9,7 +9,10 @@ pub use isahc::{AsyncBody, Request, Response};
 use smol::future::FutureExt;
 #[cfg(feature = "test-support")]
 use std::fmt;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock, Weak},
+    time::{Duration, Instant},
+};
 pub use url::Url;
 
 pub trait HttpClient: Send + Sync {
@@ -54,13 +57,21 @@ pub trait HttpClient: Send + Sync {
 }
 
 pub fn client() -> Arc<dyn HttpClient> {
-    Arc::new(
-        isahc::HttpClient::builder()
-            .connect_timeout(Duration::from_secs(5))
-            .low_speed_timeout(100, Duration::from_secs(5))
-            .build()
-            .unwrap(),
-    )
+    client_with_proxy(None)
+}
+
+/// Creates a client that sends requests through `proxy`, which may be an HTTP(S) or a SOCKS
+/// proxy. Without one, the proxy is read from the standard environment variables, such as
+/// `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`.
+pub fn client_with_proxy(proxy: Option<Uri>) -> Arc<dyn HttpClient> {
+    let mut builder = isahc::HttpClient::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .low_speed_timeout(100, Duration::from_secs(5));
+    // Passing `None` would stop the environment variables from being used.
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(Some(proxy));
+    }
+    Arc::new(builder.build().unwrap())
 }
 
 impl HttpClient for isahc::HttpClient {
@@ -69,6 +80,136 @@ impl HttpClient for isahc::HttpClient {
     }
 }
 
+/// A request extension marking a request as a retry, counting from 1 for the first retry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryAttempt(pub u32);
+
+/// How a request sent through an [`ObservedHttpClient`] went.
+#[derive(Clone, Debug)]
+pub struct RequestOutcome {
+    /// The host the request was sent to. The rest of the URL is left out, as it may
+    /// identify the user.
+    pub host: Option<String>,
+    /// See [`RetryAttempt`].
+    pub retry_attempt: u32,
+    pub latency: Duration,
+    /// The response status, or `None` if no response was received.
+    pub status: Option<StatusCode>,
+}
+
+pub trait HttpObserver: Send + Sync {
+    fn request_completed(&self, outcome: RequestOutcome);
+}
+
+/// Wraps an [`HttpClient`], telling an [`HttpObserver`] how each request went.
+pub struct ObservedHttpClient {
+    client: Arc<dyn HttpClient>,
+    // Weak, as the observer usually holds on to this client to send its own requests
+    observer: RwLock<Option<Weak<dyn HttpObserver>>>,
+}
+
+impl ObservedHttpClient {
+    pub fn new(client: Arc<dyn HttpClient>) -> Arc<Self> {
+        Arc::new(Self {
+            client,
+            observer: RwLock::new(None),
+        })
+    }
+
+    pub fn set_observer(&self, observer: Weak<dyn HttpObserver>) {
+        *self.observer.write().unwrap() = Some(observer);
+    }
+
+    fn observer(&self) -> Option<Arc<dyn HttpObserver>> {
+        self.observer.read().unwrap().as_ref()?.upgrade()
+    }
+}
+
+impl HttpClient for ObservedHttpClient {
+    fn send(&self, req: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+        let host = req.uri().host().map(str::to_string);
+        let retry_attempt = req
+            .extensions()
+            .get::<RetryAttempt>()
+            .map_or(0, |attempt| attempt.0);
+        let response = self.client.send(req);
+        Box::pin(async move {
+            let started_at = Instant::now();
+            let response = response.await;
+            if let Some(observer) = self.observer() {
+                observer.request_completed(RequestOutcome {
+                    host,
+                    retry_attempt,
+                    latency: started_at.elapsed(),
+                    status: response.as_ref().ok().map(|response| response.status()),
+                });
+            }
+            response
+        })
+    }
+}
+
+/// Wraps an [`HttpClient`], sending the requests meant for GitHub to a mirror instead, for
+/// networks that GitHub can't be reached from.
+pub struct MirroredHttpClient {
+    client: Arc<dyn HttpClient>,
+    template: String,
+}
+
+impl MirroredHttpClient {
+    /// Requests are sent to the URL produced by `template`, in which `{url}` is replaced
+    /// with the original URL, `{host}` with its host, and `{path}` with its path and query,
+    /// like `https://mirror.example.com/{url}` or `https://mirror.example.com/{host}{path}`.
+    pub fn new(client: Arc<dyn HttpClient>, template: String) -> Result<Arc<Self>> {
+        if !template.contains("{url}") && !template.contains("{path}") {
+            return Err(anyhow!(
+                "mirror {template:?} must contain {{url}} or {{path}}"
+            ));
+        }
+        let example = Uri::from_static("https://github.com/zed-industries/zed");
+        mirror_uri(&template, &example)
+            .ok_or_else(|| anyhow!("mirror {template:?} doesn't produce a valid URL"))?;
+        Ok(Arc::new(Self { client, template }))
+    }
+}
+
+impl HttpClient for MirroredHttpClient {
+    fn send(&self, req: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+        let (mut parts, body) = req.into_parts();
+        if let Some(uri) = mirror_uri(&self.template, &parts.uri) {
+            parts.uri = uri;
+        }
+        self.client.send(Request::from_parts(parts, body))
+    }
+}
+
+/// Where a request to `uri` is sent through the mirror `template`, or `None` if it isn't
+/// a request for GitHub.
+fn mirror_uri(template: &str, uri: &Uri) -> Option<Uri> {
+    let host = uri.host()?;
+    let is_github_host = ["github.com", "githubusercontent.com"]
+        .iter()
+        .any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain)
+                    .map_or(false, |subdomain| subdomain.ends_with('.'))
+        });
+    if !is_github_host {
+        return None;
+    }
+
+    let path = uri
+        .path_and_query()
+        .map_or("/", |path_and_query| path_and_query.as_str());
+    template
+        .replace("{url}", &uri.to_string())
+        .replace("{host}", host)
+        .replace("{path}", path)
+        .parse()
+        .ok()
+}
+
 #[cfg(feature = "test-support")]
 pub struct FakeHttpClient {
     handler: Box<
@@ -124,3 +265,45 @@ impl HttpClient for FakeHttpClient {
         Box::pin(async move { future.await.map(Into::into) })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_uri() {
+        let uri = |uri: &'static str| Uri::from_static(uri);
+        assert_eq!(
+            mirror_uri(
+                "https://mirror.example.com/{url}",
+                &uri("https://api.github.com/repos/rust-lang/rust-analyzer/releases?page=2")
+            ),
+            Some(uri(
+                "https://mirror.example.com/https://api.github.com/repos/rust-lang/rust-analyzer/releases?page=2"
+            ))
+        );
+        assert_eq!(
+            mirror_uri(
+                "https://mirror.example.com/{host}{path}",
+                &uri("https://objects.githubusercontent.com/release-assets/1234")
+            ),
+            Some(uri(
+                "https://mirror.example.com/objects.githubusercontent.com/release-assets/1234"
+            ))
+        );
+        assert_eq!(
+            mirror_uri(
+                "https://mirror.example.com/{url}",
+                &uri("https://registry.npmjs.org/pyright")
+            ),
+            None
+        );
+        assert_eq!(
+            mirror_uri(
+                "https://mirror.example.com/{url}",
+                &uri("https://notgithub.com/rust-lang")
+            ),
+            None
+        );
+    }
+}
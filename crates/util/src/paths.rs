@@ -15,6 +15,10 @@ lazy_static::lazy_static! {
     pub static ref COPILOT_DIR: PathBuf = HOME.join("Library/Application Support/Zed/copilot");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = HOME.join("Library/Application Support/Zed/prettier");
     pub static ref DB_DIR: PathBuf = HOME.join("Library/Application Support/Zed/db");
+    pub static ref TELEMETRY_SPOOL: PathBuf = HOME.join("Library/Application Support/Zed/telemetry/spool.ndjson");
+    pub static ref TELEMETRY_LOCAL_LOG: PathBuf = HOME.join("Library/Application Support/Zed/telemetry/events.ndjson");
+    pub static ref TELEMETRY_SENTINEL: PathBuf = HOME.join("Library/Application Support/Zed/telemetry/running.json");
+    pub static ref TELEMETRY_SENT_SUMMARY: PathBuf = HOME.join("Library/Application Support/Zed/telemetry/sent.json");
     pub static ref CRASHES_DIR: PathBuf = HOME.join("Library/Logs/DiagnosticReports");
     pub static ref CRASHES_RETIRED_DIR: PathBuf = HOME.join("Library/Logs/DiagnosticReports/Retired");
     pub static ref SETTINGS: PathBuf = CONFIG_DIR.join("settings.json");
@@ -19,7 +19,7 @@ use std::{
     panic::Location,
     pin::Pin,
     task::{Context, Poll},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 pub use take_until::*;
@@ -73,6 +73,17 @@ pub fn post_inc<T: From<u8> + AddAssign<T> + Copy>(value: &mut T) -> T {
     prev
 }
 
+/// The delay before retrying for the given attempt, starting from 1: exponential backoff from
+/// `initial_delay` up to `max_delay`, with up to 50% random jitter, so that many clients
+/// failing at once don't retry in lockstep.
+pub fn retry_delay(initial_delay: Duration, max_delay: Duration, attempt: u32) -> Duration {
+    let delay = initial_delay
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(max_delay);
+    let jitter_millis = rand::thread_rng().gen_range(0..=delay.as_millis() as u64 / 2);
+    delay + Duration::from_millis(jitter_millis)
+}
+
 /// Extend a sorted vector with a sorted sequence of items, maintaining the vector's sort order and
 /// enforcing a maximum length. This also de-duplicates items. Sort the items according to the given callback. Before calling this,
 /// both `vec` and `new_items` should already be sorted according to the `cmp` comparator.
@@ -482,6 +493,17 @@ mod tests {
         assert_eq!(vec, &[1000, 101, 21, 19, 17, 13, 9, 8]);
     }
 
+    #[test]
+    fn test_retry_delay_is_bounded() {
+        let initial_delay = Duration::from_secs(1);
+        let max_delay = Duration::from_secs(30);
+        for attempt in 0..64 {
+            let delay = retry_delay(initial_delay, max_delay, attempt);
+            assert!(delay >= initial_delay);
+            assert!(delay <= max_delay + max_delay / 2);
+        }
+    }
+
     #[test]
     fn test_iife() {
         fn option_returning_function() -> Option<()> {
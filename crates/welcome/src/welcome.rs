@@ -1,7 +1,10 @@
 mod base_keymap_picker;
 mod base_keymap_setting;
 
-use client::{telemetry::Telemetry, TelemetrySettings};
+use client::{
+    telemetry::{Telemetry, TelemetryConsent},
+    TelemetrySettings,
+};
 use db::kvp::KEY_VALUE_STORE;
 use gpui::{
     svg, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
@@ -60,6 +63,10 @@ pub struct WelcomePage {
 
 impl Render for WelcomePage {
     fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl IntoElement {
+        let disabled_by_policy = self.telemetry.disabled_by_policy();
+        let consent_unasked =
+            disabled_by_policy.is_none() && self.telemetry.consent() == TelemetryConsent::Unasked;
+
         h_flex()
             .full()
             .bg(cx.theme().colors().editor_background)
@@ -177,12 +184,15 @@ impl Render for WelcomePage {
                                     .child(
                                         Checkbox::new(
                                             "enable-telemetry",
-                                            if TelemetrySettings::get_global(cx).metrics {
+                                            if TelemetrySettings::get_global(cx).metrics
+                                                && disabled_by_policy.is_none()
+                                            {
                                                 ui::Selection::Selected
                                             } else {
                                                 ui::Selection::Unselected
                                             },
                                         )
+                                        .disabled(disabled_by_policy.is_some())
                                         .on_click(
                                             cx.listener(move |this, selection, cx| {
                                                 this.telemetry.report_app_event(
@@ -216,12 +226,15 @@ impl Render for WelcomePage {
                                     .child(
                                         Checkbox::new(
                                             "enable-crash",
-                                            if TelemetrySettings::get_global(cx).diagnostics {
+                                            if TelemetrySettings::get_global(cx).diagnostics
+                                                && disabled_by_policy.is_none()
+                                            {
                                                 ui::Selection::Selected
                                             } else {
                                                 ui::Selection::Unselected
                                             },
                                         )
+                                        .disabled(disabled_by_policy.is_some())
                                         .on_click(
                                             cx.listener(move |this, selection, cx| {
                                                 this.telemetry.report_app_event(
@@ -248,6 +261,39 @@ impl Render for WelcomePage {
                                         ),
                                     )
                                     .child(Label::new("Send crash reports")),
+                            )
+                            .when(consent_unasked, |this| {
+                                this.child(
+                                    Label::new(
+                                        "Nothing above is sent until you allow Zed to send it.",
+                                    )
+                                    .color(Color::Muted),
+                                )
+                                .child(
+                                    h_flex()
+                                        .gap_2()
+                                        .child(
+                                            Button::new("grant-telemetry-consent", "Allow")
+                                                .on_click(cx.listener(|this, _, cx| {
+                                                    this.telemetry
+                                                        .set_consent(TelemetryConsent::Granted);
+                                                    cx.notify();
+                                                })),
+                                        )
+                                        .child(
+                                            Button::new("deny-telemetry-consent", "Don't Allow")
+                                                .on_click(cx.listener(|this, _, cx| {
+                                                    this.telemetry
+                                                        .set_consent(TelemetryConsent::Denied);
+                                                    cx.notify();
+                                                })),
+                                        ),
+                                )
+                            })
+                            .children(
+                                disabled_by_policy.map(|reason| {
+                                    Label::new(reason.to_string()).color(Color::Muted)
+                                }),
                             ),
                     ),
             )
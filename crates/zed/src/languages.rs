@@ -7,29 +7,62 @@ use settings::Settings;
 use std::{borrow::Cow, str, sync::Arc};
 use util::{asset_str, paths::PLUGINS_DIR};
 
-use self::{deno::DenoSettings, elixir::ElixirSettings};
+use self::{
+    cmake::CMakeSettings, deno::DenoSettings, elixir::ElixirSettings, sql::SqlSettings,
+    vue::VueSettings,
+};
 
+mod astro;
 mod c;
+mod clojure;
+mod cmake;
+mod crystal;
 mod css;
+mod d;
+mod dart;
 mod deno;
+mod dockerfile;
 mod elixir;
+mod erlang;
+mod fortran;
 mod gleam;
 mod go;
+mod graphql;
+mod groovy;
+mod haskell;
 mod html;
 mod json;
+mod julia;
+mod jvm;
+mod kotlin;
 #[cfg(feature = "plugin_runtime")]
 mod language_plugin;
+mod latex;
 mod lua;
+mod nix;
 mod nu;
+mod ocaml;
+mod perl;
 mod php;
+mod powershell;
+mod project_root;
+mod proto;
 mod python;
 mod ruby;
 mod rust;
+mod scala;
+mod sql;
+mod starlark;
 mod svelte;
+mod swift;
 mod tailwind;
+mod terraform;
 mod typescript;
 mod uiua;
+mod venv;
+mod verilog;
 mod vue;
+mod wgsl;
 mod yaml;
 mod zig;
 
@@ -54,6 +87,10 @@ pub fn init(
 ) {
     ElixirSettings::register(cx);
     DenoSettings::register(cx);
+    SqlSettings::register(cx);
+    VueSettings::register(cx);
+    CMakeSettings::register(cx);
+    dart::init(cx);
 
     let language = |name, grammar, adapters| {
         languages.register(name, load_config(name), grammar, adapters, load_queries)
@@ -65,11 +102,38 @@ pub fn init(
         tree_sitter_c::language(),
         vec![Arc::new(c::CLspAdapter) as Arc<dyn LspAdapter>],
     );
+    language(
+        "clojure",
+        tree_sitter_clojure::language(),
+        vec![Arc::new(clojure::ClojureLspAdapter)],
+    );
+    language(
+        "edn",
+        tree_sitter_clojure::language(),
+        vec![Arc::new(clojure::ClojureLspAdapter)],
+    );
     language(
         "cpp",
         tree_sitter_cpp::language(),
         vec![Arc::new(c::CLspAdapter)],
     );
+    match &CMakeSettings::get(None, cx).lsp {
+        cmake::CMakeLspSetting::Neocmakelsp => language(
+            "cmake",
+            tree_sitter_cmake::language(),
+            vec![Arc::new(cmake::NeocmakelspAdapter)],
+        ),
+        cmake::CMakeLspSetting::CmakeLanguageServer => language(
+            "cmake",
+            tree_sitter_cmake::language(),
+            vec![Arc::new(cmake::CMakeLanguageServerAdapter)],
+        ),
+    }
+    language(
+        "crystal",
+        tree_sitter_crystal::language(),
+        vec![Arc::new(crystal::CrystalLspAdapter)],
+    );
     language(
         "css",
         tree_sitter_css::language(),
@@ -78,6 +142,23 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "d",
+        tree_sitter_d::language(),
+        vec![Arc::new(d::ServeDLspAdapter)],
+    );
+    language(
+        "dart",
+        tree_sitter_dart::language(),
+        vec![Arc::new(dart::DartLspAdapter)],
+    );
+    language(
+        "dockerfile",
+        tree_sitter_dockerfile::language(),
+        vec![Arc::new(dockerfile::DockerfileLspAdapter::new(
+            node_runtime.clone(),
+        ))],
+    );
 
     match &ElixirSettings::get(None, cx).lsp {
         elixir::ElixirLspSetting::ElixirLs => language(
@@ -103,6 +184,21 @@ pub fn init(
         ),
     }
 
+    language(
+        "erlang",
+        tree_sitter_erlang::language(),
+        vec![Arc::new(erlang::ErlangLspAdapter)],
+    );
+    language(
+        "fortran",
+        tree_sitter_fortran::language(),
+        vec![Arc::new(fortran::FortranLspAdapter)],
+    );
+    language(
+        "fortran-fixed",
+        tree_sitter_fixed_form_fortran::language(),
+        vec![Arc::new(fortran::FortranLspAdapter)],
+    );
     language(
         "gleam",
         tree_sitter_gleam::language(),
@@ -118,6 +214,24 @@ pub fn init(
         tree_sitter_zig::language(),
         vec![Arc::new(zig::ZlsAdapter)],
     );
+    language(
+        "graphql",
+        tree_sitter_graphql::language(),
+        vec![Arc::new(graphql::GraphQLLspAdapter::new(
+            node_runtime.clone(),
+        ))],
+    );
+    language(
+        "groovy",
+        tree_sitter_groovy::language(),
+        vec![Arc::new(groovy::GroovyLspAdapter)],
+    );
+    language(
+        "haskell",
+        tree_sitter_haskell::language(),
+        vec![Arc::new(haskell::HaskellLspAdapter)],
+    );
+    language("hcl", tree_sitter_hcl::language(), vec![]);
     language(
         "heex",
         tree_sitter_heex::language(),
@@ -134,6 +248,26 @@ pub fn init(
             languages.clone(),
         ))],
     );
+    language(
+        "julia",
+        tree_sitter_julia::language(),
+        vec![Arc::new(julia::JuliaLspAdapter)],
+    );
+    language(
+        "kotlin",
+        tree_sitter_kotlin::language(),
+        vec![Arc::new(kotlin::KotlinLspAdapter)],
+    );
+    language(
+        "latex",
+        tree_sitter_latex::language(),
+        vec![Arc::new(latex::TexlabAdapter)],
+    );
+    language(
+        "bibtex",
+        tree_sitter_bibtex::language(),
+        vec![Arc::new(latex::TexlabAdapter)],
+    );
     language("markdown", tree_sitter_markdown::language(), vec![]);
     language(
         "python",
@@ -147,7 +281,25 @@ pub fn init(
         tree_sitter_rust::language(),
         vec![Arc::new(rust::RustLspAdapter)],
     );
+    language(
+        "scala",
+        tree_sitter_scala::language(),
+        vec![Arc::new(scala::ScalaLspAdapter)],
+    );
+    language(
+        "terraform",
+        tree_sitter_hcl::language(),
+        vec![Arc::new(terraform::TerraformLspAdapter)],
+    );
+    language(
+        "terraform-vars",
+        tree_sitter_hcl::language(),
+        vec![Arc::new(terraform::TerraformLspAdapter)],
+    );
     language("toml", tree_sitter_toml::language(), vec![]);
+    // The TypeScript server is shared by all of these languages, so it loads the plugin that
+    // Vue components are understood with whenever Vue runs in hybrid mode.
+    let vue_hybrid_mode = VueSettings::get(None, cx).hybrid_mode;
     match &DenoSettings::get(None, cx).enable {
         true => {
             language(
@@ -177,7 +329,10 @@ pub fn init(
                 "tsx",
                 tree_sitter_typescript::language_tsx(),
                 vec![
-                    Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
+                    Arc::new(typescript::TypeScriptLspAdapter::new(
+                        node_runtime.clone(),
+                        vue_hybrid_mode,
+                    )),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
                 ],
@@ -186,7 +341,10 @@ pub fn init(
                 "typescript",
                 tree_sitter_typescript::language_typescript(),
                 vec![
-                    Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
+                    Arc::new(typescript::TypeScriptLspAdapter::new(
+                        node_runtime.clone(),
+                        vue_hybrid_mode,
+                    )),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                 ],
             );
@@ -194,7 +352,10 @@ pub fn init(
                 "javascript",
                 tree_sitter_typescript::language_tsx(),
                 vec![
-                    Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
+                    Arc::new(typescript::TypeScriptLspAdapter::new(
+                        node_runtime.clone(),
+                        vue_hybrid_mode,
+                    )),
                     Arc::new(typescript::EsLintLspAdapter::new(node_runtime.clone())),
                     Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
                 ],
@@ -229,11 +390,48 @@ pub fn init(
         tree_sitter_lua::language(),
         vec![Arc::new(lua::LuaLspAdapter)],
     );
+    language(
+        "verilog",
+        tree_sitter_verilog::language(),
+        vec![Arc::new(verilog::VeribleLspAdapter)],
+    );
+    language(
+        "wgsl",
+        tree_sitter_wgsl::language(),
+        vec![Arc::new(wgsl::WgslLspAdapter)],
+    );
     language(
         "yaml",
         tree_sitter_yaml::language(),
         vec![Arc::new(yaml::YamlLspAdapter::new(node_runtime.clone()))],
     );
+    match &SqlSettings::get(None, cx).lsp {
+        sql::SqlLspSetting::Sqls => language(
+            "sql",
+            tree_sitter_sequel::language(),
+            vec![Arc::new(sql::SqlsLspAdapter)],
+        ),
+        sql::SqlLspSetting::SqlLanguageServer => language(
+            "sql",
+            tree_sitter_sequel::language(),
+            vec![Arc::new(sql::SqlLanguageServerAdapter::new(
+                node_runtime.clone(),
+            ))],
+        ),
+    }
+    language(
+        "astro",
+        tree_sitter_astro::language(),
+        vec![
+            Arc::new(astro::AstroLspAdapter::new(node_runtime.clone())),
+            Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
+        ],
+    );
+    language(
+        "starlark",
+        tree_sitter_starlark::language(),
+        vec![Arc::new(starlark::StarlarkLspAdapter)],
+    );
     language(
         "svelte",
         tree_sitter_svelte::language(),
@@ -242,6 +440,16 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "swift",
+        tree_sitter_swift::language(),
+        vec![Arc::new(swift::SwiftLspAdapter)],
+    );
+    language(
+        "perl",
+        tree_sitter_perl::language(),
+        vec![Arc::new(perl::PerlLspAdapter)],
+    );
     language(
         "php",
         tree_sitter_php::language(),
@@ -250,20 +458,58 @@ pub fn init(
             Arc::new(tailwind::TailwindLspAdapter::new(node_runtime.clone())),
         ],
     );
+    language(
+        "powershell",
+        tree_sitter_powershell::language(),
+        vec![Arc::new(powershell::PowerShellLspAdapter)],
+    );
+    language(
+        "proto",
+        tree_sitter_proto::language(),
+        vec![Arc::new(proto::ProtoLspAdapter)],
+    );
 
     language("elm", tree_sitter_elm::language(), vec![]);
     language("glsl", tree_sitter_glsl::language(), vec![]);
-    language("nix", tree_sitter_nix::language(), vec![]);
+    language(
+        "nix",
+        tree_sitter_nix::language(),
+        vec![Arc::new(nix::NilLspAdapter::new())],
+    );
+    language(
+        "ocaml",
+        tree_sitter_ocaml::language_ocaml(),
+        vec![Arc::new(ocaml::OCamlLspAdapter)],
+    );
+    language(
+        "ocaml-interface",
+        tree_sitter_ocaml::language_ocaml_interface(),
+        vec![Arc::new(ocaml::OCamlLspAdapter)],
+    );
     language(
         "nu",
         tree_sitter_nu::language(),
         vec![Arc::new(nu::NuLanguageServer {})],
     );
-    language(
-        "vue",
-        tree_sitter_vue::language(),
-        vec![Arc::new(vue::VueLspAdapter::new(node_runtime))],
-    );
+    if vue_hybrid_mode {
+        language(
+            "vue",
+            tree_sitter_vue::language(),
+            vec![
+                Arc::new(vue::VueLspAdapter::new(node_runtime.clone(), true)),
+                Arc::new(typescript::TypeScriptLspAdapter::new(
+                    node_runtime,
+                    vue_hybrid_mode,
+                )),
+            ],
+        );
+    } else {
+        language(
+            "vue",
+            tree_sitter_vue::language(),
+            vec![Arc::new(vue::VueLspAdapter::new(node_runtime, false))],
+        );
+    }
     language(
         "uiua",
         tree_sitter_uiua::language(),
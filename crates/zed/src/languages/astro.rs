@@ -0,0 +1,161 @@
+use anyhow::{anyhow, ensure, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+const SERVER_PATH: &'static str = "node_modules/@astrojs/language-server/bin/nodeServer.js";
+const TYPESCRIPT_PATH: &'static str = "node_modules/typescript/lib";
+
+fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![server_path.into(), "--stdio".into()]
+}
+
+struct AstroLspVersion {
+    server_version: String,
+    typescript_version: String,
+}
+
+pub struct AstroLspAdapter {
+    node: Arc<dyn NodeRuntime>,
+    typescript_install_path: Mutex<Option<PathBuf>>,
+}
+
+impl AstroLspAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        AstroLspAdapter {
+            node,
+            typescript_install_path: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for AstroLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("astro-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "astro"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(AstroLspVersion {
+            server_version: self
+                .node
+                .npm_package_latest_version("@astrojs/language-server")
+                .await?,
+            typescript_version: self.node.npm_package_latest_version("typescript").await?,
+        }) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<AstroLspVersion>().unwrap();
+        let server_path = container_dir.join(SERVER_PATH);
+
+        if fs::metadata(&server_path).await.is_err() {
+            self.node
+                .npm_install_packages(
+                    &container_dir,
+                    &[
+                        ("@astrojs/language-server", version.server_version.as_str()),
+                        ("typescript", version.typescript_version.as_str()),
+                    ],
+                )
+                .await?;
+        }
+        ensure!(
+            fs::metadata(&server_path).await.is_ok(),
+            "@astrojs/language-server package installation failed"
+        );
+        *self.typescript_install_path.lock() = Some(container_dir.join(TYPESCRIPT_PATH));
+
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        let binary = get_cached_server_binary(container_dir.clone(), &*self.node).await?;
+        *self.typescript_install_path.lock() = Some(container_dir.join(TYPESCRIPT_PATH));
+        Some(binary)
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        // The server type checks the frontmatter with the TypeScript of the project, and
+        // falls back to the one installed next to it when the project has none.
+        let typescript_sdk_path = self.typescript_install_path.lock().clone();
+        Some(json!({
+            "typescript": {
+                "tsdk": typescript_sdk_path
+            },
+            "contentIntellisense": true
+        }))
+    }
+
+    fn prettier_plugins(&self) -> &[&'static str] {
+        &["prettier-plugin-astro"]
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Astro".into(), "astro".into())])
+    }
+}
+
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<LanguageServerBinary> {
+    (|| async move {
+        let server_path = container_dir.join(SERVER_PATH);
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: node.binary_path().await?,
+                arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!(
+                "missing executable in directory {:?}",
+                container_dir
+            ))
+        }
+    })()
+    .await
+    .log_err()
+}
@@ -0,0 +1,31 @@
+(tag_name) @tag
+(erroneous_end_tag_name) @keyword
+(doctype) @constant
+(attribute_name) @property
+(attribute_value) @string
+(quoted_attribute_value) @string
+(comment) @comment
+
+((tag_name) @type
+ (#match? @type "^[A-Z]"))
+
+"---" @punctuation.delimiter
+
+[
+  (html_interpolation)
+  (attribute_interpolation)
+] @embedded
+
+"=" @operator
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"
+] @punctuation.bracket
+
+[
+  "{"
+  "}"
+] @punctuation.special
@@ -0,0 +1,3 @@
+(element
+  (start_tag) @start
+  (end_tag)? @end) @indent
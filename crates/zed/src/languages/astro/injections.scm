@@ -0,0 +1,35 @@
+; The frontmatter is TypeScript, as are the expressions in the markup.
+
+(frontmatter
+  (frontmatter_js_block) @content
+  (#set! "language" "typescript"))
+
+(attribute_interpolation
+  (attribute_js_expr) @content
+  (#set! "language" "typescript"))
+
+(html_interpolation
+  (permissible_text) @content
+  (#set! "language" "typescript"))
+
+((script_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "type=\"(text/partytown|application/(ld\\+)?json)\"")
+  (#set! "language" "typescript"))
+
+((style_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "lang=")
+  (#set! "language" "css"))
+
+((style_element
+    (start_tag
+      (attribute
+        (attribute_name) @_attribute_name
+        (quoted_attribute_value (attribute_value) @_language)))
+    (raw_text) @content)
+  (#eq? @_attribute_name "lang")
+  (#match? @_language "^(css|postcss)$")
+  (#set! "language" "css"))
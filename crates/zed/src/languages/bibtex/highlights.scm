@@ -0,0 +1,44 @@
+(comment) @comment
+(junk) @comment
+
+[
+  (entry_type)
+  (string_type)
+  (preamble_type)
+  (comment_type)
+] @keyword
+
+[
+  (key_brace)
+  (key_paren)
+] @label
+
+(field
+  name: (identifier) @property)
+
+(string
+  name: (identifier) @constant)
+
+(token (identifier) @constant)
+
+[
+  (brace_word)
+  (quote_word)
+] @string
+
+(number) @number
+(command) @function
+
+[
+  "="
+  "#"
+] @operator
+
+[
+  "{"
+  "}"
+  "("
+  ")"
+] @punctuation.bracket
+
+"," @punctuation.delimiter
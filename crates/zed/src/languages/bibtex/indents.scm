@@ -0,0 +1,2 @@
+(entry "{" "}" @end) @indent
+(entry "(" ")" @end) @indent
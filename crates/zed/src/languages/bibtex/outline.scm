@@ -0,0 +1,11 @@
+(entry
+    ty: (entry_type) @context
+    key: (key_brace) @name) @item
+
+(entry
+    ty: (entry_type) @context
+    key: (key_paren) @name) @item
+
+(string
+    ty: (string_type) @context
+    name: (identifier) @name) @item
@@ -1,13 +1,12 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
 pub use language::*;
 use lsp::LanguageServerBinary;
-use smol::fs::{self, File};
+use smol::fs;
 use std::{any::Any, path::PathBuf, sync::Arc};
 use util::{
-    fs::remove_matching,
-    github::{latest_github_release, GitHubLspBinaryVersion},
+    github::{platform_asset_version, GitHubLspBinaryVersion},
     ResultExt,
 };
 
@@ -23,21 +22,25 @@ impl super::LspAdapter for CLspAdapter {
         "clangd"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "clangd",
+            // Homebrew doesn't link LLVM's executables into its `bin` directory.
+            search_paths: vec![
+                PathBuf::from("/opt/homebrew/opt/llvm/bin"),
+                PathBuf::from("/usr/local/opt/llvm/bin"),
+            ],
+            arguments: Vec::new(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release = latest_github_release("clangd/clangd", false, delegate.http_client()).await?;
-        let asset_name = format!("clangd-mac-{}.zip", release.name);
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.name,
-            url: asset.browser_download_url.clone(),
-        };
+        let release = github_release_to_install("clangd/clangd", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "clangd-", ".zip", delegate.http_client()).await?;
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -53,19 +56,9 @@ impl super::LspAdapter for CLspAdapter {
         let binary_path = version_dir.join("bin/clangd");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
 
             let unzip_status = smol::process::Command::new("unzip")
                 .current_dir(&container_dir)
@@ -76,16 +69,21 @@ impl super::LspAdapter for CLspAdapter {
             if !unzip_status.success() {
                 Err(anyhow!("failed to unzip clangd archive"))?;
             }
-
-            remove_matching(&container_dir, |entry| entry != version_dir).await;
+            fs::remove_file(&zip_path).await.log_err();
         }
 
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("clangd_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -259,6 +257,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             Ok(LanguageServerBinary {
                 path: clangd_bin,
                 arguments: vec![],
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
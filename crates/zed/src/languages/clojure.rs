@@ -0,0 +1,219 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+/// The files that declare a Clojure project's dependencies and source paths, to one of
+/// the tools that clojure-lsp reads them from.
+const PROJECT_FILE_NAMES: &[&str] = &[
+    "deps.edn",
+    "project.clj",
+    "bb.edn",
+    "shadow-cljs.edn",
+    "build.boot",
+];
+
+pub struct ClojureLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ClojureLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("clojure-lsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "clojure"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "clojure-lsp",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("clojure-lsp/clojure-lsp", false, delegate).await?;
+        let version = platform_asset_version(
+            release,
+            "clojure-lsp-native-",
+            ".zip",
+            delegate.http_client(),
+        )
+        .await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let zip_path = container_dir.join(format!("clojure-lsp_{}.zip", version.name));
+        let version_dir = container_dir.join(format!("clojure-lsp_{}", version.name));
+        let binary_path = version_dir.join("clojure-lsp");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+
+            fs::create_dir_all(&version_dir)
+                .await
+                .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+            let unzip_status = smol::process::Command::new("unzip")
+                .arg(&zip_path)
+                .arg("-d")
+                .arg(&version_dir)
+                .output()
+                .await?
+                .status;
+            if !unzip_status.success() {
+                Err(anyhow!("failed to unzip clojure-lsp archive"))?;
+            }
+            fs::remove_file(&zip_path).await.log_err();
+        }
+
+        fs::set_permissions(
+            &binary_path,
+            <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+        )
+        .await?;
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("clojure-lsp_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        clojure_project_root(worktree_path, fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("Clojure".into(), "clojure".into()),
+            ("EDN".into(), "edn".into()),
+        ])
+    }
+}
+
+/// Finds the project that clojure-lsp should analyze: the nearest directory with its own
+/// `.lsp/config.edn`, or else the nearest one with a project file.
+async fn clojure_project_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    if let Some(root) = nearest_ancestor_with(path, &[".lsp/config.edn"], fs).await {
+        return Some(root);
+    }
+    nearest_ancestor_with(path, PROJECT_FILE_NAMES, fs).await
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("clojure-lsp_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("clojure-lsp");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary::new(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing clojure-lsp binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_clojure_project_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &ClojureLspAdapter,
+            &[
+                (
+                    &["/root/deps.edn"],
+                    "/root/modules/api/src/api",
+                    Some("/root"),
+                ),
+                (
+                    &["/root/deps.edn", "/root/modules/api/project.clj"],
+                    "/root/modules/api/src/api",
+                    Some("/root/modules/api"),
+                ),
+                (
+                    &[
+                        "/root/.lsp/config.edn",
+                        "/root/deps.edn",
+                        "/root/modules/api/project.clj",
+                    ],
+                    "/root/modules/api/src/api",
+                    Some("/root"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,66 @@
+(comment) @comment
+(dis_expr) @comment
+
+(str_lit) @string
+(regex_lit) @string.regex
+(char_lit) @string.special
+(num_lit) @number
+(bool_lit) @boolean
+(nil_lit) @constant.builtin
+
+(kwd_lit) @constant
+
+(sym_lit
+  (sym_ns) @namespace)
+
+((sym_lit) @keyword
+  (#match? @keyword "^(def|defn|defn-|defmacro|defmulti|defmethod|defprotocol|defrecord|deftype|definterface|defonce|deftest|ns|fn|let|letfn|loop|recur|if|if-let|if-not|if-some|when|when-let|when-not|when-some|when-first|cond|condp|cond->|cond->>|case|do|doto|try|catch|finally|throw|quote|var|binding|for|doseq|dotimes|while|new|set!|monitor-enter|monitor-exit|->|->>|as->|some->|some->>|reify|proxy|extend-type|extend-protocol|comment)$"))
+
+(list_lit
+  .
+  (sym_lit) @function)
+
+(list_lit
+  .
+  (sym_lit) @keyword
+  .
+  (sym_lit) @function
+  (#match? @keyword "^(defn|defn-|defmacro|defmulti|defmethod)$"))
+
+(list_lit
+  .
+  (sym_lit) @keyword
+  .
+  (sym_lit) @type
+  (#match? @keyword "^(defprotocol|defrecord|deftype|definterface)$"))
+
+(list_lit
+  .
+  (sym_lit) @keyword
+  .
+  (sym_lit) @namespace
+  (#eq? @keyword "ns"))
+
+((sym_lit) @variable.special
+  (#match? @variable.special "^(&|%[0-9&]?)$"))
+
+[
+  (quoting_lit)
+  (syn_quoting_lit)
+  (unquoting_lit)
+  (unquote_splicing_lit)
+  (derefing_lit)
+  (var_quoting_lit)
+] @operator
+
+(meta_lit
+  "^" @punctuation.special)
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
@@ -0,0 +1,15 @@
+(list_lit
+    .
+    (sym_lit) @context
+    .
+    (sym_lit) @name
+    (#match? @context "^(ns|def|defn|defn-|defmacro|defmulti|defmethod|defprotocol|defrecord|deftype|definterface|defonce|deftest|s/def|s/fdef)$")) @item
+
+(list_lit
+    .
+    (sym_lit) @context
+    .
+    (meta_lit)
+    .
+    (sym_lit) @name
+    (#match? @context "^(ns|def|defn|defn-|defmacro|defonce)$")) @item
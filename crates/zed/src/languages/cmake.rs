@@ -0,0 +1,343 @@
+use super::project_root::outermost_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, LanguageServerName, LspAdapter,
+    LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use settings::Settings;
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CMakeSettings {
+    pub lsp: CMakeLspSetting,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CMakeLspSetting {
+    Neocmakelsp,
+    CmakeLanguageServer,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct CMakeSettingsContent {
+    lsp: Option<CMakeLspSetting>,
+}
+
+impl Settings for CMakeSettings {
+    const KEY: Option<&'static str> = Some("cmake");
+
+    type FileContent = CMakeSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut gpui::AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+fn neocmakelsp_arguments() -> Vec<OsString> {
+    vec!["--stdio".into()]
+}
+
+pub struct NeocmakelspAdapter;
+
+#[async_trait]
+impl LspAdapter for NeocmakelspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("neocmakelsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "neocmakelsp"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "neocmakelsp",
+            search_paths: vec![util::paths::HOME.join(".cargo/bin")],
+            arguments: neocmakelsp_arguments(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            github_release_to_install("Decodetalkers/neocmakelsp", false, delegate).await?;
+        let version = platform_asset_version(release, "neocmakelsp-", "", delegate.http_client())
+            .await
+            .context(
+                "neocmakelsp isn't published for this platform. Set `cmake.lsp` to \
+                 `cmake_language_server` in your settings to use cmake-language-server instead",
+            )?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("neocmakelsp_{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            arguments: neocmakelsp_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("neocmakelsp_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_neocmakelsp_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_neocmakelsp_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        cmake_project_root(worktree_path, fs).await
+    }
+}
+
+pub struct CMakeLanguageServerAdapter;
+
+#[async_trait]
+impl LspAdapter for CMakeLanguageServerAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("cmake-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "cmake"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "cmake-language-server",
+            search_paths: vec![util::paths::HOME.join(".local/bin")],
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if super::venv::find_python().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the CMake language server `cmake-language-server`, because `python3` was not found. \
+                 Install Python from https://www.python.org/downloads and make sure `python3` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let version = super::venv::package_version("cmake-language-server", delegate).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let venv_dir = container_dir.join(format!("cmake-language-server_{version}"));
+        let binary_path = super::venv::binary_path(&venv_dir, "cmake-language-server");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            super::venv::install_package(&venv_dir, "cmake-language-server", &version).await?;
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("cmake-language-server_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_cmake_language_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_cmake_language_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        // The variables and targets of the project are completed from the CMake File API
+        // replies in its build directory, once it's been configured.
+        Some(json!({
+            "buildDirectory": "build"
+        }))
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        cmake_project_root(worktree_path, fs).await
+    }
+}
+
+/// Finds the top-level project: the outermost directory with a `CMakeLists.txt`, whose
+/// subdirectories are included with `add_subdirectory`, so that the modules and functions
+/// it includes are found from any of them.
+async fn cmake_project_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    outermost_ancestor_with(path, &["CMakeLists.txt"], fs).await
+}
+
+async fn get_cached_neocmakelsp_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("neocmakelsp_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        let path = last_binary_path.ok_or_else(|| anyhow!("no cached binary"))?;
+        Ok(LanguageServerBinary {
+            arguments: neocmakelsp_arguments(),
+            ..LanguageServerBinary::new(path)
+        })
+    })
+    .await
+    .log_err()
+}
+
+async fn get_cached_cmake_language_server_binary(
+    container_dir: PathBuf,
+) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_venv_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("cmake-language-server_"))
+            {
+                last_venv_dir = Some(entry.path());
+            }
+        }
+
+        let venv_dir = last_venv_dir.ok_or_else(|| anyhow!("no cached environment"))?;
+        let binary_path = super::venv::binary_path(&venv_dir, "cmake-language-server");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary::new(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing cmake-language-server in environment {:?}",
+                venv_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_cmake_project_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &NeocmakelspAdapter,
+            &[
+                (
+                    &["/root/libs/core/CMakeLists.txt"],
+                    "/root/libs/core/src",
+                    Some("/root/libs/core"),
+                ),
+                (
+                    &["/root/CMakeLists.txt", "/root/libs/core/CMakeLists.txt"],
+                    "/root/libs/core/src",
+                    Some("/root"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
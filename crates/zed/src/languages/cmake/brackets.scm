@@ -0,0 +1,2 @@
+("(" @open ")" @close)
+("\"" @open "\"" @close)
@@ -0,0 +1,61 @@
+[
+  (line_comment)
+  (bracket_comment)
+] @comment
+
+[
+  (quoted_argument)
+  (bracket_argument)
+] @string
+
+(escape_sequence) @string.escape
+
+(normal_var
+  ["$" "{" "}"] @punctuation.special)
+
+(env_var
+  ["$" "ENV" "{" "}"] @punctuation.special)
+
+(cache_var
+  ["$" "CACHE" "{" "}"] @punctuation.special)
+
+(variable) @variable
+
+((unquoted_argument) @constant.builtin
+  (#match? @constant.builtin "^(?i)(ON|OFF|TRUE|FALSE|YES|NO|Y|N|IGNORE|NOTFOUND)$"))
+
+((unquoted_argument) @keyword
+  (#match? @keyword "^(NOT|AND|OR|COMMAND|POLICY|TARGET|TEST|DEFINED|IN_LIST|EXISTS|IS_NEWER_THAN|IS_DIRECTORY|IS_SYMLINK|IS_ABSOLUTE|MATCHES|LESS|GREATER|EQUAL|LESS_EQUAL|GREATER_EQUAL|STRLESS|STRGREATER|STREQUAL|VERSION_LESS|VERSION_GREATER|VERSION_EQUAL)$"))
+
+((unquoted_argument) @property
+  (#match? @property "^(PUBLIC|PRIVATE|INTERFACE|REQUIRED|COMPONENTS|CONFIG|QUIET|STATIC|SHARED|MODULE|OBJECT|IMPORTED|ALIAS|DESTINATION|FILES|TARGETS|VERSION|LANGUAGES|DESCRIPTION|CACHE|PARENT_SCOPE|FORCE)$"))
+
+(normal_command
+  (identifier) @function)
+
+(function_command
+  (argument_list
+    . (argument) @function))
+
+(macro_command
+  (argument_list
+    . (argument) @function))
+
+[
+  (function)
+  (endfunction)
+  (macro)
+  (endmacro)
+  (if)
+  (elseif)
+  (else)
+  (endif)
+  (foreach)
+  (endforeach)
+  (while)
+  (endwhile)
+  (block)
+  (endblock)
+] @keyword
+
+["(" ")"] @punctuation.bracket
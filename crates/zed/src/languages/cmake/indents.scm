@@ -0,0 +1,11 @@
+(_ "(" ")" @end) @indent
+
+(function_def (endfunction_command) @end) @indent
+(macro_def (endmacro_command) @end) @indent
+(if_condition (endif_command) @end) @indent
+(foreach_loop (endforeach_command) @end) @indent
+(while_loop (endwhile_command) @end) @indent
+(block_def (endblock_command) @end) @indent
+
+(elseif_command) @outdent
+(else_command) @outdent
@@ -0,0 +1,17 @@
+(function_def
+    (function_command
+        (function) @context
+        (argument_list
+            . (argument) @name))) @item
+
+(macro_def
+    (macro_command
+        (macro) @context
+        (argument_list
+            . (argument) @name))) @item
+
+(normal_command
+    (identifier) @context
+    (argument_list
+        . (argument) @name)
+    (#match? @context "^(?i)(project|add_executable|add_library|add_custom_target)$")) @item
@@ -0,0 +1,210 @@
+use ::fs::Fs;
+use anyhow::{anyhow, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    fs::find_executable,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+pub struct CrystalLspAdapter;
+
+#[async_trait]
+impl LspAdapter for CrystalLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("crystalline".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "crystalline"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "crystalline",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        // crystalline embeds the compiler, but not the standard library, which it loads from
+        // the installed Crystal.
+        if find_executable("crystal", std::env::var_os("PATH").as_deref(), &[])
+            .await
+            .is_none()
+        {
+            return Err(anyhow!(
+                "Could not run the Crystal language server `crystalline`, because `crystal` was not found. \
+                 Install Crystal from https://crystal-lang.org/install and make sure `crystal` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("elbywan/crystalline", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "crystalline_", ".gz", delegate.http_client()).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("crystalline_{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join("crystalline.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let mut file = File::create(&binary_path).await?;
+            futures::io::copy(decompressed_bytes, &mut file).await?;
+            fs::remove_file(&archive_path).await.log_err();
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("crystalline_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // crystalline compiles the project from the entry point named in its `shard.yml`, so
+        // that the types of the whole program are known.
+        shard_root(worktree_path, fs).await
+    }
+}
+
+/// Finds the nearest directory with a `shard.yml`, other than the ones of the dependencies
+/// that shards installs in the `lib` directory of a shard, which belong to that shard.
+async fn shard_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    for dir in path.ancestors() {
+        if !fs.is_file(&dir.join("shard.yml")).await {
+            continue;
+        }
+        let installed_in = dir
+            .parent()
+            .filter(|lib_dir| lib_dir.file_name().map_or(false, |name| name == "lib"))
+            .and_then(Path::parent);
+        match installed_in {
+            Some(shard) if fs.is_file(&shard.join("shard.yml")).await => continue,
+            _ => return Some(dir.to_path_buf()),
+        }
+    }
+    None
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("crystalline_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        let path = last_binary_path.ok_or_else(|| anyhow!("no cached binary"))?;
+        Ok(LanguageServerBinary::new(path))
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_shard_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &CrystalLspAdapter,
+            &[
+                (&["/root/lib/kemal/shard.yml"], "/root/src", None),
+                (
+                    &["/root/lib/kemal/shard.yml"],
+                    "/root/lib/kemal/src",
+                    Some("/root/lib/kemal"),
+                ),
+                (
+                    &["/root/lib/kemal/shard.yml", "/root/shard.yml"],
+                    "/root/src",
+                    Some("/root"),
+                ),
+                (
+                    &["/root/lib/kemal/shard.yml", "/root/shard.yml"],
+                    "/root/lib/kemal/src",
+                    Some("/root"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,13 @@
+("(" @open ")" @close)
+("[" @open "]" @close)
+("{" @open "}" @close)
+("\"" @open "\"" @close)
+("do" @open "end" @close)
+
+(interpolation "#{" @open "}" @close)
+
+(_ . "def" @open "end" @close)
+(_ . "class" @open "end" @close)
+(_ . "struct" @open "end" @close)
+(_ . "module" @open "end" @close)
+(_ . "macro" @open "end" @close)
@@ -0,0 +1,111 @@
+(comment) @comment
+
+[
+  (string)
+  (char)
+  (heredoc_body)
+] @string
+
+(string_escape_sequence) @string.escape
+(regex) @string.regex
+
+(symbol) @string.special.symbol
+
+[
+  (integer)
+  (float)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(nil) @constant.builtin
+(self) @variable.special
+
+(constant) @type
+
+[
+  (instance_var)
+  (class_var)
+] @property
+
+(method_def name: (_) @function)
+(abstract_method_def name: (_) @function)
+(macro_def name: (_) @function)
+(fun_def name: (_) @function)
+(call method: (identifier) @function)
+
+(param name: (identifier) @variable.parameter)
+
+(annotation) @attribute
+
+(interpolation
+  "#{" @punctuation.special
+  "}" @punctuation.special) @embedded
+
+[
+  "abstract"
+  "alias"
+  "annotation"
+  "as"
+  "begin"
+  "break"
+  "case"
+  "class"
+  "def"
+  "do"
+  "else"
+  "elsif"
+  "end"
+  "ensure"
+  "enum"
+  "extend"
+  "fun"
+  "if"
+  "in"
+  "include"
+  "lib"
+  "macro"
+  "module"
+  "next"
+  "of"
+  "private"
+  "protected"
+  "require"
+  "rescue"
+  "return"
+  "struct"
+  "then"
+  "unless"
+  "until"
+  "when"
+  "while"
+  "yield"
+] @keyword
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "!"
+  "&&"
+  "||"
+  "->"
+  "=>"
+  "<=>"
+] @operator
+
+["," ";" "." "::"] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
@@ -0,0 +1,19 @@
+(method_def "end" @end) @indent
+(class_def "end" @end) @indent
+(struct_def "end" @end) @indent
+(module_def "end" @end) @indent
+(enum_def "end" @end) @indent
+(macro_def "end" @end) @indent
+(lib_def "end" @end) @indent
+(begin "end" @end) @indent
+(do_end_block "end" @end) @indent
+
+(then) @indent
+
+(ensure) @outdent
+(rescue) @outdent
+(else) @outdent
+
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+(_ "(" ")" @end) @indent
@@ -0,0 +1,34 @@
+(class_def
+    "abstract"? @context
+    "class" @context
+    name: (_) @name) @item
+
+(struct_def
+    "abstract"? @context
+    "struct" @context
+    name: (_) @name) @item
+
+(module_def
+    "module" @context
+    name: (_) @name) @item
+
+(enum_def
+    "enum" @context
+    name: (_) @name) @item
+
+(lib_def
+    "lib" @context
+    name: (_) @name) @item
+
+(method_def
+    "def" @context
+    name: (_) @name) @item
+
+(abstract_method_def
+    "abstract" @context
+    "def" @context
+    name: (_) @name) @item
+
+(macro_def
+    "macro" @context
+    name: (_) @name) @item
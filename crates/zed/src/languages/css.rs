@@ -73,6 +73,8 @@ impl LspAdapter for CssLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -117,6 +119,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
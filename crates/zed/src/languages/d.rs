@@ -0,0 +1,199 @@
+use super::project_root::outermost_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Result};
+use async_compression::futures::bufread::XzDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+/// The files that describe a dub package.
+const DUB_FILE_NAMES: &[&str] = &["dub.json", "dub.sdl"];
+
+pub struct ServeDLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ServeDLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("serve-d".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "serve-d"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "serve-d",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("Pure-D/serve-d", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "serve-d_", ".tar.xz", delegate.http_client()).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("serve-d_{}", version.name));
+        let binary_path = version_dir.join("serve-d");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join("serve-d.tar.xz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                XzDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&version_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("serve-d_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // serve-d formats with the dfmt that's built into it, which reads its style from
+        // the project's `.editorconfig`, like the `dfmt` command does. The style can also be
+        // set with `dfmt` in the `settings` of `serve-d` in the `lsp` settings.
+        json!({
+            "d": {
+                "enableFormatting": true,
+                "enableDubLinting": true,
+                // Every dub package in the project is loaded without asking, since there's
+                // no prompt to ask with.
+                "manyProjectsAction": "load",
+            }
+        })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        dub_project_root(worktree_path, fs).await
+    }
+}
+
+/// Finds the root package of the dub project: the outermost directory with a dub file, so
+/// that the sub-packages in its subdirectories are loaded along with it.
+async fn dub_project_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    outermost_ancestor_with(path, DUB_FILE_NAMES, fs).await
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("serve-d_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("serve-d");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary::new(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing serve-d binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_dub_project_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &ServeDLspAdapter,
+            &[
+                (
+                    &["/root/core/dub.sdl"],
+                    "/root/core/source",
+                    Some("/root/core"),
+                ),
+                (
+                    &["/root/core/dub.sdl", "/root/dub.json"],
+                    "/root/core/source",
+                    Some("/root"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,124 @@
+(comment) @comment
+
+[
+  (string_literal)
+  (char_literal)
+] @string
+
+[
+  (int_literal)
+  (float_literal)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(null) @constant.builtin
+
+[
+  (this)
+  (super)
+] @variable.special
+
+(module_fqn) @title
+
+(function_declaration (identifier) @function)
+(call_expression (identifier) @function)
+(call_expression (property_expression (identifier) @function .))
+
+(class_declaration (identifier) @type)
+(struct_declaration (identifier) @type)
+(union_declaration (identifier) @type)
+(interface_declaration (identifier) @type)
+(enum_declaration (identifier) @type)
+(type (identifier) @type)
+
+(at_attribute) @attribute
+
+(parameter (identifier) @variable.parameter)
+
+[
+  (module)
+  (import)
+  (class)
+  (struct)
+  (union)
+  (interface)
+  (enum)
+  (template)
+  (mixin)
+  (alias)
+  (function)
+  (delegate)
+  (if)
+  (else)
+  (switch)
+  (case)
+  (default)
+  (while)
+  (do)
+  (for)
+  (foreach)
+  (foreach_reverse)
+  (break)
+  (continue)
+  (goto)
+  (return)
+  (try)
+  (catch)
+  (finally)
+  (throw)
+  (new)
+  (cast)
+  (auto)
+  (const)
+  (immutable)
+  (shared)
+  (static)
+  (final)
+  (abstract)
+  (override)
+  (public)
+  (private)
+  (protected)
+  (package)
+  (export)
+  (extern)
+  (ref)
+  (scope)
+  (pure)
+  (nothrow)
+  (in)
+  (out)
+  (inout)
+  (unittest)
+  (version)
+  (debug)
+  (assert)
+] @keyword
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "~"
+  "!"
+  "&&"
+  "||"
+  "=>"
+] @operator
+
+[";" "," "."] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
@@ -0,0 +1,36 @@
+(module_declaration
+    (module) @context
+    (module_fqn) @name) @item
+
+(class_declaration
+    (class) @context
+    (identifier) @name) @item
+
+(struct_declaration
+    (struct) @context
+    (identifier) @name) @item
+
+(union_declaration
+    (union) @context
+    (identifier) @name) @item
+
+(interface_declaration
+    (interface) @context
+    (identifier) @name) @item
+
+(enum_declaration
+    (enum) @context
+    (identifier) @name) @item
+
+(template_declaration
+    (template) @context
+    (identifier) @name) @item
+
+(function_declaration
+    (identifier) @name) @item
+
+(constructor
+    (this) @name) @item
+
+(unittest_declaration
+    (unittest) @name) @item
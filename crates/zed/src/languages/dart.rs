@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use gpui::{actions, AppContext};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::{fs, process::Command};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::fs::find_executable;
+
+actions!(dart, [FlutterHotReload, FlutterHotRestart]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.on_action(|_: &FlutterHotReload, cx| signal_flutter_run("-USR1", cx));
+    cx.on_action(|_: &FlutterHotRestart, cx| signal_flutter_run("-USR2", cx));
+}
+
+/// `flutter run` hot reloads the app when it receives `SIGUSR1`, and restarts it when it
+/// receives `SIGUSR2`.
+fn signal_flutter_run(signal: &'static str, cx: &mut AppContext) {
+    cx.background_executor()
+        .spawn(async move {
+            let status = Command::new("pkill")
+                .args([signal, "-f", "flutter_tools.snapshot run"])
+                .status()
+                .await?;
+            if !status.success() {
+                Err(anyhow!("no running `flutter run` process was found"))?;
+            }
+            Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["language-server".into(), "--protocol=lsp".into()]
+}
+
+pub struct DartLspAdapter;
+
+#[async_trait]
+impl LspAdapter for DartLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("dart".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "dart"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "dart",
+            search_paths: Vec::new(),
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if find_sdk_binary().await.is_none() {
+            return Err(anyhow!(
+                "Could not start the Dart analysis server, because the Dart SDK was not found. \
+                 Install Dart from https://dart.dev/get-dart or Flutter from https://docs.flutter.dev/get-started/install, \
+                 and make sure `dart` is on your PATH or `FLUTTER_ROOT` is set."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // The analysis server is part of the Dart SDK, which the project's packages are
+        // resolved for, so the SDK's own copy is always used.
+        Ok(Box::new(()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        find_sdk_binary()
+            .await
+            .ok_or_else(|| anyhow!("the Dart SDK was not found"))
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        find_sdk_binary().await
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        // Closing labels name the widget that each bracket of a deeply nested Flutter build
+        // method closes, and are shown as inlay hints.
+        Some(json!({
+            "closingLabels": true,
+            "onlyAnalyzeProjectsWithOpenFiles": true,
+            "suggestFromUnimportedLibraries": true
+        }))
+    }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        json!({
+            "dart": {
+                "completeFunctionCalls": true
+            }
+        })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Dart".into(), "dart".into())])
+    }
+}
+
+/// Finds `dart` in the SDK named by `DART_SDK`, in the one that comes with the Flutter SDK
+/// named by `FLUTTER_ROOT`, or on the `PATH`, where Flutter installations often only put
+/// `flutter`.
+async fn find_sdk_binary() -> Option<LanguageServerBinary> {
+    let mut candidates = Vec::new();
+    if let Some(dart_sdk) = std::env::var_os("DART_SDK") {
+        candidates.push(PathBuf::from(dart_sdk).join("bin/dart"));
+    }
+    if let Some(flutter_root) = std::env::var_os("FLUTTER_ROOT") {
+        candidates.push(PathBuf::from(flutter_root).join("bin/cache/dart-sdk/bin/dart"));
+    }
+    let path_var = std::env::var_os("PATH");
+    if let Some(dart) = find_executable("dart", path_var.as_deref(), &[]).await {
+        candidates.push(dart);
+    }
+    if let Some(flutter) = find_executable("flutter", path_var.as_deref(), &[]).await {
+        if let Ok(flutter) = fs::canonicalize(&flutter).await {
+            if let Some(bin_dir) = flutter.parent() {
+                candidates.push(bin_dir.join("cache/dart-sdk/bin/dart"));
+            }
+        }
+    }
+
+    for path in candidates {
+        if fs::metadata(&path)
+            .await
+            .map_or(false, |metadata| metadata.is_file())
+        {
+            return Some(LanguageServerBinary {
+                path,
+                arguments: server_binary_arguments(),
+                socket: None,
+                env: None,
+            });
+        }
+    }
+    None
+}
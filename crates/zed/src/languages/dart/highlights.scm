@@ -0,0 +1,124 @@
+(comment) @comment
+(documentation_comment) @comment
+
+(string_literal) @string
+(template_substitution) @embedded
+(escape_sequence) @string.escape
+
+[
+  (decimal_integer_literal)
+  (hex_integer_literal)
+  (decimal_floating_point_literal)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+(null_literal) @constant.builtin
+
+(type_identifier) @type
+(void_type) @type
+
+(class_definition
+  name: (identifier) @type)
+
+(function_signature
+  name: (identifier) @function)
+
+(getter_signature
+  name: (identifier) @function)
+
+(setter_signature
+  name: (identifier) @function)
+
+(constructor_signature
+  name: (identifier) @function)
+
+(annotation
+  name: (identifier) @attribute)
+
+(this) @variable.special
+(super) @variable.special
+
+((identifier) @type
+  (#match? @type "^_?[A-Z]"))
+
+((identifier) @constant
+  (#match? @constant "^_?[A-Z][A-Z\\d_]+$"))
+
+[
+  "abstract"
+  "as"
+  "async"
+  "await"
+  (break_statement)
+  (case_builtin)
+  "catch"
+  "class"
+  (const_builtin)
+  "continue"
+  "default"
+  "do"
+  "else"
+  "enum"
+  "export"
+  "extends"
+  "extension"
+  "external"
+  "factory"
+  (final_builtin)
+  "finally"
+  "for"
+  "get"
+  "if"
+  "implements"
+  "import"
+  "in"
+  "is"
+  "late"
+  "library"
+  "mixin"
+  "new"
+  "on"
+  "operator"
+  "part"
+  "required"
+  "return"
+  "set"
+  "static"
+  "switch"
+  "throw"
+  "try"
+  "typedef"
+  "var"
+  "while"
+  "with"
+  "yield"
+] @keyword
+
+[
+  "@"
+  "=>"
+  ".."
+  "??"
+  "?."
+  (negation_operator)
+  "="
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ";"
+  "."
+  ","
+  ":"
+] @punctuation.delimiter
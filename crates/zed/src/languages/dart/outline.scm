@@ -0,0 +1,29 @@
+(class_definition
+    "class" @context
+    name: (identifier) @name) @item
+
+(mixin_declaration
+    "mixin" @context
+    (identifier) @name) @item
+
+(extension_declaration
+    "extension" @context
+    name: (identifier) @name) @item
+
+(enum_declaration
+    "enum" @context
+    name: (identifier) @name) @item
+
+(function_signature
+    name: (identifier) @name) @item
+
+(getter_signature
+    "get" @context
+    name: (identifier) @name) @item
+
+(setter_signature
+    "set" @context
+    name: (identifier) @name) @item
+
+(constructor_signature
+    name: (identifier) @name) @item
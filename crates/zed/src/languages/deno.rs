@@ -1,16 +1,19 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use collections::HashMap;
 use futures::StreamExt;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::json;
 use settings::Settings;
-use smol::{fs, fs::File};
-use std::{any::Any, env::consts, ffi::OsString, path::PathBuf, sync::Arc};
-use util::{fs::remove_matching, github::latest_github_release};
+use smol::fs;
+use std::{any::Any, ffi::OsString, path::PathBuf, sync::Arc};
+use util::github::platform_asset_version;
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
@@ -62,21 +65,21 @@ impl LspAdapter for DenoLspAdapter {
         "deno-ts"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "deno",
+            search_paths: vec![util::paths::HOME.join(".deno/bin")],
+            arguments: deno_server_binary_arguments(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release = latest_github_release("denoland/deno", false, delegate.http_client()).await?;
-        let asset_name = format!("deno-{}-apple-darwin.zip", consts::ARCH);
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.name,
-            url: asset.browser_download_url.clone(),
-        };
+        let release = github_release_to_install("denoland/deno", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "deno-", ".zip", delegate.http_client()).await?;
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -92,19 +95,9 @@ impl LspAdapter for DenoLspAdapter {
         let binary_path = version_dir.join("deno");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
 
             let unzip_status = smol::process::Command::new("unzip")
                 .current_dir(&container_dir)
@@ -117,16 +110,21 @@ impl LspAdapter for DenoLspAdapter {
             if !unzip_status.success() {
                 Err(anyhow!("failed to unzip deno archive"))?;
             }
-
-            remove_matching(&container_dir, |entry| entry != version_dir).await;
+            fs::remove_file(&zip_path).await.log_err();
         }
 
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: deno_server_binary_arguments(),
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("deno_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -210,6 +208,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
                     return Ok(LanguageServerBinary {
                         path: binary,
                         arguments: deno_server_binary_arguments(),
+                        socket: None,
+                        env: None,
                     });
                 }
             }
@@ -0,0 +1,4 @@
+(from_instruction
+    "FROM" @context
+    (image_spec) @name
+    (image_alias)? @name) @item
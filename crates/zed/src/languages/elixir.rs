@@ -1,27 +1,16 @@
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use gpui::{AsyncAppContext, Task};
 pub use language::*;
 use lsp::{CompletionItemKind, LanguageServerBinary, SymbolKind};
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use settings::Settings;
-use smol::fs::{self, File};
-use std::{
-    any::Any,
-    env::consts,
-    ops::Deref,
-    path::PathBuf,
-    sync::{
-        atomic::{AtomicBool, Ordering::SeqCst},
-        Arc,
-    },
-};
+use smol::fs;
+use std::{any::Any, ops::Deref, path::PathBuf, sync::Arc};
 use util::{
     async_maybe,
-    fs::remove_matching,
-    github::{latest_github_release, GitHubLspBinaryVersion},
+    github::{asset_sha256, platform_asset_version, GitHubLspBinaryVersion},
     ResultExt,
 };
 
@@ -75,43 +64,25 @@ impl LspAdapter for ElixirLspAdapter {
         "elixir-ls"
     }
 
-    fn will_start_server(
-        &self,
-        delegate: &Arc<dyn LspAdapterDelegate>,
-        cx: &mut AsyncAppContext,
-    ) -> Option<Task<Result<()>>> {
-        static DID_SHOW_NOTIFICATION: AtomicBool = AtomicBool::new(false);
-
-        const NOTIFICATION_MESSAGE: &str = "Could not run the elixir language server, `elixir-ls`, because `elixir` was not found.";
-
-        let delegate = delegate.clone();
-        Some(cx.spawn(|cx| async move {
-            let elixir_output = smol::process::Command::new("elixir")
-                .args(["--version"])
-                .output()
-                .await;
-            if elixir_output.is_err() {
-                if DID_SHOW_NOTIFICATION
-                    .compare_exchange(false, true, SeqCst, SeqCst)
-                    .is_ok()
-                {
-                    cx.update(|cx| {
-                        delegate.show_notification(NOTIFICATION_MESSAGE, cx);
-                    })?
-                }
-                return Err(anyhow!("cannot run elixir-ls"));
-            }
-
-            Ok(())
-        }))
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        let elixir_output = smol::process::Command::new("elixir")
+            .arg("--version")
+            .output()
+            .await;
+        if elixir_output.is_err() {
+            return Err(anyhow!(
+                "Could not run the Elixir language server `elixir-ls`, because `elixir` was not found. \
+                 Install Elixir from https://elixir-lang.org/install.html and make sure `elixir` is on your PATH."
+            ));
+        }
+        Ok(())
     }
 
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let http = delegate.http_client();
-        let release = latest_github_release("elixir-lsp/elixir-ls", false, http).await?;
+        let release = github_release_to_install("elixir-lsp/elixir-ls", false, delegate).await?;
         let version_name = release
             .name
             .strip_prefix("Release ")
@@ -126,6 +97,7 @@ impl LspAdapter for ElixirLspAdapter {
             .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
 
         let version = GitHubLspBinaryVersion {
+            sha256: asset_sha256(&release, asset, delegate.http_client()).await?,
             name: version_name,
             url: asset.browser_download_url.clone(),
         };
@@ -140,25 +112,13 @@ impl LspAdapter for ElixirLspAdapter {
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
         let zip_path = container_dir.join(format!("elixir-ls_{}.zip", version.name));
-        let folder_path = container_dir.join("elixir-ls");
+        let folder_path = container_dir.join(format!("elixir-ls_{}", version.name));
         let binary_path = folder_path.join("language_server.sh");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let mut file = File::create(&zip_path)
-                .await
-                .with_context(|| format!("failed to create file {}", zip_path.display()))?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
 
             fs::create_dir_all(&folder_path)
                 .await
@@ -173,16 +133,21 @@ impl LspAdapter for ElixirLspAdapter {
             if !unzip_status.success() {
                 Err(anyhow!("failed to unzip elixir-ls archive"))?;
             }
-
-            remove_matching(&container_dir, |entry| entry != folder_path).await;
+            fs::remove_file(&zip_path).await.log_err();
         }
 
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("elixir-ls_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -285,16 +250,36 @@ impl LspAdapter for ElixirLspAdapter {
 async fn get_cached_server_binary_elixir_ls(
     container_dir: PathBuf,
 ) -> Option<LanguageServerBinary> {
-    let server_path = container_dir.join("elixir-ls/language_server.sh");
-    if server_path.exists() {
-        Some(LanguageServerBinary {
-            path: server_path,
-            arguments: vec![],
-        })
-    } else {
-        log::error!("missing executable in directory {:?}", server_path);
-        None
-    }
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("elixir-ls_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = version_dir.join("language_server.sh");
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: server_path,
+                arguments: vec![],
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!("missing executable in directory {:?}", version_dir))
+        }
+    })
+    .await
+    .log_err()
 }
 
 pub struct NextLspAdapter;
@@ -313,24 +298,9 @@ impl LspAdapter for NextLspAdapter {
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release =
-            latest_github_release("elixir-tools/next-ls", false, delegate.http_client()).await?;
-        let version = release.name.clone();
-        let platform = match consts::ARCH {
-            "x86_64" => "darwin_amd64",
-            "aarch64" => "darwin_arm64",
-            other => bail!("Running on unsupported platform: {other}"),
-        };
-        let asset_name = format!("next_ls_{}", platform);
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: version,
-            url: asset.browser_download_url.clone(),
-        };
+        let release = github_release_to_install("elixir-tools/next-ls", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "next_ls_", "", delegate.http_client()).await?;
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -345,20 +315,13 @@ impl LspAdapter for NextLspAdapter {
         let binary_path = container_dir.join("next-ls");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .map_err(|err| anyhow!("error downloading release: {}", err))?;
-
-            let mut file = smol::fs::File::create(&binary_path).await?;
-            if !response.status().is_success() {
-                Err(anyhow!(
-                    "download failed with status {}",
-                    response.status().to_string()
-                ))?;
-            }
-            futures::io::copy(response.body_mut(), &mut file).await?;
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await?;
 
             fs::set_permissions(
                 &binary_path,
@@ -370,6 +333,8 @@ impl LspAdapter for NextLspAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec!["--stdio".into()],
+            socket: None,
+            env: None,
         })
     }
 
@@ -436,6 +401,8 @@ async fn get_cached_server_binary_next(container_dir: PathBuf) -> Option<Languag
             Ok(LanguageServerBinary {
                 path,
                 arguments: Vec::new(),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
@@ -477,6 +444,8 @@ impl LspAdapter for LocalLspAdapter {
         Ok(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            socket: None,
+            env: None,
         })
     }
 
@@ -489,6 +458,8 @@ impl LspAdapter for LocalLspAdapter {
         Some(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            socket: None,
+            env: None,
         })
     }
 
@@ -497,6 +468,8 @@ impl LspAdapter for LocalLspAdapter {
         Some(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            socket: None,
+            env: None,
         })
     }
 
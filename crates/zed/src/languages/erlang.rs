@@ -0,0 +1,263 @@
+use super::project_root::{nearest_ancestor_with, outermost_ancestor_with};
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+    process::Command,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    str,
+};
+use util::{
+    async_maybe,
+    github::{asset_sha256, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+pub struct ErlangLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ErlangLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("erlang_ls".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "erlang_ls"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "erlang_ls",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if otp_release().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the Erlang language server `erlang_ls`, because `erl` was not found. \
+                 Install Erlang/OTP from https://www.erlang.org/downloads and make sure `erl` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("erlang-ls/erlang_ls", false, delegate).await?;
+        let otp_release = otp_release()
+            .await
+            .ok_or_else(|| anyhow!("erl was not found"))?;
+
+        // erlang_ls is released as an escript for each of the OTP releases it supports.
+        // Escripts run on the release they were built with and the ones after it.
+        let asset_prefix = format!("erlang_ls-{}-", std::env::consts::OS);
+        let asset = release
+            .assets
+            .iter()
+            .filter_map(|asset| {
+                let asset_otp_release = asset
+                    .name
+                    .strip_prefix(&asset_prefix)?
+                    .strip_suffix(".tar.gz")?
+                    .parse::<u32>()
+                    .ok()?;
+                (asset_otp_release <= otp_release).then_some((asset_otp_release, asset))
+            })
+            .max_by_key(|(asset_otp_release, _)| *asset_otp_release)
+            .map(|(_, asset)| asset)
+            .ok_or_else(|| {
+                anyhow!(
+                    "erlang_ls {} isn't published for OTP {otp_release} on {}",
+                    release.name,
+                    std::env::consts::OS
+                )
+            })?;
+        let version = GitHubLspBinaryVersion {
+            sha256: asset_sha256(&release, asset, delegate.http_client()).await?,
+            url: asset.browser_download_url.clone(),
+            name: release.name.clone(),
+        };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("erlang_ls_{}", version.name));
+        let binary_path = version_dir.join("erlang_ls");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join("erlang_ls.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&version_dir).await.with_context(|| {
+                format!("failed to unpack erlang_ls into {}", version_dir.display())
+            })?;
+            fs::remove_file(&archive_path).await.log_err();
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("erlang_ls_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        erlang_project_root(worktree_path, fs).await
+    }
+}
+
+/// The major version of the Erlang/OTP release on the `PATH`.
+async fn otp_release() -> Option<u32> {
+    let output = Command::new("erl")
+        .args([
+            "-noshell",
+            "-eval",
+            "io:format(\"~s\", [erlang:system_info(otp_release)]), halt().",
+        ])
+        .output()
+        .await
+        .ok()?;
+    str::from_utf8(&output.stdout).ok()?.trim().parse().ok()
+}
+
+/// Finds the project that erlang_ls should index: the nearest directory with an
+/// `erlang_ls.config`, or else the root of the rebar3 project, whose `rebar.lock` is only
+/// written next to the top-level `rebar.config` of an umbrella project.
+async fn erlang_project_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    for file_name in ["erlang_ls.config", "rebar.lock"] {
+        if let Some(root) = nearest_ancestor_with(path, &[file_name], fs).await {
+            return Some(root);
+        }
+    }
+    outermost_ancestor_with(path, &["rebar.config", "erlang.mk"], fs).await
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("erlang_ls_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("erlang_ls");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary::new(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing erlang_ls escript in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_erlang_project_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &ErlangLspAdapter,
+            &[
+                (
+                    &["/root/apps/web/rebar.config", "/root/rebar.config"],
+                    "/root/apps/web/src",
+                    Some("/root"),
+                ),
+                (
+                    &[
+                        "/root/apps/web/rebar.config",
+                        "/root/apps/web/rebar.lock",
+                        "/root/rebar.config",
+                    ],
+                    "/root/apps/web/src",
+                    Some("/root/apps/web"),
+                ),
+                (
+                    &[
+                        "/root/apps/web/rebar.config",
+                        "/root/apps/web/rebar.lock",
+                        "/root/erlang_ls.config",
+                        "/root/rebar.config",
+                    ],
+                    "/root/apps/web/src",
+                    Some("/root"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
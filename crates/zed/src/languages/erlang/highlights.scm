@@ -0,0 +1,142 @@
+(comment) @comment
+
+[
+  (string)
+  (char)
+] @string
+
+[
+  (integer)
+  (float)
+] @number
+
+(var) @variable
+(atom) @constant
+
+((atom) @boolean
+  (#match? @boolean "^(true|false)$"))
+
+(module_attribute
+  name: (atom) @namespace)
+
+(function_clause
+  name: (atom) @function)
+
+(call
+  expr: (atom) @function)
+
+(call
+  expr: (remote
+    module: (remote_module
+      module: (atom) @namespace)
+    fun: (atom) @function))
+
+(record_decl
+  name: (atom) @type)
+
+(record_expr
+  name: (record_name) @type)
+
+(record_field
+  name: (atom) @property)
+
+(type_alias
+  name: (type_name
+    name: (atom) @type))
+
+(macro_call_expr) @constant.macro
+
+(wild_attribute
+  name: (attr_name) @attribute)
+
+[
+  "module"
+  "export"
+  "import"
+  "record"
+  "define"
+  "include"
+  "include_lib"
+  "type"
+  "opaque"
+  "spec"
+  "callback"
+  "behaviour"
+  "behavior"
+] @keyword
+
+[
+  "fun"
+  "end"
+  "case"
+  "of"
+  "if"
+  "receive"
+  "after"
+  "when"
+  "try"
+  "catch"
+  "begin"
+  "maybe"
+  "else"
+] @keyword
+
+[
+  "andalso"
+  "orelse"
+  "not"
+  "and"
+  "or"
+  "xor"
+  "band"
+  "bor"
+  "bxor"
+  "bnot"
+  "bsl"
+  "bsr"
+  "div"
+  "rem"
+  "->"
+  "="
+  "=="
+  "/="
+  "=:="
+  "=/="
+  "<"
+  "=<"
+  ">"
+  ">="
+  "+"
+  "-"
+  "*"
+  "/"
+  "++"
+  "--"
+  "!"
+  "<-"
+  "<="
+  "||"
+  "|"
+  "::"
+  "=>"
+  ":="
+  "?="
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<<"
+  ">>"
+] @punctuation.bracket
+
+[
+  ","
+  "."
+  ";"
+  ":"
+] @punctuation.delimiter
@@ -0,0 +1,19 @@
+(module_attribute
+    "module" @context
+    name: (_) @name) @item
+
+(fun_decl
+    clauses: (function_clause
+        name: (_) @name)) @item
+
+(record_decl
+    "record" @context
+    name: (_) @name) @item
+
+(type_alias
+    "type" @context
+    name: (_) @name) @item
+
+(pp_define
+    "define" @context
+    lhs: (_) @name) @item
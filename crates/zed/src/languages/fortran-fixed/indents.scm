@@ -0,0 +1,13 @@
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+
+(program (end_program_statement) @end) @indent
+(module (end_module_statement) @end) @indent
+(subroutine (end_subroutine_statement) @end) @indent
+(function (end_function_statement) @end) @indent
+(interface (end_interface_statement) @end) @indent
+(derived_type_definition (end_type_statement) @end) @indent
+(if_statement (end_if_statement) @end) @indent
+(do_loop_statement (end_do_loop_statement) @end) @indent
+(select_case_statement (end_select_statement) @end) @indent
+(where_statement (end_where_statement) @end) @indent
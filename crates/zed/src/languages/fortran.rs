@@ -0,0 +1,138 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{any::Any, ffi::OsString, path::PathBuf};
+use util::{async_maybe, ResultExt};
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec![
+        "--hover_signature".into(),
+        "--use_signature_help".into(),
+        "--lowercase_intrinsics".into(),
+    ]
+}
+
+pub struct FortranLspAdapter;
+
+#[async_trait]
+impl LspAdapter for FortranLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("fortls".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "fortls"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "fortls",
+            search_paths: vec![util::paths::HOME.join(".local/bin")],
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if super::venv::find_python().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the Fortran language server `fortls`, because `python3` was not found. \
+                 Install Python from https://www.python.org/downloads and make sure `python3` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let version = super::venv::package_version("fortls", delegate).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let venv_dir = container_dir.join(format!("fortls_{version}"));
+        let binary_path = super::venv::binary_path(&venv_dir, "fortls");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            super::venv::install_package(&venv_dir, "fortls", &version).await?;
+        }
+
+        Ok(LanguageServerBinary {
+            arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("fortls_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("Fortran".into(), "fortran".into()),
+            ("Fortran (Fixed Form)".into(), "fortran_fixed-form".into()),
+        ])
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_venv_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("fortls_"))
+            {
+                last_venv_dir = Some(entry.path());
+            }
+        }
+
+        let venv_dir = last_venv_dir.ok_or_else(|| anyhow!("no cached environment"))?;
+        let binary_path = super::venv::binary_path(&venv_dir, "fortls");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                arguments: server_binary_arguments(),
+                ..LanguageServerBinary::new(binary_path)
+            })
+        } else {
+            Err(anyhow!("missing fortls in environment {:?}", venv_dir))
+        }
+    })
+    .await
+    .log_err()
+}
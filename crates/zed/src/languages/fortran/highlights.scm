@@ -0,0 +1,105 @@
+(comment) @comment
+
+(string_literal) @string
+(number_literal) @number
+(boolean_literal) @boolean
+(null_literal) @constant.builtin
+
+(preproc_include) @preproc
+(preproc_def) @preproc
+(preproc_function_def) @preproc
+(preproc_call) @preproc
+
+(intrinsic_type) @type
+(derived_type (type_name) @type)
+(derived_type_statement (type_name) @type)
+(type_qualifier) @keyword
+
+(program_statement (name) @title)
+(end_program_statement (name) @title)
+(module_statement (name) @title)
+(end_module_statement (name) @title)
+(submodule_statement (name) @title)
+
+(subroutine_statement (name) @function)
+(end_subroutine_statement (name) @function)
+(function_statement (name) @function)
+(end_function_statement (name) @function)
+(subroutine_call . (identifier) @function)
+(call_expression . (identifier) @function)
+
+(keyword_argument name: (identifier) @property)
+(derived_type_member_expression (type_member) @property)
+
+(parameters (identifier) @variable.parameter)
+
+[
+  "program"
+  "module"
+  "submodule"
+  "subroutine"
+  "function"
+  "interface"
+  "type"
+  "contains"
+  "end"
+  "use"
+  "only"
+  "implicit"
+  "none"
+  "intent"
+  "in"
+  "out"
+  "inout"
+  "result"
+  "call"
+  "if"
+  "then"
+  "else"
+  "do"
+  "while"
+  "select"
+  "case"
+  "default"
+  "where"
+  "return"
+  "stop"
+  "allocate"
+  "deallocate"
+  "print"
+  "write"
+  "read"
+] @keyword
+
+(keyword_statement) @keyword
+
+[
+  "="
+  "=>"
+  "+"
+  "-"
+  "*"
+  "**"
+  "/"
+  "//"
+  "=="
+  "/="
+  "<"
+  "<="
+  ">"
+  ">="
+  "%"
+  "::"
+] @operator
+
+[
+  "\\.and\\."
+  "\\.or\\."
+  "\\.not\\."
+  "\\.eqv\\."
+  "\\.neqv\\."
+] @operator
+
+["," ":"] @punctuation.delimiter
+
+["(" ")" "[" "]" "(/" "/)"] @punctuation.bracket
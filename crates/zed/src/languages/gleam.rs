@@ -1,19 +1,35 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
 use std::any::Any;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::io::BufReader;
 use futures::StreamExt;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
 use lsp::LanguageServerBinary;
-use smol::fs;
-use util::github::{latest_github_release, GitHubLspBinaryVersion};
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use util::fs::find_executable;
+use util::github::{platform_asset_version, GitHubLspBinaryVersion};
+use util::paths::HOME;
 use util::{async_maybe, ResultExt};
 
+/// The Gleam toolchain to run the language server from: one that's already installed, or
+/// else a release that's downloaded.
+enum GleamVersion {
+    Toolchain(PathBuf),
+    Release(GitHubLspBinaryVersion),
+}
+
 fn server_binary_arguments() -> Vec<OsString> {
     vec!["lsp".into()]
 }
@@ -30,27 +46,31 @@ impl LspAdapter for GleamLspAdapter {
         "gleam"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "gleam",
+            search_paths: toolchain_bin_dirs(),
+            arguments: server_binary_arguments(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release =
-            latest_github_release("gleam-lang/gleam", false, delegate.http_client()).await?;
-
-        let asset_name = format!(
-            "gleam-{version}-{arch}-apple-darwin.tar.gz",
-            version = release.name,
-            arch = std::env::consts::ARCH
-        );
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        Ok(Box::new(GitHubLspBinaryVersion {
-            name: release.name,
-            url: asset.browser_download_url.clone(),
-        }))
+        // The language server is part of the compiler, and shares the build directory of
+        // the project with it. A toolchain that's installed is the one the project is built
+        // with, so it's used instead of a release, unless a version is pinned.
+        if delegate.pinned_server_version().is_none() {
+            if let Some(gleam) = find_toolchain().await {
+                return Ok(Box::new(GleamVersion::Toolchain(gleam)) as Box<_>);
+            }
+        }
+
+        let release = github_release_to_install("gleam-lang/gleam", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "gleam-", ".tar.gz", delegate.http_client()).await?;
+        Ok(Box::new(GleamVersion::Release(version)) as Box<_>)
     }
 
     async fn fetch_server_binary(
@@ -59,32 +79,57 @@ impl LspAdapter for GleamLspAdapter {
         container_dir: PathBuf,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
-        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let binary_path = container_dir.join("gleam");
+        let version = match *version.downcast::<GleamVersion>().unwrap() {
+            GleamVersion::Toolchain(gleam) => {
+                return Ok(LanguageServerBinary {
+                    arguments: server_binary_arguments(),
+                    ..LanguageServerBinary::new(gleam)
+                })
+            }
+            GleamVersion::Release(version) => version,
+        };
+        let version_dir = container_dir.join(format!("gleam_{}", version.name));
+        let binary_path = version_dir.join("gleam");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .map_err(|err| anyhow!("error downloading release: {}", err))?;
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+            let archive_path = container_dir.join("gleam.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
             let archive = Archive::new(decompressed_bytes);
-            archive.unpack(container_dir).await?;
+            archive.unpack(&version_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
         }
 
         Ok(LanguageServerBinary {
-            path: binary_path,
             arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("gleam_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        match find_toolchain().await {
+            Some(gleam) => Some(LanguageServerBinary {
+                arguments: server_binary_arguments(),
+                ..LanguageServerBinary::new(gleam)
+            }),
+            None => get_cached_server_binary(container_dir).await,
+        }
     }
 
     async fn installation_test_binary(
@@ -98,20 +143,61 @@ impl LspAdapter for GleamLspAdapter {
                 binary
             })
     }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // Each package is built, and formatted with `gleam format`, from the directory of
+        // its `gleam.toml`.
+        nearest_ancestor_with(worktree_path, &["gleam.toml"], fs).await
+    }
+}
+
+/// The directories that Gleam is commonly installed in, besides the ones on the `PATH`.
+fn toolchain_bin_dirs() -> Vec<PathBuf> {
+    vec![
+        HOME.join(".asdf/shims"),
+        HOME.join(".local/share/mise/shims"),
+        HOME.join(".local/bin"),
+    ]
+}
+
+async fn find_toolchain() -> Option<PathBuf> {
+    find_executable(
+        "gleam",
+        std::env::var_os("PATH").as_deref(),
+        &toolchain_bin_dirs(),
+    )
+    .await
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last = None;
+        let mut last_version_dir = None;
         let mut entries = fs::read_dir(&container_dir).await?;
         while let Some(entry) = entries.next().await {
-            last = Some(entry?.path());
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("gleam_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
         }
 
-        anyhow::Ok(LanguageServerBinary {
-            path: last.ok_or_else(|| anyhow!("no cached binary"))?,
-            arguments: server_binary_arguments(),
-        })
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("gleam");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                arguments: server_binary_arguments(),
+                ..LanguageServerBinary::new(binary_path)
+            })
+        } else {
+            Err(anyhow!(
+                "missing gleam binary in directory {:?}",
+                version_dir
+            ))
+        }
     })
     .await
     .log_err()
@@ -2,3 +2,26 @@
     (visibility_modifier)? @context
     "fn" @context
     name: (_) @name) @item
+
+(external_function
+    (visibility_modifier)? @context
+    "fn" @context
+    name: (_) @name) @item
+
+(type_definition
+    (visibility_modifier)? @context
+    (opacity_modifier)? @context
+    "type" @context
+    (type_name
+        name: (_) @name)) @item
+
+(type_alias
+    (visibility_modifier)? @context
+    "type" @context
+    (type_name
+        name: (_) @name)) @item
+
+(constant
+    (visibility_modifier)? @context
+    "const" @context
+    name: (_) @name) @item
@@ -1,24 +1,14 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use futures::StreamExt;
-use gpui::{AsyncAppContext, Task};
 pub use language::*;
 use lazy_static::lazy_static;
 use lsp::LanguageServerBinary;
 use regex::Regex;
+use serde_json::json;
 use smol::{fs, process};
-use std::{
-    any::Any,
-    ffi::{OsStr, OsString},
-    ops::Range,
-    path::PathBuf,
-    str,
-    sync::{
-        atomic::{AtomicBool, Ordering::SeqCst},
-        Arc,
-    },
-};
-use util::{fs::remove_matching, github::latest_github_release, ResultExt};
+use std::{any::Any, ffi::OsString, ops::Range, path::PathBuf, str, sync::Arc};
+use util::{github::latest_github_release, ResultExt};
 
 fn server_binary_arguments() -> Vec<OsString> {
     vec!["-mode=stdio".into()]
@@ -41,10 +31,23 @@ impl super::LspAdapter for GoLspAdapter {
         "gopls"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "gopls",
+            search_paths: vec![util::paths::HOME.join("go/bin")],
+            arguments: server_binary_arguments(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
+        if let Some(version) = delegate.pinned_server_version() {
+            let version = version.trim_start_matches('v').to_string();
+            return Ok(Box::new(Some(version)) as Box<_>);
+        }
+
         let release = latest_github_release("golang/tools", false, delegate.http_client()).await?;
         let version: Option<String> = release.name.strip_prefix("gopls/v").map(str::to_string);
         if version.is_none() {
@@ -56,32 +59,19 @@ impl super::LspAdapter for GoLspAdapter {
         Ok(Box::new(version) as Box<_>)
     }
 
-    fn will_fetch_server(
-        &self,
-        delegate: &Arc<dyn LspAdapterDelegate>,
-        cx: &mut AsyncAppContext,
-    ) -> Option<Task<Result<()>>> {
-        static DID_SHOW_NOTIFICATION: AtomicBool = AtomicBool::new(false);
-
-        const NOTIFICATION_MESSAGE: &str =
-            "Could not install the Go language server `gopls`, because `go` was not found.";
-
-        let delegate = delegate.clone();
-        Some(cx.spawn(|cx| async move {
-            let install_output = process::Command::new("go").args(["version"]).output().await;
-            if install_output.is_err() {
-                if DID_SHOW_NOTIFICATION
-                    .compare_exchange(false, true, SeqCst, SeqCst)
-                    .is_ok()
-                {
-                    cx.update(|cx| {
-                        delegate.show_notification(NOTIFICATION_MESSAGE, cx);
-                    })?
-                }
-                return Err(anyhow!("cannot install gopls"));
-            }
-            Ok(())
-        }))
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if process::Command::new("go")
+            .arg("version")
+            .output()
+            .await
+            .is_err()
+        {
+            return Err(anyhow!(
+                "Could not install the Go language server `gopls`, because `go` was not found. \
+                 Install Go from https://go.dev/doc/install and make sure `go` is on your PATH."
+            ));
+        }
+        Ok(())
     }
 
     async fn fetch_server_binary(
@@ -97,14 +87,11 @@ impl super::LspAdapter for GoLspAdapter {
             let binary_path = container_dir.join(&format!("gopls_{version}"));
             if let Ok(metadata) = fs::metadata(&binary_path).await {
                 if metadata.is_file() {
-                    remove_matching(&container_dir, |entry| {
-                        entry != binary_path && entry.file_name() != Some(OsStr::new("gobin"))
-                    })
-                    .await;
-
                     return Ok(LanguageServerBinary {
                         path: binary_path.to_path_buf(),
                         arguments: server_binary_arguments(),
+                        socket: None,
+                        env: None,
                     });
                 }
             }
@@ -115,12 +102,19 @@ impl super::LspAdapter for GoLspAdapter {
             return Ok(path);
         }
 
+        let package = match delegate.pinned_server_version() {
+            Some(version) => format!(
+                "golang.org/x/tools/gopls@v{}",
+                version.trim_start_matches('v')
+            ),
+            None => "golang.org/x/tools/gopls@latest".to_string(),
+        };
         let gobin_dir = container_dir.join("gobin");
         fs::create_dir_all(&gobin_dir).await?;
         let install_output = process::Command::new("go")
             .env("GO111MODULE", "on")
             .env("GOBIN", &gobin_dir)
-            .args(["install", "golang.org/x/tools/gopls@latest"])
+            .args(["install", &package])
             .output()
             .await?;
         if !install_output.status.success() {
@@ -145,9 +139,15 @@ impl super::LspAdapter for GoLspAdapter {
         Ok(LanguageServerBinary {
             path: binary_path.to_path_buf(),
             arguments: server_binary_arguments(),
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("gopls_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -168,6 +168,22 @@ impl super::LspAdapter for GoLspAdapter {
             })
     }
 
+    fn initialization_options(&self) -> Option<serde_json::Value> {
+        // gopls provides no inlay hints by default. The kinds that are shown are picked
+        // by the inlay hint settings.
+        Some(json!({
+            "hints": {
+                "assignVariableTypes": true,
+                "compositeLiteralFields": true,
+                "compositeLiteralTypes": true,
+                "constantValues": true,
+                "functionTypeParameters": true,
+                "parameterNames": true,
+                "rangeVariableTypes": true
+            }
+        }))
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -348,6 +364,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             Ok(LanguageServerBinary {
                 path,
                 arguments: server_binary_arguments(),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
@@ -0,0 +1,158 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+const SERVER_PATH: &'static str = "node_modules/graphql-language-service-cli/bin/graphql.js";
+
+/// The file names that graphql-config loads a project's schema and documents from.
+const CONFIG_FILE_NAMES: &[&str] = &[
+    ".graphqlrc",
+    ".graphqlrc.json",
+    ".graphqlrc.yml",
+    ".graphqlrc.yaml",
+    ".graphqlrc.js",
+    ".graphqlrc.ts",
+    "graphql.config.json",
+    "graphql.config.yml",
+    "graphql.config.yaml",
+    "graphql.config.js",
+    "graphql.config.ts",
+];
+
+fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![
+        server_path.into(),
+        "server".into(),
+        "--method=stream".into(),
+    ]
+}
+
+pub struct GraphQLLspAdapter {
+    node: Arc<dyn NodeRuntime>,
+}
+
+impl GraphQLLspAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        GraphQLLspAdapter { node }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for GraphQLLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("graphql-lsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "graphql"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(
+            self.node
+                .npm_package_latest_version("graphql-language-service-cli")
+                .await?,
+        ) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let server_path = container_dir.join(SERVER_PATH);
+
+        if fs::metadata(&server_path).await.is_err() {
+            self.node
+                .npm_install_packages(
+                    &container_dir,
+                    &[("graphql-language-service-cli", version.as_str())],
+                )
+                .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir, &*self.node).await
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // The server finds the schema that documents are checked against from the config
+        // in its working directory, which may be above the worktree in a monorepo.
+        nearest_ancestor_with(worktree_path, CONFIG_FILE_NAMES, fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("GraphQL".into(), "graphql".into())])
+    }
+}
+
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<LanguageServerBinary> {
+    (|| async move {
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir() {
+                last_version_dir = Some(entry.path());
+            }
+        }
+        let last_version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = last_version_dir.join(SERVER_PATH);
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: node.binary_path().await?,
+                arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!(
+                "missing executable in directory {:?}",
+                last_version_dir
+            ))
+        }
+    })()
+    .await
+    .log_err()
+}
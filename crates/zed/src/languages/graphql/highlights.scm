@@ -0,0 +1,74 @@
+(comment) @comment
+
+[
+  (string_value)
+  (description)
+] @string
+
+(int_value) @number
+(float_value) @number
+(boolean_value) @boolean
+(null_value) @constant.builtin
+(enum_value) @constant
+
+(variable) @variable
+
+(named_type (name) @type)
+(object_type_definition (name) @type)
+(interface_type_definition (name) @type)
+(input_object_type_definition (name) @type)
+(enum_type_definition (name) @type)
+(union_type_definition (name) @type)
+(scalar_type_definition (name) @type)
+(fragment_name) @type
+
+(field (name) @property)
+(field (alias (name) @property))
+(field_definition (name) @property)
+(input_value_definition (name) @variable.special)
+(argument (name) @variable.special)
+(object_field (name) @property)
+
+(directive "@" @attribute (name) @attribute)
+(directive_definition "@" @attribute (name) @attribute)
+
+(operation_definition (name) @function)
+
+[
+  (operation_type)
+  "fragment"
+  "on"
+  "type"
+  "interface"
+  "input"
+  "enum"
+  "union"
+  "scalar"
+  "schema"
+  "extend"
+  "directive"
+  "implements"
+  "repeatable"
+] @keyword
+
+[
+  "="
+  "|"
+  "&"
+  "!"
+  "..."
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ":"
+  ","
+] @punctuation.delimiter
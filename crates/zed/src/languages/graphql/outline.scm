@@ -0,0 +1,34 @@
+(operation_definition
+    (operation_type) @context
+    (name) @name) @item
+
+(fragment_definition
+    "fragment" @context
+    (fragment_name) @name) @item
+
+(object_type_definition
+    "type" @context
+    (name) @name) @item
+
+(interface_type_definition
+    "interface" @context
+    (name) @name) @item
+
+(input_object_type_definition
+    "input" @context
+    (name) @name) @item
+
+(enum_type_definition
+    "enum" @context
+    (name) @name) @item
+
+(union_type_definition
+    "union" @context
+    (name) @name) @item
+
+(scalar_type_definition
+    "scalar" @context
+    (name) @name) @item
+
+(field_definition
+    (name) @name) @item
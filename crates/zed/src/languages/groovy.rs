@@ -0,0 +1,198 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use futures::{AsyncReadExt, StreamExt};
+use language::{
+    download_server_binary, InstallProgress, LanguageServerName, LspAdapter, LspAdapterDelegate,
+};
+use lsp::LanguageServerBinary;
+use serde_derive::Deserialize;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+    process::Command,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{async_maybe, ResultExt};
+
+const REPOSITORY: &str = "GroovyLanguageServer/groovy-language-server";
+const JAR_NAME: &str = "groovy-language-server-all.jar";
+
+#[derive(Deserialize)]
+struct GithubCommit {
+    sha: String,
+}
+
+pub struct GroovyLspAdapter;
+
+#[async_trait]
+impl LspAdapter for GroovyLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("groovy-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "groovy"
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        super::jvm::check_java("Groovy", "groovy-language-server", 11).await
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // The server isn't released, so it's built from a commit of its repository: the
+        // pinned one, or else the latest one.
+        let commit = delegate.pinned_server_version();
+        let url = format!(
+            "https://api.github.com/repos/{REPOSITORY}/commits/{}",
+            commit.as_deref().unwrap_or("HEAD")
+        );
+        let mut response = delegate
+            .http_client()
+            .get(&url, Default::default(), true)
+            .await?;
+        let mut body = Vec::new();
+        response
+            .body_mut()
+            .read_to_end(&mut body)
+            .await
+            .context("error reading groovy-language-server commit")?;
+        if !response.status().is_success() {
+            bail!(
+                "status error {} fetching the groovy-language-server commit",
+                response.status().as_u16()
+            );
+        }
+        let commit: GithubCommit =
+            serde_json::from_slice(&body).context("error parsing groovy-language-server commit")?;
+        Ok(Box::new(commit.sha) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let commit = version.downcast::<String>().unwrap();
+        let version_dir = container_dir.join(format!("groovy-language-server_{commit}"));
+        let jar_path = version_dir.join(JAR_NAME);
+
+        if fs::metadata(&jar_path).await.is_err() {
+            let url = format!("https://github.com/{REPOSITORY}/archive/{commit}.tar.gz");
+            let archive_path = container_dir.join("groovy-language-server.tar.gz");
+            download_server_binary(delegate, &url, None, &archive_path).await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let source_dir = container_dir.join(format!("groovy-language-server-src_{commit}"));
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&source_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
+
+            let build_result = build_jar(&source_dir, &commit, &version_dir).await;
+            fs::remove_dir_all(&source_dir).await.log_err();
+            build_result?;
+        }
+
+        let java = super::jvm::find_java()
+            .await
+            .ok_or_else(|| anyhow!("java was not found"))?;
+        Ok(server_binary(java, jar_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("groovy-language-server_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+}
+
+/// Builds the server's jar, with its dependencies, from the sources of `commit` that were
+/// unpacked into `source_dir`, with the Gradle wrapper in the repository, and moves it into
+/// `version_dir`.
+async fn build_jar(source_dir: &Path, commit: &str, version_dir: &Path) -> Result<()> {
+    let project_dir = source_dir.join(format!("groovy-language-server-{commit}"));
+    let build_output = Command::new("sh")
+        .arg("gradlew")
+        .args(["build", "-x", "test", "--no-daemon"])
+        .current_dir(&project_dir)
+        .output()
+        .await?;
+    if !build_output.status.success() {
+        bail!(
+            "failed to build groovy-language-server, which needs a JDK: {}",
+            String::from_utf8_lossy(&build_output.stderr)
+        );
+    }
+
+    fs::create_dir_all(version_dir)
+        .await
+        .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+    fs::rename(
+        project_dir.join("build/libs").join(JAR_NAME),
+        version_dir.join(JAR_NAME),
+    )
+    .await
+    .context("failed to move the groovy-language-server jar")?;
+    Ok(())
+}
+
+fn server_binary(java: PathBuf, jar_path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path: java,
+        arguments: vec!["-jar".into(), jar_path.into()],
+        socket: None,
+        env: None,
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("groovy-language-server_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let jar_path = version_dir.join(JAR_NAME);
+        if fs::metadata(&jar_path).await.is_err() {
+            return Err(anyhow!("missing {JAR_NAME} in directory {:?}", version_dir));
+        }
+        let java = super::jvm::find_java()
+            .await
+            .ok_or_else(|| anyhow!("java was not found"))?;
+        Ok(server_binary(java, jar_path))
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,96 @@
+[
+  (comment)
+  (groovy_doc)
+] @comment
+
+(string) @string
+(escape_sequence) @string.escape
+
+(interpolation
+  "$" @punctuation.special)
+
+(number_literal) @number
+(boolean_literal) @boolean
+(null_literal) @constant.builtin
+
+[
+  "this"
+  "super"
+] @variable.special
+
+(class_definition name: (identifier) @type)
+(type_with_generics (identifier) @type)
+(annotation) @attribute
+
+(function_definition name: (identifier) @function)
+(function_declaration name: (identifier) @function)
+(function_call function: (identifier) @function)
+(function_call function: (dotted_identifier (identifier) @function .))
+(juxt_function_call function: (identifier) @function)
+
+(parameter name: (identifier) @variable.parameter)
+(map_item key: (identifier) @property)
+
+[
+  "package"
+  "import"
+  "class"
+  "interface"
+  "enum"
+  "trait"
+  "extends"
+  "implements"
+  "def"
+  "new"
+  "return"
+  "if"
+  "else"
+  "for"
+  "while"
+  "do"
+  "switch"
+  "case"
+  "default"
+  "break"
+  "continue"
+  "try"
+  "catch"
+  "finally"
+  "throw"
+  "in"
+  "instanceof"
+  "as"
+  "assert"
+] @keyword
+
+(modifier) @keyword
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "<=>"
+  "=~"
+  "==~"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "!"
+  "&&"
+  "||"
+  "?:"
+  "?."
+  "*."
+  "->"
+  ".."
+] @operator
+
+[";" "," "." ":"] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
@@ -0,0 +1,15 @@
+(class_definition
+    "class" @context
+    name: (_) @name) @item
+
+(function_definition
+    name: (_) @name) @item
+
+(function_declaration
+    name: (_) @name) @item
+
+; The blocks of Gradle build files, like `dependencies { ... }`.
+(juxt_function_call
+    function: (identifier) @name
+    args: (argument_list
+        (closure))) @item
@@ -0,0 +1,213 @@
+use super::project_root::nearest_ancestor_with;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use fs::Fs;
+use futures::StreamExt;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
+use lsp::LanguageServerBinary;
+use smol::process::Command;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    str,
+};
+use util::{fs::find_executable, paths::HOME};
+
+pub struct HaskellLspAdapter;
+
+#[async_trait]
+impl LspAdapter for HaskellLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("haskell-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "hls"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "haskell-language-server-wrapper",
+            search_paths: ghcup_bin_dirs(),
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if find_tool("ghcup").await.is_none() && find_server_binary().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the Haskell language server `haskell-language-server`, because `ghcup` was not found. \
+                 Install GHCup from https://www.haskell.org/ghcup and make sure `ghcup` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // Each build of HLS only supports the GHC it was built with, so it's installed by
+        // the same tool as the project's compilers instead of being downloaded.
+        Ok(Box::new(()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        if let Some(binary) = find_server_binary().await {
+            return Ok(binary);
+        }
+
+        let ghcup = find_tool("ghcup")
+            .await
+            .ok_or_else(|| anyhow!("ghcup was not found"))?;
+        let install_output = Command::new(ghcup)
+            .args(["install", "hls", "--set"])
+            .output()
+            .await?;
+        if !install_output.status.success() {
+            Err(anyhow!(
+                "failed to install haskell-language-server with ghcup: {}",
+                String::from_utf8_lossy(&install_output.stderr)
+            ))?;
+        }
+        find_server_binary()
+            .await
+            .ok_or_else(|| anyhow!("haskell-language-server was not found after installing it"))
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        find_server_binary().await
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        cradle_root(worktree_path, fs).await
+    }
+}
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["--lsp".into()]
+}
+
+fn ghcup_bin_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(ghcup_install_base) = std::env::var_os("GHCUP_INSTALL_BASE_PREFIX") {
+        dirs.push(PathBuf::from(ghcup_install_base).join(".ghcup/bin"));
+    }
+    dirs.push(HOME.join(".ghcup/bin"));
+    dirs
+}
+
+async fn find_tool(name: &str) -> Option<PathBuf> {
+    find_executable(name, std::env::var_os("PATH").as_deref(), &ghcup_bin_dirs()).await
+}
+
+/// Finds the HLS wrapper, which starts the build of HLS that matches the GHC version of the
+/// project in its working directory. Without the wrapper, the build for the GHC on the
+/// `PATH` is started directly, the same way the wrapper would pick it.
+async fn find_server_binary() -> Option<LanguageServerBinary> {
+    let path = match find_tool("haskell-language-server-wrapper").await {
+        Some(wrapper) => wrapper,
+        None => {
+            let ghc = find_tool("ghc").await?;
+            let output = Command::new(ghc)
+                .arg("--numeric-version")
+                .output()
+                .await
+                .ok()?;
+            let ghc_version = str::from_utf8(&output.stdout).ok()?.trim();
+            find_tool(&format!("haskell-language-server-{ghc_version}")).await?
+        }
+    };
+    Some(LanguageServerBinary {
+        path,
+        arguments: server_binary_arguments(),
+        socket: None,
+        env: None,
+    })
+}
+
+/// Finds the directory that HLS should load the project's cradle from: the nearest one with
+/// an explicit `hie.yaml`, or else the root of the cabal or stack workspace, whose packages
+/// are then all loaded by one server, or else the directory of a single package.
+async fn cradle_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    for file_names in [&["hie.yaml"][..], &["cabal.project", "stack.yaml"]] {
+        if let Some(root) = nearest_ancestor_with(path, file_names, fs).await {
+            return Some(root);
+        }
+    }
+    for dir in path.ancestors() {
+        if fs.is_file(&dir.join("package.yaml")).await || contains_cabal_file(dir, fs).await {
+            return Some(dir.to_path_buf());
+        }
+    }
+    None
+}
+
+async fn contains_cabal_file(dir: &Path, fs: &dyn Fs) -> bool {
+    let Ok(mut entries) = fs.read_dir(dir).await else {
+        return false;
+    };
+    while let Some(entry) = entries.next().await {
+        if entry.map_or(false, |path| {
+            path.extension().map_or(false, |ext| ext == "cabal")
+        }) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_cradle_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &HaskellLspAdapter,
+            &[
+                (
+                    &["/root/packages/core/core.cabal"],
+                    "/root/packages/core/src",
+                    Some("/root/packages/core"),
+                ),
+                (
+                    &["/root/cabal.project", "/root/packages/core/core.cabal"],
+                    "/root/packages/core/src",
+                    Some("/root"),
+                ),
+                (
+                    &[
+                        "/root/cabal.project",
+                        "/root/packages/core/core.cabal",
+                        "/root/packages/core/hie.yaml",
+                    ],
+                    "/root/packages/core/src",
+                    Some("/root/packages/core"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
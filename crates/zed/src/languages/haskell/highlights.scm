@@ -0,0 +1,80 @@
+(comment) @comment
+(pragma) @preproc
+
+[
+  (string)
+  (char)
+] @string
+
+[
+  (integer)
+  (float)
+] @number
+
+(type) @type
+(type_variable) @type
+(constructor) @constructor
+(module) @title
+
+(signature
+  name: (variable) @function)
+
+(function
+  name: (variable) @function)
+
+(function
+  patterns: (patterns (pat_name (variable) @variable.parameter)))
+
+(exp_apply
+  . (exp_name (variable) @function))
+
+[
+  "module"
+  "import"
+  "qualified"
+  "as"
+  "hiding"
+  "let"
+  "in"
+  "case"
+  "of"
+  "if"
+  "then"
+  "else"
+  "do"
+  "data"
+  "newtype"
+  "type"
+  "class"
+  "instance"
+  "deriving"
+  "forall"
+  (where)
+] @keyword
+
+[
+  (operator)
+  (constructor_operator)
+  "="
+  "|"
+  "::"
+  "=>"
+  "->"
+  "<-"
+  "\\"
+  "@"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  (comma)
+  ";"
+] @punctuation.delimiter
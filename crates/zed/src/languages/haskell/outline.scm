@@ -0,0 +1,23 @@
+(adt
+    "data" @context
+    (type) @name) @item
+
+(newtype
+    "newtype" @context
+    (type) @name) @item
+
+(type_alias
+    "type" @context
+    (type) @name) @item
+
+(class
+    "class" @context
+    (class_head
+        (class_name) @name)) @item
+
+(instance
+    "instance" @context
+    (instance_head) @name) @item
+
+(signature
+    name: (variable) @name) @item
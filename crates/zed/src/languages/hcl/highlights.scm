@@ -0,0 +1,79 @@
+(comment) @comment
+
+[
+  (string_lit)
+  (quoted_template)
+  (heredoc_template)
+] @string
+
+(heredoc_identifier) @punctuation.delimiter
+(template_interpolation
+  "${" @punctuation.special
+  "}" @punctuation.special) @embedded
+
+(numeric_lit) @number
+(bool_lit) @boolean
+(null_lit) @constant.builtin
+
+(block
+  (identifier) @keyword)
+
+(attribute
+  (identifier) @property)
+
+(object_elem
+  key: (expression (variable_expr (identifier) @property)))
+
+(function_call
+  (identifier) @function)
+
+(get_attr
+  (identifier) @property)
+
+(variable_expr
+  (identifier) @variable)
+
+[
+  "for"
+  "in"
+  "if"
+  "else"
+  "endif"
+  "endfor"
+] @keyword
+
+[
+  "="
+  "=>"
+  "!"
+  "=="
+  "!="
+  "<"
+  ">"
+  "<="
+  ">="
+  "&&"
+  "||"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "?"
+  ":"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ".*"
+  ","
+] @punctuation.delimiter
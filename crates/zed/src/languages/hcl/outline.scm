@@ -0,0 +1,7 @@
+(block
+    (identifier) @context
+    (string_lit)? @name
+    (string_lit)? @name) @item
+
+(attribute
+    (identifier) @name) @item
@@ -0,0 +1,14 @@
+; GraphQL documents in template literals tagged with `gql` or `graphql`
+
+((call_expression
+  function: (identifier) @_tag
+  arguments: (template_string) @content)
+ (#match? @_tag "^(gql|graphql)$")
+ (#set! "language" "graphql"))
+
+((call_expression
+  function: (member_expression
+    property: (property_identifier) @_tag)
+  arguments: (template_string) @content)
+ (#match? @_tag "^(gql|graphql)$")
+ (#set! "language" "graphql"))
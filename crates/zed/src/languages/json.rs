@@ -78,6 +78,8 @@ impl LspAdapter for JsonLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -165,6 +167,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
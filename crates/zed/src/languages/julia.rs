@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{github_release_to_install, LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use smol::{fs, process::Command};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{async_maybe, fs::find_executable, ResultExt};
+
+/// Starts the server for the Julia project of its working directory, or for the default
+/// environment, while LanguageServer.jl itself is loaded from the environment it was
+/// installed in.
+const SERVER_SCRIPT: &str = r#"
+using LanguageServer
+project_path = something(Base.current_project(pwd()), Base.load_path_expand("@v#.#"))
+depot_path = get(ENV, "JULIA_DEPOT_PATH", "")
+server = LanguageServer.LanguageServerInstance(stdin, stdout, dirname(project_path), depot_path)
+server.runlinter = true
+run(server)
+"#;
+
+pub struct JuliaLspAdapter;
+
+#[async_trait]
+impl LspAdapter for JuliaLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("julia".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "julia"
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if find_julia().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the Julia language server `LanguageServer.jl`, because `julia` was not found. \
+                 Install Julia from https://julialang.org/downloads and make sure `julia` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            github_release_to_install("julia-vscode/LanguageServer.jl", false, delegate).await?;
+        let version = release.tag_name.trim_start_matches('v').to_string();
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let julia = find_julia()
+            .await
+            .ok_or_else(|| anyhow!("julia was not found"))?;
+        let env_dir = container_dir.join(format!("julia-env_{version}"));
+
+        // The server runs from an environment of its own, with its dependencies pinned by
+        // the manifest that's written when it's installed.
+        if fs::metadata(env_dir.join("Manifest.toml")).await.is_err() {
+            fs::create_dir_all(&env_dir)
+                .await
+                .with_context(|| format!("failed to create directory {}", env_dir.display()))?;
+            let install_output = Command::new(&julia)
+                .arg("--startup-file=no")
+                .arg("--history-file=no")
+                .arg(project_arg(&env_dir))
+                .arg("-e")
+                .arg(format!(
+                    r#"using Pkg; Pkg.add(name="LanguageServer", version="{version}"); Pkg.instantiate()"#
+                ))
+                .output()
+                .await?;
+            if !install_output.status.success() {
+                fs::remove_dir_all(&env_dir).await.log_err();
+                Err(anyhow!(
+                    "failed to install LanguageServer.jl {version}: {}",
+                    String::from_utf8_lossy(&install_output.stderr)
+                ))?;
+            }
+        }
+
+        Ok(server_binary(julia, &env_dir))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("julia-env_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments.truncate(binary.arguments.len() - 1);
+                binary.arguments.push("using LanguageServer".into());
+                binary
+            })
+    }
+}
+
+async fn find_julia() -> Option<PathBuf> {
+    find_executable("julia", std::env::var_os("PATH").as_deref(), &[]).await
+}
+
+fn project_arg(env_dir: &Path) -> OsString {
+    let mut arg = OsString::from("--project=");
+    arg.push(env_dir);
+    arg
+}
+
+fn server_binary(julia: PathBuf, env_dir: &Path) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path: julia,
+        arguments: vec![
+            "--startup-file=no".into(),
+            "--history-file=no".into(),
+            "--quiet".into(),
+            project_arg(env_dir),
+            "-e".into(),
+            SERVER_SCRIPT.into(),
+        ],
+        socket: None,
+        env: None,
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_env_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("julia-env_"))
+            {
+                last_env_dir = Some(entry.path());
+            }
+        }
+
+        let env_dir = last_env_dir.ok_or_else(|| anyhow!("no cached environment"))?;
+        if fs::metadata(env_dir.join("Manifest.toml")).await.is_err() {
+            return Err(anyhow!("missing manifest in directory {:?}", env_dir));
+        }
+        let julia = find_julia()
+            .await
+            .ok_or_else(|| anyhow!("julia was not found"))?;
+        Ok(server_binary(julia, &env_dir))
+    })
+    .await
+    .log_err()
+}
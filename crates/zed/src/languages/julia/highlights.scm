@@ -0,0 +1,111 @@
+(line_comment) @comment
+(block_comment) @comment
+
+[
+  (string_literal)
+  (prefixed_string_literal)
+  (command_literal)
+] @string
+
+(character_literal) @string.special
+(escape_sequence) @string.escape
+
+[
+  (integer_literal)
+  (float_literal)
+] @number
+
+((identifier) @boolean
+  (#match? @boolean "^(true|false)$"))
+
+((identifier) @constant.builtin
+  (#match? @constant.builtin "^(nothing|missing|Inf|NaN)$"))
+
+(quote_expression ":" @string.special.symbol)
+
+(function_definition
+  name: (identifier) @function)
+
+(macro_definition
+  name: (identifier) @function)
+
+(call_expression
+  (identifier) @function)
+
+(call_expression
+  (field_expression (identifier) @function .))
+
+(macro_identifier) @attribute
+
+(struct_definition
+  name: (identifier) @type)
+
+(abstract_definition
+  name: (identifier) @type)
+
+(parameter_list
+  (identifier) @variable.parameter)
+
+(typed_expression
+  (identifier) @type .)
+
+((identifier) @type
+  (#match? @type "^[A-Z][a-z]"))
+
+[
+  "abstract"
+  "baremodule"
+  "begin"
+  "break"
+  "catch"
+  "const"
+  "continue"
+  "do"
+  "else"
+  "elseif"
+  "end"
+  "export"
+  "finally"
+  "for"
+  "function"
+  "global"
+  "if"
+  "import"
+  "let"
+  "local"
+  "macro"
+  "module"
+  "mutable"
+  "primitive"
+  "quote"
+  "return"
+  "struct"
+  "try"
+  "type"
+  "using"
+  "while"
+] @keyword
+
+[
+  "in"
+  "isa"
+  "where"
+] @keyword.operator
+
+(operator) @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ","
+  ";"
+  "."
+  "::"
+] @punctuation.delimiter
@@ -0,0 +1,20 @@
+(struct_definition "end" @end) @indent
+(module_definition "end" @end) @indent
+(function_definition "end" @end) @indent
+(macro_definition "end" @end) @indent
+(if_statement "end" @end) @indent
+(for_statement "end" @end) @indent
+(while_statement "end" @end) @indent
+(try_statement "end" @end) @indent
+(let_statement "end" @end) @indent
+(do_clause "end" @end) @indent
+(quote_statement "end" @end) @indent
+
+(elseif_clause) @outdent
+(else_clause) @outdent
+(catch_clause) @outdent
+(finally_clause) @outdent
+
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+(_ "(" ")" @end) @indent
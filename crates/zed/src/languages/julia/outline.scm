@@ -0,0 +1,25 @@
+(module_definition
+    "module" @context
+    name: (identifier) @name) @item
+
+(struct_definition
+    "struct" @context
+    name: (identifier) @name) @item
+
+(abstract_definition
+    "abstract" @context
+    "type" @context
+    name: (identifier) @name) @item
+
+(function_definition
+    "function" @context
+    name: (identifier) @name) @item
+
+(macro_definition
+    "macro" @context
+    name: (identifier) @name) @item
+
+(const_declaration
+    "const" @context
+    (assignment
+        . (identifier) @name)) @item
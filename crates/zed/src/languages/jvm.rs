@@ -0,0 +1,80 @@
+use anyhow::{anyhow, Result};
+use smol::process::Command;
+use std::path::PathBuf;
+use util::fs::find_executable;
+
+/// Finds the `java` of the runtime in `JAVA_HOME`, or else the one on the `PATH`, the same
+/// way the launch scripts of the language servers that run on the JVM do.
+pub async fn find_java() -> Option<PathBuf> {
+    if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+        let java = PathBuf::from(java_home).join("bin/java");
+        if java.is_file() {
+            return Some(java);
+        }
+    }
+    find_executable("java", std::env::var_os("PATH").as_deref(), &[]).await
+}
+
+/// Checks that a Java runtime of at least `min_version` is installed, which the `server`
+/// for `language` needs to run.
+pub async fn check_java(language: &str, server: &str, min_version: u32) -> Result<()> {
+    let java_not_found = || {
+        anyhow!(
+            "Could not run the {language} language server `{server}`, because `java` was not found. \
+             Install a Java runtime, version {min_version} or newer, and make sure `java` is on your PATH or `JAVA_HOME` is set."
+        )
+    };
+    let java = find_java().await.ok_or_else(java_not_found)?;
+    let output = Command::new(&java)
+        .arg("-version")
+        .output()
+        .await
+        .map_err(|_| java_not_found())?;
+
+    // `java -version` prints to stderr, in a format that differs between vendors, but that
+    // always quotes the version.
+    let version = parse_java_version(&String::from_utf8_lossy(&output.stderr));
+    match version {
+        Some(version) if version < min_version => Err(anyhow!(
+            "Could not run the {language} language server `{server}`, because it needs Java {min_version} or newer, \
+             but `{}` is Java {version}. Install a newer Java runtime, and make sure it's on your PATH or `JAVA_HOME` is set.",
+            java.display()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Parses the major version of a Java runtime from the output of `java -version`, in which
+/// the versions before Java 9 start with `1.`.
+fn parse_java_version(output: &str) -> Option<u32> {
+    let version = output.split('"').nth(1)?;
+    let mut components = version.split(|c: char| !c.is_ascii_digit());
+    match components.next()?.parse().ok()? {
+        1 => components.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_java_version() {
+        assert_eq!(
+            parse_java_version(
+                "openjdk version \"17.0.9\" 2023-10-17\nOpenJDK Runtime Environment (build 17.0.9+9)"
+            ),
+            Some(17)
+        );
+        assert_eq!(
+            parse_java_version("java version \"1.8.0_392\"\nJava(TM) SE Runtime Environment"),
+            Some(8)
+        );
+        assert_eq!(
+            parse_java_version("openjdk version \"21\" 2023-09-19"),
+            Some(21)
+        );
+        assert_eq!(parse_java_version("unrecognized option"), None);
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{asset_sha256, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+const BINARY_PATH: &str = "server/bin/kotlin-language-server";
+
+pub struct KotlinLspAdapter;
+
+#[async_trait]
+impl LspAdapter for KotlinLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("kotlin-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "kotlin-language-server",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        super::jvm::check_java("Kotlin", "kotlin-language-server", 11).await
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            github_release_to_install("fwcd/kotlin-language-server", false, delegate).await?;
+        // The server runs on the JVM, so a single archive is published for every platform.
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == "server.zip")
+            .ok_or_else(|| anyhow!("no server.zip asset in release {}", release.name))?;
+        let version = GitHubLspBinaryVersion {
+            sha256: asset_sha256(&release, asset, delegate.http_client()).await?,
+            name: release.tag_name.clone(),
+            url: asset.browser_download_url.clone(),
+        };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let zip_path = container_dir.join(format!("kotlin-language-server_{}.zip", version.name));
+        let version_dir = container_dir.join(format!("kotlin-language-server_{}", version.name));
+        let binary_path = version_dir.join(BINARY_PATH);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+
+            fs::create_dir_all(&version_dir)
+                .await
+                .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+            let unzip_status = smol::process::Command::new("unzip")
+                .arg(&zip_path)
+                .arg("-d")
+                .arg(&version_dir)
+                .output()
+                .await?
+                .status;
+            if !unzip_status.success() {
+                Err(anyhow!("failed to unzip kotlin-language-server archive"))?;
+            }
+            fs::remove_file(&zip_path).await.log_err();
+        }
+
+        fs::set_permissions(
+            &binary_path,
+            <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+        )
+        .await?;
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: Vec::new(),
+            socket: None,
+            env: None,
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("kotlin-language-server_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // The server resolves the classpath from the project's Gradle or Maven build on its
+        // own. Its settings, like `kotlin.compiler.jvm.target`, can be passed through the
+        // `settings` of `kotlin-language-server` in the `lsp` settings.
+        json!({
+            "kotlin": {
+                // Decompiled sources of dependencies are otherwise given `kls:` URIs,
+                // which can't be opened as files.
+                "externalSources": {
+                    "useKlsScheme": false
+                }
+            }
+        })
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("kotlin-language-server_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join(BINARY_PATH);
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                path: binary_path,
+                arguments: Vec::new(),
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!(
+                "missing kotlin-language-server binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
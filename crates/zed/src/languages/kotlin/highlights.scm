@@ -0,0 +1,143 @@
+[
+  (line_comment)
+  (multiline_comment)
+] @comment
+
+[
+  (string_literal)
+  (character_literal)
+] @string
+
+(interpolated_identifier) @variable
+(interpolated_expression) @embedded
+
+[
+  (integer_literal)
+  (long_literal)
+  (hex_literal)
+  (bin_literal)
+  (unsigned_literal)
+  (real_literal)
+] @number
+
+(boolean_literal) @boolean
+"null" @constant.builtin
+
+(type_identifier) @type
+
+(class_declaration
+  (type_identifier) @type)
+
+(function_declaration
+  (simple_identifier) @function)
+
+(call_expression
+  (simple_identifier) @function)
+
+(call_expression
+  (navigation_expression
+    (navigation_suffix
+      (simple_identifier) @function)))
+
+(parameter
+  (simple_identifier) @variable.parameter)
+
+(annotation
+  "@" @attribute
+  (user_type
+    (type_identifier) @attribute))
+
+(label) @label
+
+((simple_identifier) @constant
+  (#match? @constant "^[A-Z][A-Z\\d_]+$"))
+
+[
+  "class"
+  "interface"
+  "object"
+  "companion"
+  "fun"
+  "val"
+  "var"
+  "typealias"
+  "constructor"
+  "init"
+  "package"
+  "import"
+  "if"
+  "else"
+  "when"
+  "for"
+  "while"
+  "do"
+  "try"
+  "catch"
+  "finally"
+  "throw"
+  "return"
+  "break"
+  "continue"
+  "in"
+  "is"
+  "as"
+  "as?"
+  "by"
+  "get"
+  "set"
+  "this"
+  "super"
+  (visibility_modifier)
+  (class_modifier)
+  (member_modifier)
+  (function_modifier)
+  (property_modifier)
+  (inheritance_modifier)
+  (parameter_modifier)
+] @keyword
+
+[
+  "="
+  "+="
+  "-="
+  "*="
+  "/="
+  "%="
+  "=="
+  "==="
+  "!="
+  "!=="
+  "<="
+  ">="
+  "&&"
+  "||"
+  "!"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "++"
+  "--"
+  "?:"
+  "!!"
+  "->"
+  "::"
+  ".."
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ";"
+  ":"
+] @punctuation.delimiter
@@ -0,0 +1,24 @@
+(class_declaration
+    ["class" "interface"] @context
+    (type_identifier) @name) @item
+
+(object_declaration
+    "object" @context
+    (type_identifier) @name) @item
+
+(companion_object
+    "companion" @context
+    "object" @context
+    (type_identifier)? @name) @item
+
+(function_declaration
+    "fun" @context
+    (simple_identifier) @name) @item
+
+(property_declaration
+    ["val" "var"] @context
+    (variable_declaration
+        (simple_identifier) @name)) @item
+
+(enum_entry
+    (simple_identifier) @name) @item
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+/// The PDF viewer that texlab opens for forward searches, and the arguments it passes it, in
+/// which `%f` is the source file, `%l` the line and `%p` the PDF.
+fn forward_search_viewer() -> Option<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        let displayline = "/Applications/Skim.app/Contents/SharedSupport/displayline";
+        Path::new(displayline)
+            .is_file()
+            .then_some((displayline, &["-r", "-g", "%l", "%p", "%f"][..]))
+    } else {
+        let path = std::env::var_os("PATH")?;
+        std::env::split_paths(&path)
+            .any(|dir| dir.join("zathura").is_file())
+            .then_some(("zathura", &["--synctex-forward", "%l:1:%f", "%p"][..]))
+    }
+}
+
+pub struct TexlabAdapter;
+
+#[async_trait]
+impl LspAdapter for TexlabAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("texlab".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "texlab"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "texlab",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("latex-lsp/texlab", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "texlab-", ".tar.gz", delegate.http_client()).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("texlab_{}", version.name));
+        let binary_path = version_dir.join("texlab");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join("texlab.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let archive = Archive::new(decompressed_bytes);
+            archive.unpack(&version_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("texlab_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // Documents are built whenever they're saved, after which the viewer is scrolled to
+        // the cursor when one was found. Either can be changed in the `settings` of `texlab`
+        // in the `lsp` settings.
+        let forward_search = forward_search_viewer().map(|(executable, args)| {
+            json!({
+                "executable": executable,
+                "args": args,
+            })
+        });
+        json!({
+            "texlab": {
+                "build": {
+                    "onSave": true,
+                    "forwardSearchAfter": forward_search.is_some(),
+                },
+                "forwardSearch": forward_search,
+            }
+        })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("LaTeX".into(), "latex".into()),
+            ("BibTeX".into(), "bibtex".into()),
+        ])
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("texlab_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("texlab");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary::new(binary_path))
+        } else {
+            Err(anyhow!(
+                "missing texlab binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
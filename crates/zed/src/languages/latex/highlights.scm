@@ -0,0 +1,69 @@
+[
+  (line_comment)
+  (block_comment)
+  (comment_environment)
+] @comment
+
+(command_name) @function
+
+[
+  (inline_formula)
+  (displayed_equation)
+  (math_environment)
+] @string.special
+
+(begin
+  command: _ @keyword
+  name: (curly_group_text (text) @type))
+
+(end
+  command: _ @keyword
+  name: (curly_group_text (text) @type))
+
+(part command: _ @keyword)
+(chapter command: _ @keyword)
+(section command: _ @keyword)
+(subsection command: _ @keyword)
+(subsubsection command: _ @keyword)
+(paragraph command: _ @keyword)
+
+(part text: (curly_group (text) @title))
+(chapter text: (curly_group (text) @title))
+(section text: (curly_group (text) @title))
+(subsection text: (curly_group (text) @title))
+(subsubsection text: (curly_group (text) @title))
+
+(label_definition
+  command: _ @keyword
+  name: (curly_group_text (text) @label))
+
+(label_reference
+  command: _ @keyword
+  names: (curly_group_text_list (text) @label))
+
+(citation
+  command: _ @keyword
+  keys: (curly_group_text_list (text) @label))
+
+[
+  (package_include)
+  (class_include)
+  (latex_include)
+  (bibtex_include)
+  (biblatex_include)
+  (graphics_include)
+] @keyword
+
+(new_command_definition
+  command: _ @keyword
+  declaration: _ @function)
+
+(key_value_pair
+  key: (text) @property)
+
+[
+  "{"
+  "}"
+  "["
+  "]"
+] @punctuation.bracket
@@ -0,0 +1,5 @@
+(generic_environment
+  (begin)
+  (end) @end) @indent
+
+(curly_group "{" "}" @end) @indent
@@ -0,0 +1,27 @@
+(part
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(chapter
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(section
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(subsection
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(subsubsection
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(paragraph
+    command: _ @context
+    text: (curly_group) @name) @item
+
+(new_command_definition
+    command: _ @context
+    declaration: _ @name) @item
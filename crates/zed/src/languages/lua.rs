@@ -1,15 +1,26 @@
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
-use language::{LanguageServerName, LspAdapterDelegate};
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapterDelegate, SystemBinary,
+};
 use lsp::LanguageServerBinary;
-use smol::fs;
-use std::{any::Any, env::consts, path::PathBuf};
+use serde_json::{json, Value};
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
 use util::{
     async_maybe,
-    github::{latest_github_release, GitHubLspBinaryVersion},
+    github::{platform_asset_version, GitHubLspBinaryVersion},
     ResultExt,
 };
 
@@ -26,29 +37,27 @@ impl super::LspAdapter for LuaLspAdapter {
         "lua"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "lua-language-server",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
         let release =
-            latest_github_release("LuaLS/lua-language-server", false, delegate.http_client())
-                .await?;
-        let version = release.name.clone();
-        let platform = match consts::ARCH {
-            "x86_64" => "x64",
-            "aarch64" => "arm64",
-            other => bail!("Running on unsupported platform: {other}"),
-        };
-        let asset_name = format!("lua-language-server-{version}-darwin-{platform}.tar.gz");
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.name.clone(),
-            url: asset.browser_download_url.clone(),
-        };
+            github_release_to_install("LuaLS/lua-language-server", false, delegate).await?;
+        let version = platform_asset_version(
+            release,
+            "lua-language-server-",
+            ".tar.gz",
+            delegate.http_client(),
+        )
+        .await?;
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -63,14 +72,20 @@ impl super::LspAdapter for LuaLspAdapter {
         let binary_path = container_dir.join("bin/lua-language-server");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .map_err(|err| anyhow!("error downloading release: {}", err))?;
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+            let archive_path = container_dir.join("lua-language-server.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
             let archive = Archive::new(decompressed_bytes);
             archive.unpack(container_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
         }
 
         fs::set_permissions(
@@ -81,6 +96,8 @@ impl super::LspAdapter for LuaLspAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: Vec::new(),
+            socket: None,
+            env: None,
         })
     }
 
@@ -103,6 +120,17 @@ impl super::LspAdapter for LuaLspAdapter {
                 binary
             })
     }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // The server doesn't provide inlay hints unless they're enabled.
+        json!({
+            "Lua": {
+                "hint": {
+                    "enable": true
+                }
+            }
+        })
+    }
 }
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
@@ -125,6 +153,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             Ok(LanguageServerBinary {
                 path,
                 arguments: Vec::new(),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
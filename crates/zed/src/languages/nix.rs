@@ -0,0 +1,181 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, LanguageServerName, LspAdapter,
+    LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+/// The formatters that nil is set up to run, in order of preference, when the first of them
+/// is found on the `PATH`.
+const FORMATTERS: &[&str] = &["alejandra", "nixpkgs-fmt"];
+
+pub struct NilLspAdapter {
+    formatter: Mutex<Option<PathBuf>>,
+}
+
+impl NilLspAdapter {
+    pub fn new() -> Self {
+        Self {
+            formatter: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for NilLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("nil".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "nil"
+    }
+
+    async fn check_prerequisites(&self, delegate: &dyn LspAdapterDelegate) -> Result<()> {
+        // This runs before nil is found or fetched, so it's where the formatter is looked for
+        // on the user's `PATH`, rather than whenever nil asks for its configuration.
+        let mut formatter = None;
+        for name in FORMATTERS {
+            formatter = delegate.which(name).await;
+            if formatter.is_some() {
+                break;
+            }
+        }
+        *self.formatter.lock() = formatter;
+        Ok(())
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "nil",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("oxalica/nil", false, delegate).await?;
+        let version = platform_asset_version(release, "nil-", "", delegate.http_client())
+            .await
+            .context(
+                "nil isn't published for this platform. Install it with `nix profile install nixpkgs#nil` \
+                 and enable `prefer_system_binary` for it in the `lsp` settings",
+            )?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("nil_{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("nil_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // nil doesn't format on its own, but runs the formatter it's configured with. Another
+        // one can be picked with `nil.formatting.command` in the `settings` of `nil` in the
+        // `lsp` settings.
+        json!({
+            "nil": {
+                "formatting": {
+                    "command": self.formatter.lock().as_ref().map(|formatter| vec![formatter])
+                }
+            }
+        })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // Flake inputs are resolved from the `flake.lock` next to the `flake.nix`.
+        nearest_ancestor_with(worktree_path, &["flake.nix"], fs).await
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("nil_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        if let Some(path) = last_binary_path {
+            Ok(LanguageServerBinary::new(path))
+        } else {
+            Err(anyhow!("no cached binary"))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,6 @@
+(binding
+    attrpath: (attrpath) @name) @item
+
+(inherit
+    "inherit" @context
+    attrs: (inherited_attrs) @name) @item
@@ -42,6 +42,8 @@ impl LspAdapter for NuLanguageServer {
         Some(LanguageServerBinary {
             path: "nu".into(),
             arguments: vec!["--lsp".into()],
+            socket: None,
+            env: None,
         })
     }
 
@@ -0,0 +1,135 @@
+(comment) @comment
+
+[
+  (string)
+  (quoted_string)
+] @string
+
+(character) @string.special
+(escape_sequence) @string.escape
+
+[
+  (number)
+  (signed_number)
+] @number
+
+(boolean) @boolean
+(unit) @constant.builtin
+
+[
+  (constructor_name)
+  (tag)
+] @constructor
+
+[
+  (module_name)
+  (module_type_name)
+] @type
+
+[
+  (type_constructor)
+  (type_variable)
+  (class_name)
+] @type
+
+(field_name) @property
+
+(label_name) @variable.parameter
+
+(external (value_name) @function)
+
+(method_name) @function.method
+
+(attribute_id) @attribute
+
+[
+  "and"
+  "as"
+  "assert"
+  "begin"
+  "class"
+  "constraint"
+  "do"
+  "done"
+  "downto"
+  "else"
+  "end"
+  "exception"
+  "external"
+  "for"
+  "fun"
+  "function"
+  "functor"
+  "if"
+  "in"
+  "include"
+  "inherit"
+  "initializer"
+  "lazy"
+  "let"
+  "match"
+  "method"
+  "module"
+  "mutable"
+  "new"
+  "nonrec"
+  "object"
+  "of"
+  "open"
+  "private"
+  "rec"
+  "sig"
+  "struct"
+  "then"
+  "to"
+  "try"
+  "type"
+  "val"
+  "virtual"
+  "when"
+  "while"
+  "with"
+] @keyword
+
+[
+  "="
+  "->"
+  "|"
+  "::"
+  ":="
+  "!"
+  (prefix_operator)
+  (sign_operator)
+  (pow_operator)
+  (mult_operator)
+  (add_operator)
+  (concat_operator)
+  (rel_operator)
+  (and_operator)
+  (or_operator)
+  (assign_operator)
+  (hash_operator)
+  (indexing_operator)
+  (let_operator)
+  (let_and_operator)
+  (match_operator)
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "[|"
+  "|]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ","
+  "."
+  ";"
+  ":"
+  ";;"
+] @punctuation.delimiter
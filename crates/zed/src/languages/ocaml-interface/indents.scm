@@ -0,0 +1,7 @@
+(type_binding) @indent
+
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+(_ "struct" "end" @end) @indent
+(_ "sig" "end" @end) @indent
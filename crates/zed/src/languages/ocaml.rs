@@ -0,0 +1,199 @@
+use super::project_root::outermost_ancestor_with;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use fs::Fs;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
+use lsp::LanguageServerBinary;
+use smol::process::Command;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    str,
+};
+use util::fs::find_executable;
+
+pub struct OCamlLspAdapter;
+
+#[async_trait]
+impl LspAdapter for OCamlLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("ocamllsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "ocaml"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "ocamllsp",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if opam_bin_dir().await.is_none() && esy_path().await.is_none() {
+            return Err(anyhow!(
+                "Could not install the OCaml language server `ocamllsp`, because neither `opam` nor `esy` was found. \
+                 Install opam from https://opam.ocaml.org/doc/Install.html and make sure `opam` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // ocamllsp has to be built with the same compiler as the project, so instead of being
+        // downloaded, it's taken from the project's package manager.
+        Ok(Box::new(()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        if let Some(binary) = installed_server_binary().await {
+            return Ok(binary);
+        }
+
+        // esy projects declare ocamllsp as a dependency of their own, so it's only ever
+        // installed into the current opam switch.
+        let install_output = Command::new("opam")
+            .args(["install", "--yes", "ocaml-lsp-server"])
+            .output()
+            .await?;
+        if !install_output.status.success() {
+            Err(anyhow!(
+                "failed to install ocaml-lsp-server with opam: {}",
+                String::from_utf8_lossy(&install_output.stderr)
+            ))?;
+        }
+
+        installed_server_binary()
+            .await
+            .ok_or_else(|| anyhow!("ocamllsp was not found after installing ocaml-lsp-server"))
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        installed_server_binary().await
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        dune_root(worktree_path, fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("OCaml".into(), "ocaml".into()),
+            ("OCaml Interface".into(), "ocaml.interface".into()),
+        ])
+    }
+}
+
+/// Finds ocamllsp in the current opam switch, or else runs the copy that the project
+/// depends on through esy.
+async fn installed_server_binary() -> Option<LanguageServerBinary> {
+    if let Some(bin_dir) = opam_bin_dir().await {
+        let path = bin_dir.join("ocamllsp");
+        if path.is_file() {
+            return Some(LanguageServerBinary {
+                path,
+                arguments: Vec::new(),
+                socket: None,
+                env: None,
+            });
+        }
+    }
+
+    // esy runs the command in the environment of the project in its working directory,
+    // which is the project root of the server.
+    let esy = esy_path().await?;
+    Some(LanguageServerBinary {
+        path: esy,
+        arguments: vec![OsString::from("ocamllsp")],
+        socket: None,
+        env: None,
+    })
+}
+
+async fn opam_bin_dir() -> Option<PathBuf> {
+    let output = Command::new("opam")
+        .args(["var", "bin"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let bin_dir = str::from_utf8(&output.stdout).ok()?.trim();
+    (!bin_dir.is_empty()).then(|| PathBuf::from(bin_dir))
+}
+
+async fn esy_path() -> Option<PathBuf> {
+    find_executable("esy", std::env::var_os("PATH").as_deref(), &[]).await
+}
+
+/// Finds the root that dune builds a directory in: the outermost directory containing it that
+/// has a `dune-workspace` file, or else the outermost one that has a `dune-project` file.
+async fn dune_root(path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+    if let Some(root) = outermost_ancestor_with(path, &["dune-workspace"], fs).await {
+        return Some(root);
+    }
+    outermost_ancestor_with(path, &["dune-project"], fs).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::project_root::tests::assert_project_roots;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_dune_root(cx: &mut TestAppContext) {
+        assert_project_roots(
+            &OCamlLspAdapter,
+            &[
+                (
+                    &["/root/packages/a/dune-project"],
+                    "/root/packages/a/lib",
+                    Some("/root/packages/a"),
+                ),
+                (
+                    &["/root/dune-project", "/root/packages/a/dune-project"],
+                    "/root/packages/a/lib",
+                    Some("/root"),
+                ),
+                (
+                    &[
+                        "/root/dune-project",
+                        "/root/packages/a/dune-project",
+                        "/root/packages/a/dune-workspace",
+                    ],
+                    "/root/packages/a/lib",
+                    Some("/root/packages/a"),
+                ),
+            ],
+            cx,
+        )
+        .await;
+    }
+}
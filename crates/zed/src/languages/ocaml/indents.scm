@@ -0,0 +1,13 @@
+[
+    (let_binding)
+    (type_binding)
+    (match_case)
+    (application_expression)
+] @indent
+
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+(_ "begin" "end" @end) @indent
+(_ "struct" "end" @end) @indent
+(_ "sig" "end" @end) @indent
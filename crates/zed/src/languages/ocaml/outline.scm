@@ -0,0 +1,33 @@
+(value_definition
+    "let" @context
+    (let_binding
+        pattern: (value_name) @name)) @item
+
+(type_definition
+    "type" @context
+    (type_binding
+        name: (type_constructor) @name)) @item
+
+(exception_definition
+    "exception" @context
+    (constructor_declaration
+        (constructor_name) @name)) @item
+
+(external
+    "external" @context
+    (value_name) @name) @item
+
+(module_definition
+    "module" @context
+    (module_binding
+        (module_name) @name)) @item
+
+(module_type_definition
+    "module" @context
+    "type" @context
+    (module_type_name) @name) @item
+
+(class_definition
+    "class" @context
+    (class_binding
+        (class_name) @name)) @item
@@ -0,0 +1,253 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use gpui::AppContext;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    fs::find_executable,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+/// The directories of a project that its modules are commonly loaded from: its own, and the
+/// ones that Carton installs its dependencies in.
+const INCLUDE_DIRS: &[&str] = &["lib", "local/lib/perl5"];
+
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["--stdio".into()]
+}
+
+pub struct PerlLspAdapter;
+
+#[async_trait]
+impl LspAdapter for PerlLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("perlnavigator".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "perlnavigator"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "perlnavigator",
+            search_paths: Vec::new(),
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        // The server compiles files with `perl -c` to find errors and the symbols they
+        // import.
+        if find_executable("perl", std::env::var_os("PATH").as_deref(), &[])
+            .await
+            .is_none()
+        {
+            return Err(anyhow!(
+                "Could not run the Perl language server `perlnavigator`, because `perl` was not found. \
+                 Install Perl from https://www.perl.org/get.html and make sure `perl` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("bscan/PerlNavigator", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "perlnavigator-", ".zip", delegate.http_client())
+                .await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let zip_path = container_dir.join(format!("perlnavigator_{}.zip", version.name));
+        let version_dir = container_dir.join(format!("perlnavigator_{}", version.name));
+
+        if let Some(binary_path) = find_server_binary(&version_dir).await {
+            return Ok(LanguageServerBinary {
+                arguments: server_binary_arguments(),
+                ..LanguageServerBinary::new(binary_path)
+            });
+        }
+
+        download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+            .await?;
+        delegate.report_install_progress(InstallProgress::Extracting);
+
+        fs::create_dir_all(&version_dir)
+            .await
+            .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+        let unzip_status = smol::process::Command::new("unzip")
+            .arg(&zip_path)
+            .arg("-d")
+            .arg(&version_dir)
+            .output()
+            .await?
+            .status;
+        if !unzip_status.success() {
+            Err(anyhow!("failed to unzip perlnavigator archive"))?;
+        }
+        fs::remove_file(&zip_path).await.log_err();
+
+        let binary_path = find_server_binary(&version_dir)
+            .await
+            .ok_or_else(|| anyhow!("missing perlnavigator in the release archive"))?;
+        fs::set_permissions(
+            &binary_path,
+            <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+        )
+        .await?;
+        Ok(LanguageServerBinary {
+            arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("perlnavigator_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn workspace_configuration(&self, worktree_path: &Path, _: &mut AppContext) -> Value {
+        perlnavigator_configuration(worktree_path)
+    }
+}
+
+/// The configuration for the project in `worktree_path`, which loads modules from its
+/// include directories and checks and formats files with its own perlcritic and perltidy
+/// profiles. Others can be set with `perlnavigator` in the `settings` of `perlnavigator` in
+/// the `lsp` settings, e.g. in the project's settings.
+fn perlnavigator_configuration(worktree_path: &Path) -> Value {
+    let include_paths = INCLUDE_DIRS
+        .iter()
+        .map(|dir| worktree_path.join(dir))
+        .filter(|dir| dir.is_dir())
+        .collect::<Vec<_>>();
+    let profile = |file_name: &str| {
+        let path = worktree_path.join(file_name);
+        path.is_file().then_some(path)
+    };
+    json!({
+        "perlnavigator": {
+            "includePaths": include_paths,
+            "perlcriticEnabled": true,
+            "perlcriticProfile": profile(".perlcriticrc"),
+            "perltidyProfile": profile(".perltidyrc"),
+        }
+    })
+}
+
+/// Finds the server in a release that was unzipped into `version_dir`, either at its top
+/// or in the directory named after the platform that it's sometimes archived in.
+async fn find_server_binary(version_dir: &Path) -> Option<PathBuf> {
+    let binary_path = version_dir.join("perlnavigator");
+    if fs::metadata(&binary_path)
+        .await
+        .map_or(false, |metadata| metadata.is_file())
+    {
+        return Some(binary_path);
+    }
+    let mut entries = fs::read_dir(version_dir).await.ok()?;
+    while let Some(entry) = entries.next().await {
+        let binary_path = entry.ok()?.path().join("perlnavigator");
+        if fs::metadata(&binary_path).await.is_ok() {
+            return Some(binary_path);
+        }
+    }
+    None
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("perlnavigator_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = find_server_binary(&version_dir)
+            .await
+            .ok_or_else(|| anyhow!("missing perlnavigator in directory {:?}", version_dir))?;
+        Ok(LanguageServerBinary {
+            arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    })
+    .await
+    .log_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_perlnavigator_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("lib/App")).unwrap();
+        fs::write(root.join(".perltidyrc"), "-i=2").unwrap();
+
+        let configuration = perlnavigator_configuration(root);
+        assert_eq!(
+            configuration["perlnavigator"],
+            json!({
+                "includePaths": [root.join("lib")],
+                "perlcriticEnabled": true,
+                "perlcriticProfile": null,
+                "perltidyProfile": root.join(".perltidyrc"),
+            })
+        );
+    }
+}
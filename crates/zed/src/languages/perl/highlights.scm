@@ -0,0 +1,108 @@
+[
+  (comment)
+  (pod)
+] @comment
+
+[
+  (string_literal)
+  (interpolated_string_literal)
+  (heredoc_content)
+  (command_string)
+] @string
+
+(escape_sequence) @string.escape
+
+[
+  (quoted_regexp)
+  (match_regexp)
+  (substitution_regexp)
+] @string.regex
+
+(number) @number
+
+[
+  (scalar)
+  (array)
+  (hash)
+  (arraylen)
+  (glob)
+] @variable
+
+(package_statement name: (_) @title)
+(use_statement module: (_) @title)
+
+(subroutine_declaration_statement name: (_) @function)
+(function_call_expression function: (_) @function)
+(ambiguous_function_call_expression function: (_) @function)
+(method_call_expression method: (_) @function)
+(func0op_call_expression function: _ @function.builtin)
+(func1op_call_expression function: _ @function.builtin)
+
+(attribute_name) @attribute
+
+[
+  "package"
+  "use"
+  "no"
+  "require"
+  "sub"
+  "my"
+  "our"
+  "local"
+  "state"
+  "if"
+  "elsif"
+  "else"
+  "unless"
+  "while"
+  "until"
+  "for"
+  "foreach"
+  "return"
+  "last"
+  "next"
+  "redo"
+  "and"
+  "or"
+  "not"
+  "eq"
+  "ne"
+  "lt"
+  "le"
+  "gt"
+  "ge"
+  "cmp"
+  "__END__"
+  "__DATA__"
+] @keyword
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "<=>"
+  "=~"
+  "!~"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "."
+  "x"
+  "!"
+  "&&"
+  "||"
+  "//"
+  "->"
+  "=>"
+  "\\"
+] @operator
+
+[";" "," "::"] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
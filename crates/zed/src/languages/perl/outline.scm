@@ -0,0 +1,7 @@
+(package_statement
+    "package" @context
+    name: (_) @name) @item
+
+(subroutine_declaration_statement
+    "sub" @context
+    name: (_) @name) @item
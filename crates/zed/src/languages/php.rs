@@ -70,6 +70,8 @@ impl LspAdapter for IntelephenseLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: intelephense_server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -123,6 +125,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: intelephense_server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
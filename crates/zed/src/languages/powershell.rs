@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate,
+};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    fs::find_executable,
+    github::{asset_sha256, GitHubLspBinaryVersion},
+    paths::LOGS_DIR,
+    ResultExt,
+};
+
+const STARTUP_SCRIPT_PATH: &str = "PowerShellEditorServices/Start-EditorServices.ps1";
+
+pub struct PowerShellLspAdapter;
+
+#[async_trait]
+impl LspAdapter for PowerShellLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("powershell-editor-services".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "pses"
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if find_powershell().await.is_none() {
+            return Err(anyhow!(
+                "Could not run the PowerShell language server `PowerShellEditorServices`, because `pwsh` was not found. \
+                 Install PowerShell from https://aka.ms/powershell and make sure `pwsh` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            github_release_to_install("PowerShell/PowerShellEditorServices", false, delegate)
+                .await?;
+        // The server is a PowerShell module, so a single archive is published for every
+        // platform.
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == "PowerShellEditorServices.zip")
+            .ok_or_else(|| {
+                anyhow!(
+                    "no PowerShellEditorServices.zip asset in release {}",
+                    release.name
+                )
+            })?;
+        let version = GitHubLspBinaryVersion {
+            sha256: asset_sha256(&release, asset, delegate.http_client()).await?,
+            name: release.tag_name.clone(),
+            url: asset.browser_download_url.clone(),
+        };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let zip_path = container_dir.join(format!("PowerShellEditorServices_{}.zip", version.name));
+        let version_dir = container_dir.join(format!("PowerShellEditorServices_{}", version.name));
+
+        if fs::metadata(version_dir.join(STARTUP_SCRIPT_PATH))
+            .await
+            .is_err()
+        {
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+
+            fs::create_dir_all(&version_dir)
+                .await
+                .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+            let unzip_status = smol::process::Command::new("unzip")
+                .arg(&zip_path)
+                .arg("-d")
+                .arg(&version_dir)
+                .output()
+                .await?
+                .status;
+            if !unzip_status.success() {
+                Err(anyhow!("failed to unzip PowerShellEditorServices archive"))?;
+            }
+            fs::remove_file(&zip_path).await.log_err();
+        }
+
+        let powershell = find_powershell()
+            .await
+            .ok_or_else(|| anyhow!("pwsh was not found"))?;
+        Ok(server_binary(powershell, &version_dir))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("PowerShellEditorServices_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec![
+                    "-NoLogo".into(),
+                    "-NoProfile".into(),
+                    "-Command".into(),
+                    "$PSVersionTable.PSVersion".into(),
+                ];
+                binary
+            })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("PowerShell".into(), "powershell".into())])
+    }
+}
+
+/// Finds PowerShell 7, or else Windows PowerShell, which PowerShellEditorServices also
+/// supports and which comes with Windows.
+async fn find_powershell() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH");
+    let names: &[&str] = if cfg!(windows) {
+        &["pwsh.exe", "powershell.exe"]
+    } else {
+        &["pwsh"]
+    };
+    for name in names {
+        if let Some(powershell) = find_executable(name, path.as_deref(), &[]).await {
+            return Some(powershell);
+        }
+    }
+    None
+}
+
+/// Quotes `path` as a single-quoted PowerShell string, in which only quotes are special.
+fn quote_path(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "''"))
+}
+
+/// Runs the startup script of the release in `version_dir` in stdio mode. The script is
+/// passed to `-Command` rather than `-File`, so that the empty arrays it's given are parsed
+/// as PowerShell rather than as strings.
+fn server_binary(powershell: PathBuf, version_dir: &Path) -> LanguageServerBinary {
+    let mut arguments: Vec<OsString> = vec!["-NoLogo".into(), "-NoProfile".into()];
+    // Scripts that were downloaded are blocked by the default execution policy on Windows,
+    // while the other platforms don't enforce one.
+    if cfg!(windows) {
+        arguments.extend(["-ExecutionPolicy".into(), "Bypass".into()]);
+    }
+
+    let log_dir = LOGS_DIR.join("PowerShellEditorServices");
+    let command = [
+        format!("& {}", quote_path(&version_dir.join(STARTUP_SCRIPT_PATH))),
+        format!("-BundledModulesPath {}", quote_path(version_dir)),
+        format!(
+            "-LogPath {}",
+            quote_path(&log_dir.join("EditorServices.log"))
+        ),
+        format!(
+            "-SessionDetailsPath {}",
+            quote_path(&version_dir.join("session.json"))
+        ),
+        "-FeatureFlags @() -AdditionalModules @()".into(),
+        format!(
+            "-HostName Zed -HostProfileId Zed -HostVersion {}",
+            env!("CARGO_PKG_VERSION")
+        ),
+        "-Stdio -LogLevel Normal".into(),
+    ]
+    .join(" ");
+    arguments.extend(["-Command".into(), command.into()]);
+
+    LanguageServerBinary {
+        path: powershell,
+        arguments,
+        socket: None,
+        env: None,
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("PowerShellEditorServices_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        if fs::metadata(version_dir.join(STARTUP_SCRIPT_PATH))
+            .await
+            .is_err()
+        {
+            return Err(anyhow!(
+                "missing PowerShellEditorServices startup script in directory {:?}",
+                version_dir
+            ));
+        }
+        let powershell = find_powershell()
+            .await
+            .ok_or_else(|| anyhow!("pwsh was not found"))?;
+        Ok(server_binary(powershell, &version_dir))
+    })
+    .await
+    .log_err()
+}
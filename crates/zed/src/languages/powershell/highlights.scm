@@ -0,0 +1,87 @@
+(comment) @comment
+
+[
+  (expandable_string_literal)
+  (verbatim_string_characters)
+  (expandable_here_string_literal)
+  (verbatim_here_string_characters)
+] @string
+
+(escaped_character) @string.escape
+
+[
+  (decimal_integer_literal)
+  (hexadecimal_integer_literal)
+  (real_literal)
+] @number
+
+(variable) @variable
+((variable) @variable.special
+  (#match? @variable.special "^\\$(_|PSItem|this|args|input|true|false|null)$"))
+
+(type_literal) @type
+(type_name) @type
+
+(function_name) @function
+(class_method_definition (simple_name) @function)
+(class_statement (simple_name) @type)
+(enum_statement (simple_name) @type)
+(invokation_expression (member_name) @function)
+(member_access (member_name) @property)
+
+(command
+  command_name: (command_name) @function)
+(command_parameter) @variable.parameter
+
+[
+  "if"
+  "elseif"
+  "else"
+  "switch"
+  "foreach"
+  "for"
+  "while"
+  "do"
+  "until"
+  "in"
+  "function"
+  "filter"
+  "workflow"
+  "param"
+  "begin"
+  "process"
+  "end"
+  "return"
+  "break"
+  "continue"
+  "throw"
+  "exit"
+  "try"
+  "catch"
+  "finally"
+  "trap"
+  "class"
+  "enum"
+] @keyword
+
+[
+  "="
+  "+="
+  "-="
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "!"
+  "|"
+  "::"
+  ".."
+] @operator
+
+(comparison_operator) @operator
+(assignement_operator) @operator
+
+[";" ","] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}" "@(" "@{" "$("] @punctuation.bracket
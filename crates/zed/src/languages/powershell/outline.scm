@@ -0,0 +1,14 @@
+(function_statement
+    ["function" "filter" "workflow"] @context
+    (function_name) @name) @item
+
+(class_statement
+    "class" @context
+    (simple_name) @name) @item
+
+(class_method_definition
+    (simple_name) @name) @item
+
+(enum_statement
+    "enum" @context
+    (simple_name) @name) @item
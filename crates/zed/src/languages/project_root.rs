@@ -0,0 +1,77 @@
+use fs::Fs;
+use std::path::{Path, PathBuf};
+
+/// Finds the nearest of `path` and its ancestors that contains a file named one of
+/// `file_names`.
+pub async fn nearest_ancestor_with(
+    path: &Path,
+    file_names: &[&str],
+    fs: &dyn Fs,
+) -> Option<PathBuf> {
+    for dir in path.ancestors() {
+        if contains_any(dir, file_names, fs).await {
+            return Some(dir.to_path_buf());
+        }
+    }
+    None
+}
+
+/// Finds the outermost of `path` and its ancestors that contains a file named one of
+/// `file_names`, for build systems whose workspaces contain nested projects of their own.
+pub async fn outermost_ancestor_with(
+    path: &Path,
+    file_names: &[&str],
+    fs: &dyn Fs,
+) -> Option<PathBuf> {
+    let mut outermost = None;
+    for dir in path.ancestors() {
+        if contains_any(dir, file_names, fs).await {
+            outermost = Some(dir.to_path_buf());
+        }
+    }
+    outermost
+}
+
+async fn contains_any(dir: &Path, file_names: &[&str], fs: &dyn Fs) -> bool {
+    for file_name in file_names {
+        if fs.is_file(&dir.join(file_name)).await {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+pub mod tests {
+    use fs::{FakeFs, Fs};
+    use gpui::TestAppContext;
+    use language::LspAdapter;
+    use std::path::Path;
+
+    /// Checks the project root that `adapter` finds for the worktree of each case, in a file
+    /// system that contains only the case's files. Each case is the absolute paths of the
+    /// files, the worktree's path, and the expected project root.
+    pub async fn assert_project_roots(
+        adapter: &dyn LspAdapter,
+        cases: &[(&[&str], &str, Option<&str>)],
+        cx: &TestAppContext,
+    ) {
+        for (files, worktree_path, expected_root) in cases {
+            let fs = FakeFs::new(cx.executor());
+            fs.create_dir(Path::new(worktree_path)).await.unwrap();
+            for file in *files {
+                let path = Path::new(file);
+                fs.create_dir(path.parent().unwrap()).await.unwrap();
+                fs.insert_file(path, String::new()).await;
+            }
+            assert_eq!(
+                adapter
+                    .project_root(Path::new(worktree_path), fs.as_ref())
+                    .await
+                    .as_deref(),
+                expected_root.map(Path::new),
+                "project root of {worktree_path} with {files:?}",
+            );
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    fs::find_executable,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    paths::HOME,
+    ResultExt,
+};
+
+/// The server to run for Protocol Buffers: `buf`'s own, or protols where `buf` isn't
+/// published.
+enum ProtoServerVersion {
+    Buf(GitHubLspBinaryVersion),
+    Protols(PathBuf),
+}
+
+fn buf_server_arguments() -> Vec<OsString> {
+    vec!["lsp".into(), "serve".into()]
+}
+
+pub struct ProtoLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ProtoLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("buf".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "buf"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "buf",
+            search_paths: Vec::new(),
+            arguments: buf_server_arguments(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("bufbuild/buf", false, delegate).await?;
+        let version = match platform_asset_version(
+            release,
+            "buf-",
+            ".tar.gz",
+            delegate.http_client(),
+        )
+        .await
+        {
+            Ok(version) => ProtoServerVersion::Buf(version),
+            Err(error) => {
+                let protols = find_protols().await.ok_or_else(|| {
+                    error.context("Install protols with `cargo install protols` to use it instead")
+                })?;
+                ProtoServerVersion::Protols(protols)
+            }
+        };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = match *version.downcast::<ProtoServerVersion>().unwrap() {
+            ProtoServerVersion::Buf(version) => version,
+            ProtoServerVersion::Protols(path) => return Ok(protols_binary(path)),
+        };
+        let version_dir = container_dir.join(format!("buf_{}", version.name));
+        let binary_path = buf_binary_path(&version_dir);
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let archive_path = container_dir.join("buf.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+            let archive = Archive::new(decompressed_bytes);
+            archive
+                .unpack(&version_dir)
+                .await
+                .with_context(|| format!("failed to unpack buf into {}", version_dir.display()))?;
+            fs::remove_file(&archive_path).await.log_err();
+        }
+
+        Ok(buf_binary(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("buf_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        match get_cached_server_binary(container_dir).await {
+            Some(binary) => Some(binary),
+            None => find_protols().await.map(protols_binary),
+        }
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // Imports are resolved relative to the modules of the `buf.yaml` workspace, which
+        // may be above the worktree.
+        if let Some(workspace_root) =
+            nearest_ancestor_with(worktree_path, &["buf.work.yaml"], fs).await
+        {
+            return Some(workspace_root);
+        }
+        nearest_ancestor_with(worktree_path, &["buf.yaml"], fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Proto".into(), "proto".into())])
+    }
+}
+
+fn buf_binary_path(version_dir: &Path) -> PathBuf {
+    version_dir.join("buf").join("bin").join("buf")
+}
+
+fn buf_binary(path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path,
+        arguments: buf_server_arguments(),
+        socket: None,
+        env: None,
+    }
+}
+
+fn protols_binary(path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path,
+        arguments: Vec::new(),
+        socket: None,
+        env: None,
+    }
+}
+
+async fn find_protols() -> Option<PathBuf> {
+    find_executable(
+        "protols",
+        std::env::var_os("PATH").as_deref(),
+        &[HOME.join(".cargo/bin")],
+    )
+    .await
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("buf_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = buf_binary_path(&version_dir);
+        if binary_path.exists() {
+            Ok(buf_binary(binary_path))
+        } else {
+            Err(anyhow!("missing buf binary in directory {:?}", version_dir))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,85 @@
+(comment) @comment
+
+(string) @string
+(int_lit) @number
+(float_lit) @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+[
+  "syntax"
+  "edition"
+  "package"
+  "import"
+  "weak"
+  "public"
+  "option"
+  "message"
+  "enum"
+  "oneof"
+  "map"
+  "service"
+  "rpc"
+  "returns"
+  "stream"
+  "extend"
+  "reserved"
+  "extensions"
+  "to"
+  "max"
+  "optional"
+  "required"
+  "repeated"
+] @keyword
+
+[
+  "double"
+  "float"
+  "int32"
+  "int64"
+  "uint32"
+  "uint64"
+  "sint32"
+  "sint64"
+  "fixed32"
+  "fixed64"
+  "sfixed32"
+  "sfixed64"
+  "bool"
+  "string"
+  "bytes"
+] @type.builtin
+
+(message_name) @type
+(enum_name) @type
+(service_name) @type
+(message_or_enum_type) @type
+
+(rpc_name) @function
+
+(field (identifier) @property)
+(enum_field (identifier) @constant)
+
+(full_ident) @namespace
+
+"=" @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<"
+  ">"
+] @punctuation.bracket
+
+[
+  ";"
+  ","
+  "."
+] @punctuation.delimiter
@@ -0,0 +1,18 @@
+(message
+    "message" @context
+    (message_name) @name) @item
+
+(enum
+    "enum" @context
+    (enum_name) @name) @item
+
+(service
+    "service" @context
+    (service_name) @name) @item
+
+(rpc
+    "rpc" @context
+    (rpc_name) @name) @item
+
+(field
+    (identifier) @name) @item
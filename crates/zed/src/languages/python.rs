@@ -1,6 +1,6 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
 use smol::fs;
@@ -38,6 +38,14 @@ impl LspAdapter for PythonLspAdapter {
         "pyright"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "pyright-langserver",
+            search_paths: Vec::new(),
+            arguments: vec!["--stdio".into()],
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -63,6 +71,8 @@ impl LspAdapter for PythonLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -168,6 +178,8 @@ async fn get_cached_server_binary(
         Some(LanguageServerBinary {
             path: node.binary_path().await.log_err()?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     } else {
         log::error!("missing executable in directory {:?}", server_path);
@@ -40,6 +40,8 @@ impl LspAdapter for RubyLanguageServer {
         Some(LanguageServerBinary {
             path: "solargraph".into(),
             arguments: vec!["stdio".into()],
+            socket: None,
+            env: None,
         })
     }
 
@@ -1,16 +1,18 @@
 use anyhow::{anyhow, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
+use futures::StreamExt;
 pub use language::*;
 use lazy_static::lazy_static;
 use lsp::LanguageServerBinary;
 use regex::Regex;
-use smol::fs::{self, File};
-use std::{any::Any, borrow::Cow, env::consts, path::PathBuf, str, sync::Arc};
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{any::Any, borrow::Cow, path::PathBuf, str, sync::Arc};
 use util::{
-    fs::remove_matching,
-    github::{latest_github_release, GitHubLspBinaryVersion},
+    github::{platform_asset_version, GitHubLspBinaryVersion},
     ResultExt,
 };
 
@@ -26,23 +28,24 @@ impl LspAdapter for RustLspAdapter {
         "rust"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "rust-analyzer",
+            search_paths: vec![util::paths::HOME.join(".cargo/bin")],
+            arguments: Vec::new(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
         let release =
-            latest_github_release("rust-analyzer/rust-analyzer", false, delegate.http_client())
-                .await?;
-        let asset_name = format!("rust-analyzer-{}-apple-darwin.gz", consts::ARCH);
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        Ok(Box::new(GitHubLspBinaryVersion {
-            name: release.name,
-            url: asset.browser_download_url.clone(),
-        }))
+            github_release_to_install("rust-analyzer/rust-analyzer", false, delegate).await?;
+        Ok(Box::new(
+            platform_asset_version(release, "rust-analyzer-", ".gz", delegate.http_client())
+                .await?,
+        ))
     }
 
     async fn fetch_server_binary(
@@ -55,29 +58,39 @@ impl LspAdapter for RustLspAdapter {
         let destination_path = container_dir.join(format!("rust-analyzer-{}", version.name));
 
         if fs::metadata(&destination_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .map_err(|err| anyhow!("error downloading release: {}", err))?;
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+            let archive_path = container_dir.join("rust-analyzer.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
             let mut file = File::create(&destination_path).await?;
             futures::io::copy(decompressed_bytes, &mut file).await?;
+            fs::remove_file(&archive_path).await.log_err();
             fs::set_permissions(
                 &destination_path,
                 <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
             )
             .await?;
-
-            remove_matching(&container_dir, |entry| entry != destination_path).await;
         }
 
         Ok(LanguageServerBinary {
             path: destination_path,
             arguments: Default::default(),
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("rust-analyzer-")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -282,6 +295,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
         anyhow::Ok(LanguageServerBinary {
             path: last.ok_or_else(|| anyhow!("no cached binary"))?,
             arguments: Default::default(),
+            socket: None,
+            env: None,
         })
     })()
     .await
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::{fs, process::Command};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{async_maybe, ResultExt};
+
+/// The coursier launcher that Metals is bootstrapped with. It runs on the JVM, so the same
+/// jar works on every platform.
+const COURSIER_URL: &str =
+    "https://github.com/coursier/coursier/releases/download/v2.1.10/coursier.jar";
+const JAR_NAME: &str = "metals.jar";
+
+pub struct ScalaLspAdapter;
+
+#[async_trait]
+impl LspAdapter for ScalaLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("metals".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "metals"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "metals",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        super::jvm::check_java("Scala", "metals", 11).await
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // Metals is published to Maven Central, with a GitHub release for each version.
+        let release = github_release_to_install("scalameta/metals", false, delegate).await?;
+        let version = release
+            .tag_name
+            .strip_prefix('v')
+            .unwrap_or(&release.tag_name)
+            .to_string();
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let version_dir = container_dir.join(format!("metals_{version}"));
+        let jar_path = version_dir.join(JAR_NAME);
+
+        if fs::metadata(&jar_path).await.is_err() {
+            let coursier_path = container_dir.join("coursier.jar");
+            // Coursier doesn't publish checksums for its launcher.
+            download_server_binary(delegate, COURSIER_URL, None, &coursier_path).await?;
+
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let bootstrap_result = bootstrap(&coursier_path, &version, &version_dir).await;
+            fs::remove_file(&coursier_path).await.log_err();
+            if bootstrap_result.is_err() {
+                fs::remove_dir_all(&version_dir).await.log_err();
+            }
+            bootstrap_result?;
+        }
+
+        let java = super::jvm::find_java()
+            .await
+            .ok_or_else(|| anyhow!("java was not found"))?;
+        Ok(server_binary(java, jar_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("metals_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments.push("--version".into());
+                binary
+            })
+    }
+}
+
+/// Bootstraps `version` of Metals into a standalone jar in `version_dir`, with the coursier
+/// launcher at `coursier_path`. The jar contains all of Metals' dependencies, so it runs
+/// without coursier, and while offline.
+async fn bootstrap(coursier_path: &Path, version: &str, version_dir: &Path) -> Result<()> {
+    let java = super::jvm::find_java()
+        .await
+        .ok_or_else(|| anyhow!("java was not found"))?;
+    fs::create_dir_all(version_dir)
+        .await
+        .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+    let output = Command::new(java)
+        .arg("-jar")
+        .arg(coursier_path)
+        .args(["bootstrap", "--standalone", "--force"])
+        .arg(format!("org.scalameta:metals_2.13:{version}"))
+        .arg("--output")
+        .arg(version_dir.join(JAR_NAME))
+        .output()
+        .await
+        .context("failed to run coursier")?;
+    if !output.status.success() {
+        bail!(
+            "failed to bootstrap metals {version} with coursier: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+fn server_binary(java: PathBuf, jar_path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path: java,
+        arguments: vec!["-jar".into(), jar_path.into()],
+        socket: None,
+        env: None,
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("metals_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let jar_path = version_dir.join(JAR_NAME);
+        if fs::metadata(&jar_path).await.is_err() {
+            return Err(anyhow!("missing {JAR_NAME} in directory {:?}", version_dir));
+        }
+        let java = super::jvm::find_java()
+            .await
+            .ok_or_else(|| anyhow!("java was not found"))?;
+        Ok(server_binary(java, jar_path))
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,169 @@
+[
+  (comment)
+  (block_comment)
+] @comment
+
+[
+  (string)
+  (character_literal)
+  (interpolated_string_expression)
+] @string
+
+(symbol_literal) @string.special.symbol
+
+(interpolation "$" @punctuation.special)
+(interpolation (block) @embedded)
+
+[
+  (integer_literal)
+  (floating_point_literal)
+] @number
+
+(boolean_literal) @boolean
+(null_literal) @constant.builtin
+
+(type_identifier) @type
+
+(class_definition
+  name: (identifier) @type)
+
+(trait_definition
+  name: (identifier) @type)
+
+(object_definition
+  name: (identifier) @type)
+
+(enum_definition
+  name: (identifier) @type)
+
+(full_enum_case
+  name: (identifier) @type)
+
+(simple_enum_case
+  name: (identifier) @type)
+
+((identifier) @type
+  (#match? @type "^[A-Z]"))
+
+((identifier) @constant
+  (#match? @constant "^[A-Z][A-Z\\d_]+$"))
+
+(function_definition
+  name: (identifier) @function)
+
+(function_declaration
+  name: (identifier) @function)
+
+(call_expression
+  function: (identifier) @function)
+
+(call_expression
+  function: (field_expression
+    field: (identifier) @function.method))
+
+(generic_function
+  function: (identifier) @function)
+
+(interpolated_string_expression
+  interpolator: (identifier) @function)
+
+((call_expression
+  function: (identifier) @constructor)
+  (#match? @constructor "^[A-Z]"))
+
+(parameter
+  name: (identifier) @variable.parameter)
+
+(class_parameter
+  name: (identifier) @variable.parameter)
+
+(field_expression
+  field: (identifier) @property)
+
+(annotation) @attribute
+
+((identifier) @variable.special
+  (#eq? @variable.special "this"))
+
+[
+  "abstract"
+  "case"
+  "catch"
+  "class"
+  "def"
+  "derives"
+  "do"
+  "else"
+  "end"
+  "enum"
+  "export"
+  "extends"
+  "extension"
+  "final"
+  "finally"
+  "for"
+  "given"
+  "if"
+  "implicit"
+  "import"
+  "lazy"
+  "match"
+  "new"
+  "object"
+  "override"
+  "package"
+  "private"
+  "protected"
+  "return"
+  "sealed"
+  "then"
+  "throw"
+  "trait"
+  "try"
+  "type"
+  "using"
+  "val"
+  "var"
+  "while"
+  "with"
+  "yield"
+  (opaque_modifier)
+  (infix_modifier)
+  (inline_modifier)
+  (transparent_modifier)
+  (open_modifier)
+] @keyword
+
+[
+  "="
+  "=>"
+  "<-"
+  "<:"
+  ">:"
+  "<%"
+  "?=>"
+  "=>>"
+  (operator_identifier)
+] @operator
+
+(infix_expression
+  operator: (identifier) @operator)
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ":"
+  ";"
+] @punctuation.delimiter
+
+(using_directive_key) @property
+(using_directive_value) @string
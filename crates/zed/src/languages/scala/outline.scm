@@ -0,0 +1,64 @@
+(package_clause
+    "package" @context
+    name: (package_identifier) @name) @item
+
+(class_definition
+    "case"? @context
+    "class" @context
+    name: (_) @name) @item
+
+(trait_definition
+    "trait" @context
+    name: (_) @name) @item
+
+(object_definition
+    "case"? @context
+    "object" @context
+    name: (_) @name) @item
+
+(package_object
+    "package" @context
+    "object" @context
+    name: (_) @name) @item
+
+(enum_definition
+    "enum" @context
+    name: (_) @name) @item
+
+(full_enum_case
+    name: (_) @name) @item
+
+(simple_enum_case
+    name: (_) @name) @item
+
+(given_definition
+    "given" @context
+    name: (_) @name) @item
+
+(type_definition
+    "type" @context
+    name: (_) @name) @item
+
+(function_definition
+    "def" @context
+    name: (_) @name) @item
+
+(function_declaration
+    "def" @context
+    name: (_) @name) @item
+
+(val_definition
+    "val" @context
+    pattern: (identifier) @name) @item
+
+(val_declaration
+    "val" @context
+    name: (_) @name) @item
+
+(var_definition
+    "var" @context
+    pattern: (identifier) @name) @item
+
+(var_declaration
+    "var" @context
+    name: (_) @name) @item
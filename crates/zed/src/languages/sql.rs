@@ -0,0 +1,318 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    github_release_to_install, LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use smol::{fs, process};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{async_maybe, ResultExt};
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqlSettings {
+    pub lsp: SqlLspSetting,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlLspSetting {
+    Sqls,
+    SqlLanguageServer,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct SqlSettingsContent {
+    lsp: Option<SqlLspSetting>,
+}
+
+impl Settings for SqlSettings {
+    const KEY: Option<&'static str> = Some("sql");
+
+    type FileContent = SqlSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut gpui::AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+fn sql_language_ids() -> HashMap<String, String> {
+    HashMap::from_iter([("SQL".into(), "sql".into())])
+}
+
+pub struct SqlsLspAdapter;
+
+#[async_trait]
+impl LspAdapter for SqlsLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("sqls".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "sqls"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "sqls",
+            search_paths: vec![util::paths::HOME.join("go/bin")],
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if process::Command::new("go")
+            .arg("version")
+            .output()
+            .await
+            .is_err()
+        {
+            return Err(anyhow!(
+                "Could not install the SQL language server `sqls`, because `go` was not found. \
+                 Install Go from https://go.dev/doc/install and make sure `go` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("sqls-server/sqls", false, delegate).await?;
+        let version = release.tag_name.trim_start_matches('v').to_string();
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let binary_path = container_dir.join(format!("sqls_{version}"));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            let gobin_dir = container_dir.join("gobin");
+            fs::create_dir_all(&gobin_dir).await?;
+            let install_output = process::Command::new("go")
+                .env("GO111MODULE", "on")
+                .env("GOBIN", &gobin_dir)
+                .args([
+                    "install",
+                    &format!("github.com/sqls-server/sqls@v{version}"),
+                ])
+                .output()
+                .await?;
+            if !install_output.status.success() {
+                Err(anyhow!(
+                    "failed to install sqls {version}: {}",
+                    String::from_utf8_lossy(&install_output.stderr)
+                ))?;
+            }
+            fs::rename(gobin_dir.join("sqls"), &binary_path).await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: binary_path,
+            arguments: Vec::new(),
+            socket: None,
+            env: None,
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("sqls_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_sqls_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_sqls_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        sql_language_ids()
+    }
+}
+
+async fn get_cached_sqls_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("sqls_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        if let Some(path) = last_binary_path {
+            Ok(LanguageServerBinary {
+                path,
+                arguments: Vec::new(),
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!("no cached binary"))
+        }
+    })
+    .await
+    .log_err()
+}
+
+const SQL_LANGUAGE_SERVER_PATH: &'static str = "node_modules/sql-language-server/dist/bin/cli.js";
+
+fn sql_language_server_arguments(server_path: &Path) -> Vec<OsString> {
+    vec![
+        server_path.into(),
+        "up".into(),
+        "--method".into(),
+        "stdio".into(),
+    ]
+}
+
+pub struct SqlLanguageServerAdapter {
+    node: Arc<dyn NodeRuntime>,
+}
+
+impl SqlLanguageServerAdapter {
+    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+        SqlLanguageServerAdapter { node }
+    }
+}
+
+#[async_trait]
+impl LspAdapter for SqlLanguageServerAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("sql-language-server".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "sql-language-server"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Any + Send>> {
+        Ok(Box::new(
+            self.node
+                .npm_package_latest_version("sql-language-server")
+                .await?,
+        ) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<String>().unwrap();
+        let server_path = container_dir.join(SQL_LANGUAGE_SERVER_PATH);
+
+        if fs::metadata(&server_path).await.is_err() {
+            self.node
+                .npm_install_packages(&container_dir, &[("sql-language-server", version.as_str())])
+                .await?;
+        }
+
+        Ok(LanguageServerBinary {
+            path: self.node.binary_path().await?,
+            arguments: sql_language_server_arguments(&server_path),
+            socket: None,
+            env: None,
+        })
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_sql_language_server_binary(container_dir, &*self.node).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_sql_language_server_binary(container_dir, &*self.node).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        sql_language_ids()
+    }
+}
+
+async fn get_cached_sql_language_server_binary(
+    container_dir: PathBuf,
+    node: &dyn NodeRuntime,
+) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir() {
+                last_version_dir = Some(entry.path());
+            }
+        }
+        let last_version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = last_version_dir.join(SQL_LANGUAGE_SERVER_PATH);
+        if server_path.exists() {
+            Ok(LanguageServerBinary {
+                path: node.binary_path().await?,
+                arguments: sql_language_server_arguments(&server_path),
+                socket: None,
+                env: None,
+            })
+        } else {
+            Err(anyhow!(
+                "missing executable in directory {:?}",
+                last_version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -0,0 +1,133 @@
+[
+  (comment)
+  (marginalia)
+] @comment
+
+(literal) @string
+
+((literal) @number
+  (#match? @number "^-?[0-9]+(\\.[0-9]+)?$"))
+
+[
+  (keyword_true)
+  (keyword_false)
+] @boolean
+
+(keyword_null) @constant.builtin
+
+(invocation
+  (object_reference
+    name: (identifier) @function))
+
+(object_reference
+  name: (identifier) @type)
+
+(relation
+  alias: (identifier) @variable)
+
+(field
+  name: (identifier) @property)
+
+(column_definition
+  name: (identifier) @property)
+
+(parameter) @variable.special
+
+[
+  (keyword_select)
+  (keyword_from)
+  (keyword_where)
+  (keyword_join)
+  (keyword_left)
+  (keyword_right)
+  (keyword_inner)
+  (keyword_outer)
+  (keyword_on)
+  (keyword_as)
+  (keyword_and)
+  (keyword_or)
+  (keyword_not)
+  (keyword_in)
+  (keyword_is)
+  (keyword_like)
+  (keyword_between)
+  (keyword_case)
+  (keyword_when)
+  (keyword_then)
+  (keyword_else)
+  (keyword_end)
+  (keyword_group)
+  (keyword_order)
+  (keyword_by)
+  (keyword_having)
+  (keyword_limit)
+  (keyword_offset)
+  (keyword_distinct)
+  (keyword_union)
+  (keyword_all)
+  (keyword_insert)
+  (keyword_into)
+  (keyword_values)
+  (keyword_update)
+  (keyword_set)
+  (keyword_delete)
+  (keyword_create)
+  (keyword_alter)
+  (keyword_drop)
+  (keyword_table)
+  (keyword_view)
+  (keyword_index)
+  (keyword_function)
+  (keyword_primary)
+  (keyword_key)
+  (keyword_references)
+  (keyword_default)
+  (keyword_with)
+  (keyword_returning)
+  (keyword_exists)
+  (keyword_if)
+] @keyword
+
+[
+  (keyword_int)
+  (keyword_bigint)
+  (keyword_smallint)
+  (keyword_boolean)
+  (keyword_text)
+  (keyword_varchar)
+  (keyword_char)
+  (keyword_decimal)
+  (keyword_numeric)
+  (keyword_float)
+  (keyword_date)
+  (keyword_timestamp)
+  (keyword_json)
+  (keyword_uuid)
+] @type.builtin
+
+[
+  "="
+  "<"
+  "<="
+  ">"
+  ">="
+  "<>"
+  "!="
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "||"
+] @operator
+
+[
+  "("
+  ")"
+] @punctuation.bracket
+
+[
+  ";"
+  ","
+  "."
+] @punctuation.delimiter
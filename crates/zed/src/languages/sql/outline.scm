@@ -0,0 +1,19 @@
+(create_table
+    (keyword_create) @context
+    (keyword_table) @context
+    (object_reference) @name) @item
+
+(create_view
+    (keyword_create) @context
+    (keyword_view) @context
+    (object_reference) @name) @item
+
+(create_function
+    (keyword_create) @context
+    (keyword_function) @context
+    (object_reference) @name) @item
+
+(create_index
+    (keyword_create) @context
+    (keyword_index) @context
+    column: (identifier) @name) @item
@@ -0,0 +1,194 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, LanguageServerName, LspAdapter,
+    LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    fs::find_executable,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    paths::HOME,
+    ResultExt,
+};
+
+/// The files that mark the root of a Bazel workspace, which labels starting with `//` are
+/// resolved from.
+const WORKSPACE_FILE_NAMES: &[&str] = &["MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE"];
+
+/// The server to run for Starlark: bazel-lsp, or the `starlark` interpreter's own where
+/// bazel-lsp isn't published.
+enum StarlarkServerVersion {
+    BazelLsp(GitHubLspBinaryVersion),
+    Starlark(PathBuf),
+}
+
+pub struct StarlarkLspAdapter;
+
+#[async_trait]
+impl LspAdapter for StarlarkLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("bazel-lsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "bazel-lsp"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "bazel-lsp",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release =
+            github_release_to_install("cameron-martin/bazel-lsp", false, delegate).await?;
+        let version =
+            match platform_asset_version(release, "bazel-lsp-", "", delegate.http_client()).await {
+                Ok(version) => StarlarkServerVersion::BazelLsp(version),
+                Err(error) => {
+                    let starlark = find_starlark().await.ok_or_else(|| {
+                        error.context(
+                            "Install starlark with `cargo install starlark_bin` to use it instead",
+                        )
+                    })?;
+                    StarlarkServerVersion::Starlark(starlark)
+                }
+            };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = match *version.downcast::<StarlarkServerVersion>().unwrap() {
+            StarlarkServerVersion::BazelLsp(version) => version,
+            StarlarkServerVersion::Starlark(path) => return Ok(starlark_binary(path)),
+        };
+        let binary_path = container_dir.join(format!("bazel-lsp_{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(bazel_lsp_binary(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("bazel-lsp_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        match get_cached_server_binary(container_dir).await {
+            Some(binary) => Some(binary),
+            None => find_starlark().await.map(starlark_binary),
+        }
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // bazel-lsp resolves labels, and the `load`s of other packages and external
+        // repositories, with `bazel` in the workspace, so that their definitions are found
+        // from any package in it.
+        nearest_ancestor_with(worktree_path, WORKSPACE_FILE_NAMES, fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Starlark".into(), "starlark".into())])
+    }
+}
+
+fn bazel_lsp_binary(path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path,
+        arguments: Vec::new(),
+        socket: None,
+        env: None,
+    }
+}
+
+fn starlark_binary(path: PathBuf) -> LanguageServerBinary {
+    LanguageServerBinary {
+        path,
+        arguments: vec!["--lsp".into()],
+        socket: None,
+        env: None,
+    }
+}
+
+async fn find_starlark() -> Option<PathBuf> {
+    find_executable(
+        "starlark",
+        std::env::var_os("PATH").as_deref(),
+        &[HOME.join(".cargo/bin")],
+    )
+    .await
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("bazel-lsp_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        let path = last_binary_path.ok_or_else(|| anyhow!("no cached binary"))?;
+        Ok(bazel_lsp_binary(path))
+    })
+    .await
+    .log_err()
+}
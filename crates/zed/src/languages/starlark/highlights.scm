@@ -0,0 +1,79 @@
+(comment) @comment
+
+(string) @string
+(escape_sequence) @string.escape
+
+[
+  (integer)
+  (float)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(none) @constant.builtin
+
+(function_definition
+  name: (identifier) @function)
+
+(call
+  function: (identifier) @function)
+
+(call
+  function: (attribute
+    attribute: (identifier) @function))
+
+((call
+  function: (identifier) @function.builtin)
+  (#match? @function.builtin "^(load|glob|select|package|package_name|repository_name|exports_files|licenses|rule|aspect|provider|struct|depset|fail|print|len|range|str|int|bool|list|dict|tuple|type|hasattr|getattr|sorted|reversed|enumerate|zip|any|all|min|max)$"))
+
+(parameters
+  (identifier) @variable.parameter)
+
+(keyword_argument
+  name: (identifier) @property)
+
+((identifier) @constant
+  (#match? @constant "^[A-Z][A-Z_0-9]*$"))
+
+[
+  "def"
+  "return"
+  "if"
+  "elif"
+  "else"
+  "for"
+  "in"
+  "not"
+  "and"
+  "or"
+  "pass"
+  "break"
+  "continue"
+  "lambda"
+] @keyword
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "+"
+  "-"
+  "*"
+  "/"
+  "//"
+  "%"
+  "**"
+  "|"
+  "+="
+] @operator
+
+["," "." ":"] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
@@ -0,0 +1,13 @@
+(function_definition
+    "def" @context
+    name: (_) @name) @item
+
+; The targets of BUILD files, by the `name` they're given.
+(expression_statement
+    (call
+        function: (identifier) @context
+        arguments: (argument_list
+            (keyword_argument
+                name: (identifier) @_name
+                value: (string) @name)
+            (#eq? @_name "name")))) @item
@@ -1,10 +1,12 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use collections::HashMap;
 use futures::StreamExt;
+use gpui::AppContext;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use node_runtime::NodeRuntime;
-use serde_json::json;
+use serde_json::{json, Value};
 use smol::fs;
 use std::{
     any::Any,
@@ -72,6 +74,8 @@ impl LspAdapter for SvelteLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -96,6 +100,26 @@ impl LspAdapter for SvelteLspAdapter {
         }))
     }
 
+    fn workspace_configuration(&self, _: &Path, _: &mut AppContext) -> Value {
+        // These are the checks that `svelte-check` runs over the whole project, run on the
+        // components that are open instead.
+        let diagnostics = json!({ "diagnostics": { "enable": true } });
+        json!({
+            "svelte": {
+                "plugin": {
+                    "svelte": diagnostics,
+                    "typescript": diagnostics,
+                    "css": diagnostics,
+                    "html": { "enable": true }
+                }
+            }
+        })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Svelte".into(), "svelte".into())])
+    }
+
     fn prettier_plugins(&self) -> &[&'static str] {
         &["prettier-plugin-svelte"]
     }
@@ -120,6 +144,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
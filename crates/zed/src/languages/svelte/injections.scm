@@ -1,27 +1,35 @@
 ; injections.scm
 ; --------------
-(script_element
-  (raw_text) @content
+((script_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "lang=")
   (#set! "language" "javascript"))
 
- ((script_element
-     (start_tag
-       (attribute
-         (quoted_attribute_value (attribute_value) @_language)))
-      (raw_text) @content)
-    (#eq? @_language "ts")
-    (#set! "language" "typescript"))
-
 ((script_element
     (start_tag
-        (attribute
+      (attribute
+        (attribute_name) @_attribute_name
         (quoted_attribute_value (attribute_value) @_language)))
     (raw_text) @content)
-  (#eq? @_language "typescript")
+  (#eq? @_attribute_name "lang")
+  (#match? @_language "^(ts|typescript)$")
   (#set! "language" "typescript"))
 
-(style_element
-  (raw_text) @content
+((style_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "lang=")
+  (#set! "language" "css"))
+
+((style_element
+    (start_tag
+      (attribute
+        (attribute_name) @_attribute_name
+        (quoted_attribute_value (attribute_value) @_language)))
+    (raw_text) @content)
+  (#eq? @_attribute_name "lang")
+  (#match? @_language "^(css|postcss)$")
   (#set! "language" "css"))
 
 ((raw_text_expr) @content
@@ -0,0 +1,144 @@
+use super::project_root::nearest_ancestor_with;
+use ::fs::Fs;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary};
+use lsp::LanguageServerBinary;
+use serde_json::{json, Value};
+use smol::{fs, process::Command};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    str,
+};
+use util::fs::find_executable;
+
+pub struct SwiftLspAdapter;
+
+#[async_trait]
+impl LspAdapter for SwiftLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("sourcekit-lsp".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "swift"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "sourcekit-lsp",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn check_prerequisites(&self, _: &dyn LspAdapterDelegate) -> Result<()> {
+        if find_toolchain_server().await.is_none() {
+            return Err(anyhow!(
+                "Could not find the Swift language server `sourcekit-lsp`, which comes with the Swift toolchain. \
+                 Install Xcode, or a Swift toolchain from https://www.swift.org/install, and make sure `swift` is on your PATH."
+            ));
+        }
+        Ok(())
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        // sourcekit-lsp has to match the compiler that builds the project, so the one from
+        // the active toolchain is always used.
+        Ok(Box::new(()) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        find_toolchain_server()
+            .await
+            .ok_or_else(|| anyhow!("sourcekit-lsp was not found in the active Swift toolchain"))
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        find_toolchain_server().await
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        // The compile arguments of a SwiftPM package's files come from building its manifest
+        // in the same configuration as `swift build`, so that the two can share the
+        // package's build directory. Compiler flags are added with `swiftPM.swiftCompilerFlags`
+        // in the `initialization_options` of `sourcekit-lsp` in the `lsp` settings.
+        Some(json!({
+            "swiftPM": {
+                "configuration": "debug"
+            }
+        }))
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // sourcekit-lsp only finds the compile arguments of a package's files when it's
+        // started in the directory of its `Package.swift`.
+        nearest_ancestor_with(worktree_path, &["Package.swift"], fs).await
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("Swift".into(), "swift".into())])
+    }
+}
+
+/// Finds sourcekit-lsp in the toolchain selected with `xcode-select` on macOS, or in the one
+/// that provides `swift` elsewhere.
+async fn find_toolchain_server() -> Option<LanguageServerBinary> {
+    let mut path = None;
+    if cfg!(target_os = "macos") {
+        path = xcrun_find("sourcekit-lsp").await;
+    }
+    if path.is_none() {
+        path = toolchain_sibling("sourcekit-lsp").await;
+    }
+    Some(LanguageServerBinary {
+        path: path?,
+        arguments: Vec::new(),
+        socket: None,
+        env: None,
+    })
+}
+
+async fn xcrun_find(tool: &str) -> Option<PathBuf> {
+    let output = Command::new("xcrun")
+        .args(["--find", tool])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = str::from_utf8(&output.stdout).ok()?.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Swift toolchains on Linux install their tools next to `swift`, which is often only linked
+/// to from a directory on the `PATH`.
+async fn toolchain_sibling(tool: &str) -> Option<PathBuf> {
+    let swift = find_executable("swift", std::env::var_os("PATH").as_deref(), &[]).await?;
+    let swift = fs::canonicalize(&swift).await.ok()?;
+    let path = swift.parent()?.join(tool);
+    fs::metadata(&path).await.ok()?.is_file().then_some(path)
+}
@@ -0,0 +1,133 @@
+[
+  (comment)
+  (multiline_comment)
+] @comment
+
+[
+  (line_string_literal)
+  (multi_line_string_literal)
+] @string
+
+(raw_string_literal) @string
+
+[
+  (integer_literal)
+  (hex_literal)
+  (oct_literal)
+  (bin_literal)
+  (real_literal)
+] @number
+
+(boolean_literal) @boolean
+"nil" @constant.builtin
+
+(type_identifier) @type
+
+(function_declaration
+  name: (simple_identifier) @function)
+
+(protocol_function_declaration
+  name: (simple_identifier) @function)
+
+(call_expression
+  (simple_identifier) @function)
+
+(call_expression
+  (navigation_expression
+    suffix: (navigation_suffix
+      suffix: (simple_identifier) @function)))
+
+(parameter
+  name: (simple_identifier) @variable.parameter)
+
+(attribute
+  "@" @attribute
+  (user_type
+    (type_identifier) @attribute))
+
+(self_expression) @variable.special
+
+[
+  "func"
+  "let"
+  "var"
+  "class"
+  "struct"
+  "enum"
+  "protocol"
+  "extension"
+  "typealias"
+  "import"
+  "init"
+  "deinit"
+  "return"
+  "if"
+  (else)
+  "guard"
+  "switch"
+  "case"
+  "for"
+  "in"
+  "while"
+  "repeat"
+  "break"
+  "continue"
+  (throw_keyword)
+  "try"
+  (catch_keyword)
+  "do"
+  "as"
+  "is"
+  (where_keyword)
+  (default_keyword)
+  (throws)
+  (visibility_modifier)
+  (member_modifier)
+  (function_modifier)
+  (property_modifier)
+  (mutation_modifier)
+  (inheritance_modifier)
+  (parameter_modifier)
+] @keyword
+
+[
+  "="
+  "+="
+  "-="
+  "*="
+  "/="
+  "=="
+  "!="
+  "==="
+  "!=="
+  "<="
+  ">="
+  "&&"
+  "||"
+  "!"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "??"
+  "->"
+  "..."
+  "..<"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  "."
+  ","
+  ";"
+  ":"
+] @punctuation.delimiter
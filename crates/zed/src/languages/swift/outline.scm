@@ -0,0 +1,30 @@
+(class_declaration
+    declaration_kind: _ @context
+    name: (_) @name) @item
+
+(protocol_declaration
+    "protocol" @context
+    name: (type_identifier) @name) @item
+
+(function_declaration
+    "func" @context
+    name: (simple_identifier) @name) @item
+
+(function_declaration
+    "init" @name) @item
+
+(protocol_function_declaration
+    "func" @context
+    name: (simple_identifier) @name) @item
+
+(property_declaration
+    (value_binding_pattern) @context
+    name: (pattern) @name) @item
+
+(typealias_declaration
+    "typealias" @context
+    name: (type_identifier) @name) @item
+
+(enum_entry
+    "case" @context
+    name: (simple_identifier) @name) @item
@@ -74,6 +74,8 @@ impl LspAdapter for TailwindLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -119,6 +121,7 @@ impl LspAdapter for TailwindLspAdapter {
             ("JavaScript".to_string(), "javascript".to_string()),
             ("TSX".to_string(), "typescriptreact".to_string()),
             ("Svelte".to_string(), "svelte".to_string()),
+            ("Astro".to_string(), "astro".to_string()),
             ("Elixir".to_string(), "phoenix-heex".to_string()),
             ("HEEX".to_string(), "phoenix-heex".to_string()),
             ("ERB".to_string(), "erb".to_string()),
@@ -150,6 +153,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
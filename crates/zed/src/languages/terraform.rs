@@ -0,0 +1,269 @@
+use ::fs::Fs;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{AsyncReadExt, StreamExt};
+use language::{
+    download_server_binary, InstallProgress, LanguageServerName, LspAdapter, LspAdapterDelegate,
+    SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use smol::fs;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::{
+    async_maybe,
+    github::{parse_checksum_manifest, GitHubLspBinaryVersion},
+    http::HttpClient,
+    ResultExt,
+};
+
+/// A release of terraform-ls, as described by the HashiCorp releases API.
+#[derive(Deserialize)]
+struct HashiCorpRelease {
+    version: String,
+    builds: Vec<HashiCorpBuild>,
+    url_shasums: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HashiCorpBuild {
+    os: String,
+    arch: String,
+    url: String,
+}
+
+pub struct TerraformLspAdapter;
+
+#[async_trait]
+impl LspAdapter for TerraformLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("terraform-ls".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "terraform"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "terraform-ls",
+            search_paths: Vec::new(),
+            arguments: vec!["serve".into()],
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let version = delegate
+            .pinned_server_version()
+            .map(|version| version.trim_start_matches('v').to_string());
+        let url = format!(
+            "https://api.releases.hashicorp.com/v1/releases/terraform-ls/{}",
+            version.as_deref().unwrap_or("latest")
+        );
+        let http = delegate.http_client();
+        let release: HashiCorpRelease = serde_json::from_slice(&fetch(&http, &url).await?)
+            .context("error parsing terraform-ls release")?;
+
+        let (os, arch) = platform()?;
+        let build = release
+            .builds
+            .iter()
+            .find(|build| build.os == os && build.arch == arch)
+            .ok_or_else(|| {
+                anyhow!(
+                    "terraform-ls {} isn't published for {os} on {arch}",
+                    release.version
+                )
+            })?;
+        let sha256 = match &release.url_shasums {
+            Some(url_shasums) => {
+                let shasums = fetch(&http, url_shasums).await?;
+                let file_name = build.url.rsplit('/').next().unwrap_or_default();
+                parse_checksum_manifest(&String::from_utf8_lossy(&shasums), file_name)
+            }
+            None => None,
+        };
+        let version = GitHubLspBinaryVersion {
+            name: release.version.clone(),
+            url: build.url.clone(),
+            sha256,
+        };
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let zip_path = container_dir.join(format!("terraform-ls_{}.zip", version.name));
+        let version_dir = container_dir.join(format!("terraform-ls_{}", version.name));
+        let binary_path = version_dir.join("terraform-ls");
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(delegate, &version.url, version.sha256.as_deref(), &zip_path)
+                .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+
+            fs::create_dir_all(&version_dir)
+                .await
+                .with_context(|| format!("failed to create directory {}", version_dir.display()))?;
+            let unzip_status = smol::process::Command::new("unzip")
+                .arg(&zip_path)
+                .arg("-d")
+                .arg(&version_dir)
+                .output()
+                .await?
+                .status;
+            if !unzip_status.success() {
+                Err(anyhow!("failed to unzip terraform-ls archive"))?;
+            }
+            fs::remove_file(&zip_path).await.log_err();
+        }
+
+        fs::set_permissions(
+            &binary_path,
+            <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+        )
+        .await?;
+        Ok(LanguageServerBinary {
+            arguments: vec!["serve".into()],
+            ..LanguageServerBinary::new(binary_path)
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("terraform-ls_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["version".into()];
+                binary
+            })
+    }
+
+    fn initialization_options(&self) -> Option<Value> {
+        // terraform-ls indexes every module under its root, so that references to local
+        // modules resolve. The providers and modules that they install are left out.
+        Some(json!({
+            "indexing": {
+                "ignoreDirectoryNames": [".terraform"]
+            }
+        }))
+    }
+
+    async fn project_root(&self, worktree_path: &Path, fs: &dyn Fs) -> Option<PathBuf> {
+        // The schemas of a module's providers and the sources of the modules it calls are
+        // read from where `terraform init` installed them, next to the root module that
+        // calls it.
+        for dir in worktree_path.ancestors() {
+            let is_initialized = fs
+                .metadata(&dir.join(".terraform"))
+                .await
+                .ok()
+                .flatten()
+                .map_or(false, |metadata| metadata.is_dir)
+                || fs.is_file(&dir.join(".terraform.lock.hcl")).await;
+            if is_initialized {
+                return Some(dir.to_path_buf());
+            }
+        }
+        None
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([
+            ("Terraform".into(), "terraform".into()),
+            ("Terraform Vars".into(), "terraform-vars".into()),
+        ])
+    }
+}
+
+/// The names that HashiCorp releases use for the current OS and architecture.
+fn platform() -> Result<(&'static str, &'static str)> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        os => bail!("terraform-ls isn't published for {os}"),
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => bail!("terraform-ls isn't published for {arch}"),
+    };
+    Ok((os, arch))
+}
+
+async fn fetch(http: &Arc<dyn HttpClient>, url: &str) -> Result<Vec<u8>> {
+    let mut response = http.get(url, Default::default(), true).await?;
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .context("error reading response")?;
+    if !response.status().is_success() {
+        bail!("status error {} fetching {url}", response.status().as_u16());
+    }
+    Ok(body)
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("terraform-ls_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("terraform-ls");
+        if binary_path.exists() {
+            Ok(LanguageServerBinary {
+                arguments: vec!["serve".into()],
+                ..LanguageServerBinary::new(binary_path)
+            })
+        } else {
+            Err(anyhow!(
+                "missing terraform-ls binary in directory {:?}",
+                version_dir
+            ))
+        }
+    })
+    .await
+    .log_err()
+}
@@ -4,9 +4,12 @@ use async_tar::Archive;
 use async_trait::async_trait;
 use collections::HashMap;
 use gpui::AppContext;
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use language::{
+    github_release_to_install, LanguageServerName, LspAdapter, LspAdapterDelegate, SystemBinary,
+};
 use lsp::{CodeActionKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
+use parking_lot::Mutex;
 use serde_json::{json, Value};
 use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
@@ -15,7 +18,6 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use util::{fs::remove_matching, github::latest_github_release};
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
 fn typescript_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
@@ -28,20 +30,41 @@ fn eslint_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
 
 pub struct TypeScriptLspAdapter {
     node: Arc<dyn NodeRuntime>,
+    vue_hybrid_mode: bool,
+    vue_plugin_location: Mutex<Option<PathBuf>>,
 }
 
 impl TypeScriptLspAdapter {
     const OLD_SERVER_PATH: &'static str = "node_modules/typescript-language-server/lib/cli.js";
     const NEW_SERVER_PATH: &'static str = "node_modules/typescript-language-server/lib/cli.mjs";
+    const VUE_PLUGIN_PATH: &'static str = "node_modules/@vue/typescript-plugin";
+
+    /// In Vue's hybrid mode, the server is installed with `@vue/typescript-plugin`, which it
+    /// loads to understand the scripts of Vue components.
+    pub fn new(node: Arc<dyn NodeRuntime>, vue_hybrid_mode: bool) -> Self {
+        TypeScriptLspAdapter {
+            node,
+            vue_hybrid_mode,
+            vue_plugin_location: Mutex::new(None),
+        }
+    }
 
-    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
-        TypeScriptLspAdapter { node }
+    /// Loads the Vue plugin that was installed next to the server in `container_dir`.
+    async fn find_vue_plugin(&self, container_dir: &Path) {
+        if self.vue_hybrid_mode
+            && fs::metadata(container_dir.join(Self::VUE_PLUGIN_PATH))
+                .await
+                .is_ok()
+        {
+            *self.vue_plugin_location.lock() = Some(container_dir.to_path_buf());
+        }
     }
 }
 
 struct TypeScriptVersions {
     typescript_version: String,
     server_version: String,
+    vue_plugin_version: Option<String>,
 }
 
 #[async_trait]
@@ -54,6 +77,14 @@ impl LspAdapter for TypeScriptLspAdapter {
         "tsserver"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "typescript-language-server",
+            search_paths: Vec::new(),
+            arguments: vec!["--stdio".into()],
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -64,6 +95,15 @@ impl LspAdapter for TypeScriptLspAdapter {
                 .node
                 .npm_package_latest_version("typescript-language-server")
                 .await?,
+            vue_plugin_version: if self.vue_hybrid_mode {
+                Some(
+                    self.node
+                        .npm_package_latest_version("@vue/typescript-plugin")
+                        .await?,
+                )
+            } else {
+                None
+            },
         }) as Box<_>)
     }
 
@@ -90,10 +130,26 @@ impl LspAdapter for TypeScriptLspAdapter {
                 )
                 .await?;
         }
+        if let Some(vue_plugin_version) = version.vue_plugin_version.as_deref() {
+            if fs::metadata(container_dir.join(Self::VUE_PLUGIN_PATH))
+                .await
+                .is_err()
+            {
+                self.node
+                    .npm_install_packages(
+                        &container_dir,
+                        &[("@vue/typescript-plugin", vue_plugin_version)],
+                    )
+                    .await?;
+            }
+            self.find_vue_plugin(&container_dir).await;
+        }
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: typescript_server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -102,7 +158,9 @@ impl LspAdapter for TypeScriptLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_ts_server_binary(container_dir, &*self.node).await
+        let binary = get_cached_ts_server_binary(container_dir.clone(), &*self.node).await?;
+        self.find_vue_plugin(&container_dir).await;
+        Some(binary)
     }
 
     async fn installation_test_binary(
@@ -151,11 +209,36 @@ impl LspAdapter for TypeScriptLspAdapter {
     }
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
+        let plugins = self
+            .vue_plugin_location
+            .lock()
+            .as_ref()
+            .map(|location| {
+                vec![json!({
+                    "name": "@vue/typescript-plugin",
+                    "location": location,
+                    "languages": ["vue"],
+                })]
+            })
+            .unwrap_or_default();
         Some(json!({
             "provideFormatter": true,
+            "plugins": plugins,
             "tsserver": {
                 "path": "node_modules/typescript/lib",
             },
+            // Every kind of inlay hint is requested, and the editor shows the ones that are
+            // enabled in the settings.
+            "preferences": {
+                "includeInlayParameterNameHints": "all",
+                "includeInlayParameterNameHintsWhenArgumentMatchesName": true,
+                "includeInlayFunctionParameterTypeHints": true,
+                "includeInlayVariableTypeHints": true,
+                "includeInlayVariableTypeHintsWhenTypeMatchesName": true,
+                "includeInlayPropertyDeclarationTypeHints": true,
+                "includeInlayFunctionLikeReturnTypeHints": true,
+                "includeInlayEnumMemberValueHints": true,
+            }
         }))
     }
 
@@ -164,6 +247,7 @@ impl LspAdapter for TypeScriptLspAdapter {
             ("TypeScript".into(), "typescript".into()),
             ("JavaScript".into(), "javascript".into()),
             ("TSX".into(), "typescriptreact".into()),
+            ("Vue.js".into(), "vue".into()),
         ])
     }
 }
@@ -179,11 +263,15 @@ async fn get_cached_ts_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: typescript_server_binary_arguments(&new_server_path),
+                socket: None,
+                env: None,
             })
         } else if old_server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: typescript_server_binary_arguments(&old_server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
@@ -242,11 +330,11 @@ impl LspAdapter for EsLintLspAdapter {
         // At the time of writing the latest vscode-eslint release was released in 2020 and requires
         // special custom LSP protocol extensions be handled to fully initialize. Download the latest
         // prerelease instead to sidestep this issue
-        let release =
-            latest_github_release("microsoft/vscode-eslint", true, delegate.http_client()).await?;
+        let release = github_release_to_install("microsoft/vscode-eslint", true, delegate).await?;
         Ok(Box::new(GitHubLspBinaryVersion {
             name: release.name,
             url: release.tarball_url,
+            sha256: None,
         }))
     }
 
@@ -261,8 +349,6 @@ impl LspAdapter for EsLintLspAdapter {
         let server_path = destination_path.join(Self::SERVER_PATH);
 
         if fs::metadata(&server_path).await.is_err() {
-            remove_matching(&container_dir, |entry| entry != destination_path).await;
-
             let mut response = delegate
                 .http_client()
                 .get(&version.url, Default::default(), true)
@@ -289,9 +375,15 @@ impl LspAdapter for EsLintLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: eslint_server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("vscode-eslint-")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -326,16 +418,27 @@ async fn get_cached_eslint_server_binary(
 ) -> Option<LanguageServerBinary> {
     (|| async move {
         // This is unfortunate but we don't know what the version is to build a path directly
-        let mut dir = fs::read_dir(&container_dir).await?;
-        let first = dir.next().await.ok_or(anyhow!("missing first file"))??;
-        if !first.file_type().await?.is_dir() {
-            return Err(anyhow!("First entry is not a directory"));
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("vscode-eslint-"))
+            {
+                last_version_dir = Some(entry.path());
+            }
         }
-        let server_path = first.path().join(EsLintLspAdapter::SERVER_PATH);
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let server_path = version_dir.join(EsLintLspAdapter::SERVER_PATH);
 
         Ok(LanguageServerBinary {
             path: node.binary_path().await?,
             arguments: eslint_server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     })()
     .await
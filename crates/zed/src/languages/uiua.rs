@@ -42,6 +42,8 @@ impl LspAdapter for UiuaLanguageServer {
         Some(LanguageServerBinary {
             path: "uiua".into(),
             arguments: vec!["lsp".into()],
+            socket: None,
+            env: None,
         })
     }
 
@@ -0,0 +1,92 @@
+use anyhow::{anyhow, bail, Context, Result};
+use futures::AsyncReadExt;
+use language::LspAdapterDelegate;
+use serde_derive::Deserialize;
+use smol::{fs, process::Command};
+use std::path::{Path, PathBuf};
+use util::{fs::find_executable, ResultExt};
+
+#[derive(Deserialize)]
+struct PypiPackage {
+    info: PypiPackageInfo,
+}
+
+#[derive(Deserialize)]
+struct PypiPackageInfo {
+    version: String,
+}
+
+/// Finds the Python that language servers published on PyPI are installed with.
+pub async fn find_python() -> Option<PathBuf> {
+    let path = std::env::var_os("PATH");
+    match find_executable("python3", path.as_deref(), &[]).await {
+        Some(python) => Some(python),
+        None => find_executable("python", path.as_deref(), &[]).await,
+    }
+}
+
+/// The version of `package` to install: the pinned one, or else its latest release on PyPI.
+pub async fn package_version(package: &str, delegate: &dyn LspAdapterDelegate) -> Result<String> {
+    if let Some(version) = delegate.pinned_server_version() {
+        return Ok(version.trim_start_matches('v').to_string());
+    }
+
+    let url = format!("https://pypi.org/pypi/{package}/json");
+    let mut response = delegate
+        .http_client()
+        .get(&url, Default::default(), true)
+        .await?;
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .with_context(|| format!("error reading {package} release"))?;
+    if !response.status().is_success() {
+        bail!(
+            "status error {} fetching the latest {package} release",
+            response.status().as_u16()
+        );
+    }
+    let release: PypiPackage = serde_json::from_slice(&body)
+        .with_context(|| format!("error parsing {package} release"))?;
+    Ok(release.info.version)
+}
+
+/// Installs `version` of `package` into a virtual environment of its own in `venv_dir`, so
+/// that it doesn't depend on, or change, the packages of the Python it's installed with.
+pub async fn install_package(venv_dir: &Path, package: &str, version: &str) -> Result<()> {
+    let python = find_python()
+        .await
+        .ok_or_else(|| anyhow!("python3 was not found"))?;
+    let venv_output = Command::new(python)
+        .args(["-m", "venv"])
+        .arg(venv_dir)
+        .output()
+        .await?;
+    if !venv_output.status.success() {
+        bail!(
+            "failed to create a virtual environment for {package}: {}",
+            String::from_utf8_lossy(&venv_output.stderr)
+        );
+    }
+
+    let install_output = Command::new(venv_dir.join("bin/pip"))
+        .args(["install", "--disable-pip-version-check", "--quiet"])
+        .arg(format!("{package}=={version}"))
+        .output()
+        .await?;
+    if !install_output.status.success() {
+        fs::remove_dir_all(venv_dir).await.log_err();
+        bail!(
+            "failed to install {package} {version}: {}",
+            String::from_utf8_lossy(&install_output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// The path of the executable `name` that a package installed into `venv_dir`.
+pub fn binary_path(venv_dir: &Path, name: &str) -> PathBuf {
+    venv_dir.join("bin").join(name)
+}
@@ -0,0 +1,174 @@
+use anyhow::{anyhow, Result};
+use async_compression::futures::bufread::GzipDecoder;
+use async_tar::Archive;
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
+use std::{
+    any::Any,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+const SERVER_NAME: &str = "verible-verilog-ls";
+
+/// The server looks for the `.rules.verible_lint` of each file in the file's directory and
+/// its ancestors, the same way `verible-verilog-lint` does.
+fn server_binary_arguments() -> Vec<OsString> {
+    vec!["--rules_config_search".into()]
+}
+
+pub struct VeribleLspAdapter;
+
+#[async_trait]
+impl LspAdapter for VeribleLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("verible".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "verible"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: SERVER_NAME,
+            search_paths: Vec::new(),
+            arguments: server_binary_arguments(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let release = github_release_to_install("chipsalliance/verible", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "verible-", ".tar.gz", delegate.http_client()).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let version_dir = container_dir.join(format!("verible_{}", version.name));
+
+        if let Some(binary_path) = find_server_binary(&version_dir).await {
+            return Ok(LanguageServerBinary {
+                arguments: server_binary_arguments(),
+                ..LanguageServerBinary::new(binary_path)
+            });
+        }
+
+        let archive_path = container_dir.join("verible.tar.gz");
+        download_server_binary(
+            delegate,
+            &version.url,
+            version.sha256.as_deref(),
+            &archive_path,
+        )
+        .await?;
+        delegate.report_install_progress(InstallProgress::Extracting);
+        let decompressed_bytes = GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
+        let archive = Archive::new(decompressed_bytes);
+        archive.unpack(&version_dir).await?;
+        fs::remove_file(&archive_path).await.log_err();
+
+        let binary_path = find_server_binary(&version_dir)
+            .await
+            .ok_or_else(|| anyhow!("missing {SERVER_NAME} in the verible release archive"))?;
+        Ok(LanguageServerBinary {
+            arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("verible_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("SystemVerilog".into(), "systemverilog".into())])
+    }
+}
+
+/// Finds the server in a release that was unpacked into `version_dir`. Each release archive
+/// holds a single directory, named after the release and the platform, with the server and
+/// the rest of the tools, like `verible-verilog-format`, in its `bin` directory.
+async fn find_server_binary(version_dir: &Path) -> Option<PathBuf> {
+    let mut entries = fs::read_dir(version_dir).await.ok()?;
+    while let Some(entry) = entries.next().await {
+        let binary_path = entry.ok()?.path().join("bin").join(SERVER_NAME);
+        if fs::metadata(&binary_path).await.is_ok() {
+            return Some(binary_path);
+        }
+    }
+    None
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_version_dir = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("verible_"))
+            {
+                last_version_dir = Some(entry.path());
+            }
+        }
+
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = find_server_binary(&version_dir)
+            .await
+            .ok_or_else(|| anyhow!("missing {SERVER_NAME} in directory {:?}", version_dir))?;
+        Ok(LanguageServerBinary {
+            arguments: server_binary_arguments(),
+            ..LanguageServerBinary::new(binary_path)
+        })
+    })
+    .await
+    .log_err()
+}
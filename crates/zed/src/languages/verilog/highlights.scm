@@ -0,0 +1,102 @@
+(comment) @comment
+
+(string_literal) @string
+
+[
+  (integral_number)
+  (unsigned_number)
+  (real_number)
+  (time_literal)
+] @number
+
+(module_header (simple_identifier) @type)
+(interface_identifier) @type
+(package_identifier) @type
+(class_identifier) @type
+(type_identifier) @type
+(integer_atom_type) @type.builtin
+(integer_vector_type) @type.builtin
+(non_integer_type) @type.builtin
+(net_type) @type.builtin
+
+(function_identifier) @function
+(task_identifier) @function
+(system_tf_identifier) @function.builtin
+(tf_call (simple_identifier) @function)
+
+(port_identifier) @variable.parameter
+(parameter_identifier) @constant
+
+(include_compiler_directive) @preproc
+(text_macro_definition) @preproc
+(text_macro_usage) @preproc
+
+[
+  "module"
+  "endmodule"
+  "interface"
+  "endinterface"
+  "package"
+  "endpackage"
+  "class"
+  "endclass"
+  "function"
+  "endfunction"
+  "task"
+  "endtask"
+  "begin"
+  "end"
+  "if"
+  "else"
+  "case"
+  "endcase"
+  "default"
+  "for"
+  "while"
+  "generate"
+  "endgenerate"
+  "assign"
+  "always"
+  "always_comb"
+  "always_ff"
+  "always_latch"
+  "initial"
+  "input"
+  "output"
+  "inout"
+  "parameter"
+  "localparam"
+  "typedef"
+  "enum"
+  "struct"
+  "packed"
+  "import"
+  "return"
+  "posedge"
+  "negedge"
+] @keyword
+
+[
+  "="
+  "<="
+  "=="
+  "!="
+  "+"
+  "-"
+  "*"
+  "/"
+  "&"
+  "|"
+  "^"
+  "~"
+  "!"
+  "&&"
+  "||"
+  "<<"
+  ">>"
+  "?"
+] @operator
+
+[";" "," ":" "." "::"] @punctuation.delimiter
+
+["(" ")" "[" "]" "{" "}"] @punctuation.bracket
@@ -0,0 +1,13 @@
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+
+(seq_block "begin" "end" @end) @indent
+(module_declaration "endmodule" @end) @indent
+(interface_declaration "endinterface" @end) @indent
+(package_declaration "endpackage" @end) @indent
+(class_declaration "endclass" @end) @indent
+(function_declaration "endfunction" @end) @indent
+(task_declaration "endtask" @end) @indent
+(case_statement "endcase" @end) @indent
+(generate_region "endgenerate" @end) @indent
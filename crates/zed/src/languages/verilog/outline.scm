@@ -0,0 +1,27 @@
+(module_declaration
+    (module_header
+        (module_keyword) @context
+        (simple_identifier) @name)) @item
+
+(interface_declaration
+    (interface_ansi_header
+        "interface" @context
+        (interface_identifier) @name)) @item
+
+(package_declaration
+    "package" @context
+    (package_identifier) @name) @item
+
+(class_declaration
+    "class" @context
+    (class_identifier) @name) @item
+
+(function_declaration
+    "function" @context
+    (function_body_declaration
+        (function_identifier) @name)) @item
+
+(task_declaration
+    "task" @context
+    (task_body_declaration
+        (task_identifier) @name)) @item
@@ -5,7 +5,10 @@ pub use language::*;
 use lsp::{CodeActionKind, LanguageServerBinary};
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
+use settings::Settings;
 use smol::fs::{self};
 use std::{
     any::Any,
@@ -15,6 +18,33 @@ use std::{
 };
 use util::ResultExt;
 
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VueSettings {
+    pub hybrid_mode: bool,
+}
+
+#[derive(Clone, Serialize, Default, Deserialize, JsonSchema)]
+pub struct VueSettingsContent {
+    hybrid_mode: Option<bool>,
+}
+
+impl Settings for VueSettings {
+    const KEY: Option<&'static str> = Some("vue");
+
+    type FileContent = VueSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut gpui::AppContext,
+    ) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
 pub struct VueLspVersion {
     vue_version: String,
     ts_version: String,
@@ -23,6 +53,7 @@ pub struct VueLspVersion {
 pub struct VueLspAdapter {
     node: Arc<dyn NodeRuntime>,
     typescript_install_path: Mutex<Option<PathBuf>>,
+    hybrid_mode: bool,
 }
 
 impl VueLspAdapter {
@@ -30,11 +61,15 @@ impl VueLspAdapter {
         "node_modules/@vue/language-server/bin/vue-language-server.js";
     // TODO: this can't be hardcoded, yet we have to figure out how to pass it in initialization_options.
     const TYPESCRIPT_PATH: &'static str = "node_modules/typescript/lib";
-    pub fn new(node: Arc<dyn NodeRuntime>) -> Self {
+    /// In hybrid mode, the scripts of components are left to the TypeScript server, which
+    /// loads `@vue/typescript-plugin` to understand them. Otherwise the Vue language server
+    /// takes over TypeScript itself.
+    pub fn new(node: Arc<dyn NodeRuntime>, hybrid_mode: bool) -> Self {
         let typescript_install_path = Mutex::new(None);
         Self {
             node,
             typescript_install_path,
+            hybrid_mode,
         }
     }
 }
@@ -69,6 +104,9 @@ impl super::LspAdapter for VueLspAdapter {
         Some(serde_json::json!({
             "typescript": {
                 "tsdk": typescript_sdk_path
+            },
+            "vue": {
+                "hybridMode": self.hybrid_mode
             }
         }))
     }
@@ -119,6 +157,8 @@ impl super::LspAdapter for VueLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: vue_server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -205,6 +245,8 @@ async fn get_cached_server_binary(
                 LanguageServerBinary {
                     path: node.binary_path().await?,
                     arguments: vue_server_binary_arguments(&server_path),
+                    socket: None,
+                    env: None,
                 },
                 typescript_path,
             ))
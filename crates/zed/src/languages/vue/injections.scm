@@ -1,7 +1,55 @@
-(script_element
-  (raw_text) @content
+; Blocks in Vue's single-file components are written in the language named by their
+; `lang` attribute, and default to JavaScript and CSS.
+
+((script_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "lang=")
   (#set! "language" "javascript"))
 
-(style_element
-  (raw_text) @content
+((script_element
+    (start_tag
+      (attribute
+        (attribute_name) @_attribute_name
+        (quoted_attribute_value (attribute_value) @_language)))
+    (raw_text) @content)
+  (#eq? @_attribute_name "lang")
+  (#match? @_language "^(ts|typescript)$")
+  (#set! "language" "typescript"))
+
+((script_element
+    (start_tag
+      (attribute
+        (attribute_name) @_attribute_name
+        (quoted_attribute_value (attribute_value) @_language)))
+    (raw_text) @content)
+  (#eq? @_attribute_name "lang")
+  (#match? @_language "^(tsx|jsx)$")
+  (#set! "language" "tsx"))
+
+((style_element
+    (start_tag) @_start_tag
+    (raw_text) @content)
+  (#not-match? @_start_tag "lang=")
+  (#set! "language" "css"))
+
+((style_element
+    (start_tag
+      (attribute
+        (attribute_name) @_attribute_name
+        (quoted_attribute_value (attribute_value) @_language)))
+    (raw_text) @content)
+  (#eq? @_attribute_name "lang")
+  (#eq? @_language "css")
   (#set! "language" "css"))
+
+; The expressions in the template
+
+(interpolation
+  (raw_text) @content
+  (#set! "language" "javascript"))
+
+(directive_attribute
+  (quoted_attribute_value
+    (attribute_value) @content)
+  (#set! "language" "javascript"))
@@ -0,0 +1,131 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, LanguageServerName, LspAdapter,
+    LspAdapterDelegate, SystemBinary,
+};
+use lsp::LanguageServerBinary;
+use smol::fs;
+use std::{any::Any, path::PathBuf};
+use util::{
+    async_maybe,
+    github::{platform_asset_version, GitHubLspBinaryVersion},
+    ResultExt,
+};
+
+pub struct WgslLspAdapter;
+
+#[async_trait]
+impl LspAdapter for WgslLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        LanguageServerName("wgsl-analyzer".into())
+    }
+
+    fn short_name(&self) -> &'static str {
+        "wgsl-analyzer"
+    }
+
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "wgsl_analyzer",
+            search_paths: vec![util::paths::HOME.join(".cargo/bin")],
+            arguments: Vec::new(),
+        })
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        let mut release =
+            github_release_to_install("wgsl-analyzer/wgsl-analyzer", false, delegate).await?;
+        // The VS Code extensions that are published for each platform bundle the server,
+        // which is also published on its own.
+        release
+            .assets
+            .retain(|asset| !asset.name.ends_with(".vsix"));
+        let version =
+            platform_asset_version(release, "wgsl_analyzer-", "", delegate.http_client()).await?;
+        Ok(Box::new(version) as Box<_>)
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        version: Box<dyn 'static + Send + Any>,
+        container_dir: PathBuf,
+        delegate: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
+        let binary_path = container_dir.join(format!("wgsl_analyzer_{}", version.name));
+
+        if fs::metadata(&binary_path).await.is_err() {
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &binary_path,
+            )
+            .await?;
+            fs::set_permissions(
+                &binary_path,
+                <fs::Permissions as fs::unix::PermissionsExt>::from_mode(0o755),
+            )
+            .await?;
+        }
+
+        Ok(LanguageServerBinary::new(binary_path))
+    }
+
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("wgsl_analyzer_")
+    }
+
+    async fn cached_server_binary(
+        &self,
+        container_dir: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir).await
+    }
+
+    async fn installation_test_binary(
+        &self,
+        container_dir: PathBuf,
+    ) -> Option<LanguageServerBinary> {
+        get_cached_server_binary(container_dir)
+            .await
+            .map(|mut binary| {
+                binary.arguments = vec!["--version".into()];
+                binary
+            })
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("WGSL".into(), "wgsl".into())])
+    }
+}
+
+async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+    async_maybe!({
+        let mut last_binary_path = None;
+        let mut entries = fs::read_dir(&container_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            if entry.file_type().await?.is_file()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| name.starts_with("wgsl_analyzer_"))
+            {
+                last_binary_path = Some(entry.path());
+            }
+        }
+
+        let path = last_binary_path.ok_or_else(|| anyhow!("no cached binary"))?;
+        Ok(LanguageServerBinary::new(path))
+    })
+    .await
+    .log_err()
+}
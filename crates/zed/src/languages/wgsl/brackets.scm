@@ -0,0 +1,5 @@
+((paren_left) @open (paren_right) @close)
+((bracket_left) @open (bracket_right) @close)
+((brace_left) @open (brace_right) @close)
+(variable_qualifier (less_than) @open (greater_than) @close)
+(type_decl_without_ident (less_than) @open (greater_than) @close)
@@ -0,0 +1,110 @@
+[
+  (int_literal)
+  (float_literal)
+] @number
+
+(bool_literal) @boolean
+
+(type_decl_without_ident) @type
+(type_decl (ident) @type)
+(struct_decl (ident) @type)
+(type_alias_decl (ident) @type)
+
+(function_header (ident) @function)
+(func_call_statement (ident) @function)
+(primary_expression
+  (callable (ident) @function)
+  (argument_expression_list))
+
+(param
+  (variable_ident_decl
+    (ident) @variable.parameter))
+
+(struct_member
+  (variable_ident_decl
+    (ident) @property))
+
+(postfix_expression
+  (period)
+  .
+  (ident) @property)
+
+(attribute
+  (attr) @attribute
+  (ident) @attribute)
+
+[
+  (fn)
+  (let)
+  (var)
+  (const)
+  (override)
+  (struct)
+  (type)
+  (enable)
+  (return)
+  (if)
+  (else)
+  (switch)
+  (case)
+  (default)
+  (loop)
+  (for)
+  (while)
+  (break)
+  (continue)
+  (continuing)
+  (discard)
+  (fallthrough)
+  (bitcast)
+  (staticAssert)
+] @keyword
+
+[
+  (address_space)
+  (access_mode)
+] @keyword
+
+[
+  (equal)
+  (equal_equal)
+  (not_equal)
+  (less_than)
+  (less_than_equal)
+  (greater_than)
+  (greater_than_equal)
+  (plus)
+  (minus)
+  (star)
+  (forward_slash)
+  (modulo)
+  (and)
+  (or)
+  (xor)
+  (tilde)
+  (bang)
+  (and_and)
+  (or_or)
+  (plus_plus)
+  (minus_minus)
+  (plus_equal)
+  (minus_equal)
+  (times_equal)
+  (division_equal)
+  (modulo_equal)
+  (and_equal)
+  (or_equal)
+  (xor_equal)
+  (arrow)
+] @operator
+
+[(semicolon) (comma) (period) (colon)] @punctuation.delimiter
+
+[
+  (paren_left)
+  (paren_right)
+  (bracket_left)
+  (bracket_right)
+  (brace_left)
+  (brace_right)
+] @punctuation.bracket
@@ -0,0 +1,3 @@
+(_ (bracket_left) (bracket_right) @end) @indent
+(_ (brace_left) (brace_right) @end) @indent
+(_ (paren_left) (paren_right) @end) @indent
@@ -0,0 +1,33 @@
+(function_decl
+    (function_header
+        (fn) @context
+        (ident) @name)) @item
+
+(struct_decl
+    (struct) @context
+    (ident) @name) @item
+
+(struct_member
+    (variable_ident_decl
+        (ident) @name)) @item
+
+(global_variable_decl
+    (variable_decl
+        (var) @context
+        [
+            (ident) @name
+            (variable_ident_decl
+                (ident) @name)
+        ])) @item
+
+(global_constant_decl
+    [(const) (override)] @context
+    [
+        (ident) @name
+        (variable_ident_decl
+            (ident) @name)
+    ]) @item
+
+(type_alias_decl
+    (type) @context
+    (ident) @name) @item
@@ -19,6 +19,24 @@ use util::ResultExt;
 
 const SERVER_PATH: &'static str = "node_modules/yaml-language-server/bin/yaml-language-server";
 
+/// The schema of the Compose file format, which yaml-language-server validates and completes
+/// Compose files against.
+const COMPOSE_SCHEMA_URL: &'static str =
+    "https://raw.githubusercontent.com/compose-spec/compose-spec/master/schema/compose-spec.json";
+
+/// The file names that `docker compose` looks for by default, along with the overrides that
+/// it merges into them.
+const COMPOSE_FILE_GLOBS: &[&str] = &[
+    "**/compose.yml",
+    "**/compose.yaml",
+    "**/compose.*.yml",
+    "**/compose.*.yaml",
+    "**/docker-compose.yml",
+    "**/docker-compose.yaml",
+    "**/docker-compose.*.yml",
+    "**/docker-compose.*.yaml",
+];
+
 fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
     vec![server_path.into(), "--stdio".into()]
 }
@@ -75,6 +93,8 @@ impl LspAdapter for YamlLspAdapter {
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
             arguments: server_binary_arguments(&server_path),
+            socket: None,
+            env: None,
         })
     }
 
@@ -95,7 +115,10 @@ impl LspAdapter for YamlLspAdapter {
     fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
         serde_json::json!({
             "yaml": {
-                "keyOrdering": false
+                "keyOrdering": false,
+                "schemas": {
+                    COMPOSE_SCHEMA_URL: COMPOSE_FILE_GLOBS,
+                }
             },
             "[yaml]": {
                 "editor.tabSize": all_language_settings(None, cx)
@@ -125,6 +148,8 @@ async fn get_cached_server_binary(
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
                 arguments: server_binary_arguments(&server_path),
+                socket: None,
+                env: None,
             })
         } else {
             Err(anyhow!(
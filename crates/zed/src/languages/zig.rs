@@ -1,15 +1,20 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
-use futures::{io::BufReader, StreamExt};
-use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
+use futures::StreamExt;
+use language::{
+    download_server_binary, github_release_to_install, InstallProgress, LanguageServerName,
+    LspAdapter, LspAdapterDelegate, SystemBinary,
+};
 use lsp::LanguageServerBinary;
-use smol::fs;
-use std::env::consts::ARCH;
+use smol::{
+    fs::{self, File},
+    io::BufReader,
+};
 use std::{any::Any, path::PathBuf};
 use util::async_maybe;
-use util::github::latest_github_release;
+use util::github::platform_asset_version;
 use util::{github::GitHubLspBinaryVersion, ResultExt};
 
 pub struct ZlsAdapter;
@@ -24,22 +29,21 @@ impl LspAdapter for ZlsAdapter {
         "zls"
     }
 
+    fn system_binary(&self) -> Option<SystemBinary> {
+        Some(SystemBinary {
+            name: "zls",
+            search_paths: Vec::new(),
+            arguments: Vec::new(),
+        })
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<Box<dyn 'static + Send + Any>> {
-        let release = latest_github_release("zigtools/zls", false, delegate.http_client()).await?;
-        let asset_name = format!("zls-{}-macos.tar.gz", ARCH);
-        let asset = release
-            .assets
-            .iter()
-            .find(|asset| asset.name == asset_name)
-            .ok_or_else(|| anyhow!("no asset found matching {:?}", asset_name))?;
-        let version = GitHubLspBinaryVersion {
-            name: release.name,
-            url: asset.browser_download_url.clone(),
-        };
-
+        let release = github_release_to_install("zigtools/zls", false, delegate).await?;
+        let version =
+            platform_asset_version(release, "zls-", ".tar.gz", delegate.http_client()).await?;
         Ok(Box::new(version) as Box<_>)
     }
 
@@ -50,17 +54,24 @@ impl LspAdapter for ZlsAdapter {
         delegate: &dyn LspAdapterDelegate,
     ) -> Result<LanguageServerBinary> {
         let version = version.downcast::<GitHubLspBinaryVersion>().unwrap();
-        let binary_path = container_dir.join("bin/zls");
+        let version_dir = container_dir.join(format!("zls_{}", version.name));
+        let binary_path = version_dir.join("bin/zls");
 
         if fs::metadata(&binary_path).await.is_err() {
-            let mut response = delegate
-                .http_client()
-                .get(&version.url, Default::default(), true)
-                .await
-                .context("error downloading release")?;
-            let decompressed_bytes = GzipDecoder::new(BufReader::new(response.body_mut()));
+            let archive_path = container_dir.join("zls.tar.gz");
+            download_server_binary(
+                delegate,
+                &version.url,
+                version.sha256.as_deref(),
+                &archive_path,
+            )
+            .await?;
+            delegate.report_install_progress(InstallProgress::Extracting);
+            let decompressed_bytes =
+                GzipDecoder::new(BufReader::new(File::open(&archive_path).await?));
             let archive = Archive::new(decompressed_bytes);
-            archive.unpack(container_dir).await?;
+            archive.unpack(&version_dir).await?;
+            fs::remove_file(&archive_path).await.log_err();
         }
 
         fs::set_permissions(
@@ -71,9 +82,15 @@ impl LspAdapter for ZlsAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
+            socket: None,
+            env: None,
         })
     }
 
+    fn installed_version_prefix(&self) -> Option<&'static str> {
+        Some("zls_")
+    }
+
     async fn cached_server_binary(
         &self,
         container_dir: PathBuf,
@@ -97,27 +114,31 @@ impl LspAdapter for ZlsAdapter {
 
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     async_maybe!({
-        let mut last_binary_path = None;
+        let mut last_version_dir = None;
         let mut entries = fs::read_dir(&container_dir).await?;
         while let Some(entry) = entries.next().await {
             let entry = entry?;
-            if entry.file_type().await?.is_file()
+            if entry.file_type().await?.is_dir()
                 && entry
                     .file_name()
                     .to_str()
-                    .map_or(false, |name| name == "zls")
+                    .map_or(false, |name| name.starts_with("zls_"))
             {
-                last_binary_path = Some(entry.path());
+                last_version_dir = Some(entry.path());
             }
         }
 
-        if let Some(path) = last_binary_path {
+        let version_dir = last_version_dir.ok_or_else(|| anyhow!("no cached binary"))?;
+        let binary_path = version_dir.join("bin/zls");
+        if binary_path.exists() {
             Ok(LanguageServerBinary {
-                path,
+                path: binary_path,
                 arguments: Vec::new(),
+                socket: None,
+                env: None,
             })
         } else {
-            Err(anyhow!("no cached binary"))
+            Err(anyhow!("missing zls binary in directory {:?}", version_dir))
         }
     })
     .await
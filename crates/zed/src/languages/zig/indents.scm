@@ -1,22 +1,3 @@
-[
-  (Block)
-  (ContainerDecl)
-  (SwitchExpr)
-  (InitList)
-] @indent.begin
-
-[
-  "("
-  ")"
-  "["
-  "]"
-  "{"
-  "}"
-] @indent.branch
-
-[
-  (line_comment)
-  (container_doc_comment)
-  (doc_comment)
-  (LINESTRING)
-] @indent.ignore
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+(_ "(" ")" @end) @indent
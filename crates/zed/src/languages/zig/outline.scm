@@ -0,0 +1,22 @@
+(Decl
+  (FnProto
+    "fn" @context
+    function: (_) @name)) @item
+
+(Decl
+  (VarDecl
+    [
+      "const"
+      "var"
+    ] @context
+    variable_type_function: (_) @name)) @item
+
+(TestDecl
+  "test" @context
+  [
+    (STRINGLITERALSINGLE)
+    (IDENTIFIER)
+  ] @name) @item
+
+(ContainerField
+  (IDENTIFIER) @name) @item
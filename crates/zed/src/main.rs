@@ -5,7 +5,10 @@ use anyhow::{anyhow, Context as _, Result};
 use backtrace::Backtrace;
 use chrono::Utc;
 use cli::FORCE_CLI_MODE_ENV_VAR_NAME;
-use client::{Client, UserStore};
+use client::{
+    telemetry::{self, Telemetry, TelemetryConsent},
+    Client, UserStore,
+};
 use collab_ui::channel_view::ChannelView;
 use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
@@ -26,29 +29,33 @@ use settings::{
 use simplelog::ConfigBuilder;
 use smol::process::Command;
 use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
     env,
     ffi::OsStr,
     fs::OpenOptions,
     io::{IsTerminal, Write},
     panic,
     path::{Path, PathBuf},
+    rc::Rc,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc, Weak,
     },
     thread,
+    time::Instant,
 };
 use theme::ActiveTheme;
 use util::{
     async_maybe,
     channel::{parse_zed_link, AppCommitSha, ReleaseChannel, RELEASE_CHANNEL},
-    http::{self, HttpClient},
+    http::{self, HttpClient, ObservedHttpClient},
     paths::{self, CRASHES_DIR, CRASHES_RETIRED_DIR},
     ResultExt,
 };
 use uuid::Uuid;
 use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
-use workspace::{AppState, WorkspaceStore};
+use workspace::{AppState, Workspace, WorkspaceStore};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
     handle_keymap_file_changes, initialize_workspace, languages, IsOnlyInstance, OpenListener,
@@ -59,7 +66,9 @@ fn main() {
     menu::init();
     zed_actions::init();
 
-    let http = http::client();
+    // Observed so that telemetry can report how requests to Zed's APIs are going
+    let observed_http = ObservedHttpClient::new(http::client());
+    let http: Arc<dyn HttpClient> = observed_http.clone();
     init_paths();
     init_logger();
 
@@ -75,6 +84,12 @@ fn main() {
         .block(installation_id())
         .ok()
         .unzip();
+    // Nothing is sent until consent is known, so fall back to asking for it
+    let telemetry_consent = app
+        .background_executor()
+        .block(telemetry::load_consent(existing_installation_id_found))
+        .log_err()
+        .unwrap_or(TelemetryConsent::Unasked);
     let session_id = Uuid::new_v4().to_string();
     init_panic_hook(&app, installation_id.clone(), session_id.clone());
 
@@ -130,7 +145,9 @@ fn main() {
             .set_default_settings(default_settings().as_ref(), cx)
             .unwrap();
         cx.set_global(store);
+        let settings_load_started_at = Instant::now();
         handle_settings_file_changes(user_settings_file_rx, cx);
+        let settings_load_duration = settings_load_started_at.elapsed();
         handle_keymap_file_changes(user_keymap_file_rx, cx);
 
         let client = client::Client::new(http.clone(), cx);
@@ -142,7 +159,9 @@ fn main() {
         let node_runtime = RealNodeRuntime::new(http.clone());
 
         language::init(cx);
+        let languages_load_started_at = Instant::now();
         languages::init(languages.clone(), node_runtime.clone(), cx);
+        let languages_load_duration = languages_load_started_at.elapsed();
         let user_store = cx.new_model(|cx| UserStore::new(client.clone(), cx));
         let workspace_store = cx.new_model(|cx| WorkspaceStore::new(client.clone(), cx));
 
@@ -176,7 +195,13 @@ fn main() {
         .detach();
 
         let telemetry = client.telemetry();
-        telemetry.start(installation_id, session_id, cx);
+        observed_http.set_observer(Arc::downgrade(telemetry));
+        telemetry.start(installation_id, session_id, telemetry_consent, cx);
+        telemetry.install_panic_hook();
+        telemetry.report_timing("settings load", settings_load_duration);
+        telemetry.report_timing("languages load", languages_load_duration);
+        report_time_to_first_window(telemetry.clone(), cx);
+        track_session_activity(telemetry.clone(), cx);
         telemetry.report_setting_event("theme", cx.theme().name.to_string());
         telemetry.report_setting_event("keymap", BaseKeymap::get_global(cx).to_string());
         telemetry.report_app_event(
@@ -210,6 +235,7 @@ fn main() {
         outline::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
+        hierarchy_panel::init(cx);
         channel::init(&client, user_store.clone(), cx);
         search::init(cx);
         semantic_index::init(fs.clone(), http.clone(), languages.clone(), cx);
@@ -605,10 +631,10 @@ fn init_panic_hook(app: &App, installation_id: Option<String>, session_id: Strin
 }
 
 fn upload_panics_and_crashes(http: Arc<dyn HttpClient>, cx: &mut AppContext) {
-    let telemetry_settings = *client::TelemetrySettings::get_global(cx);
+    let telemetry_settings = client::TelemetrySettings::get_global(cx).clone();
     cx.background_executor()
         .spawn(async move {
-            upload_previous_panics(http.clone(), telemetry_settings)
+            upload_previous_panics(http.clone(), telemetry_settings.clone())
                 .await
                 .log_err();
             upload_previous_crashes(http, telemetry_settings)
@@ -802,6 +828,42 @@ fn collect_url_args() -> Vec<String> {
         .collect()
 }
 
+fn report_time_to_first_window(telemetry: Arc<Telemetry>, cx: &mut AppContext) {
+    let reported = Cell::new(false);
+    cx.observe_new_views(move |_: &mut Workspace, _| {
+        if !reported.replace(true) {
+            telemetry.report_timing("first window", telemetry.time_since_launch());
+        }
+    })
+    .detach();
+}
+
+fn track_session_activity(telemetry: Arc<Telemetry>, cx: &mut AppContext) {
+    cx.observe_keystrokes({
+        let telemetry = telemetry.clone();
+        move |_, _| telemetry.report_input_activity()
+    })
+    .detach();
+
+    // Windows can be activated before the previously active one is deactivated.
+    let active_windows = Rc::new(RefCell::new(HashSet::default()));
+    cx.observe_new_views(move |_: &mut Workspace, cx| {
+        let telemetry = telemetry.clone();
+        let active_windows = active_windows.clone();
+        cx.observe_window_activation(move |_, cx| {
+            let mut active_windows = active_windows.borrow_mut();
+            if cx.is_window_active() {
+                active_windows.insert(cx.window_handle());
+            } else {
+                active_windows.remove(&cx.window_handle());
+            }
+            telemetry.set_window_active(!active_windows.is_empty());
+        })
+        .detach();
+    })
+    .detach();
+}
+
 fn load_embedded_fonts(cx: &AppContext) {
     let asset_source = cx.asset_source();
     let font_paths = asset_source.list("fonts").unwrap();
@@ -9,8 +9,8 @@ use breadcrumbs::Breadcrumbs;
 use collections::VecDeque;
 use editor::{Editor, MultiBuffer};
 use gpui::{
-    actions, point, px, AppContext, Context, FocusableView, PromptLevel, TitlebarOptions, View,
-    ViewContext, VisualContext, WindowBounds, WindowKind, WindowOptions,
+    actions, point, px, Action, AppContext, Context, FocusableView, PromptLevel, TitlebarOptions,
+    View, ViewContext, VisualContext, WindowBounds, WindowKind, WindowOptions,
 };
 pub use only_instance::*;
 pub use open_listener::*;
@@ -18,6 +18,8 @@ pub use open_listener::*;
 use anyhow::{anyhow, Context as _};
 use assets::Assets;
 use futures::{channel::mpsc, select_biased, StreamExt};
+use hierarchy_panel::HierarchyPanel;
+use language_tools::OpenTelemetryInspector;
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
 use rope::Rope;
@@ -36,7 +38,7 @@ use welcome::BaseKeymap;
 use workspace::Pane;
 use workspace::{
     create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, Workspace, WorkspaceSettings,
+    open_new, AppState, NewFile, NewWindow, Toast, Workspace, WorkspaceSettings,
 };
 use zed_actions::{OpenBrowser, OpenSettings, OpenZedURL, Quit};
 
@@ -60,6 +62,7 @@ actions!(
         ResetBufferFontSize,
         ResetDatabase,
         ShowAll,
+        TestTelemetry,
         ToggleFullScreen,
         Zoom,
     ]
@@ -154,6 +157,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let assistant_panel = AssistantPanel::load(workspace_handle.clone(), cx.clone());
+            let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
             let chat_panel =
@@ -166,6 +170,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 project_panel,
                 terminal_panel,
                 assistant_panel,
+                hierarchy_panel,
                 channels_panel,
                 chat_panel,
                 notification_panel,
@@ -173,6 +178,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 project_panel,
                 terminal_panel,
                 assistant_panel,
+                hierarchy_panel,
                 channels_panel,
                 chat_panel,
                 notification_panel,
@@ -182,6 +188,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(project_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(assistant_panel, cx);
+                workspace.add_panel(hierarchy_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
@@ -240,6 +247,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                     open_telemetry_log_file(workspace, cx);
                 },
             )
+            .register_action(|workspace, _: &TestTelemetry, cx| {
+                test_telemetry(workspace, cx);
+            })
             .register_action(
                 move |_: &mut Workspace, _: &OpenKeymap, cx: &mut ViewContext<Workspace>| {
                     open_settings_file(&paths::KEYMAP, Rope::default, cx);
@@ -671,6 +681,33 @@ fn open_telemetry_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Works
     }).detach();
 }
 
+fn test_telemetry(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    const TOAST_ID: usize = 0x7e1e;
+
+    let report = workspace.app_state().client.telemetry().test_connectivity();
+    cx.spawn(|workspace, mut cx| async move {
+        let report = report.await;
+        let message = match &report.result {
+            Ok(status) => format!(
+                "Telemetry test to {} returned {} in {}ms",
+                report.url,
+                status,
+                report.latency.as_millis()
+            ),
+            Err(error) => format!("Telemetry test to {} failed: {}", report.url, error),
+        };
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(TOAST_ID, message).on_click("Open telemetry inspector", |cx| {
+                    cx.dispatch_action(OpenTelemetryInspector.boxed_clone())
+                }),
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 fn open_bundled_file(
     workspace: &mut Workspace,
     text: Cow<'static, str>,
@@ -2742,6 +2779,7 @@ mod tests {
             project_panel::init_settings(cx);
             collab_ui::init(&app_state, cx);
             project_panel::init((), cx);
+            hierarchy_panel::init(cx);
             terminal_view::init(cx);
             assistant::init(cx);
             initialize_workspace(app_state.clone(), cx);